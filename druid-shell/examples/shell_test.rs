@@ -0,0 +1,404 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A window that exercises most of the shell, for testing backends by hand.
+//!
+//! Every `WinHandler` callback is logged to stdout with a timestamp, and the
+//! window shows its size, scale and frame count. Press `h` for the list of
+//! keys that trigger each feature.
+
+use std::any::Any;
+use std::fmt::Display;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use druid_shell::kurbo::{Line, Point, Rect, Size};
+use druid_shell::piet::{
+    Color, FontFamily, Piet, RenderContext, Text, TextLayout, TextLayoutBuilder,
+};
+
+use druid_shell::{
+    Application, Cursor, KbKey, KeyEvent, MouseEvent, Region, Scale, TimerToken, WinHandler,
+    WindowBuilder, WindowHandle, WindowLevel, WindowState,
+};
+
+const BG_COLOR: Color = Color::rgb8(0x27, 0x28, 0x22);
+const GRID_COLOR: Color = Color::rgb8(0x3a, 0x3b, 0x35);
+const FG_COLOR: Color = Color::rgb8(0xf0, 0xf0, 0xea);
+const ACCENT_COLOR: Color = Color::rgb8(0xff, 0x80, 0x80);
+const POPUP_COLOR: Color = Color::rgb8(0x40, 0x60, 0x80);
+
+const GRID_SPACING: f64 = 50.0;
+const ANIMATION_LENGTH: Duration = Duration::from_secs(2);
+const TIMER_DELAY: Duration = Duration::from_secs(1);
+
+const CURSORS: &[(&str, Cursor)] = &[
+    ("arrow", Cursor::Arrow),
+    ("I-beam", Cursor::IBeam),
+    ("pointer", Cursor::Pointer),
+    ("crosshair", Cursor::Crosshair),
+    ("not allowed", Cursor::NotAllowed),
+    ("resize left-right", Cursor::ResizeLeftRight),
+    ("resize up-down", Cursor::ResizeUpDown),
+];
+
+const HELP: &[&str] = &[
+    "n  open another window",
+    "p  open a popup at the mouse",
+    "t  request a timer",
+    "c  copy the frame count",
+    "v  paste and log the clipboard",
+    "u  change the cursor",
+    "m  toggle maximized",
+    "r  invalidate a random rect",
+    "a  animate for two seconds",
+    "q  close this window",
+];
+
+/// The number of open windows; the app quits when the last one closes.
+static OPEN_WINDOWS: AtomicUsize = AtomicUsize::new(0);
+
+struct ShellTest {
+    handle: WindowHandle,
+    name: String,
+    is_popup: bool,
+    start: Instant,
+    size: Size,
+    scale: Option<Scale>,
+    frames: u64,
+    mouse_pos: Point,
+    cursor: usize,
+    timers: Vec<TimerToken>,
+    animate_until: Option<Instant>,
+    /// The last rect invalidated with `r`, outlined until the next one.
+    random_rect: Option<Rect>,
+    /// State of the xorshift generator used for random rects.
+    seed: u64,
+    show_help: bool,
+    children: usize,
+}
+
+impl ShellTest {
+    fn new(name: String, start: Instant) -> Self {
+        ShellTest {
+            handle: Default::default(),
+            name,
+            is_popup: false,
+            start,
+            size: Size::ZERO,
+            scale: None,
+            frames: 0,
+            mouse_pos: Point::ORIGIN,
+            cursor: 0,
+            timers: Vec::new(),
+            animate_until: None,
+            random_rect: None,
+            seed: 0x2545_f491_4f6c_dd1d,
+            show_help: true,
+            children: 0,
+        }
+    }
+
+    fn log(&self, what: impl Display) {
+        let elapsed = self.start.elapsed().as_secs_f64();
+        println!("[{:>9.3}s] {}: {}", elapsed, self.name, what);
+    }
+
+    fn random(&mut self) -> f64 {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 7;
+        self.seed ^= self.seed << 17;
+        (self.seed >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn new_window(&mut self, popup: bool) {
+        self.children += 1;
+        let kind = if popup { "popup" } else { "window" };
+        let name = format!("{} {}.{}", kind, self.name, self.children);
+        let mut handler = ShellTest::new(name.clone(), self.start);
+        handler.is_popup = popup;
+        handler.show_help = !popup;
+
+        let mut builder = WindowBuilder::new(Application::global());
+        builder.set_title(name);
+        if popup {
+            builder.set_level(WindowLevel::DropDown);
+            builder.show_titlebar(false);
+            builder.resizable(false);
+            builder.set_size(Size::new(200.0, 120.0));
+            builder.set_position(self.handle.get_position() + self.mouse_pos.to_vec2());
+        }
+        builder.set_handler(Box::new(handler));
+        match builder.build() {
+            Ok(window) => window.show(),
+            Err(e) => self.log(format_args!("failed to open a {}: {}", kind, e)),
+        }
+    }
+
+    fn handle_key(&mut self, key: &str) {
+        match key {
+            "h" => {
+                self.show_help = !self.show_help;
+                self.handle.invalidate();
+            }
+            "n" => self.new_window(false),
+            "p" => self.new_window(true),
+            "t" => {
+                let token = self.handle.request_timer(TIMER_DELAY);
+                self.log(format_args!("requested {:?}", token));
+                self.timers.push(token);
+            }
+            "c" => {
+                let text = format!("{} frame {}", self.name, self.frames);
+                Application::global().clipboard().put_string(&text);
+                self.log(format_args!("copied {:?}", text));
+            }
+            "v" => {
+                let text = Application::global().clipboard().get_string();
+                self.log(format_args!("pasted {:?}", text));
+            }
+            "u" => {
+                self.cursor = (self.cursor + 1) % CURSORS.len();
+                let (name, cursor) = &CURSORS[self.cursor];
+                self.handle.set_cursor(cursor);
+                self.log(format_args!("cursor is now {}", name));
+                self.handle.invalidate();
+            }
+            "m" => {
+                let state = match self.handle.get_window_state() {
+                    WindowState::Maximized => WindowState::Restored,
+                    _ => WindowState::Maximized,
+                };
+                self.log(format_args!("setting window state to {:?}", state));
+                self.handle.set_window_state(state);
+            }
+            "r" => {
+                let (x, y) = (self.random(), self.random());
+                let (width, height) = (self.random(), self.random());
+                let origin = Point::new(x * self.size.width, y * self.size.height);
+                let size = Size::new(
+                    width * (self.size.width - origin.x),
+                    height * (self.size.height - origin.y),
+                );
+                let rect = Rect::from_origin_size(origin, size);
+                self.log(format_args!("invalidating {:?}", rect));
+                // invalidate the old outline too, so that it goes away
+                if let Some(old) = self.random_rect.replace(rect) {
+                    self.handle.invalidate_rect(old.inflate(1.0, 1.0));
+                }
+                self.handle.invalidate_rect(rect.inflate(1.0, 1.0));
+            }
+            "a" => {
+                self.animate_until = Some(Instant::now() + ANIMATION_LENGTH);
+                self.handle.request_anim_frame();
+            }
+            "q" => self.handle.close(),
+            _ => (),
+        }
+    }
+
+    fn draw_text(&self, piet: &mut Piet, text: String, pos: Point, color: &Color) -> f64 {
+        let layout = piet
+            .text()
+            .new_text_layout(text)
+            .font(FontFamily::MONOSPACE, 14.0)
+            .text_color(color.clone())
+            .build()
+            .unwrap();
+        piet.draw_text(&layout, pos);
+        layout.size().height
+    }
+}
+
+impl WinHandler for ShellTest {
+    fn connect(&mut self, handle: &WindowHandle) {
+        self.handle = handle.clone();
+        OPEN_WINDOWS.fetch_add(1, Ordering::SeqCst);
+        self.log("connect");
+    }
+
+    fn size(&mut self, size: Size) {
+        self.log(format_args!("size {:?}", size));
+        self.size = size;
+    }
+
+    fn scale(&mut self, scale: Scale) {
+        self.log(format_args!("scale {:?}", scale));
+        self.scale = Some(scale);
+    }
+
+    fn prepare_paint(&mut self) {
+        match self.animate_until {
+            Some(until) if Instant::now() < until => {
+                self.handle.invalidate();
+                self.handle.request_anim_frame();
+            }
+            Some(_) => {
+                self.log("animation done");
+                self.animate_until = None;
+            }
+            None => (),
+        }
+    }
+
+    fn paint(&mut self, piet: &mut Piet, invalid: &Region) {
+        self.frames += 1;
+        self.log(format_args!(
+            "paint #{}, {} invalid rects",
+            self.frames,
+            invalid.rects().len()
+        ));
+
+        let background = if self.is_popup { POPUP_COLOR } else { BG_COLOR };
+        piet.fill(self.size.to_rect(), &background);
+        let mut x = 0.0;
+        while x < self.size.width {
+            piet.stroke(Line::new((x, 0.0), (x, self.size.height)), &GRID_COLOR, 1.0);
+            x += GRID_SPACING;
+        }
+        let mut y = 0.0;
+        while y < self.size.height {
+            piet.stroke(Line::new((0.0, y), (self.size.width, y)), &GRID_COLOR, 1.0);
+            y += GRID_SPACING;
+        }
+        // the diagonal shows whether the whole window is painted at the right size
+        piet.stroke(
+            Line::new((0.0, self.size.height), (self.size.width, 0.0)),
+            &FG_COLOR,
+            1.0,
+        );
+
+        let scale = match self.scale.or_else(|| self.handle.get_scale().ok()) {
+            Some(scale) => format!("{:.2} x {:.2}", scale.x(), scale.y()),
+            None => "unknown".into(),
+        };
+        let mut lines = vec![
+            self.name.clone(),
+            format!("size: {:.0} x {:.0}", self.size.width, self.size.height),
+            format!("scale: {}", scale),
+            format!("frame: {}", self.frames),
+            format!("cursor: {}", CURSORS[self.cursor].0),
+            format!("pending timers: {}", self.timers.len()),
+        ];
+        if self.show_help {
+            lines.push(String::new());
+            lines.extend(HELP.iter().map(|line| line.to_string()));
+        }
+        let mut pos = Point::new(10.0, 10.0);
+        for line in lines {
+            pos.y += self.draw_text(piet, line, pos, &FG_COLOR);
+        }
+
+        if let Some(rect) = self.random_rect {
+            piet.stroke(rect, &ACCENT_COLOR, 2.0);
+        }
+    }
+
+    fn key_down(&mut self, event: KeyEvent) -> bool {
+        self.log(format_args!("key_down {:?} {:?}", event.key, event.mods));
+        match &event.key {
+            KbKey::Character(key) if !event.repeat => {
+                self.handle_key(&key.to_lowercase());
+                true
+            }
+            KbKey::Escape if self.is_popup => {
+                self.handle.close();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn key_up(&mut self, event: KeyEvent) {
+        self.log(format_args!("key_up {:?}", event.key));
+    }
+
+    fn wheel(&mut self, event: &MouseEvent) {
+        self.log(format_args!("wheel {:?}", event.wheel_delta));
+    }
+
+    fn zoom(&mut self, delta: f64) {
+        self.log(format_args!("zoom {}", delta));
+    }
+
+    fn mouse_move(&mut self, event: &MouseEvent) {
+        self.mouse_pos = event.pos;
+        self.handle.set_cursor(&CURSORS[self.cursor].1);
+    }
+
+    fn mouse_down(&mut self, event: &MouseEvent) {
+        self.log(format_args!(
+            "mouse_down {:?} at {:?}, count {}",
+            event.button, event.pos, event.count
+        ));
+    }
+
+    fn mouse_up(&mut self, event: &MouseEvent) {
+        self.log(format_args!(
+            "mouse_up {:?} at {:?}",
+            event.button, event.pos
+        ));
+    }
+
+    fn mouse_leave(&mut self) {
+        self.log("mouse_leave");
+    }
+
+    fn timer(&mut self, token: TimerToken) {
+        self.timers.retain(|t| *t != token);
+        self.log(format_args!("timer {:?}", token));
+        self.handle.invalidate();
+    }
+
+    fn got_focus(&mut self) {
+        self.log("got_focus");
+    }
+
+    fn lost_focus(&mut self) {
+        self.log("lost_focus");
+        if self.is_popup {
+            self.handle.close();
+        }
+    }
+
+    fn request_close(&mut self) {
+        self.log("request_close");
+        self.handle.close();
+    }
+
+    fn destroy(&mut self) {
+        self.log("destroy");
+        if OPEN_WINDOWS.fetch_sub(1, Ordering::SeqCst) == 1 {
+            Application::global().quit()
+        }
+    }
+
+    fn as_any(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+fn main() {
+    tracing_subscriber::fmt().init();
+    let app = Application::new().unwrap();
+    let mut builder = WindowBuilder::new(app.clone());
+    builder.set_handler(Box::new(ShellTest::new("window 1".into(), Instant::now())));
+    builder.set_title("Shell test");
+    builder.set_size(Size::new(640.0, 480.0));
+
+    let window = builder.build().unwrap();
+    window.show();
+
+    app.run(None);
+}
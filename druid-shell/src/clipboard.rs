@@ -13,6 +13,8 @@
 // limitations under the License.
 
 //! Interacting with the system pasteboard/clipboard.
+use std::path::PathBuf;
+
 pub use crate::platform::clipboard as platform;
 
 /// A handle to the system clipboard.
@@ -161,6 +163,23 @@ impl Clipboard {
         self.0.get_format(format)
     }
 
+    /// Put a list of files on the clipboard, so that they can be pasted in
+    /// a file manager.
+    ///
+    /// On Linux the paths are written as a `text/uri-list`; this is not yet
+    /// implemented on other platforms.
+    pub fn put_files(&mut self, paths: &[PathBuf]) {
+        self.0.put_files(paths)
+    }
+
+    /// Get the files on the clipboard, such as files copied in a file manager.
+    ///
+    /// On Linux this reads a `text/uri-list`; entries that are not local
+    /// files are skipped. This is not yet implemented on other platforms.
+    pub fn get_files(&self) -> Option<Vec<PathBuf>> {
+        self.0.get_files()
+    }
+
     /// For debugging: print the resolved identifiers for each type currently
     /// on the clipboard.
     #[doc(hidden)]
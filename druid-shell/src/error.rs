@@ -20,12 +20,23 @@ use std::sync::Arc;
 use crate::platform::error as platform;
 
 /// Shell errors.
+///
+/// Backends report failures through the variants below where they can be
+/// classified; anything else ends up in [`Error::Platform`] or [`Error::Other`].
+/// The underlying error is available through [`std::error::Error::source`].
 #[derive(Debug, Clone)]
 pub enum Error {
     /// The Application instance has already been created.
     ApplicationAlreadyExists,
+    /// Could not connect to the display server.
+    ///
+    /// This usually means that no display server is running, or that the
+    /// environment (e.g. `DISPLAY`) doesn't point at one.
+    ConnectionFailed(Arc<anyhow::Error>),
     /// The window has already been destroyed.
     WindowDropped,
+    /// The requested feature is not supported by this platform.
+    Unsupported(&'static str),
     /// Platform specific error.
     Platform(platform::Error),
     /// Other miscellaneous error.
@@ -38,14 +49,28 @@ impl fmt::Display for Error {
             Error::ApplicationAlreadyExists => {
                 write!(f, "An application instance has already been created.")
             }
+            Error::ConnectionFailed(err) => {
+                write!(f, "Could not connect to the display server: {}", err)
+            }
             Error::Platform(err) => fmt::Display::fmt(err, f),
             Error::WindowDropped => write!(f, "The window has already been destroyed."),
+            Error::Unsupported(feature) => {
+                write!(f, "{} is not supported on this platform.", feature)
+            }
             Error::Other(s) => write!(f, "{}", s),
         }
     }
 }
 
-impl std::error::Error for Error {}
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::ConnectionFailed(err) | Error::Other(err) => Some(err.as_ref().as_ref()),
+            Error::Platform(err) => Some(err),
+            _ => None,
+        }
+    }
+}
 
 impl From<anyhow::Error> for Error {
     fn from(src: anyhow::Error) -> Error {
@@ -58,3 +83,20 @@ impl From<platform::Error> for Error {
         Error::Platform(src)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error as _;
+
+    #[test]
+    fn source_chain() {
+        let inner = anyhow::anyhow!("connection refused");
+        let err = Error::ConnectionFailed(Arc::new(inner));
+        assert_eq!(err.source().unwrap().to_string(), "connection refused");
+        assert!(Error::WindowDropped.source().is_none());
+
+        let err = Error::from(anyhow::anyhow!("something else"));
+        assert_eq!(err.source().unwrap().to_string(), "something else");
+    }
+}
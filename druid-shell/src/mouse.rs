@@ -241,7 +241,17 @@ impl MouseButtons {
 
 impl std::fmt::Debug for MouseButtons {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "MouseButtons({:05b})", self.0 >> 1)
+        let buttons = [
+            MouseButton::Left,
+            MouseButton::Right,
+            MouseButton::Middle,
+            MouseButton::X1,
+            MouseButton::X2,
+        ];
+        write!(f, "MouseButtons ")?;
+        f.debug_set()
+            .entries(buttons.iter().filter(|button| self.contains(**button)))
+            .finish()
     }
 }
 
@@ -14,6 +14,8 @@
 
 //! GTK implementation of features at the application scope.
 
+use std::sync::Arc;
+
 use gio::prelude::ApplicationExtManual;
 use gio::{ApplicationExt, ApplicationFlags, Cancellable};
 use gtk::{Application as GtkApplication, GtkApplicationExt};
@@ -29,7 +31,11 @@ pub(crate) struct Application {
 }
 
 impl Application {
-    pub fn new() -> Result<Application, Error> {
+    pub fn new() -> Result<Application, crate::Error> {
+        // Initialize GTK ourselves so that a missing display is reported as an error;
+        // otherwise the application's startup handler aborts the process.
+        gtk::init().map_err(|err| crate::Error::ConnectionFailed(Arc::new(err.into())))?;
+
         // TODO: we should give control over the application ID to the user
         let gtk_app = match GtkApplication::new(
             Some("com.github.linebender.druid"),
@@ -41,7 +47,7 @@ impl Application {
             ApplicationFlags::NON_UNIQUE,
         ) {
             Ok(app) => app,
-            Err(err) => return Err(Error::BoolError(err)),
+            Err(err) => return Err(Error::BoolError(err).into()),
         };
 
         gtk_app.connect_activate(|_app| {
@@ -49,7 +55,7 @@ impl Application {
         });
 
         if let Err(err) = gtk_app.register(None as Option<&Cancellable>) {
-            return Err(Error::Error(err));
+            return Err(Error::Error(err).into());
         }
 
         Ok(Application { gtk_app })
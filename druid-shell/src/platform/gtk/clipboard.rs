@@ -14,10 +14,13 @@
 
 //! Interactions with the system pasteboard on GTK+.
 
+use std::path::PathBuf;
+
 use gdk::Atom;
 use gtk::{TargetEntry, TargetFlags};
 
 use crate::clipboard::{ClipboardFormat, FormatId};
+use crate::platform::shared::uri_list::{self, URI_LIST};
 
 const CLIPBOARD_TARGETS: [&str; 5] = [
     "UTF8_STRING",
@@ -126,6 +129,20 @@ impl Clipboard {
             .map(|data| data.get_data())
     }
 
+    /// Put a list of files on the clipboard, so they can be pasted in a file manager.
+    pub fn put_files(&mut self, paths: &[PathBuf]) {
+        self.put_formats(&[ClipboardFormat::new(
+            URI_LIST,
+            uri_list::encode_uri_list(paths),
+        )]);
+    }
+
+    /// Get the files on the clipboard, such as files copied in a file manager.
+    pub fn get_files(&self) -> Option<Vec<PathBuf>> {
+        let data = self.get_format(URI_LIST)?;
+        Some(uri_list::parse_uri_list(&data))
+    }
+
     pub fn available_type_names(&self) -> Vec<String> {
         let display = gdk::Display::get_default().unwrap();
         let clipboard = gtk::Clipboard::get_default(&display).unwrap();
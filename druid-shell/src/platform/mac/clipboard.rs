@@ -14,6 +14,8 @@
 
 //! Interactions with the system pasteboard on macOS.
 
+use std::path::PathBuf;
+
 use cocoa::appkit::NSPasteboardTypeString;
 use cocoa::base::{id, nil, BOOL, YES};
 use cocoa::foundation::{NSArray, NSInteger, NSUInteger};
@@ -127,6 +129,17 @@ impl Clipboard {
         }
     }
 
+    /// Put a list of files on the clipboard.
+    pub fn put_files(&mut self, _paths: &[PathBuf]) {
+        tracing::warn!("Clipboard::put_files is currently unimplemented for macOS.");
+    }
+
+    /// Get the files on the clipboard, if there are any.
+    pub fn get_files(&self) -> Option<Vec<PathBuf>> {
+        tracing::warn!("Clipboard::get_files is currently unimplemented for macOS.");
+        None
+    }
+
     pub fn available_type_names(&self) -> Vec<String> {
         unsafe {
             let pasteboard: id = msg_send![class!(NSPasteboard), generalPasteboard];
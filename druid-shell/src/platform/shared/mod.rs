@@ -20,3 +20,9 @@ cfg_if::cfg_if! {
         pub use keyboard::*;
     }
 }
+
+cfg_if::cfg_if! {
+    if #[cfg(target_os = "linux")] {
+        pub(crate) mod uri_list;
+    }
+}
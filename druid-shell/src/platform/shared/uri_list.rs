@@ -0,0 +1,202 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Reading and writing `text/uri-list` payloads, as used by file managers
+//! for copying files to the clipboard and for drag and drop.
+//!
+//! The format is described in [RFC 2483]: one URI per line, lines separated
+//! by CRLF, and lines starting with `#` are comments. In practice we also
+//! see bare LF line endings, a trailing NUL, and `file://localhost/` in
+//! place of `file:///`, so the parser accepts all of these.
+//!
+//! A file URI percent-encodes the bytes of the path. On Linux a path is just
+//! bytes, so the decoded path is not required to be UTF-8. A line that is not
+//! itself valid UTF-8 is not a valid URI, though, and is skipped with a
+//! warning, as are URIs with a scheme other than `file` or a host other than
+//! the local one.
+//!
+//! [RFC 2483]: https://tools.ietf.org/html/rfc2483#section-5
+
+use std::ffi::OsStr;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+
+use tracing::warn;
+
+const FILE_SCHEME: &str = "file://";
+
+/// The clipboard and drag and drop format for a list of files.
+pub const URI_LIST: &str = "text/uri-list";
+
+/// Parse a `text/uri-list` payload into the local paths it contains.
+pub fn parse_uri_list(bytes: &[u8]) -> Vec<PathBuf> {
+    bytes
+        .split(|b| *b == b'\n')
+        .filter_map(|line| {
+            let line = match std::str::from_utf8(line) {
+                Ok(line) => line.trim_end_matches(['\r', '\0']).trim(),
+                Err(e) => {
+                    warn!("skipping uri-list line that is not UTF-8: {}", e);
+                    return None;
+                }
+            };
+            if line.is_empty() || line.starts_with('#') {
+                return None;
+            }
+            let path = parse_file_uri(line);
+            if path.is_none() {
+                warn!(
+                    "skipping uri-list entry that is not a local file: {:?}",
+                    line
+                );
+            }
+            path
+        })
+        .collect()
+}
+
+/// Encode `paths` as a `text/uri-list` payload.
+///
+/// Relative paths can't be expressed as file URIs, and are skipped with a warning.
+pub fn encode_uri_list(paths: &[PathBuf]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for path in paths {
+        if !path.is_absolute() {
+            warn!("skipping relative path in uri-list: {:?}", path);
+            continue;
+        }
+        out.extend_from_slice(FILE_SCHEME.as_bytes());
+        percent_encode(path, &mut out);
+        out.extend_from_slice(b"\r\n");
+    }
+    out
+}
+
+fn parse_file_uri(uri: &str) -> Option<PathBuf> {
+    let scheme = uri.get(..FILE_SCHEME.len())?;
+    if !scheme.eq_ignore_ascii_case(FILE_SCHEME) {
+        return None;
+    }
+    let rest = &uri[FILE_SCHEME.len()..];
+    let path_start = rest.find('/')?;
+    let host = &rest[..path_start];
+    if !host.is_empty() && !host.eq_ignore_ascii_case("localhost") {
+        return None;
+    }
+    let path = percent_decode(&rest[path_start..]);
+    Some(PathBuf::from(OsStr::from_bytes(&path)))
+}
+
+/// Decode `%XX` escapes. Malformed escapes are kept as they are.
+fn percent_decode(s: &str) -> Vec<u8> {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let escape = bytes
+                .get(i + 1..i + 3)
+                .and_then(|hex| std::str::from_utf8(hex).ok())
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+            if let Some(byte) = escape {
+                out.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    out
+}
+
+/// Encode everything except unreserved characters and the path separator.
+fn percent_encode(path: &Path, out: &mut Vec<u8>) {
+    for &byte in path.as_os_str().as_bytes() {
+        match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                out.push(byte)
+            }
+            _ => out.extend_from_slice(format!("%{:02X}", byte).as_bytes()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_env_log::test;
+
+    fn paths(paths: &[&str]) -> Vec<PathBuf> {
+        paths.iter().map(PathBuf::from).collect()
+    }
+
+    #[test]
+    fn line_endings_and_comments() {
+        let list = b"# a comment\r\nfile:///tmp/a\r\n\r\nfile:///tmp/b\nfile:///tmp/c\0";
+        assert_eq!(parse_uri_list(list), paths(&["/tmp/a", "/tmp/b", "/tmp/c"]));
+    }
+
+    #[test]
+    fn percent_decoding() {
+        let list = b"file:///home/me/My%20Files/%C3%A9t%C3%A9.txt\r\n";
+        assert_eq!(parse_uri_list(list), paths(&["/home/me/My Files/été.txt"]));
+        // malformed escapes are passed through
+        assert_eq!(parse_uri_list(b"file:///a%2/b%zz%"), paths(&["/a%2/b%zz%"]));
+        // unencoded characters, as some applications produce
+        assert_eq!(parse_uri_list(b"file:///a b"), paths(&["/a b"]));
+    }
+
+    #[test]
+    fn hosts_and_schemes() {
+        let list = b"file://localhost/tmp/a\r\n\
+                     FILE:///tmp/b\r\n\
+                     file://elsewhere/tmp/c\r\n\
+                     https://example.com/d\r\n\
+                     file:relative\r\n";
+        assert_eq!(parse_uri_list(list), paths(&["/tmp/a", "/tmp/b"]));
+    }
+
+    #[test]
+    fn non_utf8() {
+        // a decoded path need not be UTF-8
+        let parsed = parse_uri_list(b"file:///tmp/%FF");
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].as_os_str().as_bytes(), b"/tmp/\xFF");
+        // but a raw non-UTF-8 line is not a URI
+        assert_eq!(
+            parse_uri_list(b"file:///tmp/\xFF\r\nfile:///tmp/ok"),
+            paths(&["/tmp/ok"])
+        );
+    }
+
+    #[test]
+    fn encode() {
+        let list = encode_uri_list(&paths(&["/tmp/My Files/été.txt", "relative", "/a%b#c"]));
+        assert_eq!(
+            list,
+            b"file:///tmp/My%20Files/%C3%A9t%C3%A9.txt\r\nfile:///a%25b%23c\r\n".to_vec()
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let original = vec![
+            PathBuf::from("/tmp/with space"),
+            PathBuf::from("/tmp/100%"),
+            PathBuf::from(OsStr::from_bytes(b"/tmp/\xFE\xFF")),
+        ];
+        assert_eq!(parse_uri_list(&encode_uri_list(&original)), original);
+    }
+}
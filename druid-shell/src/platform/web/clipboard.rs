@@ -14,6 +14,8 @@
 
 //! Interactions with the browser pasteboard.
 
+use std::path::PathBuf;
+
 use crate::clipboard::{ClipboardFormat, FormatId};
 
 /// The browser clipboard.
@@ -53,6 +55,17 @@ impl Clipboard {
         None
     }
 
+    /// Put a list of files on the clipboard.
+    pub fn put_files(&mut self, _paths: &[PathBuf]) {
+        tracing::warn!("unimplemented");
+    }
+
+    /// Get the files on the clipboard, if there are any.
+    pub fn get_files(&self) -> Option<Vec<PathBuf>> {
+        tracing::warn!("unimplemented");
+        None
+    }
+
     pub fn available_type_names(&self) -> Vec<String> {
         tracing::warn!("unimplemented");
         Vec::new()
@@ -645,7 +645,7 @@ impl WindowHandle {
         _ty: FileDialogType,
         _options: FileDialogOptions,
     ) -> Result<OsString, ShellError> {
-        Err(ShellError::Platform(Error::Unimplemented))
+        Err(ShellError::Unsupported("File dialogs"))
     }
 
     /// Get a handle that can be used to schedule an idle task.
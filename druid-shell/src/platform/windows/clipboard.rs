@@ -16,6 +16,7 @@
 
 use std::ffi::CString;
 use std::mem;
+use std::path::PathBuf;
 use std::ptr;
 
 use winapi::shared::minwindef::{FALSE, UINT};
@@ -142,6 +143,17 @@ impl Clipboard {
         .flatten()
     }
 
+    /// Put a list of files on the clipboard.
+    pub fn put_files(&mut self, _paths: &[PathBuf]) {
+        tracing::warn!("Clipboard::put_files is currently unimplemented for Windows.");
+    }
+
+    /// Get the files on the clipboard, if there are any.
+    pub fn get_files(&self) -> Option<Vec<PathBuf>> {
+        tracing::warn!("Clipboard::get_files is currently unimplemented for Windows.");
+        None
+    }
+
     pub fn available_type_names(&self) -> Vec<String> {
         with_clipboard(|| {
             iter_clipboard_types()
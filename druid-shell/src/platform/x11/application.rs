@@ -105,7 +105,12 @@ pub(crate) struct Cursors {
 }
 
 impl Application {
-    pub fn new() -> Result<Application, Error> {
+    pub fn new() -> Result<Application, crate::Error> {
+        let (conn, screen_num) = connect(None)?;
+        Ok(Application::with_connection(conn, screen_num)?)
+    }
+
+    fn with_connection(conn: XCBConnection, screen_num: usize) -> Result<Application, Error> {
         // If we want to support OpenGL, we will need to open a connection with Xlib support (see
         // https://xcb.freedesktop.org/opengl/ for background).  There is some sample code for this
         // in the `rust-xcb` crate (see `connect_with_xlib_display`), although it may be missing
@@ -114,7 +119,6 @@ impl Application {
         // might randomly eat your events / move them to its own event queue.
         //
         // https://github.com/linebender/druid/pull/1025#discussion_r442777892
-        let (conn, screen_num) = XCBConnection::connect(None)?;
         let rdb = Rc::new(ResourceDb::new_from_default(&conn)?);
         let connection = Rc::new(conn);
         let window_id = Application::create_event_window(&connection, screen_num as i32)?;
@@ -664,3 +668,23 @@ fn poll_with_timeout(
 
     Ok(())
 }
+
+/// Connect to the X server, reporting failure as [`crate::Error::ConnectionFailed`].
+///
+/// `display` is a display name like `:0`; `None` means the `DISPLAY` environment variable.
+fn connect(display: Option<&std::ffi::CStr>) -> Result<(XCBConnection, usize), crate::Error> {
+    XCBConnection::connect(display)
+        .map_err(|e| crate::Error::ConnectionFailed(std::sync::Arc::new(e.into())))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn connection_failure_is_classified() {
+        let display = std::ffi::CString::new("not a display").unwrap();
+        let result = connect(Some(&display));
+        assert!(matches!(result, Err(crate::Error::ConnectionFailed(_))));
+    }
+}
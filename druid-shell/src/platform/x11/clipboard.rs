@@ -14,7 +14,10 @@
 
 //! Interactions with the system pasteboard on X11.
 
+use std::path::PathBuf;
+
 use crate::clipboard::{ClipboardFormat, FormatId};
+use crate::platform::shared::uri_list::{self, URI_LIST};
 use tracing::warn;
 
 #[derive(Debug, Clone, Default)]
@@ -49,6 +52,20 @@ impl Clipboard {
         None
     }
 
+    /// Put a list of files on the clipboard, so they can be pasted in a file manager.
+    pub fn put_files(&mut self, paths: &[PathBuf]) {
+        self.put_formats(&[ClipboardFormat::new(
+            URI_LIST,
+            uri_list::encode_uri_list(paths),
+        )]);
+    }
+
+    /// Get the files on the clipboard, such as files copied in a file manager.
+    pub fn get_files(&self) -> Option<Vec<PathBuf>> {
+        let data = self.get_format(URI_LIST)?;
+        Some(uri_list::parse_uri_list(&data))
+    }
+
     pub fn available_type_names(&self) -> Vec<String> {
         // TODO(x11/clipboard): implement Clipboard::available_type_names
         warn!("Clipboard::available_type_names is currently unimplemented for X11 platforms.");
@@ -0,0 +1,253 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Benchmarks of the core passes, on the test harness.
+//!
+//! These run headless, with the mock text engine and a fixed window size, so
+//! the numbers only depend on druid itself and can be compared between runs
+//! on the same machine. The mock engine needs the `testing` feature; run them
+//! with `cargo bench -p druid --features testing`.
+//!
+//! Things these benchmarks show that are worth looking into:
+//!
+//! - A change to one value in `nested_flex` updates all 1000 labels, because
+//!   `Label::dynamic` recomputes its text on every `update` to find out
+//!   whether it changed. Most of the update pass is spent formatting strings
+//!   that are then thrown away.
+//! - `List::paint` visits every row and relies on `WidgetPod::paint` to skip
+//!   the invisible ones, so scrolling a list of 10k rows costs time in
+//!   proportion to the whole list, not to what is on screen.
+//! - A targeted command is routed along the path to its target that the
+//!   window keeps, so `targeted_command` stays cheap. Every container still
+//!   asks each of its children whether they are on that path, and
+//!   `path.contains` is a linear search, so the cost grows with the number
+//!   of siblings along the way rather than with the depth of the target.
+//! - Laying out the list of 10k text boxes for the first time takes tens of
+//!   seconds, and the time grows with the square of the number of rows. Every
+//!   text box invalidates its IME state when it learns its window origin, and
+//!   `Window::ime_invalidation_fn` finds the registration for each of those
+//!   with a linear search through all the text fields in the window. Keeping
+//!   the registrations in a map by widget id would make this linear.
+
+use std::sync::Arc;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use druid::tests::harness::Harness;
+use druid::tests::scroll_mouse;
+use druid::text::MockTextEngine;
+use druid::widget::{prelude::*, Controller, Flex, Label, List, Scroll, TextBox};
+use druid::{Data, Lens, Selector, WidgetExt};
+
+/// The number of widgets in a row, a column of rows, and a column of those.
+const FANOUT: usize = 10;
+
+/// Sets a value in a `Values`, from outside the harness.
+const SET_VALUE: Selector<(usize, u64)> = Selector::new("druid-bench.set-value");
+
+/// A no-op command, sent to a single widget.
+const PING: Selector = Selector::new("druid-bench.ping");
+
+type Values = Arc<Vec<u64>>;
+
+struct SetValue;
+
+impl<W: Widget<Values>> Controller<Values, W> for SetValue {
+    fn event(
+        &mut self,
+        child: &mut W,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut Values,
+        env: &Env,
+    ) {
+        if let Event::Command(cmd) = event {
+            if let Some((index, value)) = cmd.get(SET_VALUE) {
+                Arc::make_mut(data)[*index] = *value;
+                return;
+            }
+        }
+        child.event(ctx, event, data, env)
+    }
+}
+
+/// Three levels of `Flex`, with `FANOUT` children each, and a label for each
+/// value at the bottom.
+fn nested_flex(leaf_id: WidgetId) -> impl Widget<Values> {
+    let mut outer = Flex::column();
+    for i in 0..FANOUT {
+        let mut row = Flex::row();
+        for j in 0..FANOUT {
+            let mut column = Flex::column();
+            for k in 0..FANOUT {
+                let index = (i * FANOUT + j) * FANOUT + k;
+                let label = Label::dynamic(move |values: &Values, _| values[index].to_string());
+                if index == FANOUT * FANOUT * FANOUT - 1 {
+                    column.add_child(label.with_id(leaf_id));
+                } else {
+                    column.add_child(label);
+                }
+            }
+            row.add_child(column);
+        }
+        outer.add_child(row);
+    }
+    outer.controller(SetValue)
+}
+
+fn values() -> Values {
+    Arc::new((0..(FANOUT * FANOUT * FANOUT) as u64).collect())
+}
+
+// The harness is created outside of `bench_function`, whose closure runs once
+// per sample; setting up the bigger trees takes much longer than measuring them.
+
+fn bench_nested_flex(c: &mut Criterion) {
+    let leaf = WidgetId::next();
+    Harness::create_simple(values(), nested_flex(leaf), |harness| {
+        harness.set_text_engine(MockTextEngine);
+        harness.send_initial_events();
+        harness.just_layout();
+        harness.paint();
+        let mut value = 0;
+        c.bench_function(
+            "nested_flex: update, layout and paint after one change",
+            |b| {
+                b.iter(|| {
+                    value += 1;
+                    harness.submit_command(SET_VALUE.with((FANOUT * FANOUT, value)));
+                    harness.just_layout();
+                    harness.paint();
+                })
+            },
+        );
+    });
+}
+
+fn bench_targeted_command(c: &mut Criterion) {
+    let leaf = WidgetId::next();
+    Harness::create_simple(values(), nested_flex(leaf), |harness| {
+        harness.set_text_engine(MockTextEngine);
+        harness.send_initial_events();
+        harness.just_layout();
+        c.bench_function("targeted_command: dispatch to a leaf of nested_flex", |b| {
+            b.iter(|| harness.submit_command(PING.to(leaf)))
+        });
+    });
+}
+
+fn bench_scroll(c: &mut Criterion) {
+    let rows: Arc<Vec<u64>> = Arc::new((0..10_000).collect());
+    let list = List::new(|| Label::dynamic(|row: &u64, _| row.to_string()));
+    Harness::create_simple(rows, Scroll::new(list).vertical(), |harness| {
+        scroll_and_paint(c, "list: scroll and paint 10k labels", harness);
+    });
+
+    let rows: Arc<Vec<String>> = Arc::new((0..10_000).map(|row| row.to_string()).collect());
+    let list = List::new(TextBox::new);
+    Harness::create_simple(rows, Scroll::new(list).vertical(), |harness| {
+        scroll_and_paint(c, "list: scroll and paint 10k text boxes", harness);
+    });
+}
+
+/// Scroll the list up and down by a row, painting after each step.
+fn scroll_and_paint<T: Data>(c: &mut Criterion, name: &str, harness: &mut Harness<T>) {
+    harness.set_text_engine(MockTextEngine);
+    harness.send_initial_events();
+    harness.just_layout();
+    harness.paint();
+    let mut down = true;
+    c.bench_function(name, |b| {
+        b.iter(|| {
+            let delta = if down { 40.0 } else { -40.0 };
+            down = !down;
+            harness.event(Event::Wheel(scroll_mouse((10., 10.), (0., delta))));
+            harness.paint();
+        })
+    });
+}
+
+#[derive(Clone, Data, Lens)]
+struct Contact {
+    name: String,
+    email: String,
+    tags: Arc<Vec<String>>,
+}
+
+#[derive(Clone, Data, Lens)]
+struct Settings {
+    dark_mode: bool,
+    font_size: f64,
+    recent_files: Arc<Vec<String>>,
+}
+
+#[derive(Clone, Data, Lens)]
+struct AppState {
+    settings: Settings,
+    contacts: Arc<Vec<Contact>>,
+    selected: Option<usize>,
+    search: String,
+}
+
+fn app_state() -> AppState {
+    let contacts = (0..1000)
+        .map(|i| Contact {
+            name: format!("Contact {}", i),
+            email: format!("contact{}@example.com", i),
+            tags: Arc::new(vec!["friend".into(), "work".into()]),
+        })
+        .collect();
+    AppState {
+        settings: Settings {
+            dark_mode: true,
+            font_size: 14.0,
+            recent_files: Arc::new((0..20).map(|i| format!("file{}.txt", i)).collect()),
+        },
+        contacts: Arc::new(contacts),
+        selected: Some(3),
+        search: "cont".into(),
+    }
+}
+
+fn bench_data_same(c: &mut Criterion) {
+    let state = app_state();
+    let clone = state.clone();
+    c.bench_function("Data::same: unchanged app state", |b| {
+        b.iter(|| black_box(&state).same(black_box(&clone)))
+    });
+
+    // the common case of an edit: the text changed, but the collections are shared
+    let mut edited = state.clone();
+    edited.search.push('a');
+    c.bench_function("Data::same: app state with an edited string", |b| {
+        b.iter(|| black_box(&state).same(black_box(&edited)))
+    });
+
+    // `Arc`s are compared by pointer, so a copy counts as a change without
+    // looking at the contacts
+    let mut copied = state.clone();
+    copied.contacts = Arc::new(state.contacts.as_ref().clone());
+    c.bench_function("Data::same: app state with copied contacts", |b| {
+        b.iter(|| black_box(&state).same(black_box(&copied)))
+    });
+}
+
+criterion_group!(
+    benches,
+    bench_nested_flex,
+    bench_targeted_command,
+    bench_scroll,
+    bench_data_same
+);
+criterion_main!(benches);
@@ -0,0 +1,127 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! An example of a custom container widget, built on a `ChildSet`.
+//!
+//! The container shows its children as a cascade of cards, each one a bit
+//! further right and down than the one below it. Clicking a card brings it
+//! to the top.
+
+use druid::widget::prelude::*;
+use druid::widget::{Label, WidgetExt};
+use druid::{AppLauncher, ChildSet, Color, LocalizedString, Point, WidgetPod, WindowDesc};
+
+/// How far each card is from the one below it.
+const STEP: f64 = 40.0;
+const CARD_SIZE: Size = Size::new(160.0, 100.0);
+
+struct Cascade {
+    children: ChildSet<u32>,
+}
+
+impl Cascade {
+    fn new() -> Self {
+        Cascade {
+            children: ChildSet::new(),
+        }
+    }
+
+    fn with_child(mut self, child: impl Widget<u32> + 'static) -> Self {
+        self.children.push(WidgetPod::new(child.boxed()));
+        self
+    }
+}
+
+impl Widget<u32> for Cascade {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut u32, env: &Env) {
+        // The children get the event first, from top to bottom.
+        self.children.event(ctx, event, data, env);
+
+        // Then we bring the card under the mouse to the top. This changes the
+        // order in which the cards are painted, so we have to repaint.
+        if let Event::MouseDown(mouse) = event {
+            let clicked = self
+                .children
+                .iter()
+                .rev()
+                .find(|child| child.layout_rect().contains(mouse.pos))
+                .map(|child| child.id());
+            if let Some(id) = clicked {
+                self.children.raise_to_top(id);
+                *data += 1;
+                ctx.request_paint();
+            }
+        }
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &u32, env: &Env) {
+        self.children.lifecycle(ctx, event, data, env)
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &u32, data: &u32, env: &Env) {
+        self.children.update(ctx, data, env)
+    }
+
+    // This is the only part that is specific to this container: every child
+    // is laid out, and then has to be given its origin with `set_origin`
+    // before it can be painted.
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &u32, env: &Env) -> Size {
+        let child_bc = BoxConstraints::tight(CARD_SIZE);
+        let mut size = Size::ZERO;
+        for (i, (child, child_size)) in self
+            .children
+            .layout_each(ctx, &child_bc, data, env)
+            .enumerate()
+        {
+            let origin = Point::new(STEP * i as f64, STEP * i as f64);
+            child.set_origin(ctx, data, env, origin);
+            size.width = size.width.max(origin.x + child_size.width);
+            size.height = size.height.max(origin.y + child_size.height);
+        }
+        bc.constrain(size)
+    }
+
+    // The children are painted from bottom to top.
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &u32, env: &Env) {
+        self.children.paint(ctx, data, env)
+    }
+}
+
+fn card(name: &'static str, color: Color) -> impl Widget<u32> {
+    Label::new(move |clicks: &u32, _: &Env| format!("{}\n{} clicks so far", name, clicks))
+        .with_text_color(Color::BLACK)
+        .padding(10.0)
+        .background(color)
+        .border(Color::grey8(0x33), 1.0)
+        .rounded(6.0)
+}
+
+fn build_root_widget() -> impl Widget<u32> {
+    Cascade::new()
+        .with_child(card("Red", Color::rgb8(0xf4, 0x8f, 0x8f)))
+        .with_child(card("Green", Color::rgb8(0x8f, 0xe0, 0x9a)))
+        .with_child(card("Blue", Color::rgb8(0x8f, 0xb8, 0xf4)))
+        .with_child(card("Yellow", Color::rgb8(0xf4, 0xe0, 0x8f)))
+        .center()
+}
+
+pub fn main() {
+    let main_window = WindowDesc::new(build_root_widget())
+        .title(LocalizedString::new("custom-container-demo-window-title").with_placeholder("Cards"))
+        .window_size((400.0, 400.0));
+    AppLauncher::with_window(main_window)
+        .log_to_console()
+        .launch(0)
+        .expect("launch failed");
+}
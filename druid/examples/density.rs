@@ -0,0 +1,121 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The same form in two windows, one using the comfortable metrics and the
+//! other the dense ones.
+
+use druid::theme::metrics;
+use druid::widget::{
+    Button, Checkbox, CrossAxisAlignment, EnvScope, Flex, Label, List, Scroll, Slider, TextBox,
+};
+use druid::{
+    AppDelegate, AppLauncher, Data, DelegateCtx, Env, Lens, Widget, WidgetExt, WindowDesc, WindowId,
+};
+use std::sync::Arc;
+
+#[derive(Clone, Data, Lens)]
+struct AppState {
+    name: String,
+    email: String,
+    volume: f64,
+    subscribed: bool,
+    tags: Arc<Vec<String>>,
+    /// Use the dense metrics in the first window, and comfortable ones in the second.
+    swapped: bool,
+}
+
+pub fn main() {
+    let comfortable = WindowDesc::new(make_window(false))
+        .title("Comfortable")
+        .window_size((320., 420.))
+        .set_position((100., 100.));
+    let dense = WindowDesc::new(make_window(true))
+        .title("Dense")
+        .window_size((320., 420.))
+        .set_position((440., 100.));
+
+    let state = AppState {
+        name: "Ada".into(),
+        email: "ada@example.com".into(),
+        volume: 0.5,
+        subscribed: true,
+        tags: Arc::new(vec!["alpha".into(), "beta".into(), "gamma".into()]),
+        swapped: false,
+    };
+
+    AppLauncher::with_window(comfortable)
+        .delegate(SecondWindow(Some(dense)))
+        .log_to_console()
+        .launch(state)
+        .expect("launch failed");
+}
+
+/// Opens the dense window once the first one is up.
+struct SecondWindow(Option<WindowDesc<AppState>>);
+
+impl AppDelegate<AppState> for SecondWindow {
+    fn window_added(
+        &mut self,
+        _id: WindowId,
+        _data: &mut AppState,
+        _env: &Env,
+        ctx: &mut DelegateCtx,
+    ) {
+        if let Some(window) = self.0.take() {
+            ctx.new_window(window);
+        }
+    }
+}
+
+/// The form, with the dense metrics when `dense` differs from `swapped`.
+fn make_window(dense: bool) -> impl Widget<AppState> {
+    EnvScope::new(
+        move |env, data: &AppState| {
+            if dense != data.swapped {
+                metrics::DENSE.apply_to(env);
+            }
+        },
+        make_form(),
+    )
+}
+
+fn make_form() -> impl Widget<AppState> {
+    let tags = List::new(|| Label::dynamic(|tag: &String, _| tag.clone())).lens(AppState::tags);
+
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(Label::new("Name"))
+        .with_default_spacer()
+        .with_child(TextBox::new().lens(AppState::name).expand_width())
+        .with_default_spacer()
+        .with_child(Label::new("Email"))
+        .with_default_spacer()
+        .with_child(TextBox::new().lens(AppState::email).expand_width())
+        .with_default_spacer()
+        .with_child(Slider::new().lens(AppState::volume).expand_width())
+        .with_default_spacer()
+        .with_child(Checkbox::new("Subscribe").lens(AppState::subscribed))
+        .with_default_spacer()
+        .with_flex_child(Scroll::new(tags).vertical(), 1.0)
+        .with_default_spacer()
+        .with_child(
+            Flex::row()
+                .with_child(Button::new("Cancel"))
+                .with_default_spacer()
+                .with_child(Button::new("Save"))
+                .with_flex_spacer(1.0)
+                .with_child(Checkbox::new("Swap densities").lens(AppState::swapped)),
+        )
+        .padding(10.)
+}
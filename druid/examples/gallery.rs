@@ -0,0 +1,346 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A kitchen sink of the built-in widgets, for theme work and for checking
+//! for visual regressions.
+//!
+//! Every page is bound to live data, and the toolbar can disable the page
+//! and switch the theme and metrics of the whole app while it is running.
+//! To add a widget to the gallery, add an entry to [`pages`].
+
+use std::sync::Arc;
+
+use druid::theme::{self, metrics};
+use druid::widget::{
+    Button, Checkbox, Controller, CrossAxisAlignment, Flex, Label, List, ListSelection,
+    ProgressBar, RadioGroup, Scroll, SelectableList, Slider, Spinner, Stepper, Switch, Tabs,
+    TextBox, ViewSwitcher,
+};
+use druid::{
+    commands, AppLauncher, Color, Data, Env, EnvUpdate, Event, EventCtx, Lens, Widget, WidgetExt,
+    WidgetId, WindowDesc,
+};
+
+/// The text box on the text page, so that a button can focus it.
+const TEXT_BOX: WidgetId = WidgetId::reserved(1);
+
+/// The number of rows on the stress page.
+const STRESS_ROWS: u32 = 5000;
+
+#[derive(Clone, Copy, Data, PartialEq)]
+enum Flavor {
+    Vanilla,
+    Chocolate,
+    Strawberry,
+}
+
+#[derive(Clone, Data, Lens)]
+struct AppState {
+    /// The page names, for the sidebar.
+    pages: Arc<Vec<String>>,
+    selection: ListSelection,
+    disabled: bool,
+    light: bool,
+    dense: bool,
+    clicks: u32,
+    checked: bool,
+    flavor: Flavor,
+    value: f64,
+    text: String,
+    notes: String,
+    rows: Arc<Vec<u32>>,
+}
+
+type PageBuilder = fn() -> Box<dyn Widget<AppState>>;
+
+/// The pages of the gallery, in sidebar order.
+fn pages() -> Vec<(&'static str, PageBuilder)> {
+    vec![
+        ("Buttons", buttons_page),
+        ("Toggles", toggles_page),
+        ("Values", values_page),
+        ("Text", text_page),
+        ("Tabs", tabs_page),
+        ("Stress", stress_page),
+    ]
+}
+
+pub fn main() {
+    let window = WindowDesc::new(make_ui())
+        .title("Widget gallery")
+        .window_size((720., 480.));
+
+    let mut selection = ListSelection::new();
+    selection.select(0);
+    let state = AppState {
+        pages: Arc::new(pages().iter().map(|(name, _)| name.to_string()).collect()),
+        selection,
+        disabled: false,
+        light: false,
+        dense: false,
+        clicks: 0,
+        checked: true,
+        flavor: Flavor::Vanilla,
+        value: 0.5,
+        text: String::new(),
+        notes: "Some notes\nover two lines".into(),
+        rows: Arc::new((0..STRESS_ROWS).collect()),
+    };
+
+    AppLauncher::with_window(window)
+        .log_to_console()
+        .launch(state)
+        .expect("launch failed");
+}
+
+fn make_ui() -> impl Widget<AppState> {
+    let sidebar =
+        SelectableList::new(|| Label::dynamic(|name: &String, _| name.clone()).padding(4.))
+            .lens((AppState::pages, AppState::selection))
+            .scroll()
+            .vertical()
+            .fix_width(140.);
+
+    let page = ViewSwitcher::new(
+        |data: &AppState, _| data.selection.selected().next().unwrap_or(0),
+        |&index, _, _| {
+            let builder = pages()[index].1;
+            builder()
+        },
+    )
+    .disabled_if(|data, _| data.disabled)
+    .padding(10.);
+
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(toolbar())
+        .with_flex_child(
+            Flex::row()
+                .cross_axis_alignment(CrossAxisAlignment::Start)
+                .with_child(sidebar)
+                .with_flex_child(page.expand(), 1.0)
+                .expand(),
+            1.0,
+        )
+}
+
+fn toolbar() -> impl Widget<AppState> {
+    Flex::row()
+        .with_child(Checkbox::new("Disabled").lens(AppState::disabled))
+        .with_default_spacer()
+        .with_child(Checkbox::new("Light theme").lens(AppState::light))
+        .with_default_spacer()
+        .with_child(Checkbox::new("Dense").lens(AppState::dense))
+        .padding(10.)
+        .controller(ThemeSwitch)
+}
+
+/// Updates the app's env when the theme toggles change.
+struct ThemeSwitch;
+
+impl<W: Widget<AppState>> Controller<AppState, W> for ThemeSwitch {
+    fn event(
+        &mut self,
+        child: &mut W,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut AppState,
+        env: &Env,
+    ) {
+        let before = (data.light, data.dense);
+        child.event(ctx, event, data, env);
+        let (light, dense) = (data.light, data.dense);
+        if before != (light, dense) {
+            ctx.submit_command(commands::UPDATE_ENV.with(EnvUpdate::new(move |env| {
+                if light {
+                    light_colors(env)
+                } else {
+                    dark_colors(env)
+                }
+                if dense {
+                    metrics::DENSE.apply_to(env)
+                } else {
+                    metrics::COMFORTABLE.apply_to(env)
+                }
+            })));
+        }
+    }
+}
+
+/// The default colors, to switch back to from the light ones.
+fn dark_colors(env: &mut Env) {
+    env.set(
+        theme::WINDOW_BACKGROUND_COLOR,
+        Color::rgb8(0x29, 0x29, 0x29),
+    );
+    env.set(theme::TEXT_COLOR, Color::rgb8(0xf0, 0xf0, 0xea));
+    env.set(theme::DISABLED_TEXT_COLOR, Color::rgb8(0xa0, 0xa0, 0x9a));
+    env.set(theme::BACKGROUND_LIGHT, Color::rgb8(0x3a, 0x3a, 0x3a));
+    env.set(theme::BACKGROUND_DARK, Color::rgb8(0x31, 0x31, 0x31));
+    env.set(theme::FOREGROUND_LIGHT, Color::rgb8(0xf9, 0xf9, 0xf9));
+    env.set(theme::FOREGROUND_DARK, Color::rgb8(0xbf, 0xbf, 0xbf));
+    env.set(theme::BUTTON_DARK, Color::BLACK);
+    env.set(theme::BUTTON_LIGHT, Color::rgb8(0x21, 0x21, 0x21));
+    env.set(theme::DISABLED_BUTTON_DARK, Color::grey8(0x28));
+    env.set(theme::DISABLED_BUTTON_LIGHT, Color::grey8(0x38));
+    env.set(theme::BORDER_DARK, Color::rgb8(0x3a, 0x3a, 0x3a));
+    env.set(theme::BORDER_LIGHT, Color::rgb8(0xa1, 0xa1, 0xa1));
+    env.set(theme::CURSOR_COLOR, Color::WHITE);
+}
+
+fn light_colors(env: &mut Env) {
+    env.set(
+        theme::WINDOW_BACKGROUND_COLOR,
+        Color::rgb8(0xf4, 0xf4, 0xf2),
+    );
+    env.set(theme::TEXT_COLOR, Color::rgb8(0x1e, 0x1e, 0x1e));
+    env.set(theme::DISABLED_TEXT_COLOR, Color::rgb8(0x90, 0x90, 0x8a));
+    env.set(theme::BACKGROUND_LIGHT, Color::rgb8(0xff, 0xff, 0xff));
+    env.set(theme::BACKGROUND_DARK, Color::rgb8(0xe8, 0xe8, 0xe8));
+    env.set(theme::FOREGROUND_LIGHT, Color::rgb8(0x30, 0x30, 0x30));
+    env.set(theme::FOREGROUND_DARK, Color::rgb8(0x60, 0x60, 0x60));
+    env.set(theme::BUTTON_DARK, Color::rgb8(0xd8, 0xd8, 0xd8));
+    env.set(theme::BUTTON_LIGHT, Color::rgb8(0xf8, 0xf8, 0xf8));
+    env.set(theme::DISABLED_BUTTON_DARK, Color::grey8(0xe0));
+    env.set(theme::DISABLED_BUTTON_LIGHT, Color::grey8(0xec));
+    env.set(theme::BORDER_DARK, Color::rgb8(0xc0, 0xc0, 0xc0));
+    env.set(theme::BORDER_LIGHT, Color::rgb8(0x80, 0x80, 0x80));
+    env.set(theme::CURSOR_COLOR, Color::BLACK);
+}
+
+/// A titled column of examples.
+fn page(title: &str, content: Flex<AppState>) -> Box<dyn Widget<AppState>> {
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(Label::new(title).with_text_size(20.))
+        .with_default_spacer()
+        .with_flex_child(content, 1.0)
+        .boxed()
+}
+
+fn buttons_page() -> Box<dyn Widget<AppState>> {
+    page(
+        "Buttons",
+        Flex::column()
+            .cross_axis_alignment(CrossAxisAlignment::Start)
+            .with_child(
+                Button::new("Click me").on_click(|_, data: &mut AppState, _| data.clicks += 1),
+            )
+            .with_default_spacer()
+            .with_child(Label::dynamic(|data: &AppState, _| {
+                format!("Clicked {} times", data.clicks)
+            }))
+            .with_default_spacer()
+            .with_child(Button::new("Reset").on_click(|_, data: &mut AppState, _| data.clicks = 0)),
+    )
+}
+
+fn toggles_page() -> Box<dyn Widget<AppState>> {
+    page(
+        "Toggles",
+        Flex::column()
+            .cross_axis_alignment(CrossAxisAlignment::Start)
+            .with_child(Checkbox::new("Checkbox").lens(AppState::checked))
+            .with_default_spacer()
+            .with_child(Switch::new().lens(AppState::checked))
+            .with_default_spacer()
+            .with_child(
+                RadioGroup::new(vec![
+                    ("Vanilla", Flavor::Vanilla),
+                    ("Chocolate", Flavor::Chocolate),
+                    ("Strawberry", Flavor::Strawberry),
+                ])
+                .lens(AppState::flavor),
+            ),
+    )
+}
+
+fn values_page() -> Box<dyn Widget<AppState>> {
+    page(
+        "Values",
+        Flex::column()
+            .cross_axis_alignment(CrossAxisAlignment::Start)
+            .with_child(Slider::new().lens(AppState::value).fix_width(200.))
+            .with_default_spacer()
+            .with_child(
+                Flex::row()
+                    .with_child(Stepper::new().with_step(0.1).lens(AppState::value))
+                    .with_default_spacer()
+                    .with_child(Label::dynamic(|data: &AppState, _| {
+                        format!("{:.2}", data.value)
+                    })),
+            )
+            .with_default_spacer()
+            .with_child(ProgressBar::new().lens(AppState::value).fix_width(200.))
+            .with_default_spacer()
+            .with_child(Spinner::new()),
+    )
+}
+
+fn text_page() -> Box<dyn Widget<AppState>> {
+    page(
+        "Text",
+        Flex::column()
+            .cross_axis_alignment(CrossAxisAlignment::Start)
+            .with_child(
+                Flex::row()
+                    .with_child(
+                        TextBox::new()
+                            .with_placeholder("Type here")
+                            .lens(AppState::text)
+                            .fix_width(200.)
+                            .with_id(TEXT_BOX),
+                    )
+                    .with_default_spacer()
+                    .with_child(Button::new("Focus").on_click(|ctx, _, _| ctx.set_focus(TEXT_BOX))),
+            )
+            .with_default_spacer()
+            .with_child(
+                TextBox::multiline()
+                    .lens(AppState::notes)
+                    .fix_size(200., 80.),
+            ),
+    )
+}
+
+fn tabs_page() -> Box<dyn Widget<AppState>> {
+    page(
+        "Tabs",
+        Flex::column().with_flex_child(
+            Tabs::new()
+                .with_tab("Checkbox", Checkbox::new("Checked").lens(AppState::checked))
+                .with_tab("Slider", Slider::new().lens(AppState::value))
+                .with_tab("Text", TextBox::new().lens(AppState::text))
+                .expand(),
+            1.0,
+        ),
+    )
+}
+
+fn stress_page() -> Box<dyn Widget<AppState>> {
+    let rows = List::new(|| {
+        Label::dynamic(|row: &u32, _| format!("Row {}", row))
+            .padding(2.)
+            .expand_width()
+    })
+    .lens(AppState::rows);
+
+    page(
+        "Stress",
+        Flex::column()
+            .with_child(Label::new(format!("{} rows", STRESS_ROWS)))
+            .with_default_spacer()
+            .with_flex_child(Scroll::new(rows).vertical().expand(), 1.0),
+    )
+}
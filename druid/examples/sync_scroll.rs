@@ -0,0 +1,101 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A spreadsheet-like table, whose header row and header column stay in
+//! place while the body scrolls, using a `ScrollGroup`.
+
+use druid::widget::{Container, Flex, Label, Scroll, ScrollGroup, SizedBox, SyncAxes, WidgetExt};
+use druid::{AppLauncher, Color, LocalizedString, UnitPoint, Widget, WindowDesc};
+
+const ROWS: usize = 100;
+const COLUMNS: usize = 26;
+const CELL_WIDTH: f64 = 80.0;
+const CELL_HEIGHT: f64 = 24.0;
+const HEADER_WIDTH: f64 = 40.0;
+
+const HEADER_COLOR: Color = Color::grey8(0x30);
+const BORDER_COLOR: Color = Color::grey8(0x50);
+
+fn cell(text: String, width: f64) -> impl Widget<()> {
+    Label::new(text)
+        .align_vertical(UnitPoint::CENTER)
+        .padding((4.0, 0.0))
+        .fix_size(width, CELL_HEIGHT)
+        .border(BORDER_COLOR, 0.5)
+}
+
+fn column_name(column: usize) -> String {
+    ((b'A' + column as u8) as char).to_string()
+}
+
+fn build_app() -> impl Widget<()> {
+    let group = ScrollGroup::next();
+
+    let mut header_row = Flex::row();
+    for column in 0..COLUMNS {
+        header_row.add_child(cell(column_name(column), CELL_WIDTH));
+    }
+    let header_row = Scroll::new(header_row)
+        .horizontal()
+        .disable_scrollbars()
+        .sync_scroll(group, SyncAxes::Horizontal);
+
+    let mut header_column = Flex::column();
+    for row in 0..ROWS {
+        header_column.add_child(cell((row + 1).to_string(), HEADER_WIDTH));
+    }
+    let header_column = Scroll::new(header_column)
+        .vertical()
+        .disable_scrollbars()
+        .sync_scroll(group, SyncAxes::Vertical);
+
+    let mut body = Flex::column();
+    for row in 0..ROWS {
+        let mut cells = Flex::row();
+        for column in 0..COLUMNS {
+            cells.add_child(cell(
+                format!("{}{}", column_name(column), row + 1),
+                CELL_WIDTH,
+            ));
+        }
+        body.add_child(cells);
+    }
+    let body = Scroll::new(body).sync_scroll(group, SyncAxes::Both);
+
+    let corner = SizedBox::empty().fix_size(HEADER_WIDTH, CELL_HEIGHT);
+    Flex::column()
+        .with_child(
+            Container::new(
+                Flex::row()
+                    .with_child(corner)
+                    .with_flex_child(header_row, 1.0),
+            )
+            .background(HEADER_COLOR),
+        )
+        .with_flex_child(
+            Flex::row()
+                .with_child(Container::new(header_column).background(HEADER_COLOR))
+                .with_flex_child(body, 1.0),
+            1.0,
+        )
+}
+
+pub fn main() {
+    let main_window = WindowDesc::new(build_app())
+        .title(LocalizedString::new("sync-scroll-demo-window-title").with_placeholder("Table"));
+    AppLauncher::with_window(main_window)
+        .log_to_console()
+        .launch(())
+        .expect("launch failed");
+}
@@ -57,11 +57,14 @@ macro_rules! impl_example {
 impl_example!(anim);
 impl_example!(calc);
 impl_example!(cursor);
+impl_example!(custom_container);
 impl_example!(custom_widget);
+impl_example!(density);
 impl_example!(disabled);
 impl_example!(either);
 impl_example!(event_viewer);
 impl_example!(flex);
+impl_example!(gallery);
 impl_example!(game_of_life);
 impl_example!(hello);
 impl_example!(identity);
@@ -78,6 +81,7 @@ impl_example!(scroll);
 impl_example!(split_demo);
 impl_example!(styled_text.unwrap());
 impl_example!(switches);
+impl_example!(sync_scroll);
 impl_example!(timer);
 impl_example!(tabs);
 impl_example!(textbox);
@@ -39,3 +39,27 @@ common-menu-copy = Kopieren
 common-menu-paste = Einfügen
 common-menu-undo = Rückgängig
 common-menu-redo = Wiederherstellen
+
+# calendar and date picker
+calendar-month-title = { $month } { $year }
+calendar-january = Januar
+calendar-february = Februar
+calendar-march = März
+calendar-april = April
+calendar-may = Mai
+calendar-june = Juni
+calendar-july = Juli
+calendar-august = August
+calendar-september = September
+calendar-october = Oktober
+calendar-november = November
+calendar-december = Dezember
+calendar-monday-short = Mo
+calendar-tuesday-short = Di
+calendar-wednesday-short = Mi
+calendar-thursday-short = Do
+calendar-friday-short = Fr
+calendar-saturday-short = Sa
+calendar-sunday-short = So
+calendar-first-weekday = Monday
+date-picker-format = %d.%m.%Y
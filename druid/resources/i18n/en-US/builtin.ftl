@@ -39,3 +39,29 @@ common-menu-copy = Copy
 common-menu-paste = Paste
 common-menu-undo = Undo
 common-menu-redo = Redo
+
+# calendar and date picker
+calendar-month-title = { $month } { $year }
+calendar-january = January
+calendar-february = February
+calendar-march = March
+calendar-april = April
+calendar-may = May
+calendar-june = June
+calendar-july = July
+calendar-august = August
+calendar-september = September
+calendar-october = October
+calendar-november = November
+calendar-december = December
+calendar-monday-short = Mo
+calendar-tuesday-short = Tu
+calendar-wednesday-short = We
+calendar-thursday-short = Th
+calendar-friday-short = Fr
+calendar-saturday-short = Sa
+calendar-sunday-short = Su
+# the English name of the first day of the week
+calendar-first-weekday = Sunday
+# a chrono format string, see https://docs.rs/chrono/0.4/chrono/format/strftime/
+date-picker-format = %m/%d/%Y
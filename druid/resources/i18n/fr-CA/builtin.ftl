@@ -39,3 +39,27 @@ common-menu-copy = Copier
 common-menu-paste = Coller
 common-menu-undo = Annuler
 common-menu-redo = Rétablir
+
+# calendar and date picker
+calendar-month-title = { $month } { $year }
+calendar-january = janvier
+calendar-february = février
+calendar-march = mars
+calendar-april = avril
+calendar-may = mai
+calendar-june = juin
+calendar-july = juillet
+calendar-august = août
+calendar-september = septembre
+calendar-october = octobre
+calendar-november = novembre
+calendar-december = décembre
+calendar-monday-short = lu
+calendar-tuesday-short = ma
+calendar-wednesday-short = me
+calendar-thursday-short = je
+calendar-friday-short = ve
+calendar-saturday-short = sa
+calendar-sunday-short = di
+calendar-first-weekday = Sunday
+date-picker-format = %Y-%m-%d
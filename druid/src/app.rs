@@ -14,20 +14,35 @@
 
 //! Window building and app lifecycle.
 
+use std::sync::Arc;
+
+use crate::asset_cache::AssetCache;
+use crate::commands as sys_cmd;
 use crate::ext_event::{ExtEventHost, ExtEventSink};
 use crate::kurbo::{Point, Size};
 use crate::menu::MenuManager;
 use crate::shell::{Application, Error as PlatformError, WindowBuilder, WindowHandle, WindowLevel};
+use crate::single_instance::{self, Instance};
+use crate::text::{shared_engine, SharedTextEngine, TextEngine};
 use crate::widget::LabelText;
 use crate::win_handler::{AppHandler, AppState};
 use crate::window::WindowId;
-use crate::{AppDelegate, Data, Env, LocalizedString, Menu, Widget};
+use crate::{AppDelegate, Data, Env, ImageBuf, LocalizedString, Menu, Target, UnitPoint, Widget};
 
 use druid_shell::WindowState;
 
 /// A function that modifies the initial environment.
 type EnvSetupFn<T> = dyn FnOnce(&mut Env, &T);
 
+/// Logged alongside a [`PlatformError::ConnectionFailed`] to point at the likely cause.
+#[cfg(all(target_os = "linux", feature = "x11"))]
+const CONNECTION_FAILED_HINT: &str = "Is an X server running, and is DISPLAY set correctly?";
+#[cfg(all(target_os = "linux", not(feature = "x11")))]
+const CONNECTION_FAILED_HINT: &str =
+    "Is a display server running, and is WAYLAND_DISPLAY or DISPLAY set correctly?";
+#[cfg(not(target_os = "linux"))]
+const CONNECTION_FAILED_HINT: &str = "Is this process running in a graphical session?";
+
 /// Handles initial setup of an application, and starts the runloop.
 pub struct AppLauncher<T> {
     windows: Vec<WindowDesc<T>>,
@@ -35,6 +50,8 @@ pub struct AppLauncher<T> {
     l10n_resources: Option<(Vec<String>, String)>,
     delegate: Option<Box<dyn AppDelegate<T>>>,
     ext_event_host: ExtEventHost,
+    single_instance: Option<String>,
+    asset_cache: AssetCache,
 }
 
 /// Defines how a windows size should be determined
@@ -84,7 +101,10 @@ pub struct PendingWindow<T> {
     pub(crate) transparent: bool,
     pub(crate) menu: Option<MenuManager<T>>,
     pub(crate) size_policy: WindowSizePolicy, // This is copied over from the WindowConfig
-                                              // when the native window is constructed.
+    // when the native window is constructed.
+    pub(crate) toast_position: UnitPoint,
+    pub(crate) max_visible_toasts: usize,
+    pub(crate) text_engine: Option<SharedTextEngine>,
 }
 
 impl<T: Data> PendingWindow<T> {
@@ -100,6 +120,9 @@ impl<T: Data> PendingWindow<T> {
             menu: MenuManager::platform_default(),
             transparent: false,
             size_policy: WindowSizePolicy::User,
+            toast_position: UnitPoint::BOTTOM_RIGHT,
+            max_visible_toasts: 3,
+            text_engine: None,
         }
     }
 
@@ -132,6 +155,36 @@ impl<T: Data> PendingWindow<T> {
         self.menu = Some(MenuManager::new(menu));
         self
     }
+
+    /// Set where in the window [toasts] are shown.
+    ///
+    /// The default is [`UnitPoint::BOTTOM_RIGHT`].
+    ///
+    /// [toasts]: crate::commands::SHOW_TOAST
+    pub fn toast_position(mut self, position: UnitPoint) -> Self {
+        self.toast_position = position;
+        self
+    }
+
+    /// Set how many [toasts] the window shows at once. Toasts beyond this
+    /// number wait until one of the others goes away.
+    ///
+    /// The default is 3.
+    ///
+    /// [toasts]: crate::commands::SHOW_TOAST
+    pub fn max_visible_toasts(mut self, max: usize) -> Self {
+        self.max_visible_toasts = max;
+        self
+    }
+
+    /// Build this window's [`TextLayout`]s with `engine` instead of the
+    /// platform's text system.
+    ///
+    /// [`TextLayout`]: crate::TextLayout
+    pub fn text_engine(mut self, engine: impl TextEngine) -> Self {
+        self.text_engine = Some(shared_engine(engine));
+        self
+    }
 }
 
 impl<T: Data> AppLauncher<T> {
@@ -143,6 +196,8 @@ impl<T: Data> AppLauncher<T> {
             l10n_resources: None,
             delegate: None,
             ext_event_host: ExtEventHost::new(),
+            single_instance: None,
+            asset_cache: AssetCache::default(),
         }
     }
 
@@ -163,6 +218,56 @@ impl<T: Data> AppLauncher<T> {
         self
     }
 
+    /// Make this a single instance app, identified by `id`, such as
+    /// `"org.example.MyApp"`.
+    ///
+    /// If the app is already running when it is launched, the arguments of
+    /// the new launch are sent to the running instance as an
+    /// [`ACTIVATE_EXISTING`] command, and [`launch`] returns without opening
+    /// any windows. If that isn't possible, such as on platforms where this
+    /// is not supported yet, the app is launched as usual, with a warning.
+    ///
+    /// This is currently supported on unix platforms, where the instances talk
+    /// through a socket in `XDG_RUNTIME_DIR`, or in a directory in the
+    /// temporary directory that only the current user can access. This is
+    /// used instead of D-Bus name ownership on Linux, so that no D-Bus
+    /// dependency is needed.
+    ///
+    /// [`ACTIVATE_EXISTING`]: crate::commands::ACTIVATE_EXISTING
+    /// [`launch`]: AppLauncher::launch
+    pub fn single_instance(mut self, id: impl Into<String>) -> Self {
+        self.single_instance = Some(id.into());
+        self
+    }
+
+    /// Set the limit on the memory used by decoded images in the asset
+    /// cache, in bytes. The default is 64 MiB.
+    ///
+    /// When the limit is exceeded, the least recently used images are dropped
+    /// from the cache; they are loaded again the next time they are needed.
+    ///
+    /// See [`EventCtx::get_image`].
+    ///
+    /// [`EventCtx::get_image`]: crate::EventCtx::get_image
+    pub fn asset_cache_capacity(mut self, bytes: usize) -> Self {
+        self.asset_cache.set_capacity(bytes);
+        self
+    }
+
+    /// Set the function that loads [`AssetKey::Custom`] images for the asset
+    /// cache, such as by downloading them.
+    ///
+    /// The function is called on a background thread, with the name in the key.
+    ///
+    /// [`AssetKey::Custom`]: crate::AssetKey::Custom
+    pub fn asset_loader(
+        mut self,
+        loader: impl Fn(&str) -> Result<ImageBuf, String> + Send + Sync + 'static,
+    ) -> Self {
+        self.asset_cache.set_loader(Arc::new(loader));
+        self
+    }
+
     /// Initialize a minimal logger with DEBUG max level for printing logs out to stderr.
     ///
     /// This is meant for use during development only.
@@ -237,7 +342,40 @@ impl<T: Data> AppLauncher<T> {
     /// Returns an error if a window cannot be instantiated. This is usually
     /// a fatal error.
     pub fn launch(mut self, data: T) -> Result<(), PlatformError> {
-        let app = Application::new()?;
+        // kept until the app quits, so that later launches can find us
+        let _instance = match self.single_instance.take() {
+            Some(id) => {
+                let sink = self.ext_event_host.make_sink();
+                let args: Vec<String> = std::env::args().skip(1).collect();
+                let on_activate = move |args| {
+                    if sink
+                        .submit_command(sys_cmd::ACTIVATE_EXISTING, Box::new(args), Target::Global)
+                        .is_err()
+                    {
+                        tracing::warn!("failed to deliver the arguments of a new launch");
+                    }
+                };
+                match single_instance::acquire(&id, &args, on_activate) {
+                    Ok(Instance::Forwarded) => {
+                        tracing::info!("{} is already running, forwarded the launch to it", id);
+                        return Ok(());
+                    }
+                    Ok(instance) => Some(instance),
+                    Err(err) => {
+                        tracing::warn!("can't check for a running instance of {}: {}", id, err);
+                        None
+                    }
+                }
+            }
+            None => None,
+        };
+
+        let app = Application::new().map_err(|err| {
+            if let PlatformError::ConnectionFailed(_) = err {
+                tracing::error!("{}\n{}", err, CONNECTION_FAILED_HINT);
+            }
+            err
+        })?;
 
         let mut env = self
             .l10n_resources
@@ -254,6 +392,7 @@ impl<T: Data> AppLauncher<T> {
             env,
             self.delegate.take(),
             self.ext_event_host,
+            self.asset_cache,
         );
 
         for desc in self.windows {
@@ -582,6 +721,36 @@ impl<T: Data> WindowDesc<T> {
         self
     }
 
+    /// Set where in the window [toasts] are shown.
+    ///
+    /// The default is [`UnitPoint::BOTTOM_RIGHT`].
+    ///
+    /// [toasts]: crate::commands::SHOW_TOAST
+    pub fn toast_position(mut self, position: UnitPoint) -> Self {
+        self.pending = self.pending.toast_position(position);
+        self
+    }
+
+    /// Set how many [toasts] the window shows at once. Toasts beyond this
+    /// number wait until one of the others goes away.
+    ///
+    /// The default is 3.
+    ///
+    /// [toasts]: crate::commands::SHOW_TOAST
+    pub fn max_visible_toasts(mut self, max: usize) -> Self {
+        self.pending = self.pending.max_visible_toasts(max);
+        self
+    }
+
+    /// Build this window's [`TextLayout`]s with `engine` instead of the
+    /// platform's text system.
+    ///
+    /// [`TextLayout`]: crate::TextLayout
+    pub fn text_engine(mut self, engine: impl TextEngine) -> Self {
+        self.pending = self.pending.text_engine(engine);
+        self
+    }
+
     /// Attempt to create a platform window from this `WindowDesc`.
     pub(crate) fn build_native(
         self,
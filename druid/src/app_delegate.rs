@@ -18,7 +18,7 @@ use std::any::{Any, TypeId};
 
 use crate::{
     commands, core::CommandQueue, ext_event::ExtEventHost, Command, Data, Env, Event, ExtEventSink,
-    Handled, SingleUse, Target, WindowDesc, WindowId,
+    Handled, QueryRequest, SingleUse, Target, WindowDesc, WindowId,
 };
 
 /// A context passed in to [`AppDelegate`] functions.
@@ -133,4 +133,46 @@ pub trait AppDelegate<T: Data> {
     /// The handler for window deletion events.
     /// This function is called after a window has been removed.
     fn window_removed(&mut self, id: WindowId, data: &mut T, env: &Env, ctx: &mut DelegateCtx) {}
+
+    /// The handler for synchronous queries from widgets.
+    ///
+    /// This is called when a widget calls [`EventCtx::query`], before that call
+    /// returns. Answer the query with [`QueryRequest::respond`]; if it is left
+    /// unanswered, the widget gets `None`.
+    ///
+    /// The application data is not available here, because the widget asking
+    /// is in the middle of handling an event and holds it. Any commands
+    /// submitted to the `ctx` are only run after that event has been handled.
+    ///
+    /// This blocks the UI thread, so it should be a cheap lookup.
+    ///
+    /// [`EventCtx::query`]: struct.EventCtx.html#method.query
+    /// [`QueryRequest::respond`]: struct.QueryRequest.html#method.respond
+    fn query(&mut self, ctx: &mut DelegateCtx, request: &mut QueryRequest, env: &Env) {}
+}
+
+/// Something that can answer queries from widgets.
+///
+/// This erases the app data type of the delegate, so that it can be carried
+/// by the widget contexts.
+pub(crate) trait QueryHandler {
+    fn query(&mut self, queue: &mut CommandQueue, request: &mut QueryRequest);
+}
+
+/// Answers queries with an [`AppDelegate`].
+pub(crate) struct DelegateQueries<'a, T> {
+    pub(crate) delegate: &'a mut dyn AppDelegate<T>,
+    pub(crate) ext_event_host: &'a ExtEventHost,
+    pub(crate) env: &'a Env,
+}
+
+impl<T: Data> QueryHandler for DelegateQueries<'_, T> {
+    fn query(&mut self, queue: &mut CommandQueue, request: &mut QueryRequest) {
+        let mut ctx = DelegateCtx {
+            command_queue: queue,
+            ext_event_host: self.ext_event_host,
+            app_data_type: TypeId::of::<T>(),
+        };
+        self.delegate.query(&mut ctx, request, self.env);
+    }
 }
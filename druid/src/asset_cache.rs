@@ -0,0 +1,435 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A cache of decoded images, shared by all the windows of an app.
+//!
+//! Images are loaded and decoded on a background thread. Widgets ask for an
+//! image with [`get_image`], and the widgets that asked for an image while it
+//! was loading are sent [`ASSET_LOADED`] once it is ready.
+//!
+//! [`get_image`]: crate::EventCtx::get_image
+//! [`ASSET_LOADED`]: crate::commands::ASSET_LOADED
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex, Weak};
+use std::thread;
+
+use tracing::warn;
+
+use crate::piet::ImageBuf;
+use crate::{commands, ExtEventSink, Target, WidgetId};
+
+/// The default limit on the size of the decoded images in the cache, 64 MiB.
+const DEFAULT_CAPACITY: usize = 64 * 1024 * 1024;
+
+/// A function that loads the image for an [`AssetKey::Custom`] key.
+pub(crate) type AssetLoader = dyn Fn(&str) -> Result<ImageBuf, String> + Send + Sync;
+
+/// Identifies an image in the asset cache.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum AssetKey {
+    /// An image file, decoded with the [`image` crate].
+    ///
+    /// This needs the `image` feature; without it, loading fails.
+    ///
+    /// [`image` crate]: https://crates.io/crates/image
+    Path(PathBuf),
+    /// An image loaded by the function passed to [`AppLauncher::asset_loader`],
+    /// such as one downloaded from the network.
+    ///
+    /// [`AppLauncher::asset_loader`]: crate::AppLauncher::asset_loader
+    Custom(String),
+}
+
+/// The state of an image in the asset cache.
+#[derive(Debug, Clone)]
+pub enum AssetState {
+    /// The image is ready.
+    Loaded(ImageBuf),
+    /// The image is being loaded; the widget that asked for it will receive
+    /// [`ASSET_LOADED`] when it is done.
+    ///
+    /// [`ASSET_LOADED`]: crate::commands::ASSET_LOADED
+    Loading,
+    /// The image could not be loaded, for the given reason.
+    Failed(String),
+}
+
+/// A handle to the asset cache; clones share the same cache.
+#[derive(Clone)]
+pub(crate) struct AssetCache {
+    inner: Arc<Mutex<Inner>>,
+    loader: Option<Arc<AssetLoader>>,
+}
+
+struct Inner {
+    entries: HashMap<AssetKey, Entry>,
+    /// The size of the decoded images that we try to stay under.
+    capacity: usize,
+    /// The size of the decoded images in the cache.
+    used: usize,
+    /// Counts cache accesses, to find the least recently used image.
+    clock: u64,
+    /// Sends keys to the thread that loads them, once it is running.
+    worker: Option<Sender<(AssetKey, ExtEventSink)>>,
+}
+
+enum Entry {
+    Loading {
+        waiters: Vec<WidgetId>,
+    },
+    Loaded {
+        image: ImageBuf,
+        bytes: usize,
+        last_used: u64,
+    },
+    Failed(String),
+}
+
+impl Default for AssetCache {
+    fn default() -> Self {
+        AssetCache {
+            inner: Arc::new(Mutex::new(Inner {
+                entries: HashMap::new(),
+                capacity: DEFAULT_CAPACITY,
+                used: 0,
+                clock: 0,
+                worker: None,
+            })),
+            loader: None,
+        }
+    }
+}
+
+impl AssetCache {
+    /// Set the limit on the size of the decoded images in the cache, in bytes.
+    pub(crate) fn set_capacity(&mut self, capacity: usize) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.capacity = capacity;
+        inner.evict(None);
+    }
+
+    /// Set the function that loads [`AssetKey::Custom`] keys.
+    ///
+    /// This must be called before the first image is requested.
+    pub(crate) fn set_loader(&mut self, loader: Arc<AssetLoader>) {
+        self.loader = Some(loader);
+    }
+
+    /// Get the image for `key`, starting to load it if it isn't cached.
+    ///
+    /// If the image is not ready, `requester` is sent [`ASSET_LOADED`]
+    /// through `sink` once it is.
+    ///
+    /// [`ASSET_LOADED`]: crate::commands::ASSET_LOADED
+    pub(crate) fn get_image(
+        &self,
+        key: &AssetKey,
+        requester: WidgetId,
+        sink: &ExtEventSink,
+    ) -> AssetState {
+        let mut inner = self.inner.lock().unwrap();
+        inner.clock += 1;
+        let now = inner.clock;
+        match inner.entries.get_mut(key) {
+            Some(Entry::Loaded {
+                image, last_used, ..
+            }) => {
+                *last_used = now;
+                return AssetState::Loaded(image.clone());
+            }
+            Some(Entry::Failed(err)) => return AssetState::Failed(err.clone()),
+            Some(Entry::Loading { waiters }) => {
+                if !waiters.contains(&requester) {
+                    waiters.push(requester);
+                }
+                return AssetState::Loading;
+            }
+            None => (),
+        }
+
+        inner.entries.insert(
+            key.clone(),
+            Entry::Loading {
+                waiters: vec![requester],
+            },
+        );
+        let request = (key.clone(), sink.clone());
+        let request = match &inner.worker {
+            Some(worker) => match worker.send(request) {
+                Ok(()) => return AssetState::Loading,
+                Err(mpsc::SendError(request)) => request,
+            },
+            None => request,
+        };
+        match self.spawn_worker() {
+            Some(worker) => {
+                let _ = worker.send(request);
+                inner.worker = Some(worker);
+            }
+            // without threads, as on the web, we load the image right away
+            None => {
+                drop(inner);
+                load(&self.inner, self.loader.as_deref(), request.0, &request.1);
+            }
+        }
+        AssetState::Loading
+    }
+
+    /// Start the thread that loads images; it stops once the cache is dropped.
+    fn spawn_worker(&self) -> Option<Sender<(AssetKey, ExtEventSink)>> {
+        let (send, recv) = mpsc::channel::<(AssetKey, ExtEventSink)>();
+        let inner = Arc::downgrade(&self.inner);
+        let loader = self.loader.clone();
+        thread::Builder::new()
+            .name("druid asset loader".into())
+            .spawn(move || {
+                for (key, sink) in recv {
+                    match Weak::upgrade(&inner) {
+                        Some(inner) => load(&inner, loader.as_deref(), key, &sink),
+                        None => break,
+                    }
+                }
+            })
+            .map_err(|err| warn!("failed to start the asset loader thread: {}", err))
+            .ok()?;
+        Some(send)
+    }
+}
+
+/// Load the image for `key` and notify the widgets that are waiting for it.
+fn load(inner: &Mutex<Inner>, loader: Option<&AssetLoader>, key: AssetKey, sink: &ExtEventSink) {
+    let result = match &key {
+        AssetKey::Path(path) => load_file(path),
+        AssetKey::Custom(name) => match loader {
+            Some(loader) => loader(name),
+            None => Err("no asset loader was set with AppLauncher::asset_loader".into()),
+        },
+    };
+    if let Err(err) = &result {
+        warn!("failed to load asset {:?}: {}", key, err);
+    }
+
+    let waiters = inner.lock().unwrap().finish(&key, result);
+    for widget in waiters {
+        if sink
+            .submit_command(commands::ASSET_LOADED, key.clone(), Target::Widget(widget))
+            .is_err()
+        {
+            // the app is gone
+            return;
+        }
+    }
+}
+
+impl Inner {
+    /// Store the result of loading `key`, returning the widgets waiting for it.
+    fn finish(&mut self, key: &AssetKey, result: Result<ImageBuf, String>) -> Vec<WidgetId> {
+        let entry = match result {
+            Ok(image) => {
+                let bytes = image.raw_pixels().len();
+                self.used += bytes;
+                self.clock += 1;
+                Entry::Loaded {
+                    image,
+                    bytes,
+                    last_used: self.clock,
+                }
+            }
+            Err(err) => Entry::Failed(err),
+        };
+        let waiters = match self.entries.insert(key.clone(), entry) {
+            Some(Entry::Loading { waiters }) => waiters,
+            _ => Vec::new(),
+        };
+        self.evict(Some(key));
+        waiters
+    }
+
+    /// Drop the least recently used images until we are within our capacity,
+    /// keeping the image for `keep`.
+    fn evict(&mut self, keep: Option<&AssetKey>) {
+        while self.used > self.capacity {
+            let oldest = self
+                .entries
+                .iter()
+                .filter(|(key, _)| Some(*key) != keep)
+                .filter_map(|(key, entry)| match entry {
+                    Entry::Loaded { last_used, .. } => Some((key, *last_used)),
+                    _ => None,
+                })
+                .min_by_key(|(_, last_used)| *last_used)
+                .map(|(key, _)| key.clone());
+            match oldest.and_then(|key| self.entries.remove(&key)) {
+                Some(Entry::Loaded { bytes, .. }) => self.used -= bytes,
+                _ => break,
+            }
+        }
+    }
+}
+
+#[cfg(feature = "image")]
+fn load_file(path: &std::path::Path) -> Result<ImageBuf, String> {
+    use crate::piet::ImageFormat;
+
+    let image = crate::image::open(path)
+        .map_err(|err| err.to_string())?
+        .to_rgba8();
+    let (width, height) = image.dimensions();
+    Ok(ImageBuf::from_raw(
+        image.into_raw(),
+        ImageFormat::RgbaSeparate,
+        width as usize,
+        height as usize,
+    ))
+}
+
+#[cfg(not(feature = "image"))]
+fn load_file(_path: &std::path::Path) -> Result<ImageBuf, String> {
+    Err("loading image files needs druid's `image` feature".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc::Receiver;
+    use std::time::Duration;
+
+    use test_env_log::test;
+
+    use super::*;
+    use crate::ext_event::ExtEventHost;
+    use crate::piet::ImageFormat;
+
+    /// A cache whose loader waits for the test to send each image.
+    fn cache(capacity: usize) -> (AssetCache, Sender<Result<ImageBuf, String>>) {
+        let (send, recv) = mpsc::channel();
+        let recv = Mutex::new(recv);
+        let mut cache = AssetCache::default();
+        cache.set_capacity(capacity);
+        cache.set_loader(Arc::new(move |_: &str| {
+            let recv: &Receiver<_> = &recv.lock().unwrap();
+            recv.recv_timeout(Duration::from_secs(2)).unwrap()
+        }));
+        (cache, send)
+    }
+
+    /// A gray image that takes up `bytes` bytes.
+    fn image(bytes: usize) -> ImageBuf {
+        ImageBuf::from_raw(vec![128; bytes], ImageFormat::Grayscale, bytes, 1)
+    }
+
+    fn key(name: &str) -> AssetKey {
+        AssetKey::Custom(name.into())
+    }
+
+    /// Wait for the loader thread to notify a widget, returning the key and the widget.
+    fn loaded(host: &mut ExtEventHost) -> (AssetKey, WidgetId) {
+        for _ in 0..200 {
+            if let Some(cmd) = host.recv() {
+                let key = cmd.get_unchecked(commands::ASSET_LOADED).clone();
+                match cmd.target() {
+                    Target::Widget(id) => return (key, id),
+                    target => panic!("unexpected target {:?}", target),
+                }
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        panic!("no asset was loaded");
+    }
+
+    #[test]
+    fn requests_are_deduplicated_and_cached() {
+        let mut host = ExtEventHost::new();
+        let sink = host.make_sink();
+        let (cache, send) = cache(1000);
+        let (first, second) = (WidgetId::next(), WidgetId::next());
+
+        assert!(matches!(
+            cache.get_image(&key("a"), first, &sink),
+            AssetState::Loading
+        ));
+        assert!(matches!(
+            cache.get_image(&key("a"), second, &sink),
+            AssetState::Loading
+        ));
+
+        // only one load happens, and both widgets are told about it
+        send.send(Ok(image(10))).unwrap();
+        assert_eq!(loaded(&mut host), (key("a"), first));
+        assert_eq!(loaded(&mut host), (key("a"), second));
+        assert!(host.recv().is_none());
+
+        let third = WidgetId::next();
+        match cache.get_image(&key("a"), third, &sink) {
+            AssetState::Loaded(image) => assert_eq!(image.raw_pixels().len(), 10),
+            _ => panic!("the image should be cached"),
+        }
+        assert!(host.recv().is_none());
+    }
+
+    #[test]
+    fn least_recently_used_images_are_evicted() {
+        let mut host = ExtEventHost::new();
+        let sink = host.make_sink();
+        let (cache, send) = cache(25);
+        let widget = WidgetId::next();
+
+        for name in &["a", "b"] {
+            cache.get_image(&key(name), widget, &sink);
+            send.send(Ok(image(10))).unwrap();
+            loaded(&mut host);
+        }
+        // use "a", so that "b" is the oldest
+        assert!(matches!(
+            cache.get_image(&key("a"), widget, &sink),
+            AssetState::Loaded(_)
+        ));
+        cache.get_image(&key("c"), widget, &sink);
+        send.send(Ok(image(10))).unwrap();
+        loaded(&mut host);
+
+        assert!(matches!(
+            cache.get_image(&key("a"), widget, &sink),
+            AssetState::Loaded(_)
+        ));
+        assert!(matches!(
+            cache.get_image(&key("c"), widget, &sink),
+            AssetState::Loaded(_)
+        ));
+        assert!(matches!(
+            cache.get_image(&key("b"), widget, &sink),
+            AssetState::Loading
+        ));
+        assert_eq!(cache.inner.lock().unwrap().used, 20);
+    }
+
+    #[test]
+    fn failures_are_remembered() {
+        let mut host = ExtEventHost::new();
+        let sink = host.make_sink();
+        let (cache, send) = cache(1000);
+        let widget = WidgetId::next();
+
+        cache.get_image(&key("missing"), widget, &sink);
+        send.send(Err("not found".into())).unwrap();
+        loaded(&mut host);
+        match cache.get_image(&key("missing"), widget, &sink) {
+            AssetState::Failed(err) => assert_eq!(err, "not found"),
+            _ => panic!("the failure should be cached"),
+        }
+        assert!(host.recv().is_none());
+    }
+}
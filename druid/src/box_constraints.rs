@@ -54,13 +54,23 @@ impl BoxConstraints {
     /// Create constraints based on minimum and maximum size.
     ///
     /// The given sizes are also [rounded away from zero],
-    /// so that the layout is aligned to integers.
+    /// so that the layout is aligned to integers. Negative values are
+    /// clamped to zero, and then a minimum larger than the maximum is
+    /// clamped to the maximum; `NaN` is a bug, and panics in debug builds.
     ///
     /// [rounded away from zero]: struct.Size.html#method.expand
     pub fn new(min: Size, max: Size) -> BoxConstraints {
+        debug_assert!(
+            !has_nan(min) && !has_nan(max),
+            "NaN in BoxConstraints::new(min: {}, max: {})",
+            min,
+            max
+        );
+        let max = non_negative(max).expand();
+        let min = non_negative(min).expand();
         BoxConstraints {
-            min: min.expand(),
-            max: max.expand(),
+            min: Size::new(min.width.min(max.width), min.height.min(max.height)),
+            max,
         }
     }
 
@@ -69,11 +79,13 @@ impl BoxConstraints {
     /// A "tight" constraint can only be satisfied by a single size.
     ///
     /// The given size is also [rounded away from zero],
-    /// so that the layout is aligned to integers.
+    /// so that the layout is aligned to integers. Negative values are
+    /// clamped to zero; `NaN` is a bug, and panics in debug builds.
     ///
     /// [rounded away from zero]: struct.Size.html#method.expand
     pub fn tight(size: Size) -> BoxConstraints {
-        let size = size.expand();
+        debug_assert!(!has_nan(size), "NaN in BoxConstraints::tight({})", size);
+        let size = non_negative(size).expand();
         BoxConstraints {
             min: size,
             max: size,
@@ -97,7 +109,14 @@ impl BoxConstraints {
     ///
     /// [rounded away from zero]: struct.Size.html#method.expand
     pub fn constrain(&self, size: impl Into<Size>) -> Size {
-        size.into().expand().clamp(self.min, self.max)
+        let size = size.into();
+        debug_assert!(
+            !has_nan(size),
+            "NaN in BoxConstraints::constrain({}) with {:?}",
+            size,
+            self
+        );
+        non_negative(size).expand().clamp(self.min, self.max)
     }
 
     /// Returns the max size of these constraints.
@@ -147,11 +166,19 @@ impl BoxConstraints {
     /// Shrink min and max constraints by size
     ///
     /// The given size is also [rounded away from zero],
-    /// so that the layout is aligned to integers.
+    /// so that the layout is aligned to integers. If `diff` is larger than
+    /// the constraints, they shrink to zero.
     ///
     /// [rounded away from zero]: struct.Size.html#method.expand
     pub fn shrink(&self, diff: impl Into<Size>) -> BoxConstraints {
-        let diff = diff.into().expand();
+        let diff = diff.into();
+        debug_assert!(
+            !has_nan(diff),
+            "NaN in BoxConstraints::shrink({}) with {:?}",
+            diff,
+            self
+        );
+        let diff = diff.expand();
         let min = Size::new(
             (self.min().width - diff.width).max(0.),
             (self.min().height - diff.height).max(0.),
@@ -272,6 +299,15 @@ impl BoxConstraints {
     }
 }
 
+fn has_nan(size: Size) -> bool {
+    size.width.is_nan() || size.height.is_nan()
+}
+
+/// Clamp negative values, and `NaN`, to zero.
+fn non_negative(size: Size) -> Size {
+    Size::new(size.width.max(0.), size.height.max(0.))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -378,4 +414,45 @@ mod tests {
 
         assert_eq!(BoxConstraints::UNBOUNDED.min(), Size::ZERO);
     }
+
+    #[test]
+    fn negative_values_clamp_to_zero() {
+        let bc = bc(-10.0, 5.0, 20.0, -1.0);
+        assert_eq!(bc.min(), Size::new(0.0, 0.0));
+        assert_eq!(bc.max(), Size::new(20.0, 0.0));
+
+        // a minimum over the maximum is clamped to it
+        let bc = self::bc(30.0, 0.0, 20.0, 10.0);
+        assert_eq!(bc.min(), Size::new(20.0, 0.0));
+
+        let tight = BoxConstraints::tight(Size::new(-3.0, 4.0));
+        assert_eq!(tight.min(), Size::new(0.0, 4.0));
+        assert_eq!(tight.max(), Size::new(0.0, 4.0));
+    }
+
+    #[test]
+    fn shrink_past_zero() {
+        let shrunk = bc(10.0, 10.0, 50.0, 50.0).shrink((60.0, 20.0));
+        assert_eq!(shrunk.min(), Size::new(0.0, 0.0));
+        assert_eq!(shrunk.max(), Size::new(0.0, 30.0));
+        assert_eq!(shrunk.loosen().min(), Size::ZERO);
+        assert_eq!(shrunk.loosen().max(), shrunk.max());
+    }
+
+    #[test]
+    fn constrain_clamps() {
+        let bc = bc(10.0, 10.0, 50.0, 50.0);
+        assert_eq!(bc.constrain((-5.0, 100.0)), Size::new(10.0, 50.0));
+        assert_eq!(
+            BoxConstraints::UNBOUNDED.constrain((-5.0, 7.5)),
+            Size::new(0.0, 8.0)
+        );
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "NaN in BoxConstraints::shrink")]
+    fn shrink_by_nan() {
+        bc(0.0, 0.0, 10.0, 10.0).shrink((f64::NAN, 0.0));
+    }
 }
@@ -0,0 +1,329 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! An ordered list of child widgets, for writing containers.
+
+#[cfg(feature = "debug_state")]
+use crate::debug_state::DebugState;
+use crate::{
+    BoxConstraints, Data, Env, Event, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx, Size,
+    UpdateCtx, Widget, WidgetPod,
+};
+
+/// An entry in a [`ChildList`].
+///
+/// Most containers store their children as plain [`WidgetPod`]s. A container
+/// that needs to keep something else with each child, such as its layout
+/// parameters, or that has entries without a widget, such as spacers, can
+/// store its own type instead.
+pub trait ListChild<T> {
+    /// The type of the child widget.
+    type Widget: Widget<T>;
+
+    /// The child widget, if this entry has one.
+    fn widget(&self) -> Option<&WidgetPod<T, Self::Widget>>;
+
+    /// The child widget, mutably, if this entry has one.
+    fn widget_mut(&mut self) -> Option<&mut WidgetPod<T, Self::Widget>>;
+}
+
+impl<T, W: Widget<T>> ListChild<T> for WidgetPod<T, W> {
+    type Widget = W;
+
+    fn widget(&self) -> Option<&WidgetPod<T, W>> {
+        Some(self)
+    }
+
+    fn widget_mut(&mut self) -> Option<&mut WidgetPod<T, W>> {
+        Some(self)
+    }
+}
+
+/// The children of a container, in order.
+///
+/// A `ChildList` does the parts of a container that are the same for most
+/// containers: it passes events, lifecycle events and updates on to every
+/// child, and paints them in order. A container built on it only has to
+/// decide where its children go, in its `layout` method. The simplest way
+/// is [`layout_each`], which lays out all the children with the same
+/// constraints; each child then has to be positioned with
+/// [`WidgetPod::set_origin`] before it is painted.
+///
+/// The entries are [`WidgetPod`]s by default; see [`ListChild`] for storing
+/// something else.
+///
+/// Like any container, the owner of a `ChildList` must call
+/// [`children_changed`] after adding or removing children.
+///
+/// [`layout_each`]: ChildList::layout_each
+/// [`children_changed`]: crate::EventCtx::children_changed
+pub struct ChildList<T, C = WidgetPod<T, Box<dyn Widget<T>>>> {
+    children: Vec<C>,
+    phantom: std::marker::PhantomData<T>,
+}
+
+impl<T, C: ListChild<T>> ChildList<T, C> {
+    /// Create an empty `ChildList`.
+    pub fn new() -> Self {
+        ChildList {
+            children: Vec::new(),
+            phantom: Default::default(),
+        }
+    }
+
+    /// The number of entries.
+    pub fn len(&self) -> usize {
+        self.children.len()
+    }
+
+    /// Returns `true` if there are no entries.
+    pub fn is_empty(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    /// Get the entry at `index`.
+    pub fn get(&self, index: usize) -> Option<&C> {
+        self.children.get(index)
+    }
+
+    /// Get the entry at `index`, mutably.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut C> {
+        self.children.get_mut(index)
+    }
+
+    /// Add an entry at the end.
+    pub fn push(&mut self, child: C) {
+        self.children.push(child);
+    }
+
+    /// Add an entry at `index`, shifting the ones after it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than the number of entries.
+    pub fn insert(&mut self, index: usize, child: C) {
+        self.children.insert(index, child);
+    }
+
+    /// Remove the entry at `index` and return it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn remove(&mut self, index: usize) -> C {
+        self.children.remove(index)
+    }
+
+    /// Keep only the entries for which `keep` returns `true`.
+    pub fn retain(&mut self, keep: impl FnMut(&C) -> bool) {
+        self.children.retain(keep);
+    }
+
+    /// Iterate over the entries in order.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &C> + ExactSizeIterator {
+        self.children.iter()
+    }
+
+    /// Iterate mutably over the entries in order.
+    pub fn iter_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut C> + ExactSizeIterator {
+        self.children.iter_mut()
+    }
+
+    /// Iterate over the child widgets in order, skipping entries without one.
+    pub fn widgets(&self) -> impl DoubleEndedIterator<Item = &WidgetPod<T, C::Widget>> {
+        self.children.iter().filter_map(C::widget)
+    }
+
+    /// Iterate mutably over the child widgets in order, skipping entries
+    /// without one.
+    pub fn widgets_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut WidgetPod<T, C::Widget>> {
+        self.children.iter_mut().filter_map(C::widget_mut)
+    }
+}
+
+impl<T: Data, C: ListChild<T>> ChildList<T, C> {
+    /// Pass an event on to every child, in order.
+    pub fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        for child in self.widgets_mut() {
+            child.event(ctx, event, data, env);
+        }
+    }
+
+    /// Pass a lifecycle event on to every child.
+    pub fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        for child in self.widgets_mut() {
+            child.lifecycle(ctx, event, data, env);
+        }
+    }
+
+    /// Update every child.
+    pub fn update(&mut self, ctx: &mut UpdateCtx, data: &T, env: &Env) {
+        for child in self.widgets_mut() {
+            child.update(ctx, data, env);
+        }
+    }
+
+    /// Lay out every child with the same constraints, in order, and iterate
+    /// over the children along with the sizes they chose.
+    ///
+    /// Each child has to be positioned with [`WidgetPod::set_origin`]
+    /// before it is painted.
+    pub fn layout_each<'a>(
+        &'a mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> impl DoubleEndedIterator<Item = (&'a mut WidgetPod<T, C::Widget>, Size)> + ExactSizeIterator
+    {
+        let children: Vec<_> = self
+            .widgets_mut()
+            .map(|child| {
+                let size = child.layout(ctx, bc, data, env);
+                (child, size)
+            })
+            .collect();
+        children.into_iter()
+    }
+
+    /// Paint the children, in order.
+    pub fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        for child in self.widgets_mut() {
+            child.paint(ctx, data, env);
+        }
+    }
+
+    /// Describe each child, in order, for a container's
+    /// [`Widget::debug_state`].
+    #[cfg(feature = "debug_state")]
+    pub fn debug_state(&self, data: &T) -> Vec<DebugState> {
+        self.widgets()
+            .map(|child| child.debug_state(data))
+            .collect()
+    }
+}
+
+impl<T, C: ListChild<T>> Default for ChildList<T, C> {
+    fn default() -> Self {
+        ChildList::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::{widget_ids, ModularWidget};
+    use crate::{Point, WidgetExt, WidgetId};
+    use test_env_log::test;
+
+    /// A child, or a gap between children.
+    #[allow(clippy::large_enum_variant)]
+    enum Entry {
+        Child(WidgetPod<(), Box<dyn Widget<()>>>),
+        Gap(f64),
+    }
+
+    impl ListChild<()> for Entry {
+        type Widget = Box<dyn Widget<()>>;
+
+        fn widget(&self) -> Option<&WidgetPod<(), Box<dyn Widget<()>>>> {
+            match self {
+                Entry::Child(widget) => Some(widget),
+                Entry::Gap(_) => None,
+            }
+        }
+
+        fn widget_mut(&mut self) -> Option<&mut WidgetPod<(), Box<dyn Widget<()>>>> {
+            match self {
+                Entry::Child(widget) => Some(widget),
+                Entry::Gap(_) => None,
+            }
+        }
+    }
+
+    /// Children in a row, with gaps between them.
+    struct Row {
+        children: ChildList<(), Entry>,
+    }
+
+    impl Widget<()> for Row {
+        fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut (), env: &Env) {
+            self.children.event(ctx, event, data, env)
+        }
+
+        fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &(), env: &Env) {
+            self.children.lifecycle(ctx, event, data, env)
+        }
+
+        fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &(), data: &(), env: &Env) {
+            self.children.update(ctx, data, env)
+        }
+
+        fn layout(
+            &mut self,
+            ctx: &mut LayoutCtx,
+            bc: &BoxConstraints,
+            data: &(),
+            env: &Env,
+        ) -> Size {
+            let mut x = 0.0;
+            for entry in self.children.iter_mut() {
+                match entry {
+                    Entry::Child(child) => {
+                        let size = child.layout(ctx, &bc.loosen(), data, env);
+                        child.set_origin(ctx, data, env, Point::new(x, 0.0));
+                        x += size.width;
+                    }
+                    Entry::Gap(gap) => x += *gap,
+                }
+            }
+            bc.constrain(Size::new(x, 10.0))
+        }
+
+        fn paint(&mut self, ctx: &mut PaintCtx, data: &(), env: &Env) {
+            self.children.paint(ctx, data, env)
+        }
+    }
+
+    #[test]
+    fn entries_without_widgets_are_skipped() {
+        let [a, b] = widget_ids();
+        let painted = Rc::new(RefCell::new(Vec::new()));
+        let child = |id: WidgetId| {
+            let painted = painted.clone();
+            let widget = ModularWidget::new(id)
+                .layout_fn(|_, _, bc, _, _| bc.constrain(Size::new(20., 10.)))
+                .paint_fn(move |id, _, _, _| painted.borrow_mut().push(*id));
+            Entry::Child(WidgetPod::new(widget.with_id(id).boxed()))
+        };
+        let mut children = ChildList::new();
+        children.push(child(a));
+        children.push(Entry::Gap(5.0));
+        children.push(child(b));
+        assert_eq!(children.len(), 3);
+        assert_eq!(children.widgets().count(), 2);
+
+        Harness::create_simple((), Row { children }, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            assert_eq!(harness.get_state(b).layout_rect().origin().x, 25.0);
+            harness.paint();
+            assert_eq!(*painted.borrow(), vec![a, b]);
+        });
+    }
+}
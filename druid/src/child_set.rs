@@ -0,0 +1,371 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A collection of child widgets that can be looked up by id.
+
+use std::collections::HashMap;
+
+use crate::{
+    BoxConstraints, ChildList, Data, Env, Event, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx,
+    PaintCtx, Size, UpdateCtx, Widget, WidgetId, WidgetPod,
+};
+
+/// The children of a container, in z-order, that can be looked up by [`WidgetId`].
+///
+/// This is meant for containers with many dynamic children, which need to get
+/// at a specific child while handling their own events, for instance when a
+/// command names the child to change.
+///
+/// The children are kept from bottom to top: [`iter`] and [`iter_mut`] yield
+/// them in the order they should be painted.
+///
+/// Like a [`ChildList`], a `ChildSet` passes events, lifecycle events and
+/// updates on to every child, and paints them in order, so a container
+/// built on it only has to decide where its children go, in its `layout`
+/// method. Unlike a `ChildList`, it gives events to the child on top first.
+///
+/// Like any container, the owner of a `ChildSet` must call
+/// [`children_changed`] after adding or removing children, and
+/// [`request_paint`] after reordering them.
+///
+/// [`iter`]: ChildSet::iter
+/// [`iter_mut`]: ChildSet::iter_mut
+/// [`children_changed`]: crate::EventCtx::children_changed
+/// [`request_paint`]: crate::EventCtx::request_paint
+pub struct ChildSet<T, W = Box<dyn Widget<T>>> {
+    children: ChildList<T, WidgetPod<T, W>>,
+    /// The position of each child in `children`.
+    positions: HashMap<WidgetId, usize>,
+}
+
+impl<T, W: Widget<T>> ChildSet<T, W> {
+    /// Create an empty `ChildSet`.
+    pub fn new() -> Self {
+        ChildSet {
+            children: ChildList::new(),
+            positions: HashMap::new(),
+        }
+    }
+
+    /// The number of children.
+    pub fn len(&self) -> usize {
+        self.children.len()
+    }
+
+    /// Returns `true` if there are no children.
+    pub fn is_empty(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    /// Returns `true` if the child with this id is in the set.
+    pub fn contains(&self, id: WidgetId) -> bool {
+        self.positions.contains_key(&id)
+    }
+
+    /// The position of a child in z-order, where `0` is the bottom.
+    pub fn position(&self, id: WidgetId) -> Option<usize> {
+        self.positions.get(&id).copied()
+    }
+
+    /// Get a child by id.
+    pub fn get(&self, id: WidgetId) -> Option<&WidgetPod<T, W>> {
+        self.children.get(self.position(id)?)
+    }
+
+    /// Get a child by id, mutably.
+    pub fn get_mut(&mut self, id: WidgetId) -> Option<&mut WidgetPod<T, W>> {
+        let position = self.position(id)?;
+        self.children.get_mut(position)
+    }
+
+    /// Add a child on top of the others, returning its id.
+    ///
+    /// If a child with the same id is already in the set, it is replaced,
+    /// and the new child goes on top.
+    pub fn push(&mut self, child: WidgetPod<T, W>) -> WidgetId {
+        self.insert(usize::MAX, child)
+    }
+
+    /// Add a child at a position in z-order, where `0` is the bottom, and
+    /// return its id.
+    ///
+    /// Positions past the end put the child on top. If a child with the same
+    /// id is already in the set, it is replaced: the old child is removed,
+    /// and the new one is inserted at `position` among the remaining ones.
+    pub fn insert(&mut self, position: usize, child: WidgetPod<T, W>) -> WidgetId {
+        let id = child.id();
+        self.remove(id);
+        let position = position.min(self.len());
+        self.children.insert(position, child);
+        self.reindex(position);
+        id
+    }
+
+    /// Remove a child, returning it if it was in the set.
+    pub fn remove(&mut self, id: WidgetId) -> Option<WidgetPod<T, W>> {
+        let position = self.positions.remove(&id)?;
+        let child = self.children.remove(position);
+        self.reindex(position);
+        Some(child)
+    }
+
+    /// Move a child to a new position in z-order, where `0` is the bottom.
+    ///
+    /// Positions past the end put the child on top. Returns `false` if the
+    /// child is not in the set.
+    pub fn move_to(&mut self, id: WidgetId, position: usize) -> bool {
+        let old = match self.position(id) {
+            Some(old) => old,
+            None => return false,
+        };
+        let new = position.min(self.len() - 1);
+        let child = self.children.remove(old);
+        self.children.insert(new, child);
+        self.reindex(old.min(new));
+        true
+    }
+
+    /// Move a child on top of all the others.
+    ///
+    /// Returns `false` if the child is not in the set.
+    pub fn raise_to_top(&mut self, id: WidgetId) -> bool {
+        self.move_to(id, usize::MAX)
+    }
+
+    /// Keep only the children for which `keep` returns `true`.
+    pub fn retain(&mut self, keep: impl FnMut(&WidgetPod<T, W>) -> bool) {
+        self.children.retain(keep);
+        self.positions.clear();
+        self.reindex(0);
+    }
+
+    /// Iterate over the children from bottom to top.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &WidgetPod<T, W>> + ExactSizeIterator {
+        self.children.iter()
+    }
+
+    /// Iterate mutably over the children from bottom to top.
+    pub fn iter_mut(
+        &mut self,
+    ) -> impl DoubleEndedIterator<Item = &mut WidgetPod<T, W>> + ExactSizeIterator {
+        self.children.iter_mut()
+    }
+
+    /// Update the positions of the children from `start` on.
+    fn reindex(&mut self, start: usize) {
+        for (position, child) in self.children.iter().enumerate().skip(start) {
+            self.positions.insert(child.id(), position);
+        }
+    }
+}
+
+impl<T: Data, W: Widget<T>> ChildSet<T, W> {
+    /// Pass an event on to the children, from top to bottom, so that the
+    /// child on top gets the first chance to handle it.
+    pub fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        for child in self.children.iter_mut().rev() {
+            child.event(ctx, event, data, env);
+        }
+    }
+
+    /// Pass a lifecycle event on to every child.
+    pub fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        self.children.lifecycle(ctx, event, data, env)
+    }
+
+    /// Update every child.
+    pub fn update(&mut self, ctx: &mut UpdateCtx, data: &T, env: &Env) {
+        self.children.update(ctx, data, env)
+    }
+
+    /// Lay out every child with the same constraints, from bottom to top,
+    /// and iterate over the children along with the sizes they chose.
+    ///
+    /// Each child has to be positioned with [`WidgetPod::set_origin`]
+    /// before it is painted.
+    pub fn layout_each<'a>(
+        &'a mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> impl DoubleEndedIterator<Item = (&'a mut WidgetPod<T, W>, Size)> + ExactSizeIterator {
+        self.children.layout_each(ctx, bc, data, env)
+    }
+
+    /// Paint the children, from bottom to top.
+    pub fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.children.paint(ctx, data, env)
+    }
+}
+
+impl<T, W: Widget<T>> Default for ChildSet<T, W> {
+    fn default() -> Self {
+        ChildSet::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::{widget_ids, ModularWidget};
+    use crate::tests::move_mouse;
+    use crate::widget::SizedBox;
+    use crate::{MouseButton, Point, WidgetExt};
+    use test_env_log::test;
+
+    const STEP: f64 = 10.0;
+
+    /// Children on top of each other, each one `STEP` further right and down.
+    struct Cascade {
+        children: ChildSet<()>,
+    }
+
+    impl Widget<()> for Cascade {
+        fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut (), env: &Env) {
+            self.children.event(ctx, event, data, env)
+        }
+
+        fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &(), env: &Env) {
+            self.children.lifecycle(ctx, event, data, env)
+        }
+
+        fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &(), data: &(), env: &Env) {
+            self.children.update(ctx, data, env)
+        }
+
+        fn layout(
+            &mut self,
+            ctx: &mut LayoutCtx,
+            bc: &BoxConstraints,
+            data: &(),
+            env: &Env,
+        ) -> Size {
+            let mut size = Size::ZERO;
+            for (i, (child, child_size)) in self
+                .children
+                .layout_each(ctx, &bc.loosen(), data, env)
+                .enumerate()
+            {
+                let origin = Point::new(STEP * i as f64, STEP * i as f64);
+                child.set_origin(ctx, data, env, origin);
+                size.width = size.width.max(origin.x + child_size.width);
+                size.height = size.height.max(origin.y + child_size.height);
+            }
+            bc.constrain(size)
+        }
+
+        fn paint(&mut self, ctx: &mut PaintCtx, data: &(), env: &Env) {
+            self.children.paint(ctx, data, env)
+        }
+    }
+
+    fn child() -> WidgetPod<(), Box<dyn Widget<()>>> {
+        WidgetPod::new(SizedBox::empty()).boxed()
+    }
+
+    fn ids(set: &ChildSet<()>) -> Vec<WidgetId> {
+        set.iter().map(|child| child.id()).collect()
+    }
+
+    #[test]
+    fn lookup_follows_mutations() {
+        let mut set = ChildSet::new();
+        let a = set.push(child());
+        let b = set.push(child());
+        let c = set.push(child());
+        assert_eq!(ids(&set), vec![a, b, c]);
+
+        assert!(set.raise_to_top(a));
+        assert_eq!(ids(&set), vec![b, c, a]);
+        assert_eq!(set.position(a), Some(2));
+        assert_eq!(set.get_mut(c).map(|child| child.id()), Some(c));
+
+        assert!(set.move_to(a, 0));
+        assert_eq!(ids(&set), vec![a, b, c]);
+
+        assert_eq!(set.remove(b).map(|child| child.id()), Some(b));
+        assert!(!set.contains(b));
+        assert_eq!(set.position(c), Some(1));
+        assert!(!set.move_to(b, 0));
+
+        let d = set.insert(1, child());
+        assert_eq!(ids(&set), vec![a, d, c]);
+        for (position, id) in ids(&set).into_iter().enumerate() {
+            assert_eq!(set.position(id), Some(position));
+        }
+    }
+
+    #[test]
+    fn duplicate_id_replaces_child() {
+        let [a] = widget_ids();
+        let with_id = || WidgetPod::new(SizedBox::empty().with_id(a)).boxed();
+        let mut set = ChildSet::new();
+        set.push(with_id());
+        let b = set.push(child());
+        let c = set.push(child());
+
+        assert_eq!(set.push(with_id()), a);
+        assert_eq!(ids(&set), vec![b, c, a]);
+
+        assert_eq!(set.insert(1, with_id()), a);
+        assert_eq!(ids(&set), vec![b, a, c]);
+        assert_eq!(set.len(), 3);
+        assert_eq!(set.position(c), Some(2));
+    }
+
+    #[test]
+    fn container_on_a_child_set() {
+        let [a, b, c] = widget_ids();
+        let clicked = Rc::new(RefCell::new(Vec::new()));
+        let mut children: ChildSet<()> = ChildSet::new();
+        for id in &[a, b, c] {
+            let clicked = clicked.clone();
+            let child = ModularWidget::new(*id)
+                .event_fn(move |id, ctx, event, _, _| {
+                    if let Event::MouseDown(_) = event {
+                        clicked.borrow_mut().push(*id);
+                        ctx.set_handled();
+                    }
+                })
+                .layout_fn(|_, _, bc, _, _| bc.constrain(Size::new(50., 50.)));
+            children.push(WidgetPod::new(child.with_id(*id).boxed()));
+        }
+
+        Harness::create_simple((), Cascade { children }, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            let origins: Vec<_> = [a, b, c]
+                .iter()
+                .map(|id| harness.get_state(*id).layout_rect().origin())
+                .collect();
+            assert_eq!(
+                origins,
+                vec![Point::ORIGIN, Point::new(10., 10.), Point::new(20., 20.)]
+            );
+
+            // the point is on all three, and the one on top handles the click
+            let mut down = move_mouse((30., 30.));
+            down.button = MouseButton::Left;
+            down.count = 1;
+            harness.event(Event::MouseDown(down));
+            assert_eq!(*clicked.borrow(), vec![c]);
+            harness.paint();
+        });
+    }
+}
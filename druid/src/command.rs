@@ -14,12 +14,17 @@
 
 //! Custom commands.
 
-use std::any::{self, Any};
+use std::any::{self, Any, TypeId};
 use std::{
     marker::PhantomData,
-    sync::{Arc, Mutex},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, RwLock,
+    },
 };
 
+use tracing::warn;
+
 use crate::{WidgetId, WindowId};
 
 /// The identity of a [`Selector`].
@@ -32,6 +37,8 @@ pub(crate) type SelectorSymbol = &'static str;
 /// This should be a unique string identifier.
 /// Having multiple selectors with the same identifier but different payload
 /// types is not allowed and can cause [`Command::get`] and [`get_unchecked`] to panic.
+/// In debug builds, an error is logged as soon as an identifier is used with a
+/// second payload type.
 ///
 /// The type parameter `T` specifies the command's payload type.
 /// See [`Command`] for more information.
@@ -74,11 +81,17 @@ pub struct Selector<T = ()>(SelectorSymbol, PhantomData<T>);
 /// [`EventCtx::new_window`]: struct.EventCtx.html#method.new_window
 /// [`SingleUse`]: struct.SingleUse.html
 /// [`Selector`]: struct.Selector.html
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct Command {
     symbol: SelectorSymbol,
     payload: Arc<dyn Any>,
+    /// The name of the payload's type, for error messages.
+    payload_type: &'static str,
     target: Target,
+    /// Set if this command is a [`Request`], shared by all its clones.
+    request: Option<Arc<RequestState>>,
+    /// Set if the payload is a [`SingleUse`]; returns whether it was taken.
+    single_use: Option<IsTaken>,
 }
 
 /// A message passed up the tree from a [`Widget`] to its ancestors.
@@ -102,6 +115,7 @@ pub struct Command {
 pub struct Notification {
     symbol: SelectorSymbol,
     payload: Arc<dyn Any>,
+    payload_type: &'static str,
     source: WidgetId,
 }
 
@@ -166,6 +180,105 @@ pub enum Target {
     Auto,
 }
 
+/// An identifier for a synchronous query, from a widget to the [`AppDelegate`].
+///
+/// Like a [`Selector`], this should be a unique string identifier. The type
+/// parameter `P` is the type of the payload sent with the query, and `R` is
+/// the type of the response.
+///
+/// Queries are made with [`EventCtx::query`] and answered by
+/// [`AppDelegate::query`]. They are answered immediately, on the UI thread,
+/// so they are meant for cheap lookups ("is this file already open?"), not
+/// for long running work; use a [`Command`] and an [`ExtEventSink`] for that.
+///
+/// [`AppDelegate`]: crate::AppDelegate
+/// [`AppDelegate::query`]: crate::AppDelegate::query
+/// [`EventCtx::query`]: crate::EventCtx::query
+/// [`ExtEventSink`]: crate::ExtEventSink
+#[derive(Debug, PartialEq, Eq)]
+pub struct Query<P = (), R = ()>(SelectorSymbol, PhantomData<fn(&P) -> R>);
+
+/// A [`Query`] waiting for an answer.
+///
+/// This is passed to [`AppDelegate::query`], which can inspect the payload
+/// with [`get`] and answer with [`respond`].
+///
+/// [`AppDelegate::query`]: crate::AppDelegate::query
+/// [`get`]: #method.get
+/// [`respond`]: #method.respond
+pub struct QueryRequest<'a> {
+    symbol: SelectorSymbol,
+    payload: &'a dyn Any,
+    payload_type: &'static str,
+    response: Option<Box<dyn Any>>,
+}
+
+/// An identifier for a request from one widget to another, that expects a
+/// response.
+///
+/// Like a [`Selector`], this should be a unique string identifier. The type
+/// parameter `P` is the type of the payload sent with the request, and `R`
+/// is the type of the response.
+///
+/// A request is sent with [`EventCtx::submit_request`], and arrives at its
+/// target as an [`Event::Command`], where [`Command::get_request`] returns
+/// its payload. The target answers by calling [`EventCtx::submit_response`]
+/// while handling it, and the response arrives back at the widget that sent
+/// the request as another [`Event::Command`], which can be read with
+/// [`Command::get_response`].
+///
+/// If the request is not answered, because its target does not exist or
+/// ignored it, the requesting widget gets a [`RequestCanceled`] response.
+///
+/// # Examples
+///
+/// ```
+/// use druid::{Event, EventCtx, Request, Vec2};
+///
+/// const SCROLL_OFFSET: Request<(), Vec2> = Request::new("my-app.scroll-offset");
+///
+/// // in the scroll area
+/// fn answer(ctx: &mut EventCtx, event: &Event, offset: Vec2) {
+///     if let Event::Command(cmd) = event {
+///         if cmd.get_request(SCROLL_OFFSET).is_some() {
+///             ctx.submit_response(cmd, SCROLL_OFFSET, offset);
+///         }
+///     }
+/// }
+///
+/// // in the widget that wants to know
+/// fn receive(event: &Event) {
+///     if let Event::Command(cmd) = event {
+///         match cmd.get_response(SCROLL_OFFSET) {
+///             Some(Ok(offset)) => println!("the offset is {}", offset),
+///             Some(Err(_)) => println!("nobody answered"),
+///             None => (),
+///         }
+///     }
+/// }
+/// ```
+///
+/// [`Event::Command`]: crate::Event::Command
+/// [`EventCtx::submit_request`]: crate::EventCtx::submit_request
+/// [`EventCtx::submit_response`]: crate::EventCtx::submit_response
+#[derive(Debug, PartialEq, Eq)]
+pub struct Request<P = (), R = ()>(SelectorSymbol, PhantomData<fn(&P) -> R>);
+
+/// The response to a [`Request`] that was not answered, either because its
+/// target does not exist or because the target did not respond.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequestCanceled;
+
+/// What we need to know about a request to answer or cancel it.
+#[derive(Debug)]
+pub(crate) struct RequestState {
+    requester: WidgetId,
+    answered: AtomicBool,
+}
+
+/// The payload of a response command.
+struct Response<R>(R);
+
 /// Commands with special meaning, defined by druid.
 ///
 /// See [`Command`] for more info.
@@ -177,7 +290,8 @@ pub mod sys {
     use super::Selector;
     use crate::{
         sub_window::{SubWindowDesc, SubWindowUpdate},
-        FileDialogOptions, FileInfo, SingleUse, WidgetId, WindowConfig,
+        AssetKey, EnvUpdate, FileDialogOptions, FileInfo, OverlayToken, Rect, SingleUse, ToastDesc,
+        WidgetId, WindowConfig,
     };
 
     /// Quit the running application. This command is handled by the druid library.
@@ -210,10 +324,65 @@ pub mod sys {
     /// will automatically target the window containing the widget.
     pub const SHOW_WINDOW: Selector = Selector::new("druid-builtin.show-window");
 
+    /// Ask a window to describe its widget tree.
+    ///
+    /// The command must target a specific window. The window answers by
+    /// submitting [`DEBUG_STATE`] to itself, where an [`AppDelegate`] can
+    /// pick it up in its [`command`] method.
+    ///
+    /// [`AppDelegate`]: crate::AppDelegate
+    /// [`command`]: crate::AppDelegate::command
+    #[cfg(feature = "debug_state")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "debug_state")))]
+    pub const QUERY_DEBUG_STATE: Selector = Selector::new("druid-builtin.query-debug-state");
+
+    /// The answer to [`QUERY_DEBUG_STATE`]: the [`DebugState`] of the window's
+    /// root widget, targeted at that window.
+    ///
+    /// [`DebugState`]: crate::DebugState
+    #[cfg(feature = "debug_state")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "debug_state")))]
+    pub const DEBUG_STATE: Selector<crate::DebugState> = Selector::new("druid-builtin.debug-state");
+
+    /// Sent to a single-instance app when the app is launched again, with the
+    /// arguments of the new launch, not including the program name.
+    ///
+    /// The command is delivered to the [`AppDelegate`] and then to every
+    /// window, after which druid brings the oldest open window to the front.
+    ///
+    /// See [`AppLauncher::single_instance`].
+    ///
+    /// [`AppDelegate`]: crate::AppDelegate
+    /// [`AppLauncher::single_instance`]: crate::AppLauncher::single_instance
+    pub const ACTIVATE_EXISTING: Selector<Vec<String>> =
+        Selector::new("druid-builtin.activate-existing");
+
+    /// Sent to a widget that asked for an image from the asset cache while it
+    /// was loading, once loading is done; the payload is the image's key.
+    ///
+    /// Ask for the image again with [`get_image`] to find out whether it loaded.
+    ///
+    /// [`get_image`]: crate::EventCtx::get_image
+    pub const ASSET_LOADED: Selector<AssetKey> = Selector::new("druid-builtin.asset-loaded");
+
     /// Apply the configuration payload to an existing window. The target should be a WindowId.
     pub const CONFIGURE_WINDOW: Selector<WindowConfig> =
         Selector::new("druid-builtin.configure-window");
 
+    /// Change the application's [`Env`], for instance to switch themes while
+    /// the app is running.
+    ///
+    /// The payload is applied to the env shared by all windows, including
+    /// windows opened later, and every window is then updated and repainted.
+    /// Widgets that depend on a changed key for their size should request
+    /// layout when [`UpdateCtx::env_key_changed`] says so.
+    ///
+    /// The target is ignored; the change applies to the whole application.
+    ///
+    /// [`Env`]: crate::Env
+    /// [`UpdateCtx::env_key_changed`]: crate::UpdateCtx::env_key_changed
+    pub const UPDATE_ENV: Selector<EnvUpdate> = Selector::new("druid-builtin.update-env");
+
     /// Display a context (right-click) menu. The payload must be the [`ContextMenu`]
     /// object to be displayed.
     ///
@@ -221,6 +390,28 @@ pub mod sys {
     pub(crate) const SHOW_CONTEXT_MENU: Selector<SingleUse<Box<dyn Any>>> =
         Selector::new("druid-builtin.show-context-menu");
 
+    /// Show a toast: a short notification in a corner of the target window,
+    /// which goes away by itself after a while.
+    ///
+    /// Submitted by a widget without a target, the toast is shown in the
+    /// widget's window. Submitted with [`Target::Global`], as happens by
+    /// default from the [`AppDelegate`] or an [`ExtEventSink`], it is shown in
+    /// the first window that is open.
+    ///
+    /// [`Target::Global`]: crate::Target::Global
+    /// [`AppDelegate`]: crate::AppDelegate
+    /// [`ExtEventSink`]: crate::ExtEventSink
+    pub const SHOW_TOAST: Selector<ToastDesc> = Selector::new("druid-builtin.show-toast");
+
+    /// Show an overlay in the target window. The payload must be an `OverlayDesc`
+    /// of the application's data type.
+    pub(crate) const SHOW_OVERLAY: Selector<SingleUse<Box<dyn Any>>> =
+        Selector::new("druid-builtin.show-overlay");
+
+    /// Dismiss an overlay in the target window.
+    pub(crate) const DISMISS_OVERLAY: Selector<OverlayToken> =
+        Selector::new("druid-builtin.dismiss-overlay");
+
     /// This is sent to the window handler to create a new sub window.
     pub(crate) const NEW_SUB_WINDOW: Selector<SingleUse<SubWindowDesc>> =
         Selector::new("druid-builtin.new-sub-window");
@@ -316,6 +507,43 @@ pub mod sys {
     /// Select all.
     pub const SELECT_ALL: Selector = Selector::new("druid-builtin.menu-select-all");
 
+    /// A [`Notification`] asking the closest [`Scroll`] ancestor to scroll so
+    /// that the payload, a [`Rect`] in window coordinates, becomes visible.
+    ///
+    /// Widgets should generally use [`EventCtx::scroll_to_view`] or
+    /// [`EventCtx::scroll_area_to_view`] instead of submitting this directly.
+    ///
+    /// [`Notification`]: crate::Notification
+    /// [`Scroll`]: crate::widget::Scroll
+    /// [`EventCtx::scroll_to_view`]: crate::EventCtx::scroll_to_view
+    /// [`EventCtx::scroll_area_to_view`]: crate::EventCtx::scroll_area_to_view
+    pub const SCROLL_TO_VIEW: Selector<Rect> = Selector::new("druid-builtin.scroll-to-view");
+
+    /// Move keyboard focus to the widget with the given id, and scroll it into
+    /// view if it is inside a [`Scroll`].
+    ///
+    /// This can be submitted from anywhere, such as from the [`AppDelegate`]
+    /// when a shortcut is pressed. The widget must be able to take focus: it
+    /// must have called [`register_for_focus`], and not be disabled or hidden.
+    /// Otherwise the request is logged and focus does not change.
+    ///
+    /// [`Scroll`]: crate::widget::Scroll
+    /// [`AppDelegate`]: crate::AppDelegate
+    /// [`register_for_focus`]: crate::LifeCycleCtx::register_for_focus
+    pub const REQUEST_FOCUS: Selector<WidgetId> = Selector::new("druid-builtin.request-focus");
+
+    /// Take keyboard focus away from the focused widget, leaving no widget
+    /// focused in the window.
+    pub const RESIGN_FOCUS: Selector = Selector::new("druid-builtin.resign-focus");
+
+    /// Sent by the window to a widget that should take focus, once it has
+    /// checked a [`REQUEST_FOCUS`]; the widget's `WidgetPod` handles it.
+    pub(crate) const TAKE_FOCUS: Selector = Selector::new("druid-builtin.take-focus");
+
+    /// Sent by the window to the focused widget, for a [`RESIGN_FOCUS`];
+    /// the widget's `WidgetPod` handles it.
+    pub(crate) const GIVE_UP_FOCUS: Selector = Selector::new("druid-builtin.give-up-focus");
+
     /// Text input state has changed, and we need to notify the platform.
     pub(crate) const INVALIDATE_IME: Selector<ImeInvalidation> =
         Selector::new("druid-builtin.invalidate-ime");
@@ -350,6 +578,13 @@ impl<T> Selector<T> {
     pub(crate) const fn symbol(self) -> SelectorSymbol {
         self.0
     }
+
+    /// Returns the name of this `Selector`'s payload type.
+    ///
+    /// The name is only meant for diagnostics; see [`std::any::type_name`].
+    pub fn type_name(self) -> &'static str {
+        any::type_name::<T>()
+    }
 }
 
 impl<T: Any> Selector<T> {
@@ -372,23 +607,96 @@ impl Command {
     ///
     /// If you do not need a payload, [`Selector`] implements `Into<Command>`.
     pub fn new<T: Any>(selector: Selector<T>, payload: T, target: impl Into<Target>) -> Self {
+        register_payload_type::<T>(selector.symbol());
         Command {
             symbol: selector.symbol(),
+            single_use: single_use_check(&payload),
             payload: Arc::new(payload),
+            payload_type: selector.type_name(),
             target: target.into(),
+            request: None,
         }
     }
 
     /// Used to create a `Command` from the types sent via an `ExtEventSink`.
-    pub(crate) fn from_ext(symbol: SelectorSymbol, payload: Box<dyn Any>, target: Target) -> Self {
+    pub(crate) fn from_ext(
+        symbol: SelectorSymbol,
+        payload_type: &'static str,
+        payload: Box<dyn Any>,
+        target: Target,
+    ) -> Self {
         Command {
             symbol,
+            single_use: single_use_check(&*payload),
             payload: payload.into(),
+            payload_type,
             target,
+            request: None,
         }
         .default_to(Target::Global)
     }
 
+    /// Create the command for a [`Request`], sent by `requester`.
+    pub(crate) fn request<P: Any, R>(
+        request: Request<P, R>,
+        payload: P,
+        target: Target,
+        requester: WidgetId,
+    ) -> Self {
+        register_payload_type::<P>(request.0);
+        Command {
+            symbol: request.0,
+            single_use: single_use_check(&payload),
+            payload: Arc::new(payload),
+            payload_type: any::type_name::<P>(),
+            target,
+            request: Some(Arc::new(RequestState {
+                requester,
+                answered: AtomicBool::new(false),
+            })),
+        }
+    }
+
+    /// Create the response to this command, if it is a request for `request`
+    /// that has not been answered yet.
+    pub(crate) fn response<P, R: Any>(
+        &self,
+        request: Request<P, R>,
+        response: R,
+    ) -> Option<Command> {
+        let state = self.request.as_ref().filter(|_| self.symbol == request.0)?;
+        if state.answered.swap(true, Ordering::Relaxed) {
+            return None;
+        }
+        Some(Command {
+            symbol: self.symbol,
+            payload: Arc::new(Response(response)),
+            payload_type: any::type_name::<Response<R>>(),
+            target: Target::Widget(state.requester),
+            request: None,
+            single_use: None,
+        })
+    }
+
+    /// If this is a request that has not been answered, mark it as answered
+    /// and return the [`RequestCanceled`] response for the widget that sent it.
+    ///
+    /// This is called by the app once the request has been delivered.
+    pub(crate) fn cancellation(&self) -> Option<Command> {
+        let state = self.request.as_ref()?;
+        if state.answered.swap(true, Ordering::Relaxed) {
+            return None;
+        }
+        Some(Command {
+            symbol: self.symbol,
+            payload: Arc::new(RequestCanceled),
+            payload_type: any::type_name::<RequestCanceled>(),
+            target: Target::Widget(state.requester),
+            request: None,
+            single_use: None,
+        })
+    }
+
     /// A helper method for creating a `Notification` from a `Command`.
     ///
     /// This is slightly icky; it lets us do `SOME_SELECTOR.with(SOME_PAYLOAD)`
@@ -398,6 +706,7 @@ impl Command {
         Notification {
             symbol: self.symbol,
             payload: self.payload,
+            payload_type: self.payload_type,
             source,
         }
     }
@@ -429,6 +738,52 @@ impl Command {
         self.target
     }
 
+    /// Returns the payload, if this command is a request for `request`.
+    ///
+    /// Answer it with [`EventCtx::submit_response`].
+    ///
+    /// [`EventCtx::submit_response`]: crate::EventCtx::submit_response
+    pub fn get_request<P: Any, R>(&self, request: Request<P, R>) -> Option<&P> {
+        if self.request.is_some() && self.symbol == request.0 {
+            self.payload.downcast_ref()
+        } else {
+            None
+        }
+    }
+
+    /// Returns the response, if this command is the response to `request`.
+    ///
+    /// The response is a [`RequestCanceled`] error if the request was not
+    /// answered.
+    pub fn get_response<P, R: Any>(
+        &self,
+        request: Request<P, R>,
+    ) -> Option<Result<&R, RequestCanceled>> {
+        if self.request.is_some() || self.symbol != request.0 {
+            return None;
+        }
+        if let Some(Response(response)) = self.payload.downcast_ref::<Response<R>>() {
+            Some(Ok(response))
+        } else if self.payload.is::<RequestCanceled>() {
+            Some(Err(RequestCanceled))
+        } else {
+            None
+        }
+    }
+
+    /// Returns `true` if the payload is a [`SingleUse`].
+    ///
+    /// Once a widget takes the payload of a single-use command, the command
+    /// is not delivered to any more widgets.
+    pub fn is_single_use(&self) -> bool {
+        self.single_use.is_some()
+    }
+
+    /// Returns `true` if the payload is a [`SingleUse`] that has been taken.
+    pub(crate) fn single_use_taken(&self) -> bool {
+        matches!(self.single_use, Some(is_taken) if is_taken(&*self.payload))
+    }
+
     /// Returns `true` if `self` matches this `selector`.
     pub fn is<T>(&self, selector: Selector<T>) -> bool {
         self.symbol == selector.symbol()
@@ -449,10 +804,22 @@ impl Command {
     /// [`get_unchecked`]: #method.get_unchecked
     pub fn get<T: Any>(&self, selector: Selector<T>) -> Option<&T> {
         if self.symbol == selector.symbol() {
+            register_payload_type::<T>(selector.symbol());
+            if self.single_use_taken() {
+                warn!(
+                    "The SingleUse payload of \"{}\" has already been taken; \
+                    was the command delivered to more than one widget?",
+                    selector.symbol()
+                );
+            }
             Some(self.payload.downcast_ref().unwrap_or_else(|| {
                 panic!(
-                    "The selector \"{}\" exists twice with different types. See druid::Command::get for more information",
-                    selector.symbol()
+                    "The selector \"{}\" exists twice with different types: \
+                    expected {} but the payload is {}. \
+                    See druid::Command::get for more information",
+                    selector.symbol(),
+                    selector.type_name(),
+                    self.payload_type
                 );
             }))
         } else {
@@ -502,11 +869,15 @@ impl Notification {
     /// [`is`]: #method.is
     pub fn get<T: Any>(&self, selector: Selector<T>) -> Option<&T> {
         if self.symbol == selector.symbol() {
+            register_payload_type::<T>(selector.symbol());
             Some(self.payload.downcast_ref().unwrap_or_else(|| {
                 panic!(
-                    "The selector \"{}\" exists twice with different types. \
+                    "The selector \"{}\" exists twice with different types: \
+                    expected {} but the payload is {}. \
                     See druid::Command::get for more information",
-                    selector.symbol()
+                    selector.symbol(),
+                    selector.type_name(),
+                    self.payload_type
                 );
             }))
         } else {
@@ -514,6 +885,30 @@ impl Notification {
         }
     }
 
+    /// Returns a reference to this `Notification`'s payload.
+    ///
+    /// If the selector has already been checked with [`is`], then `get_unchecked` can be used safely.
+    /// Otherwise you should use [`get`] instead.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `self.is(selector) == false`.
+    ///
+    /// Panics when the payload has a different type, than what the selector is supposed to carry.
+    /// This can happen when two selectors with different types but the same key are used.
+    ///
+    /// [`is`]: #method.is
+    /// [`get`]: #method.get
+    pub fn get_unchecked<T: Any>(&self, selector: Selector<T>) -> &T {
+        self.get(selector).unwrap_or_else(|| {
+            panic!(
+                "Expected selector \"{}\" but the notification was \"{}\".",
+                selector.symbol(),
+                self.symbol
+            )
+        })
+    }
+
     /// The [`WidgetId`] of the [`Widget`] that sent this [`Notification`].
     ///
     /// [`Widget`]: crate::Widget
@@ -522,9 +917,88 @@ impl Notification {
     }
 }
 
+/// The payload types each selector symbol has been used with, in the order
+/// they were first seen.
+#[cfg(debug_assertions)]
+static PAYLOAD_TYPES: Mutex<
+    std::collections::BTreeMap<SelectorSymbol, Vec<(TypeId, &'static str)>>,
+> = Mutex::new(std::collections::BTreeMap::new());
+
+/// Records that `symbol` was used with a payload of type `T`.
+///
+/// In debug builds, the first time `symbol` is seen with a payload type other
+/// than the one it was first used with, this logs an error and returns the name
+/// of that first type. In release builds this does nothing.
+#[cfg_attr(not(debug_assertions), allow(clippy::extra_unused_type_parameters))]
+pub(crate) fn register_payload_type<T: Any>(symbol: SelectorSymbol) -> Option<&'static str> {
+    #[cfg(debug_assertions)]
+    {
+        let mut registry = PAYLOAD_TYPES.lock().unwrap_or_else(|e| e.into_inner());
+        let seen = registry.entry(symbol).or_default();
+        let type_id = TypeId::of::<T>();
+        if seen.iter().any(|(id, _)| *id == type_id) {
+            return None;
+        }
+        let type_name = any::type_name::<T>();
+        seen.push((type_id, type_name));
+        let (_, first) = seen[0];
+        if seen.len() == 1 {
+            return None;
+        }
+        tracing::error!(
+            "The selector \"{}\" is used with payload type {}, \
+            but it was first used with {}; selectors must have unique identifiers",
+            symbol,
+            type_name,
+            first
+        );
+        Some(first)
+    }
+    #[cfg(not(debug_assertions))]
+    {
+        let _ = symbol;
+        None
+    }
+}
+
+/// Checks whether a [`SingleUse`], given as `&dyn Any`, has been taken.
+type IsTaken = fn(&dyn Any) -> bool;
+
+/// For each type of [`SingleUse`] that has been created, its [`IsTaken`] check.
+///
+/// This is how a [`Command`] knows its payload is single-use without
+/// knowing its type, including when it was sent through an [`ExtEventSink`].
+/// Every command reads it, but it is only written the first time a type of
+/// `SingleUse` is created, so readers almost never wait.
+///
+/// [`ExtEventSink`]: crate::ExtEventSink
+static SINGLE_USE_TYPES: RwLock<Option<std::collections::HashMap<TypeId, IsTaken>>> =
+    RwLock::new(None);
+
+/// Returns the [`IsTaken`] check for `payload`, if it is a [`SingleUse`].
+fn single_use_check(payload: &dyn Any) -> Option<IsTaken> {
+    let types = SINGLE_USE_TYPES.read().unwrap_or_else(|e| e.into_inner());
+    types.as_ref()?.get(&payload.type_id()).copied()
+}
+
 impl<T: Any> SingleUse<T> {
     /// Create a new single-use payload.
     pub fn new(data: T) -> Self {
+        let type_id = TypeId::of::<Self>();
+        let known = {
+            let types = SINGLE_USE_TYPES.read().unwrap_or_else(|e| e.into_inner());
+            matches!(&*types, Some(types) if types.contains_key(&type_id))
+        };
+        if !known {
+            SINGLE_USE_TYPES
+                .write()
+                .unwrap_or_else(|e| e.into_inner())
+                .get_or_insert_with(Default::default)
+                .entry(type_id)
+                .or_insert(|payload| {
+                    matches!(payload.downcast_ref::<Self>(), Some(payload) if payload.is_taken())
+                });
+        }
         SingleUse(Mutex::new(Some(data)))
     }
 
@@ -532,14 +1006,35 @@ impl<T: Any> SingleUse<T> {
     pub fn take(&self) -> Option<T> {
         self.0.lock().unwrap().take()
     }
+
+    /// Takes the value, logging a warning if it has already been taken.
+    pub fn take_or_log(&self) -> Option<T> {
+        let value = self.take();
+        if value.is_none() {
+            warn!(
+                "SingleUse<{}> has already been taken",
+                any::type_name::<T>()
+            );
+        }
+        value
+    }
+
+    /// Returns `true` if the value has been taken.
+    pub fn is_taken(&self) -> bool {
+        self.0.lock().unwrap().is_none()
+    }
 }
 
 impl From<Selector> for Command {
     fn from(selector: Selector) -> Command {
+        register_payload_type::<()>(selector.symbol());
         Command {
             symbol: selector.symbol(),
             payload: Arc::new(()),
+            payload_type: any::type_name::<()>(),
             target: Target::Auto,
+            request: None,
+            single_use: None,
         }
     }
 }
@@ -592,6 +1087,124 @@ impl From<WidgetId> for Option<Target> {
     }
 }
 
+impl<P, R> Query<P, R> {
+    /// Create a new `Query` with the given string.
+    pub const fn new(s: &'static str) -> Query<P, R> {
+        Query(s, PhantomData)
+    }
+}
+
+impl<P, R> Request<P, R> {
+    /// Create a new `Request` with the given string.
+    pub const fn new(s: &'static str) -> Request<P, R> {
+        Request(s, PhantomData)
+    }
+}
+
+impl<P, R> Copy for Request<P, R> {}
+impl<P, R> Clone for Request<P, R> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<P, R> std::fmt::Display for Request<P, R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Request(\"{}\", {})", self.0, any::type_name::<P>())
+    }
+}
+
+impl<P, R> Copy for Query<P, R> {}
+impl<P, R> Clone for Query<P, R> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<P, R> std::fmt::Display for Query<P, R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Query(\"{}\", {})", self.0, any::type_name::<P>())
+    }
+}
+
+impl<'a> QueryRequest<'a> {
+    pub(crate) fn new<P: Any, R>(query: Query<P, R>, payload: &'a P) -> Self {
+        QueryRequest {
+            symbol: query.0,
+            payload,
+            payload_type: any::type_name::<P>(),
+            response: None,
+        }
+    }
+
+    /// Returns `true` if this is a request for `query`.
+    pub fn is<P, R>(&self, query: Query<P, R>) -> bool {
+        self.symbol == query.0
+    }
+
+    /// Returns the payload, if this is a request for `query`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the payload has a different type than the one `query` expects.
+    /// This can only happen when two queries share an identifier.
+    pub fn get<P: Any, R>(&self, query: Query<P, R>) -> Option<&P> {
+        if self.symbol == query.0 {
+            Some(self.payload.downcast_ref().unwrap_or_else(|| {
+                panic!(
+                    "The query \"{}\" exists twice with different types: \
+                    expected {} but the payload is {}. \
+                    See druid::QueryRequest::get for more information",
+                    self.symbol,
+                    any::type_name::<P>(),
+                    self.payload_type
+                );
+            }))
+        } else {
+            None
+        }
+    }
+
+    /// Answer `query`.
+    ///
+    /// Does nothing if this is not a request for `query`. If called more than
+    /// once, the last response wins.
+    pub fn respond<P, R: Any>(&mut self, query: Query<P, R>, response: R) {
+        if self.symbol == query.0 {
+            self.response = Some(Box::new(response));
+        }
+    }
+
+    /// Returns `true` if this request has been answered.
+    pub fn is_answered(&self) -> bool {
+        self.response.is_some()
+    }
+
+    pub(crate) fn into_response<P, R: Any>(self, query: Query<P, R>) -> Option<R> {
+        self.response
+            .and_then(|response| match response.downcast() {
+                Ok(response) => Some(*response),
+                Err(_) => {
+                    debug_panic!(
+                        "The query \"{}\" exists twice with different types.",
+                        query.0
+                    );
+                    None
+                }
+            })
+    }
+}
+
+// The payload is not shown, since it isn't required to be `Debug`.
+impl std::fmt::Debug for Command {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("Command")
+            .field("selector", &self.symbol)
+            .field("target", &self.target)
+            .finish()
+    }
+}
+
 impl std::fmt::Debug for Notification {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(
@@ -615,6 +1228,68 @@ mod tests {
         assert_eq!(command.get(sel), Some(&vec![0, 1, 2]));
     }
 
+    #[test]
+    fn get_notification_payload() {
+        let sel = Selector::new("my-selector");
+        let other = Selector::<u32>::new("other-selector");
+        let note = sel.with(vec![0, 1, 2]).into_notification(WidgetId::next());
+        assert_eq!(note.get(sel), Some(&vec![0, 1, 2]));
+        assert_eq!(note.get_unchecked(sel), &vec![0, 1, 2]);
+        assert_eq!(note.get(other), None);
+    }
+
+    #[test]
+    fn single_use_payload() {
+        let sel = Selector::new("my-selector");
+        let command = sel.with(SingleUse::new(vec![0, 1, 2]));
+        assert!(command.is_single_use());
+        assert!(!command.single_use_taken());
+        assert_eq!(command.get_unchecked(sel).take(), Some(vec![0, 1, 2]));
+        assert!(command.get_unchecked(sel).is_taken());
+        assert!(command.single_use_taken());
+
+        // the payload type is erased on the way through an `ExtEventSink`
+        let payload: Box<dyn Any> = Box::new(SingleUse::new(vec![3]));
+        let command = Command::from_ext(sel.symbol(), sel.type_name(), payload, Target::Global);
+        assert!(command.is_single_use());
+        assert!(!Selector::<u8>::new("other").with(3).is_single_use());
+    }
+
+    #[test]
+    fn debug_shows_selector_and_target() {
+        let command = Selector::<u32>::new("my-selector")
+            .with(3)
+            .to(Target::Global);
+        assert_eq!(
+            format!("{:?}", command),
+            r#"Command { selector: "my-selector", target: Global }"#
+        );
+    }
+
+    #[test]
+    fn type_name_is_the_payload_type() {
+        assert_eq!(Selector::<u32>::new("my-selector").type_name(), "u32");
+        assert_eq!(Selector::NOOP.type_name(), "()");
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    fn registry_reports_conflicting_payload_types() {
+        let symbol = "registry-test-selector";
+        assert_eq!(register_payload_type::<u32>(symbol), None);
+        assert_eq!(register_payload_type::<u32>(symbol), None);
+        assert_eq!(register_payload_type::<String>(symbol), Some("u32"));
+        // each conflicting type is only reported once
+        assert_eq!(register_payload_type::<String>(symbol), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "expected u32 but the payload is alloc::string::String")]
+    fn get_with_wrong_type_names_both_types() {
+        let command = Selector::<String>::new("wrong-type-selector").with("hi".into());
+        command.get(Selector::<u32>::new("wrong-type-selector"));
+    }
+
     #[test]
     fn selector_is_send_and_sync() {
         fn assert_send_sync<T: Send + Sync>() {}
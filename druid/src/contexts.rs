@@ -16,6 +16,7 @@
 
 use std::{
     any::{Any, TypeId},
+    cell::RefCell,
     collections::VecDeque,
     ops::{Deref, DerefMut},
     rc::Rc,
@@ -23,17 +24,20 @@ use std::{
 };
 use tracing::{error, trace, warn};
 
-use crate::core::{CommandQueue, CursorChange, FocusChange, WidgetState};
+use crate::asset_cache::AssetCache;
+use crate::core::{CommandQueue, CursorChange, FocusChange, PointerCaptureChange, WidgetState};
 use crate::env::KeyLike;
 use crate::menu::ContextMenu;
 use crate::piet::{Piet, PietText, RenderContext};
 use crate::shell::text::Event as ImeInvalidation;
 use crate::shell::Region;
-use crate::text::{ImeHandlerRef, TextFieldRegistration};
+use crate::text::{DynTextEngine, ImeHandlerRef, TextFactory, TextFieldRegistration};
 use crate::{
-    commands, sub_window::SubWindowDesc, widget::Widget, Affine, Command, Cursor, Data, Env,
-    ExtEventSink, Insets, Menu, Notification, Point, Rect, SingleUse, Size, Target, TimerToken,
-    Vec2, WidgetId, WindowConfig, WindowDesc, WindowHandle, WindowId,
+    app_delegate::QueryHandler, commands, overlay::OverlayDesc, sub_window::SubWindowDesc,
+    widget::Widget, Affine, AssetKey, AssetState, Command, Cursor, Data, Env, ExtEventSink, Insets,
+    Menu, Notification, OverlayPosition, OverlayToken, Point, Query, QueryRequest, Rect, Request,
+    SingleUse, Size, Target, TimerToken, Vec2, WidgetId, WindowConfig, WindowDesc, WindowHandle,
+    WindowId,
 };
 
 /// A macro for implementing methods on multiple contexts.
@@ -54,12 +58,23 @@ macro_rules! impl_context_method {
 pub(crate) struct ContextState<'a> {
     pub(crate) command_queue: &'a mut CommandQueue,
     pub(crate) ext_handle: &'a ExtEventSink,
+    /// The app's cache of decoded images.
+    pub(crate) asset_cache: &'a AssetCache,
     pub(crate) window_id: WindowId,
     pub(crate) window: &'a WindowHandle,
     pub(crate) text: PietText,
+    /// The engine that `TextLayout`s in this window are built with.
+    pub(crate) text_engine: Option<&'a RefCell<dyn DynTextEngine>>,
     /// The id of the widget that currently has focus.
     pub(crate) focus_widget: Option<WidgetId>,
+    /// The id of the widget that currently holds the pointer capture.
+    pub(crate) pointer_capture: Option<WidgetId>,
+    /// Answers queries from widgets; this is the app delegate, if there is one.
+    pub(crate) query_handler: Option<&'a mut dyn QueryHandler>,
     pub(crate) root_app_data_type: TypeId,
+    /// The ancestors of the widget the current command is targeted at, if the
+    /// window knows them.
+    pub(crate) target_path: Option<&'a [WidgetId]>,
 }
 
 /// A mutable context provided to event handling methods of widgets.
@@ -166,6 +181,18 @@ impl_context_method!(
         pub fn text(&mut self) -> &mut PietText {
             &mut self.state.text
         }
+
+        /// Get a factory that builds [`TextLayout`]s with this window's
+        /// [`TextEngine`].
+        ///
+        /// Pass this to [`TextLayout::rebuild_if_needed`].
+        ///
+        /// [`TextLayout`]: crate::TextLayout
+        /// [`TextEngine`]: crate::text::TextEngine
+        /// [`TextLayout::rebuild_if_needed`]: crate::TextLayout::rebuild_if_needed
+        pub fn text_factory(&mut self) -> TextFactory<'_> {
+            TextFactory::new(&mut self.state.text, self.state.text_engine)
+        }
     }
 );
 
@@ -277,6 +304,15 @@ impl_context_method!(
             self.widget_state.has_focus
         }
 
+        /// Returns `true` if this widget holds the window's pointer capture.
+        ///
+        /// See [`EventCtx::capture_pointer`] for more information.
+        ///
+        /// [`EventCtx::capture_pointer`]: struct.EventCtx.html#method.capture_pointer
+        pub fn is_capturing_pointer(&self) -> bool {
+            self.state.pointer_capture == Some(self.widget_id())
+        }
+
         /// The disabled state of a widget.
         ///
         /// Returns `true` if this widget or any of its ancestors is explicitly disabled.
@@ -358,9 +394,24 @@ impl_context_method!(EventCtx<'_, '_>, UpdateCtx<'_, '_>, LifeCycleCtx<'_, '_>,
     /// Request a [`paint`] pass for redrawing a rectangle, which is given
     /// relative to our layout rectangle.
     ///
+    /// Rects with coordinates that aren't finite are ignored; the first one
+    /// from each widget is logged as a warning.
+    ///
     /// [`paint`]: trait.Widget.html#tymethod.paint
     pub fn request_paint_rect(&mut self, rect: Rect) {
         trace!("request_paint_rect {}", rect);
+        if !rect.is_finite() {
+            if !self.widget_state.warned_non_finite_paint {
+                self.widget_state.warned_non_finite_paint = true;
+                warn!(
+                    "{:?} requested a paint of the non-finite rect {}, ignoring it; \
+                    further ones from this widget will not be reported",
+                    self.widget_id(),
+                    rect
+                );
+            }
+            return;
+        }
         self.widget_state.invalid.add_rect(rect);
     }
 
@@ -450,6 +501,58 @@ impl_context_method!(EventCtx<'_, '_>, UpdateCtx<'_, '_>, LifeCycleCtx<'_, '_>,
         self.submit_command(commands::NEW_SUB_WINDOW.with(SingleUse::new(req)));
         window_id
     }
+
+    /// Show a widget in the overlay layer of the current window.
+    ///
+    /// Overlays float above the rest of the window: they are painted after it,
+    /// in the order they were shown, and get pointer events within their
+    /// bounds before anything below them. They are laid out against the whole
+    /// window, and placed according to `position`.
+    ///
+    /// The overlay stays until it is dismissed with [`dismiss_overlay`], or
+    /// until the current widget is removed.
+    ///
+    /// `T` must be the application's root `Data` type (the type provided to [`AppLauncher::launch`]).
+    ///
+    /// [`dismiss_overlay`]: Self::dismiss_overlay
+    /// [`AppLauncher::launch`]: crate::AppLauncher::launch
+    pub fn show_overlay<T: Any>(
+        &mut self,
+        widget: impl Widget<T> + 'static,
+        position: OverlayPosition,
+    ) -> OverlayToken {
+        trace!("show_overlay");
+        let token = OverlayToken::next();
+        if self.state.root_app_data_type == TypeId::of::<T>() {
+            let desc = OverlayDesc {
+                token,
+                anchor: self.widget_id(),
+                anchor_rect: Rect::from_origin_size(self.window_origin(), self.size()),
+                position,
+                widget: Box::new(widget),
+            };
+            self.submit_command(
+                commands::SHOW_OVERLAY
+                    .with(SingleUse::new(Box::new(desc)))
+                    .to(Target::Window(self.state.window_id)),
+            );
+        } else {
+            debug_panic!("show_overlay<T> - T must match the application data type.");
+        }
+        token
+    }
+
+    /// Dismiss an overlay that was shown with [`show_overlay`].
+    ///
+    /// [`show_overlay`]: Self::show_overlay
+    pub fn dismiss_overlay(&mut self, token: OverlayToken) {
+        trace!("dismiss_overlay");
+        self.submit_command(
+            commands::DISMISS_OVERLAY
+                .with(token)
+                .to(Target::Window(self.state.window_id)),
+        );
+    }
 });
 
 // methods on everyone but paintctx
@@ -475,6 +578,35 @@ impl_context_method!(
             self.state.submit_command(cmd.into())
         }
 
+        /// Get an image from the app's asset cache.
+        ///
+        /// If the image isn't cached, this starts loading it in the background
+        /// and returns [`AssetState::Loading`]; once loading is done, this
+        /// widget receives [`ASSET_LOADED`] and can ask for the image again.
+        ///
+        /// [`AssetState::Loading`]: crate::AssetState::Loading
+        /// [`ASSET_LOADED`]: crate::commands::ASSET_LOADED
+        pub fn get_image(&mut self, key: &AssetKey) -> AssetState {
+            self.state
+                .asset_cache
+                .get_image(key, self.widget_state.id, self.state.ext_handle)
+        }
+
+        /// Send a [`Request`] to `target`, usually a widget.
+        ///
+        /// The response arrives at this widget as a [`Command`]; see
+        /// [`Command::get_response`].
+        pub fn submit_request<P: Any, R>(
+            &mut self,
+            request: Request<P, R>,
+            payload: P,
+            target: impl Into<Target>,
+        ) {
+            trace!("submit_request");
+            let cmd = Command::request(request, payload, target.into(), self.widget_id());
+            self.state.submit_command(cmd)
+        }
+
         /// Returns an [`ExtEventSink`] that can be moved between threads,
         /// and can be used to submit commands back to the application.
         ///
@@ -496,6 +628,22 @@ impl_context_method!(
 );
 
 impl EventCtx<'_, '_> {
+    /// Answer a [`Request`], from the handling of the [`Command`] it came in.
+    ///
+    /// The response is sent to the widget that sent the request. A request
+    /// can only be answered once; later responses are ignored.
+    pub fn submit_response<P, R: Any>(
+        &mut self,
+        cmd: &Command,
+        request: Request<P, R>,
+        response: R,
+    ) {
+        match cmd.response(request, response) {
+            Some(response) => self.state.submit_command(response),
+            None => warn!("{:?} is not an unanswered request for {}", cmd, request),
+        }
+    }
+
     /// Submit a [`Notification`].
     ///
     /// The provided argument can be a [`Selector`] or a [`Command`]; this lets
@@ -525,6 +673,31 @@ impl EventCtx<'_, '_> {
         self.notifications.push_back(note);
     }
 
+    /// Ask the closest [`Scroll`] ancestor to scroll this widget into view.
+    ///
+    /// This is equivalent to calling [`scroll_area_to_view`] with the widget's
+    /// full layout rect.
+    ///
+    /// [`Scroll`]: crate::widget::Scroll
+    /// [`scroll_area_to_view`]: EventCtx::scroll_area_to_view
+    pub fn scroll_to_view(&mut self) {
+        self.scroll_area_to_view(self.size().to_rect());
+    }
+
+    /// Ask the closest [`Scroll`] ancestor to scroll the given area into view.
+    ///
+    /// The `area` is in this widget's coordinate space. This is delivered as a
+    /// [`SCROLL_TO_VIEW`] notification, so it has no effect if the widget is not
+    /// inside a [`Scroll`].
+    ///
+    /// [`Scroll`]: crate::widget::Scroll
+    /// [`SCROLL_TO_VIEW`]: crate::commands::SCROLL_TO_VIEW
+    pub fn scroll_area_to_view(&mut self, area: Rect) {
+        trace!("scroll_area_to_view {}", area);
+        let area = area + self.window_origin().to_vec2();
+        self.submit_notification(commands::SCROLL_TO_VIEW.with(area));
+    }
+
     /// Set the "active" state of the widget.
     ///
     /// See [`EventCtx::is_active`](struct.EventCtx.html#method.is_active).
@@ -534,6 +707,67 @@ impl EventCtx<'_, '_> {
         // TODO: plumb mouse grab through to platform (through druid-shell)
     }
 
+    /// Capture the pointer, so that this widget receives all pointer events in
+    /// the window until it calls [`release_pointer`].
+    ///
+    /// Unlike [`set_active`], which only ensures that an active widget keeps
+    /// receiving pointer events, capture routes pointer events *only* to the
+    /// capturing widget, whether or not a button is pressed and wherever the
+    /// pointer is in the window. Positions are relative to the widget's origin
+    /// as usual; use [`is_hot`] to tell whether the pointer is inside the widget.
+    ///
+    /// Only one widget per window holds the capture. If another widget already
+    /// holds it, that widget loses it. The change is delivered as
+    /// [`LifeCycle::PointerCaptureChanged`] after the current event has been
+    /// handled. The capture is released automatically when the widget is
+    /// removed or disabled.
+    ///
+    /// The capture is local to the window: events that the platform doesn't
+    /// deliver to the window, such as movement outside of it when no button
+    /// is pressed, are not received.
+    ///
+    /// [`release_pointer`]: #method.release_pointer
+    /// [`set_active`]: #method.set_active
+    /// [`is_hot`]: #method.is_hot
+    /// [`LifeCycle::PointerCaptureChanged`]: enum.LifeCycle.html#variant.PointerCaptureChanged
+    pub fn capture_pointer(&mut self) {
+        trace!("capture_pointer");
+        let change = PointerCaptureChange::Capture(self.widget_id());
+        self.widget_state.request_pointer_capture = Some(change);
+    }
+
+    /// Release the pointer capture, if this widget holds it.
+    ///
+    /// See [`capture_pointer`] for more information.
+    ///
+    /// [`capture_pointer`]: #method.capture_pointer
+    pub fn release_pointer(&mut self) {
+        trace!("release_pointer");
+        let change = PointerCaptureChange::Release(self.widget_id());
+        self.widget_state.request_pointer_capture = Some(change);
+    }
+
+    /// Ask the [`AppDelegate`] a question, and get the answer right away.
+    ///
+    /// The delegate's [`query`] method is called before this returns. If there
+    /// is no delegate, or it doesn't answer, this returns `None`.
+    ///
+    /// This is meant for cheap lookups that would otherwise need state to be
+    /// duplicated in the widget tree, such as "is this file already open?".
+    /// The delegate runs on the UI thread while this event is being handled,
+    /// so it should not do any long running work. It cannot call back into
+    /// widgets; commands it submits are run after this event, as usual.
+    ///
+    /// [`AppDelegate`]: trait.AppDelegate.html
+    /// [`query`]: trait.AppDelegate.html#method.query
+    pub fn query<P: Any, R: Any>(&mut self, query: Query<P, R>, payload: P) -> Option<R> {
+        trace!("query {}", query);
+        let handler = self.state.query_handler.as_mut()?;
+        let mut request = QueryRequest::new(query, &payload);
+        handler.query(self.state.command_queue, &mut request);
+        request.into_response(query)
+    }
+
     /// Create a new window.
     /// `T` must be the application's root `Data` type (the type provided to [`AppLauncher::launch`]).
     ///
@@ -883,21 +1117,30 @@ impl PaintCtx<'_, '_, '_> {
 }
 
 impl<'a> ContextState<'a> {
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new<T: 'static>(
         command_queue: &'a mut CommandQueue,
         ext_handle: &'a ExtEventSink,
+        asset_cache: &'a AssetCache,
         window: &'a WindowHandle,
         window_id: WindowId,
         focus_widget: Option<WidgetId>,
+        pointer_capture: Option<WidgetId>,
+        text_engine: Option<&'a RefCell<dyn DynTextEngine>>,
     ) -> Self {
         ContextState {
             command_queue,
             ext_handle,
+            asset_cache,
             window,
             window_id,
             focus_widget,
+            pointer_capture,
+            query_handler: None,
             text: window.text(),
+            text_engine,
             root_app_data_type: TypeId::of::<T>(),
+            target_path: None,
         }
     }
 
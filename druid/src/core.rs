@@ -18,12 +18,17 @@ use std::collections::{HashMap, VecDeque};
 use tracing::{info_span, trace, warn};
 
 use crate::bloom::Bloom;
-use crate::command::sys::{CLOSE_WINDOW, SUB_WINDOW_HOST_TO_PARENT, SUB_WINDOW_PARENT_TO_HOST};
+use crate::command::sys::{
+    CLOSE_WINDOW, GIVE_UP_FOCUS, SUB_WINDOW_HOST_TO_PARENT, SUB_WINDOW_PARENT_TO_HOST, TAKE_FOCUS,
+};
 use crate::contexts::ContextState;
 use crate::kurbo::{Affine, Insets, Point, Rect, Shape, Size, Vec2};
 use crate::sub_window::SubWindowUpdate;
 use crate::text::TextFieldRegistration;
 use crate::util::ExtendDrain;
+use crate::widget::{Axis, Intrinsic};
+#[cfg(feature = "debug_state")]
+use crate::DebugState;
 use crate::{
     ArcStr, BoxConstraints, Color, Command, Cursor, Data, Env, Event, EventCtx, InternalEvent,
     InternalLifeCycle, LayoutCtx, LifeCycle, LifeCycleCtx, Notification, PaintCtx, Region,
@@ -33,6 +38,10 @@ use crate::{
 /// Our queue type
 pub(crate) type CommandQueue = VecDeque<Command>;
 
+/// An intrinsic size, and the axis, kind and cross-axis constraints
+/// it was computed for.
+type IntrinsicEntry = (Axis, Intrinsic, f64, f64, f64);
+
 /// A container for one widget in the hierarchy.
 ///
 /// Generally, container widgets don't contain other widgets directly,
@@ -55,6 +64,11 @@ pub struct WidgetPod<T, W> {
     inner: W,
     // stashed layout so we don't recompute this when debugging
     debug_widget_text: TextLayout<ArcStr>,
+    // the constraints of the last layout pass, when `Env::DEBUG_PAINT` is set
+    #[cfg(feature = "debug_state")]
+    debug_constraints: Option<BoxConstraints>,
+    // intrinsic sizes computed since the widget last asked for layout
+    intrinsic_cache: Vec<IntrinsicEntry>,
 }
 
 /// Generic state for all widgets in the hierarchy.
@@ -137,6 +151,10 @@ pub struct WidgetState {
     /// Descendants of the focused widget are not in the focused path.
     pub(crate) has_focus: bool,
 
+    /// In the pointer capture path, starting from window and ending at the widget
+    /// that holds the pointer capture.
+    pub(crate) has_pointer_capture: bool,
+
     /// Any descendant has requested an animation frame.
     pub(crate) request_anim: bool,
 
@@ -147,6 +165,7 @@ pub struct WidgetState {
 
     pub(crate) focus_chain: Vec<WidgetId>,
     pub(crate) request_focus: Option<FocusChange>,
+    pub(crate) request_pointer_capture: Option<PointerCaptureChange>,
     pub(crate) children: Bloom<WidgetId>,
     pub(crate) children_changed: bool,
     /// Associate timers with widgets that requested them.
@@ -161,6 +180,10 @@ pub struct WidgetState {
     pub(crate) sub_window_hosts: Vec<(WindowId, WidgetId)>,
 
     pub(crate) text_registrations: Vec<TextFieldRegistration>,
+
+    /// Set once this widget has been warned about requesting a paint of a
+    /// non-finite rect, so that the warning is not repeated every frame.
+    pub(crate) warned_non_finite_paint: bool,
 }
 
 /// Methods by which a widget can attempt to change focus state.
@@ -176,6 +199,15 @@ pub(crate) enum FocusChange {
     Previous,
 }
 
+/// Methods by which a widget can change the window's pointer capture.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum PointerCaptureChange {
+    /// A specific widget wants to capture the pointer.
+    Capture(WidgetId),
+    /// A specific widget is releasing the pointer, if it holds it.
+    Release(WidgetId),
+}
+
 /// The possible cursor states for a widget.
 #[derive(Clone, Debug)]
 pub(crate) enum CursorChange {
@@ -204,6 +236,9 @@ impl<T, W: Widget<T>> WidgetPod<T, W> {
             env: None,
             inner,
             debug_widget_text: TextLayout::new(),
+            #[cfg(feature = "debug_state")]
+            debug_constraints: None,
+            intrinsic_cache: Vec::new(),
         }
     }
 
@@ -238,6 +273,14 @@ impl<T, W: Widget<T>> WidgetPod<T, W> {
         self.state.is_hot
     }
 
+    /// Returns `true` if this widget or any descendant holds the pointer capture.
+    ///
+    /// See [`EventCtx::capture_pointer`](struct.EventCtx.html#method.capture_pointer)
+    /// for additional information.
+    pub fn has_pointer_capture(&self) -> bool {
+        self.state.has_pointer_capture
+    }
+
     /// Get the identity of the widget.
     pub fn id(&self) -> WidgetId {
         self.state.id
@@ -263,6 +306,10 @@ impl<T, W: Widget<T>> WidgetPod<T, W> {
     ///
     /// The child will receive the [`LifeCycle::Size`] event informing them of the final [`Size`].
     ///
+    /// The order matters: every time a child is laid out, it has to be given
+    /// its origin before it is painted. In debug builds, painting a child that
+    /// has been laid out but not positioned panics.
+    ///
     /// [`Widget::layout`]: trait.Widget.html#tymethod.layout
     /// [`Rect`]: struct.Rect.html
     /// [`Size`]: struct.Size.html
@@ -283,6 +330,7 @@ impl<T, W: Widget<T>> WidgetPod<T, W> {
             data,
             env,
         ) {
+            self.clear_stale_intrinsics();
             ctx.widget_state.merge_up(&mut self.state);
         }
     }
@@ -300,6 +348,29 @@ impl<T, W: Widget<T>> WidgetPod<T, W> {
         self.state.layout_rect()
     }
 
+    /// Describe the child widget, along with its id and layout rect.
+    ///
+    /// See [`Widget::debug_state`] for more information.
+    ///
+    /// [`Widget::debug_state`]: trait.Widget.html#method.debug_state
+    #[cfg(feature = "debug_state")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "debug_state")))]
+    pub fn debug_state(&self, data: &T) -> DebugState {
+        let mut state = DebugState {
+            widget_id: Some(self.id()),
+            layout_rect: Some(self.layout_rect()),
+            ..self.inner.debug_state(data)
+        };
+        if let Some(bc) = self.debug_constraints {
+            let constraints = format!("{} ..= {}", bc.min(), bc.max());
+            state.other_values.insert("constraints".into(), constraints);
+            state
+                .other_values
+                .insert("size".into(), self.state.size.to_string());
+        }
+        state
+    }
+
     /// Set the viewport offset.
     ///
     /// This is relevant only for children of a scroll view (or similar). It must
@@ -473,6 +544,14 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
 
     /// Shared implementation that can skip drawing non-visible content.
     fn paint_impl(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env, paint_if_not_visible: bool) {
+        if self.state.is_expecting_set_origin_call {
+            debug_panic!(
+                "{:?}: paint method called before set_origin; a container must call \
+                 layout and then set_origin on a child before painting it.",
+                self.state.id
+            );
+        }
+
         if !paint_if_not_visible && !ctx.region().intersects(self.state.paint_rect()) {
             return;
         }
@@ -510,7 +589,8 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
             self.debug_widget_text.set_text(id_string.into());
             self.debug_widget_text.set_text_size(10.0);
             self.debug_widget_text.set_text_color(text_color);
-            self.debug_widget_text.rebuild_if_needed(ctx.text(), env);
+            self.debug_widget_text
+                .rebuild_if_needed(ctx.text_factory(), env);
         }
     }
 
@@ -574,6 +654,14 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
             mouse_pos: child_mouse_pos,
         };
 
+        #[cfg(feature = "debug_state")]
+        {
+            self.debug_constraints = if env.get(Env::DEBUG_PAINT) {
+                Some(*bc)
+            } else {
+                None
+            };
+        }
         let new_size = self.inner.layout(&mut child_ctx, bc, data, env);
         if new_size != prev_size {
             let mut child_ctx = LifeCycleCtx {
@@ -595,6 +683,62 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
         new_size
     }
 
+    /// Compute an intrinsic size of the widget.
+    ///
+    /// Generally called by container widgets as part of their [`layout`] or
+    /// [`compute_intrinsic_size`] methods. See
+    /// [`Widget::compute_intrinsic_size`] for what the arguments mean.
+    ///
+    /// The results are cached until the widget asks for layout again, so a
+    /// container can ask its children for their intrinsic sizes without the
+    /// cost growing exponentially with the depth of the tree.
+    ///
+    /// [`layout`]: trait.Widget.html#tymethod.layout
+    /// [`compute_intrinsic_size`]: trait.Widget.html#method.compute_intrinsic_size
+    /// [`Widget::compute_intrinsic_size`]: trait.Widget.html#method.compute_intrinsic_size
+    pub fn compute_intrinsic_size(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        axis: Axis,
+        kind: Intrinsic,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        if !self.is_initialized() {
+            debug_panic!(
+                "{:?}: compute_intrinsic_size method called before receiving WidgetAdded.",
+                ctx.widget_id()
+            );
+            return 0.0;
+        }
+
+        // Only the constraints on the other axis matter.
+        let (min, max) = (axis.minor(bc.min()), axis.minor(bc.max()));
+        let cached = self
+            .intrinsic_cache
+            .iter()
+            .find(|entry| entry.0 == axis && entry.1 == kind && entry.2 == min && entry.3 == max);
+        if let Some(entry) = cached {
+            return entry.4;
+        }
+
+        let child_mouse_pos = ctx
+            .mouse_pos
+            .map(|pos| pos - self.layout_rect().origin().to_vec2() + self.viewport_offset());
+        let mut child_ctx = LayoutCtx {
+            widget_state: &mut self.state,
+            state: ctx.state,
+            mouse_pos: child_mouse_pos,
+        };
+        let value = self
+            .inner
+            .compute_intrinsic_size(&mut child_ctx, axis, kind, bc, data, env);
+        ctx.widget_state.merge_up(child_ctx.widget_state);
+        self.intrinsic_cache.push((axis, kind, min, max, value));
+        value
+    }
+
     fn log_layout_issues(&self, size: Size) {
         if size.width.is_infinite() {
             let name = self.widget().type_name();
@@ -658,6 +802,7 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
             return;
         }
         let had_active = self.state.has_active;
+        let has_capture = self.state.has_pointer_capture;
         let rect = self.layout_rect();
 
         // If we need to replace either the event or its data.
@@ -683,11 +828,13 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
                             modified_event = Some(Event::Command(cmd.clone()));
                             true
                         }
-                        Target::Widget(id) => {
+                        Target::Widget(id) => match ctx.state.target_path {
+                            // The window knows the way to the target.
+                            Some(path) => path.contains(&self.id()),
                             // Recurse when the target widget could be our descendant.
                             // The bloom filter we're checking can return false positives.
-                            self.state.children.may_contain(&id)
-                        }
+                            None => self.state.children.may_contain(&id),
+                        },
                         Target::Global | Target::Window(_) => {
                             modified_event = Some(Event::Command(cmd.clone()));
                             true
@@ -723,6 +870,16 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
                 self.state.needs_layout = true;
                 ctx.is_root
             }
+            // While the pointer is captured, only the capture path sees pointer events.
+            Event::MouseDown(_)
+            | Event::MouseUp(_)
+            | Event::MouseMove(_)
+            | Event::Wheel(_)
+            | Event::Zoom(_)
+                if ctx.state.pointer_capture.is_some() && !has_capture =>
+            {
+                false
+            }
             Event::MouseDown(mouse_event) => {
                 WidgetPod::set_hot_state(
                     &mut self.inner,
@@ -733,7 +890,7 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
                     data,
                     env,
                 );
-                if had_active || self.state.is_hot {
+                if had_active || has_capture || self.state.is_hot {
                     let mut mouse_event = mouse_event.clone();
                     mouse_event.pos -= rect.origin().to_vec2();
                     modified_event = Some(Event::MouseDown(mouse_event));
@@ -752,7 +909,7 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
                     data,
                     env,
                 );
-                if had_active || self.state.is_hot {
+                if had_active || has_capture || self.state.is_hot {
                     let mut mouse_event = mouse_event.clone();
                     mouse_event.pos -= rect.origin().to_vec2();
                     modified_event = Some(Event::MouseUp(mouse_event));
@@ -774,7 +931,7 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
                 // MouseMove is recursed even if the widget is not active and not hot,
                 // but was hot previously. This is to allow the widget to respond to the movement,
                 // e.g. drag functionality where the widget wants to follow the mouse.
-                if had_active || self.state.is_hot || hot_changed {
+                if had_active || has_capture || self.state.is_hot || hot_changed {
                     let mut mouse_event = mouse_event.clone();
                     mouse_event.pos -= rect.origin().to_vec2();
                     modified_event = Some(Event::MouseMove(mouse_event));
@@ -793,7 +950,7 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
                     data,
                     env,
                 );
-                if had_active || self.state.is_hot {
+                if had_active || has_capture || self.state.is_hot {
                     let mut mouse_event = mouse_event.clone();
                     mouse_event.pos -= rect.origin().to_vec2();
                     modified_event = Some(Event::Wheel(mouse_event));
@@ -810,7 +967,7 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
             Event::KeyDown(_) => self.state.has_focus,
             Event::KeyUp(_) => self.state.has_focus,
             Event::Paste(_) => self.state.has_focus,
-            Event::Zoom(_) => had_active || self.state.is_hot,
+            Event::Zoom(_) => had_active || has_capture || self.state.is_hot,
             Event::Timer(_) => false, // This event was targeted only to our parent
             Event::ImeStateChange => true, // once delivered to the focus widget, recurse to the component?
             Event::Command(_) => true,
@@ -839,11 +996,29 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
                     }
                     ctx.is_handled = true
                 }
+                // the window sends these to the widget they are about, after
+                // checking a `REQUEST_FOCUS` or `RESIGN_FOCUS`
+                Event::Command(cmd)
+                    if cmd.target() == Target::Widget(inner_ctx.widget_id())
+                        && (cmd.is(TAKE_FOCUS) || cmd.is(GIVE_UP_FOCUS)) =>
+                {
+                    if cmd.is(TAKE_FOCUS) {
+                        inner_ctx.request_focus();
+                        inner_ctx.scroll_to_view();
+                    } else {
+                        inner_ctx.resign_focus();
+                    }
+                    ctx.is_handled = true
+                }
                 _ => {
                     self.inner.event(&mut inner_ctx, &inner_event, data, env);
 
                     inner_ctx.widget_state.has_active |= inner_ctx.widget_state.is_active;
                     ctx.is_handled |= inner_ctx.is_handled;
+                    // once the payload is gone there is nothing left for other widgets
+                    if let Event::Command(cmd) = &inner_event {
+                        ctx.is_handled |= cmd.single_use_taken();
+                    }
                 }
             }
 
@@ -855,6 +1030,7 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
 
         // Always merge even if not needed, because merging is idempotent and gives us simpler code.
         // Doing this conditionally only makes sense when there's a measurable performance boost.
+        self.clear_stale_intrinsics();
         ctx.widget_state.merge_up(&mut self.state);
     }
 
@@ -970,6 +1146,22 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
                         _ => false,
                     }
                 }
+                InternalLifeCycle::RoutePointerCaptureChanged { old, new } => {
+                    if *old == Some(self.state.id) {
+                        extra_event = Some(LifeCycle::PointerCaptureChanged(false));
+                    } else if *new == Some(self.state.id) {
+                        extra_event = Some(LifeCycle::PointerCaptureChanged(true));
+                    }
+                    self.state.has_pointer_capture = *new == Some(self.state.id);
+
+                    // Recurse when the target widgets could be our descendants.
+                    // The bloom filter we're checking can return false positives.
+                    match (old, new) {
+                        (Some(old), _) if self.state.children.may_contain(old) => true,
+                        (_, Some(new)) if self.state.children.may_contain(new) => true,
+                        _ => false,
+                    }
+                }
                 InternalLifeCycle::ParentWindowOrigin => {
                     self.state.parent_window_origin = ctx.widget_state.window_origin();
                     self.state.needs_window_origin = false;
@@ -989,6 +1181,10 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
                     f.call(&self.state);
                     true
                 }
+                InternalLifeCycle::CollectWidgetPaths(collector) => {
+                    collector.enter(self.id());
+                    true
+                }
             },
             LifeCycle::WidgetAdded => {
                 assert!(self.old_data.is_none());
@@ -1034,6 +1230,10 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
                 // Descendants don't inherit focus, so don't recurse.
                 false
             }
+            LifeCycle::PointerCaptureChanged(_) => {
+                // Like focus, the pointer capture isn't inherited.
+                false
+            }
             LifeCycle::BuildFocusChain => {
                 if self.state.update_focus_chain {
                     // Replace has_focus to check if the value changed in the meantime
@@ -1079,6 +1279,10 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
                     // FocusChange is routed after we updated the focus-chain.
                     self.state.request_focus = Some(FocusChange::Resign);
                 }
+                if self.state.is_disabled() && ctx.state.pointer_capture == Some(self.state.id) {
+                    let release = PointerCaptureChange::Release(self.state.id);
+                    self.state.request_pointer_capture = Some(release);
+                }
 
                 // Delete changes of disabled state that happened during DisabledChanged to avoid
                 // recursions.
@@ -1102,9 +1306,13 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
                     ctx.widget_state.focus_chain.extend(&self.state.focus_chain);
                 }
             }
+            LifeCycle::Internal(InternalLifeCycle::CollectWidgetPaths(collector)) => {
+                collector.leave();
+            }
             _ => (),
         }
 
+        self.clear_stale_intrinsics();
         ctx.widget_state.merge_up(&mut self.state);
     }
 
@@ -1169,6 +1377,7 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
         self.env = Some(env.clone());
 
         self.state.request_update = false;
+        self.clear_stale_intrinsics();
         ctx.widget_state.merge_up(&mut self.state);
     }
 }
@@ -1193,6 +1402,13 @@ impl<T, W> WidgetPod<T, W> {
     pub fn widget_mut(&mut self) -> &mut W {
         &mut self.inner
     }
+    /// Forget the intrinsic sizes if the widget asked for layout, since they
+    /// may have changed.
+    fn clear_stale_intrinsics(&mut self) {
+        if self.state.needs_layout {
+            self.intrinsic_cache.clear();
+        }
+    }
 }
 
 impl WidgetState {
@@ -1216,9 +1432,11 @@ impl WidgetState {
             is_active: false,
             has_active: false,
             has_focus: false,
+            has_pointer_capture: false,
             request_anim: false,
             request_update: false,
             request_focus: None,
+            request_pointer_capture: None,
             focus_chain: Vec::new(),
             children: Bloom::new(),
             children_changed: false,
@@ -1229,6 +1447,7 @@ impl WidgetState {
             is_explicitly_disabled_new: false,
             text_registrations: Vec::new(),
             update_focus_chain: false,
+            warned_non_finite_paint: false,
         }
     }
 
@@ -1284,6 +1503,11 @@ impl WidgetState {
         self.children_changed |= child_state.children_changed;
         self.request_update |= child_state.request_update;
         self.request_focus = child_state.request_focus.take().or(self.request_focus);
+        self.has_pointer_capture |= child_state.has_pointer_capture;
+        self.request_pointer_capture = child_state
+            .request_pointer_capture
+            .take()
+            .or(self.request_pointer_capture);
         self.timers.extend_drain(&mut child_state.timers);
         self.text_registrations
             .extend(child_state.text_registrations.drain(..));
@@ -1351,6 +1575,7 @@ impl CursorChange {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::asset_cache::AssetCache;
     use crate::ext_event::ExtEventHost;
     use crate::text::ParseFormatter;
     use crate::widget::{Flex, Scroll, Split, TextBox};
@@ -1393,12 +1618,16 @@ mod tests {
         let window = WindowHandle::default();
         let ext_host = ExtEventHost::default();
         let ext_handle = ext_host.make_sink();
+        let asset_cache = AssetCache::default();
         let mut state = ContextState::new::<Option<u32>>(
             &mut command_queue,
             &ext_handle,
+            &asset_cache,
             &window,
             WindowId::next(),
             None,
+            None,
+            None,
         );
 
         let mut ctx = LifeCycleCtx {
@@ -19,8 +19,9 @@ use std::rc::Rc;
 use std::sync::Arc;
 
 use crate::kurbo::{self, ParamCurve};
+use crate::lens::{InVersioned, Lens};
 use crate::piet;
-use crate::shell::Scale;
+use crate::shell::{Counter, Scale};
 
 pub use druid_derive::Data;
 use piet::ImageBuf;
@@ -556,7 +557,9 @@ impl Data for ImageBuf {
     }
 }
 
+// `Date` is deprecated in newer versions of chrono, but still supported.
 #[cfg(feature = "chrono")]
+#[allow(deprecated)]
 impl<Tz: chrono::offset::TimeZone + 'static> Data for chrono::Date<Tz> {
     fn same(&self, other: &Self) -> bool {
         self == other
@@ -617,9 +620,112 @@ impl<T: Data, const N: usize> Data for [T; N] {
     }
 }
 
+/// Shared data with a version, so that it can be compared without looking
+/// inside.
+///
+/// A `Versioned` holds its value in an [`Arc`], along with a version that
+/// changes every time the value is mutated through [`make_mut`] or
+/// [`map_inner`]. [`Data::same`] only compares the versions, which makes it
+/// a good fit for large values, such as a parsed document, that would be slow
+/// to compare field by field.
+///
+/// Versions are unique across all `Versioned` values, so two clones that are
+/// mutated separately never look the same.
+///
+/// The version can only be trusted if the value is never changed behind its
+/// back; mutating it through interior mutability, for instance, will not be
+/// noticed by widgets.
+///
+/// Use [`LensExt::in_versioned`] to lens into the value.
+///
+/// ```
+/// # use druid::{Data, Versioned};
+/// let doc = Versioned::new(vec![1, 2, 3]);
+/// let mut edited = doc.clone();
+/// assert!(doc.same(&edited));
+/// edited.make_mut().push(4);
+/// assert!(!doc.same(&edited));
+/// ```
+///
+/// [`make_mut`]: Versioned::make_mut
+/// [`map_inner`]: Versioned::map_inner
+/// [`LensExt::in_versioned`]: crate::LensExt::in_versioned
+#[derive(Debug, Default)]
+pub struct Versioned<T> {
+    inner: Arc<T>,
+    version: u64,
+}
+
+impl<T> Versioned<T> {
+    /// Wrap a value.
+    pub fn new(value: T) -> Self {
+        Versioned {
+            inner: Arc::new(value),
+            version: Versioned::<T>::next_version(),
+        }
+    }
+
+    /// The current version of the value.
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    fn next_version() -> u64 {
+        static VERSION_COUNTER: Counter = Counter::new();
+        VERSION_COUNTER.next()
+    }
+}
+
+impl<T: Clone> Versioned<T> {
+    /// Get mutable access to the value, and give it a new version.
+    ///
+    /// Like [`Arc::make_mut`], this clones the value if it is shared.
+    pub fn make_mut(&mut self) -> &mut T {
+        self.version = Versioned::<T>::next_version();
+        Arc::make_mut(&mut self.inner)
+    }
+
+    /// Change the part of the value that `lens` points to.
+    ///
+    /// The value is only cloned, and given a new version, if `f` leaves the
+    /// part different from what it was.
+    pub fn map_inner<U: Data, V>(
+        &mut self,
+        lens: impl Lens<T, U>,
+        f: impl FnOnce(&mut U) -> V,
+    ) -> V {
+        InVersioned::new(lens).with_mut(self, f)
+    }
+}
+
+impl<T> Clone for Versioned<T> {
+    fn clone(&self) -> Self {
+        Versioned {
+            inner: self.inner.clone(),
+            version: self.version,
+        }
+    }
+}
+
+impl<T> std::ops::Deref for Versioned<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.inner
+    }
+}
+
+impl<T: 'static> Data for Versioned<T> {
+    fn same(&self, other: &Self) -> bool {
+        self.version == other.version
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use super::Data;
+    use super::{Data, Versioned};
+    use crate::lens::{self, LensExt};
+    use std::sync::Arc;
     use test_env_log::test;
 
     #[test]
@@ -649,6 +755,43 @@ mod test {
         assert!(!one.same(&two));
     }
 
+    #[test]
+    fn versioned_changes_version_on_mutation() {
+        let doc = Versioned::new(vec![1, 2, 3]);
+        let mut edited = doc.clone();
+        assert!(doc.same(&edited));
+
+        edited.make_mut()[0] = 10;
+        assert!(!doc.same(&edited));
+        assert_eq!(*doc, vec![1, 2, 3]);
+        assert_eq!(*edited, vec![10, 2, 3]);
+
+        // clones that are mutated separately don't end up the same
+        let mut other = doc.clone();
+        other.make_mut()[0] = 10;
+        assert!(!other.same(&edited));
+    }
+
+    #[test]
+    fn versioned_map_inner_only_changes_on_difference() {
+        let mut doc = Versioned::new(vec![1, 2, 3]);
+        let version = doc.version();
+        let original = doc.clone();
+
+        doc.map_inner(lens::Identity.index(1), |x| *x = 2);
+        assert_eq!(doc.version(), version);
+        assert!(
+            Arc::ptr_eq(&doc.inner, &original.inner),
+            "no copy on a no-op write"
+        );
+
+        let old = doc.map_inner(lens::Identity.index(1), |x| std::mem::replace(x, 20));
+        assert_eq!(old, 2);
+        assert!(doc.version() > version);
+        assert_eq!(*doc, vec![1, 20, 3]);
+        assert_eq!(*original, vec![1, 2, 3]);
+    }
+
     #[test]
     fn static_strings() {
         let first = "test";
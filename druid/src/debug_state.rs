@@ -0,0 +1,121 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A description of the widget tree, for tests and tooling.
+
+use std::collections::HashMap;
+
+use crate::kurbo::Rect;
+use crate::WidgetId;
+
+/// A snapshot of a widget and its descendants.
+///
+/// This is returned by [`Widget::debug_state`], and is meant for inspecting a
+/// widget tree from outside of it: for assertions in tests, for debugging
+/// tools, and eventually for accessibility.
+///
+/// Widgets fill in the name, value and children; the [`WidgetPod`] that
+/// contains a widget fills in its id and layout rect.
+///
+/// [`Widget::debug_state`]: crate::Widget::debug_state
+/// [`WidgetPod`]: crate::WidgetPod
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DebugState {
+    /// The name of the widget, usually its type.
+    pub display_name: String,
+    /// The most important value of the widget, such as a label's text.
+    pub main_value: String,
+    /// Any other values that describe the widget's state.
+    pub other_values: HashMap<String, String>,
+    /// The states of this widget's children.
+    pub children: Vec<DebugState>,
+    /// The widget's layout rect, in its parent's coordinate space.
+    ///
+    /// This is only known for widgets that are directly inside a [`WidgetPod`].
+    ///
+    /// [`WidgetPod`]: crate::WidgetPod
+    pub layout_rect: Option<Rect>,
+    /// The widget's id, if it is directly inside a [`WidgetPod`].
+    ///
+    /// [`WidgetPod`]: crate::WidgetPod
+    pub widget_id: Option<WidgetId>,
+}
+
+impl DebugState {
+    /// Create a state with the given name, and no value or children.
+    pub fn new(display_name: impl Into<String>) -> Self {
+        DebugState {
+            display_name: display_name.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Create a state with a name and a main value.
+    pub fn with_value(display_name: impl Into<String>, main_value: impl Into<String>) -> Self {
+        DebugState {
+            main_value: main_value.into(),
+            ..DebugState::new(display_name)
+        }
+    }
+
+    /// Create a state with a name and children.
+    pub fn with_children(display_name: impl Into<String>, children: Vec<DebugState>) -> Self {
+        DebugState {
+            children,
+            ..DebugState::new(display_name)
+        }
+    }
+
+    /// The default state for a widget type: its short type name.
+    pub(crate) fn for_type(type_name: &str) -> Self {
+        DebugState::new(short_type_name(type_name))
+    }
+
+    /// Iterate over this state and all of its descendants, depth first.
+    pub fn iter(&self) -> impl Iterator<Item = &DebugState> {
+        let mut stack = vec![self];
+        std::iter::from_fn(move || {
+            let next = stack.pop()?;
+            stack.extend(next.children.iter().rev());
+            Some(next)
+        })
+    }
+
+    /// Find the first state, depth first, that satisfies `predicate`.
+    pub fn find(&self, predicate: impl Fn(&DebugState) -> bool) -> Option<&DebugState> {
+        self.iter().find(|state| predicate(state))
+    }
+}
+
+/// `druid::widget::Label<u32>` -> `Label`
+fn short_type_name(type_name: &str) -> &str {
+    let base = type_name.split('<').next().unwrap_or(type_name);
+    base.rsplit("::").next().unwrap_or(base)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_env_log::test;
+
+    #[test]
+    fn type_names() {
+        assert_eq!(short_type_name("druid::widget::Label<u32>"), "Label");
+        assert_eq!(
+            short_type_name("druid::widget::Flex<alloc::string::String>"),
+            "Flex"
+        );
+        assert_eq!(short_type_name("Spinner"), "Spinner");
+    }
+}
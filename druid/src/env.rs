@@ -200,6 +200,22 @@ pub struct MissingKeyError {
     key: Arc<str>,
 }
 
+/// A change to the application's [`Env`], submitted with the
+/// [`UPDATE_ENV`] command.
+///
+/// # Examples
+///
+/// ```
+/// use druid::{commands, theme, Color, EnvUpdate};
+///
+/// let command = commands::UPDATE_ENV.with(EnvUpdate::new(|env| {
+///     env.set(theme::WINDOW_BACKGROUND_COLOR, Color::WHITE);
+/// }));
+/// ```
+///
+/// [`UPDATE_ENV`]: crate::commands::UPDATE_ENV
+pub struct EnvUpdate(Box<dyn Fn(&mut Env)>);
+
 impl Env {
     /// State for whether or not to paint colorful rectangles for layout
     /// debugging.
@@ -536,6 +552,23 @@ impl<T> From<Key<T>> for ArcStr {
     }
 }
 
+impl EnvUpdate {
+    /// Create an update from a closure that sets values on the env.
+    pub fn new(f: impl Fn(&mut Env) + 'static) -> EnvUpdate {
+        EnvUpdate(Box::new(f))
+    }
+
+    pub(crate) fn apply(&self, env: &mut Env) {
+        (self.0)(env)
+    }
+}
+
+impl Debug for EnvUpdate {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        f.write_str("EnvUpdate")
+    }
+}
+
 impl ValueTypeError {
     fn new(expected: &'static str, found: Value) -> ValueTypeError {
         ValueTypeError { expected, found }
@@ -303,6 +303,16 @@ pub enum LifeCycle {
     ///
     /// [`EventCtx::is_focused`]: struct.EventCtx.html#method.is_focused
     FocusChanged(bool),
+    /// Called when the pointer capture changes.
+    ///
+    /// The widget that captured the pointer will receive this with `true`,
+    /// and the widget that lost the capture, whether because it released it,
+    /// another widget took it, or it was disabled, will receive this with `false`.
+    ///
+    /// See [`EventCtx::capture_pointer`] for more information.
+    ///
+    /// [`EventCtx::capture_pointer`]: struct.EventCtx.html#method.capture_pointer
+    PointerCaptureChanged(bool),
     /// Internal druid lifecycle event.
     ///
     /// This should always be passed down to descendant [`WidgetPod`]s.
@@ -329,6 +339,13 @@ pub enum InternalLifeCycle {
         /// the widget that is gaining focus, if any
         new: Option<WidgetId>,
     },
+    /// Used to route the `PointerCaptureChanged` event.
+    RoutePointerCaptureChanged {
+        /// the widget that is losing the pointer capture, if any
+        old: Option<WidgetId>,
+        /// the widget that is gaining the pointer capture, if any
+        new: Option<WidgetId>,
+    },
     /// Used to route the `DisabledChanged` event to the required widgets.
     RouteDisabledChanged,
     /// The parents widget origin in window coordinate space has changed.
@@ -347,6 +364,9 @@ pub enum InternalLifeCycle {
     },
     /// For testing: apply the given function on every widget.
     DebugInspectState(StateCheckFn),
+    /// Used to record the path to every widget, so that commands can be
+    /// routed straight to their target.
+    CollectWidgetPaths(PathCollector),
 }
 
 impl Event {
@@ -433,6 +453,7 @@ impl LifeCycle {
             LifeCycle::Size(_)
             | LifeCycle::HotChanged(_)
             | LifeCycle::FocusChanged(_)
+            | LifeCycle::PointerCaptureChanged(_)
             | LifeCycle::BuildFocusChain => false,
         }
     }
@@ -445,15 +466,18 @@ impl InternalLifeCycle {
         match self {
             InternalLifeCycle::RouteWidgetAdded
             | InternalLifeCycle::RouteFocusChanged { .. }
+            | InternalLifeCycle::RoutePointerCaptureChanged { .. }
             | InternalLifeCycle::RouteDisabledChanged => true,
             InternalLifeCycle::ParentWindowOrigin => false,
             InternalLifeCycle::DebugRequestState { .. }
-            | InternalLifeCycle::DebugInspectState(_) => true,
+            | InternalLifeCycle::DebugInspectState(_)
+            | InternalLifeCycle::CollectWidgetPaths(_) => true,
         }
     }
 }
 
 pub(crate) use state_cell::{StateCell, StateCheckFn};
+pub(crate) use widget_paths::PathCollector;
 
 mod state_cell {
     use crate::core::WidgetState;
@@ -523,3 +547,46 @@ mod state_cell {
         }
     }
 }
+
+mod widget_paths {
+    use crate::WidgetId;
+    use std::collections::HashMap;
+    use std::{cell::RefCell, rc::Rc};
+
+    /// Records the ancestors of every widget it visits.
+    #[derive(Clone, Default)]
+    pub struct PathCollector(Rc<RefCell<PathState>>);
+
+    #[derive(Default)]
+    struct PathState {
+        /// The widgets we are currently inside of, starting at the root.
+        stack: Vec<WidgetId>,
+        paths: HashMap<WidgetId, Vec<WidgetId>>,
+    }
+
+    impl PathCollector {
+        /// Called when a widget is visited, before its children.
+        pub(crate) fn enter(&self, widget: WidgetId) {
+            let mut state = self.0.borrow_mut();
+            let path = state.stack.clone();
+            state.paths.insert(widget, path);
+            state.stack.push(widget);
+        }
+
+        /// Called when a widget and all its children have been visited.
+        pub(crate) fn leave(&self) {
+            self.0.borrow_mut().stack.pop();
+        }
+
+        /// The ancestors of each visited widget, starting at the root.
+        pub(crate) fn take(&self) -> HashMap<WidgetId, Vec<WidgetId>> {
+            std::mem::take(&mut self.0.borrow_mut().paths)
+        }
+    }
+
+    impl std::fmt::Debug for PathCollector {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "PathCollector({} paths)", self.0.borrow().paths.len())
+        }
+    }
+}
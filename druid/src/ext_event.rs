@@ -18,11 +18,12 @@ use std::any::Any;
 use std::collections::VecDeque;
 use std::sync::{Arc, Mutex};
 
+use crate::command::{register_payload_type, SelectorSymbol};
 use crate::shell::IdleHandle;
 use crate::win_handler::EXT_EVENT_IDLE_TOKEN;
-use crate::{command::SelectorSymbol, Command, Selector, Target, WindowId};
+use crate::{Command, Selector, Target, WindowId};
 
-pub(crate) type ExtCommand = (SelectorSymbol, Box<dyn Any + Send>, Target);
+pub(crate) type ExtCommand = (SelectorSymbol, &'static str, Box<dyn Any + Send>, Target);
 
 /// A thing that can move into other threads and be used to submit commands back
 /// to the running application.
@@ -80,7 +81,9 @@ impl ExtEventHost {
             .lock()
             .unwrap()
             .pop_front()
-            .map(|(selector, payload, target)| Command::from_ext(selector, payload, target))
+            .map(|(selector, payload_type, payload, target)| {
+                Command::from_ext(selector, payload_type, payload, target)
+            })
     }
 }
 
@@ -91,14 +94,71 @@ impl ExtEventSink {
     /// instead you have to pass the [`Selector`] and the payload
     /// separately, and it will be turned into a [`Command`] when it is received.
     ///
-    /// The `payload` must implement `Any + Send`.
+    /// The `payload` must implement `Any + Send`. It is delivered as an
+    /// ordinary [`Command`], so [`Command::get`] checks its type against the
+    /// selector as usual, and a [`SingleUse`] payload can be taken by the
+    /// first widget that handles it.
     ///
-    /// If the [`Target::Auto`] is equivalent to [`Target::Global`].
+    /// [`Target::Auto`] is equivalent to [`Target::Global`]; pass a
+    /// [`WidgetId`] to deliver the command to a single widget.
+    ///
+    /// # Examples
+    ///
+    /// A worker thread reporting its progress to a [`ProgressBar`]:
+    ///
+    /// ```
+    /// use std::thread;
+    ///
+    /// use druid::widget::{Controller, ProgressBar};
+    /// use druid::{Env, Event, EventCtx, ExtEventSink, Selector, Widget, WidgetExt, WidgetId};
+    ///
+    /// const PROGRESS: Selector<f64> = Selector::new("my-app.progress");
+    ///
+    /// /// Sets the data of the wrapped widget from `PROGRESS` commands.
+    /// struct UpdateProgress;
+    ///
+    /// impl<W: Widget<f64>> Controller<f64, W> for UpdateProgress {
+    ///     fn event(
+    ///         &mut self,
+    ///         child: &mut W,
+    ///         ctx: &mut EventCtx,
+    ///         event: &Event,
+    ///         data: &mut f64,
+    ///         env: &Env,
+    ///     ) {
+    ///         match event {
+    ///             Event::Command(cmd) if cmd.is(PROGRESS) => *data = *cmd.get_unchecked(PROGRESS),
+    ///             _ => child.event(ctx, event, data, env),
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// fn progress_bar(id: WidgetId) -> impl Widget<f64> {
+    ///     ProgressBar::new().controller(UpdateProgress).with_id(id)
+    /// }
+    ///
+    /// fn start_work(sink: ExtEventSink, progress_bar: WidgetId) {
+    ///     thread::spawn(move || {
+    ///         for step in 1..=10 {
+    ///             // ... do a tenth of the work ...
+    ///             let progress = step as f64 / 10.0;
+    ///             if sink.submit_command(PROGRESS, progress, progress_bar).is_err() {
+    ///                 // the application has gone away
+    ///                 break;
+    ///             }
+    ///         }
+    ///     });
+    /// }
+    /// ```
     ///
     /// [`Command`]: struct.Command.html
+    /// [`Command::get`]: struct.Command.html#method.get
     /// [`Selector`]: struct.Selector.html
+    /// [`SingleUse`]: struct.SingleUse.html
     /// [`Target::Auto`]: enum.Target.html#variant.Auto
     /// [`Target::Global`]: enum.Target.html#variant.Global
+    /// [`WidgetId`]: struct.WidgetId.html
+    /// [`ProgressBar`]: widget/struct.ProgressBar.html
     pub fn submit_command<T: Any + Send>(
         &self,
         selector: Selector<T>,
@@ -110,8 +170,10 @@ impl ExtEventSink {
         if let Some(handle) = self.handle.lock().unwrap().as_mut() {
             handle.schedule_idle(EXT_EVENT_IDLE_TOKEN);
         }
+        register_payload_type::<T>(selector.symbol());
         self.queue.lock().map_err(|_| ExtEventError)?.push_back((
             selector.symbol(),
+            selector.type_name(),
             payload,
             target,
         ));
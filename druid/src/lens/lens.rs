@@ -16,7 +16,7 @@ use std::marker::PhantomData;
 use std::ops;
 use std::sync::Arc;
 
-use crate::Data;
+use crate::{Data, Versioned};
 
 /// A lens is a datatype that gives access to a part of a larger
 /// data structure.
@@ -208,6 +208,29 @@ pub trait LensExt<A: ?Sized, B: ?Sized>: Lens<A, B> {
         InArc::new(self)
     }
 
+    /// Adapt to operate on the contents of a [`Versioned`], giving it a new
+    /// version only when a different value is written
+    ///
+    /// ```
+    /// # use druid::*;
+    /// let lens = lens::Identity.index(2).in_versioned();
+    /// let mut x = Versioned::new(vec![0, 1, 2, 3]);
+    /// let original = x.clone();
+    /// lens.put(&mut x, 2);
+    /// assert!(x.same(&original), "no-op writes don't change the version");
+    /// lens.put(&mut x, 42);
+    /// assert!(!x.same(&original));
+    /// assert_eq!(&*x, &[0, 1, 42, 3]);
+    /// ```
+    fn in_versioned(self) -> InVersioned<Self>
+    where
+        A: Clone,
+        B: Data,
+        Self: Sized,
+    {
+        InVersioned::new(self)
+    }
+
     /// A lens that reverses a boolean value
     ///
     /// # Examples
@@ -517,6 +540,49 @@ where
     }
 }
 
+/// A `Lens` that exposes data within a [`Versioned`]
+///
+/// The value is only cloned, and given a new version, in the event that a
+/// different value is written.
+#[derive(Debug, Copy, Clone)]
+pub struct InVersioned<L> {
+    inner: L,
+}
+
+impl<L> InVersioned<L> {
+    /// Adapt a lens to operate on a [`Versioned`]
+    ///
+    /// See also `LensExt::in_versioned`
+    pub fn new<A, B>(inner: L) -> Self
+    where
+        A: Clone,
+        B: Data,
+        L: Lens<A, B>,
+    {
+        Self { inner }
+    }
+}
+
+impl<A, B, L> Lens<Versioned<A>, B> for InVersioned<L>
+where
+    A: Clone,
+    B: Data,
+    L: Lens<A, B>,
+{
+    fn with<V, F: FnOnce(&B) -> V>(&self, data: &Versioned<A>, f: F) -> V {
+        self.inner.with(data, f)
+    }
+
+    fn with_mut<V, F: FnOnce(&mut B) -> V>(&self, data: &mut Versioned<A>, f: F) -> V {
+        let mut temp = self.inner.with(data, |x| x.clone());
+        let v = f(&mut temp);
+        if self.inner.with(data, |x| !x.same(&temp)) {
+            self.inner.with_mut(data.make_mut(), |x| *x = temp);
+        }
+        v
+    }
+}
+
 /// A `Lens` that always yields ().
 ///
 /// This is useful when you wish to have a display only widget, require a type-erased widget, or
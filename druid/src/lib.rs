@@ -97,7 +97,10 @@
 //!          which is made available via the [`im` module].
 //! * `svg` - Scalable Vector Graphics for icons and other scalable images using the [`usvg` crate].
 //! * `image` - Bitmap image support using the [`image` crate].
+//! * `chrono` - Dates and times from the [`chrono` crate], and the [`Calendar`] and [`DatePicker`] widgets.
 //! * `x11` - Work-in-progress X11 Linux backend instead of GTK.
+//! * `debug_state` - `Widget::debug_state` and `DebugState`, for inspecting the widget tree.
+//! * `testing` - `MockTextEngine`, which lays text out with fixed-width glyphs, for tests.
 //!
 //! Features can be added with `cargo`. For example, in your `Cargo.toml`:
 //! ```no_compile
@@ -119,6 +122,9 @@
 //! [`im` module]: im/index.html
 //! [`usvg` crate]: https://crates.io/crates/usvg
 //! [`image` crate]: https://crates.io/crates/image
+//! [`chrono` crate]: https://crates.io/crates/chrono
+//! [`Calendar`]: widget/struct.Calendar.html
+//! [`DatePicker`]: widget/struct.DatePicker.html
 
 #![deny(
     broken_intra_doc_links,
@@ -150,12 +156,18 @@ mod util;
 
 mod app;
 mod app_delegate;
+mod asset_cache;
 mod bloom;
 mod box_constraints;
+mod child_list;
+mod child_set;
 mod command;
 mod contexts;
 mod core;
 mod data;
+#[cfg(feature = "debug_state")]
+#[cfg_attr(docsrs, doc(cfg(feature = "debug_state")))]
+pub mod debug_state;
 mod dialog;
 mod env;
 mod event;
@@ -163,12 +175,15 @@ mod ext_event;
 mod localization;
 pub mod menu;
 mod mouse;
+mod overlay;
 pub mod scroll_component;
+mod single_instance;
 mod sub_window;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod tests;
 pub mod text;
 pub mod theme;
+mod toast;
 pub mod widget;
 mod win_handler;
 mod window;
@@ -191,18 +206,28 @@ pub use shell::{
 pub use crate::core::WidgetPod;
 pub use app::{AppLauncher, WindowConfig, WindowDesc, WindowSizePolicy};
 pub use app_delegate::{AppDelegate, DelegateCtx};
+pub use asset_cache::{AssetKey, AssetState};
 pub use box_constraints::BoxConstraints;
-pub use command::{sys as commands, Command, Notification, Selector, SingleUse, Target};
+pub use child_list::{ChildList, ListChild};
+pub use child_set::ChildSet;
+pub use command::{
+    sys as commands, Command, Notification, Query, QueryRequest, Request, RequestCanceled,
+    Selector, SingleUse, Target,
+};
 pub use contexts::{EventCtx, LayoutCtx, LifeCycleCtx, PaintCtx, UpdateCtx};
-pub use data::Data;
+pub use data::{Data, Versioned};
+#[cfg(feature = "debug_state")]
+pub use debug_state::DebugState;
 pub use dialog::FileDialogOptions;
-pub use env::{Env, Key, KeyOrValue, Value, ValueType, ValueTypeError};
+pub use env::{Env, EnvUpdate, Key, KeyOrValue, Value, ValueType, ValueTypeError};
 pub use event::{Event, InternalEvent, InternalLifeCycle, LifeCycle};
 pub use ext_event::{ExtEventError, ExtEventSink};
 pub use lens::{Lens, LensExt};
 pub use localization::LocalizedString;
 pub use menu::{sys as platform_menus, Menu, MenuItem};
 pub use mouse::MouseEvent;
+pub use overlay::{OverlayPosition, OverlayToken};
+pub use toast::{ToastDesc, ToastSeverity};
 pub use util::Handled;
 pub use widget::{Widget, WidgetExt, WidgetId};
 pub use win_handler::DruidHandler;
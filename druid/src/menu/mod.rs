@@ -24,6 +24,11 @@
 //! item. Each callback has access to the application data, and also gets access to a
 //! [`MenuEventCtx`], which allows for submitting [`Command`]s.
 //!
+//! For simple actions there is no need to define a [`Selector`]: a closure passed to
+//! [`MenuItem::on_activate`] can change the data directly. Closure items and items using
+//! [`MenuItem::command`] can be freely mixed in the same menu, and behave the same in window
+//! menus, context menus and when triggered by their hotkey.
+//!
 //! ## Refreshing and rebuilding
 //!
 //! Menus, like widgets, update themselves based on changes in the data. There are two different
@@ -108,6 +113,7 @@
 //! [`LabelText::Dynamic`]: crate::widget::LabelText::Dynamic
 //! [`WindowDesc::menu`]: crate::WindowDesc::menu
 //! [`Command`]: crate::Command
+//! [`Selector`]: crate::Selector
 
 use std::num::NonZeroU32;
 
@@ -250,7 +256,8 @@ impl<T: Data> MenuManager<T> {
 
 /// This context is available to the callback that is called when a menu item is activated.
 ///
-/// Currently, it only allows for submission of [`Command`]s.
+/// It allows for submission of [`Command`]s, including those that operate on the
+/// menu's window.
 ///
 /// [`Command`]: crate::Command
 pub struct MenuEventCtx<'a> {
@@ -297,6 +304,13 @@ impl MenuBuildCtx {
 }
 
 impl<'a> MenuEventCtx<'a> {
+    /// The window this menu belongs to, if it belongs to a window.
+    ///
+    /// Application menus on macOS may not be associated with any window.
+    pub fn window_id(&self) -> Option<WindowId> {
+        self.window
+    }
+
     /// Submit a [`Command`] to be handled by the main widget tree.
     ///
     /// If the command's target is [`Target::Auto`], it will be sent to the menu's window if the
@@ -838,3 +852,92 @@ impl MenuItemId {
         MenuItemId(NonZeroU32::new(id))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Selector;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use test_env_log::test;
+
+    const TOGGLE: Selector = Selector::new("druid-test.toggle");
+
+    type Ids = Rc<RefCell<Vec<MenuItemId>>>;
+
+    /// Builds a menu with a closure item and a command item, recording their ids.
+    ///
+    /// The closure item only exists while the data is below 10, like a menu that
+    /// is rebuilt when the data changes.
+    fn build(ids: &Ids, data: u32) -> Menu<u32> {
+        let mut menu = Menu::new("Test").rebuild_on(|old, new, _| old != new);
+        ids.borrow_mut().clear();
+        if data < 10 {
+            let increment =
+                MenuItem::new("Increment").on_activate(|_, data: &mut u32, _| *data += 1);
+            ids.borrow_mut().push(increment.id);
+            menu = menu.entry(increment);
+        }
+        let reset = MenuItem::new("Reset").on_activate(|_, data: &mut u32, _| *data = 0);
+        let toggle = MenuItem::new("Toggle").command(TOGGLE);
+        ids.borrow_mut().extend(&[reset.id, toggle.id]);
+        menu.entry(reset).separator().entry(toggle)
+    }
+
+    fn activate(menu: &mut Menu<u32>, id: MenuItemId, data: &mut u32) -> CommandQueue {
+        let mut queue = CommandQueue::new();
+        let window = WindowId::next();
+        let mut ctx = MenuEventCtx {
+            window: Some(window),
+            queue: &mut queue,
+        };
+        assert_eq!(ctx.window_id(), Some(window));
+        menu.activate(&mut ctx, id, data, &Env::default());
+        queue
+    }
+
+    #[test]
+    fn closure_and_command_items() {
+        let ids: Ids = Default::default();
+        let mut data = 0;
+        let mut menu = build(&ids, data);
+        let (increment, reset, toggle) = (ids.borrow()[0], ids.borrow()[1], ids.borrow()[2]);
+
+        assert!(activate(&mut menu, increment, &mut data).is_empty());
+        assert!(activate(&mut menu, increment, &mut data).is_empty());
+        assert_eq!(data, 2);
+
+        let queue = activate(&mut menu, toggle, &mut data);
+        assert_eq!(queue.len(), 1);
+        assert!(queue[0].is(TOGGLE));
+        assert_eq!(data, 2);
+
+        activate(&mut menu, reset, &mut data);
+        assert_eq!(data, 0);
+    }
+
+    #[test]
+    fn rebuild_keeps_closures_with_their_items() {
+        let ids: Ids = Default::default();
+        let env = Env::default();
+        let mut data = 9;
+        let mut menu = build(&ids, data);
+        let increment = ids.borrow()[0];
+        activate(&mut menu, increment, &mut data);
+        assert_eq!(data, 10);
+
+        assert!(matches!(
+            menu.update(&9, &data, &env),
+            MenuUpdate::NeedsRebuild
+        ));
+        let mut menu = build(&ids, data);
+        // the increment item is gone, so its old id does nothing
+        activate(&mut menu, increment, &mut data);
+        assert_eq!(data, 10);
+
+        let (reset, toggle) = (ids.borrow()[0], ids.borrow()[1]);
+        assert!(activate(&mut menu, reset, &mut data).is_empty());
+        assert_eq!(data, 0);
+        assert!(activate(&mut menu, toggle, &mut data)[0].is(TOGGLE));
+    }
+}
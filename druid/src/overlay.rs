@@ -0,0 +1,576 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Floating widgets that are painted above the rest of a window.
+
+use crate::commands::{DISMISS_OVERLAY, SHOW_OVERLAY, SHOW_TOAST};
+use crate::core::WidgetState;
+use crate::shell::Counter;
+use crate::toast::ToastStack;
+use crate::widget::prelude::*;
+use crate::{InternalEvent, InternalLifeCycle, Point, Rect, StateCell, UnitPoint, WidgetPod};
+use tracing::{instrument, warn};
+
+/// A token that identifies an overlay, returned by [`EventCtx::show_overlay`].
+///
+/// [`EventCtx::show_overlay`]: crate::EventCtx::show_overlay
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct OverlayToken(u64);
+
+impl OverlayToken {
+    pub(crate) fn next() -> OverlayToken {
+        static OVERLAY_COUNTER: Counter = Counter::new();
+        OverlayToken(OVERLAY_COUNTER.next())
+    }
+}
+
+/// Where an overlay is placed in the window.
+///
+/// Overlays are always kept inside the window, as far as they fit.
+#[derive(Debug, Clone, Copy)]
+pub enum OverlayPosition {
+    /// The overlay's top left corner is at this point, in window coordinates.
+    Absolute(Point),
+    /// The overlay is placed relative to the widget that showed it, by putting
+    /// the `overlay` point of the overlay on the `anchor` point of that widget.
+    ///
+    /// For instance, an `anchor` of [`UnitPoint::BOTTOM_LEFT`] and an `overlay`
+    /// of [`UnitPoint::TOP_LEFT`] places the overlay right below the widget.
+    /// The overlay follows the widget when it moves.
+    Anchored {
+        /// The point on the widget that showed the overlay.
+        anchor: UnitPoint,
+        /// The point on the overlay.
+        overlay: UnitPoint,
+    },
+    /// The overlay fills the whole window.
+    Fill,
+}
+
+/// The request to show an overlay, sent from a context to the window.
+pub(crate) struct OverlayDesc<T> {
+    pub(crate) token: OverlayToken,
+    pub(crate) anchor: WidgetId,
+    pub(crate) anchor_rect: Rect,
+    pub(crate) position: OverlayPosition,
+    pub(crate) widget: Box<dyn Widget<T>>,
+}
+
+struct Overlay<T> {
+    token: OverlayToken,
+    /// The widget that showed the overlay; the overlay is dismissed when it is removed.
+    anchor: WidgetId,
+    /// The layout rect of the anchor, in window coordinates.
+    anchor_rect: Rect,
+    position: OverlayPosition,
+    child: WidgetPod<T, Box<dyn Widget<T>>>,
+}
+
+/// The root widget of every window, which hosts the window's content, its
+/// overlays and its toasts.
+///
+/// Overlays are painted after the content, in the order they were shown, and
+/// get pointer events before the content and the overlays below them. The
+/// toasts are above everything else.
+pub(crate) struct OverlayHost<T> {
+    root: WidgetPod<T, Box<dyn Widget<T>>>,
+    overlays: Vec<Overlay<T>>,
+    toasts: WidgetPod<(), ToastStack>,
+}
+
+impl<T: Data> OverlayHost<T> {
+    pub(crate) fn new(root: Box<dyn Widget<T>>, toasts: ToastStack) -> Self {
+        OverlayHost {
+            root: WidgetPod::new(root),
+            overlays: Vec::new(),
+            toasts: WidgetPod::new(toasts),
+        }
+    }
+
+    /// The debug state of the window's content, without the overlays.
+    #[cfg(feature = "debug_state")]
+    pub(crate) fn root_debug_state(&self, data: &T) -> DebugState {
+        self.root.debug_state(data)
+    }
+
+    fn show(&mut self, ctx: &mut EventCtx, desc: OverlayDesc<T>) {
+        self.overlays.push(Overlay {
+            token: desc.token,
+            anchor: desc.anchor,
+            anchor_rect: desc.anchor_rect,
+            position: desc.position,
+            child: WidgetPod::new(desc.widget),
+        });
+        ctx.children_changed();
+    }
+
+    fn dismiss(&mut self, token: OverlayToken) -> bool {
+        let count = self.overlays.len();
+        self.overlays.retain(|overlay| overlay.token != token);
+        self.overlays.len() != count
+    }
+
+    /// Find the state of a widget in the content or in any overlay.
+    fn find_widget(
+        &mut self,
+        ctx: &mut LifeCycleCtx,
+        widget: WidgetId,
+        data: &T,
+        env: &Env,
+    ) -> Option<WidgetState> {
+        let state_cell = StateCell::default();
+        let event = LifeCycle::Internal(InternalLifeCycle::DebugRequestState {
+            widget,
+            state_cell: state_cell.clone(),
+        });
+        let pods = std::iter::once(&mut self.root)
+            .chain(self.overlays.iter_mut().map(|overlay| &mut overlay.child));
+        for pod in pods {
+            if pod.id() == widget || pod.state().children.may_contain(&widget) {
+                pod.lifecycle(ctx, &event, data, env);
+                if let Some(state) = state_cell.take() {
+                    return Some(state);
+                }
+            }
+        }
+        None
+    }
+
+    /// Dismiss the overlays whose anchor was removed, and keep track of where
+    /// the other anchors are.
+    fn update_anchors(&mut self, ctx: &mut LifeCycleCtx, data: &T, env: &Env) {
+        let mut removed = Vec::new();
+        for i in 0..self.overlays.len() {
+            let anchor = self.overlays[i].anchor;
+            match self.find_widget(ctx, anchor, data, env) {
+                Some(state) => {
+                    let rect = Rect::from_origin_size(state.window_origin(), state.size());
+                    let overlay = &mut self.overlays[i];
+                    if rect != overlay.anchor_rect {
+                        overlay.anchor_rect = rect;
+                        if let OverlayPosition::Anchored { .. } = overlay.position {
+                            ctx.request_layout();
+                        }
+                    }
+                }
+                None => removed.push(self.overlays[i].token),
+            }
+        }
+        if !removed.is_empty() {
+            self.overlays
+                .retain(|overlay| !removed.contains(&overlay.token));
+            ctx.children_changed();
+            ctx.request_layout();
+        }
+    }
+}
+
+impl<T: Data> Widget<T> for OverlayHost<T> {
+    #[instrument(
+        name = "OverlayHost",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::Command(cmd) if cmd.is(SHOW_OVERLAY) => {
+                match cmd
+                    .get_unchecked(SHOW_OVERLAY)
+                    .take()
+                    .and_then(|desc| desc.downcast::<OverlayDesc<T>>().ok())
+                {
+                    Some(desc) => self.show(ctx, *desc),
+                    None => warn!("{} command must carry an OverlayDesc", SHOW_OVERLAY),
+                }
+                ctx.set_handled();
+                return;
+            }
+            Event::Command(cmd) if cmd.is(DISMISS_OVERLAY) => {
+                if self.dismiss(*cmd.get_unchecked(DISMISS_OVERLAY)) {
+                    ctx.children_changed();
+                    ctx.request_layout();
+                }
+                ctx.set_handled();
+                return;
+            }
+            Event::Command(cmd) if cmd.is(SHOW_TOAST) => {
+                self.toasts.event(ctx, event, &mut (), env);
+                return;
+            }
+            _ => (),
+        }
+
+        let pointer_pos = match event {
+            Event::MouseDown(mouse)
+            | Event::MouseUp(mouse)
+            | Event::MouseMove(mouse)
+            | Event::Wheel(mouse) => Some(mouse.pos),
+            _ => None,
+        };
+        match pointer_pos {
+            Some(pos) => {
+                let covered = self.toasts.widget().covers(pos)
+                    || self
+                        .overlays
+                        .iter()
+                        .any(|overlay| overlay.child.layout_rect().contains(pos));
+                self.toasts.event(ctx, event, &mut (), env);
+                for overlay in self.overlays.iter_mut().rev() {
+                    if ctx.is_handled() {
+                        break;
+                    }
+                    overlay.child.event(ctx, event, data, env);
+                }
+                // The content only gets the event if no overlay is in the way,
+                // unless it is in the middle of a drag.
+                if !ctx.is_handled() && (!covered || self.root.has_active()) {
+                    self.root.event(ctx, event, data, env);
+                } else if let Event::MouseMove(_) = event {
+                    let leave = Event::Internal(InternalEvent::MouseLeave);
+                    self.root.event(ctx, &leave, data, env);
+                }
+            }
+            None => {
+                // As far as the content knows, it is the root of the window.
+                let is_root = std::mem::replace(&mut ctx.is_root, true);
+                self.root.event(ctx, event, data, env);
+                ctx.is_root = is_root;
+                for overlay in &mut self.overlays {
+                    overlay.child.event(ctx, event, data, env);
+                }
+                self.toasts.event(ctx, event, &mut (), env);
+            }
+        }
+    }
+
+    #[instrument(
+        name = "OverlayHost",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        self.root.lifecycle(ctx, event, data, env);
+        for overlay in &mut self.overlays {
+            overlay.child.lifecycle(ctx, event, data, env);
+        }
+        self.toasts.lifecycle(ctx, event, &(), env);
+        if let LifeCycle::Internal(InternalLifeCycle::RouteWidgetAdded)
+        | LifeCycle::Internal(InternalLifeCycle::ParentWindowOrigin) = event
+        {
+            if !self.overlays.is_empty() {
+                self.update_anchors(ctx, data, env);
+            }
+        }
+    }
+
+    #[instrument(
+        name = "OverlayHost",
+        level = "trace",
+        skip(self, ctx, _old, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old: &T, data: &T, env: &Env) {
+        self.root.update(ctx, data, env);
+        for overlay in &mut self.overlays {
+            overlay.child.update(ctx, data, env);
+        }
+        self.toasts.update(ctx, &(), env);
+    }
+
+    #[instrument(name = "OverlayHost", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        let size = self.root.layout(ctx, bc, data, env);
+        self.root.set_origin(ctx, data, env, Point::ORIGIN);
+
+        for overlay in &mut self.overlays {
+            let child_bc = match overlay.position {
+                OverlayPosition::Fill => BoxConstraints::tight(size),
+                _ => BoxConstraints::new(Size::ZERO, size),
+            };
+            let child_size = overlay.child.layout(ctx, &child_bc, data, env);
+            let origin = match overlay.position {
+                OverlayPosition::Absolute(origin) => origin,
+                OverlayPosition::Anchored {
+                    anchor,
+                    overlay: point,
+                } => {
+                    anchor.resolve(overlay.anchor_rect)
+                        - point.resolve(child_size.to_rect()).to_vec2()
+                }
+                OverlayPosition::Fill => Point::ORIGIN,
+            };
+            // keep the overlay inside the window
+            let origin = Point::new(
+                origin.x.min(size.width - child_size.width).max(0.0),
+                origin.y.min(size.height - child_size.height).max(0.0),
+            );
+            overlay.child.set_origin(ctx, data, env, origin);
+        }
+
+        self.toasts
+            .layout(ctx, &BoxConstraints::tight(size), &(), env);
+        self.toasts.set_origin(ctx, &(), env, Point::ORIGIN);
+        size
+    }
+
+    #[instrument(name = "OverlayHost", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.root.paint(ctx, data, env);
+        for overlay in &mut self.overlays {
+            overlay.child.paint(ctx, data, env);
+        }
+        self.toasts.paint(ctx, &(), env);
+    }
+
+    #[cfg(feature = "debug_state")]
+    fn debug_state(&self, data: &T) -> DebugState {
+        let mut children = vec![self.root.debug_state(data)];
+        children.extend(self.overlays.iter().map(|o| o.child.debug_state(data)));
+        children.push(self.toasts.debug_state(&()));
+        DebugState::with_children("OverlayHost", children)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use test_env_log::test;
+
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::{widget_ids, ModularWidget};
+    use crate::widget::SizedBox;
+    use crate::{MouseButton, MouseEvent, Selector, WidgetExt};
+
+    const SHOW: Selector<OverlayPosition> = Selector::new("druid-test.show-overlay");
+    const DISMISS: Selector = Selector::new("druid-test.dismiss-overlay");
+    const REMOVE_ANCHOR: Selector = Selector::new("druid-test.remove-anchor");
+
+    fn click(pos: impl Into<Point>) -> Event {
+        let mut mouse = crate::tests::move_mouse(pos);
+        mouse.button = MouseButton::Left;
+        mouse.buttons.insert(MouseButton::Left);
+        mouse.count = 1;
+        Event::MouseDown(mouse)
+    }
+
+    /// A box that counts the clicks it gets, and handles them.
+    fn clickable(id: WidgetId, size: f64, clicks: Rc<Cell<usize>>) -> impl Widget<()> {
+        ModularWidget::new(clicks)
+            .event_fn(|clicks, ctx, event, _, _| {
+                if let Event::MouseDown(MouseEvent { .. }) = event {
+                    clicks.set(clicks.get() + 1);
+                    ctx.set_handled();
+                }
+            })
+            .layout_fn(move |_, _, bc, _, _| bc.constrain(Size::new(size, size)))
+            .with_id(id)
+    }
+
+    /// A widget that shows the overlays built by `make_overlay` on [`SHOW`],
+    /// and dismisses the most recent one on [`DISMISS`].
+    fn anchor(
+        id: WidgetId,
+        mut make_overlay: impl FnMut() -> Box<dyn Widget<()>> + 'static,
+    ) -> impl Widget<()> {
+        ModularWidget::new(Vec::new())
+            .event_fn(move |tokens, ctx, event, _, _| {
+                if let Event::Command(cmd) = event {
+                    if let Some(position) = cmd.get(SHOW) {
+                        tokens.push(ctx.show_overlay(make_overlay(), *position));
+                    } else if cmd.is(DISMISS) {
+                        if let Some(token) = tokens.pop() {
+                            ctx.dismiss_overlay(token);
+                        }
+                    }
+                }
+            })
+            .layout_fn(|_, _, bc, _, _| bc.constrain(Size::new(50., 20.)))
+            .with_id(id)
+    }
+
+    #[test]
+    fn overlay_gets_pointer_events_first() {
+        let [anchor_id, overlay_id] = widget_ids();
+        let root_clicks = Rc::new(Cell::new(0));
+        let overlay_clicks = Rc::new(Cell::new(0));
+
+        let overlay = {
+            let clicks = overlay_clicks.clone();
+            move || Box::new(clickable(overlay_id, 40., clicks.clone())) as Box<dyn Widget<()>>
+        };
+        let root = {
+            let clicks = root_clicks.clone();
+            ModularWidget::new(WidgetPod::new(anchor(anchor_id, overlay)))
+                .event_fn(move |child, ctx, event, data, env| {
+                    child.event(ctx, event, data, env);
+                    if let Event::MouseDown(_) = event {
+                        clicks.set(clicks.get() + 1);
+                    }
+                })
+                .lifecycle_fn(|child, ctx, event, data, env| child.lifecycle(ctx, event, data, env))
+                .update_fn(|child, ctx, _, data, env| child.update(ctx, data, env))
+                .layout_fn(|child, ctx, bc, data, env| {
+                    child.layout(ctx, &bc.loosen(), data, env);
+                    child.set_origin(ctx, data, env, Point::ORIGIN);
+                    bc.max()
+                })
+        };
+
+        Harness::create_simple((), root, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.submit_command(SHOW.with(OverlayPosition::Absolute(Point::new(100., 100.))));
+            harness.just_layout();
+            assert_eq!(
+                harness.get_state(overlay_id).layout_rect(),
+                Rect::new(100., 100., 140., 140.)
+            );
+
+            harness.event(click((120., 120.)));
+            assert_eq!(overlay_clicks.get(), 1);
+            assert_eq!(root_clicks.get(), 0);
+
+            harness.event(click((10., 10.)));
+            assert_eq!(overlay_clicks.get(), 1);
+            assert_eq!(root_clicks.get(), 1);
+
+            harness.submit_command(DISMISS);
+            harness.just_layout();
+            assert!(harness.try_get_state(overlay_id).is_none());
+            harness.event(click((120., 120.)));
+            assert_eq!(overlay_clicks.get(), 1);
+            assert_eq!(root_clicks.get(), 2);
+        });
+    }
+
+    #[test]
+    fn later_overlays_are_on_top() {
+        let [anchor_id, first_id, second_id] = widget_ids();
+        let first_clicks = Rc::new(Cell::new(0));
+        let second_clicks = Rc::new(Cell::new(0));
+
+        let mut shown = 0;
+        let overlay = {
+            let first_clicks = first_clicks.clone();
+            let second_clicks = second_clicks.clone();
+            move || {
+                shown += 1;
+                if shown == 1 {
+                    Box::new(clickable(first_id, 40., first_clicks.clone())) as Box<dyn Widget<()>>
+                } else {
+                    Box::new(clickable(second_id, 40., second_clicks.clone()))
+                }
+            }
+        };
+
+        Harness::create_simple((), anchor(anchor_id, overlay), |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.submit_command(SHOW.with(OverlayPosition::Absolute(Point::new(100., 100.))));
+            harness.submit_command(SHOW.with(OverlayPosition::Absolute(Point::new(120., 120.))));
+            harness.just_layout();
+
+            // where they overlap, the second one wins
+            harness.event(click((130., 130.)));
+            assert_eq!(first_clicks.get(), 0);
+            assert_eq!(second_clicks.get(), 1);
+            harness.event(click((110., 110.)));
+            assert_eq!(first_clicks.get(), 1);
+
+            // and once it is gone, the first one gets the clicks
+            harness.submit_command(DISMISS);
+            harness.just_layout();
+            harness.event(click((130., 130.)));
+            assert_eq!(first_clicks.get(), 2);
+            assert_eq!(second_clicks.get(), 1);
+        });
+    }
+
+    #[test]
+    fn anchored_overlay_goes_away_with_its_anchor() {
+        let [anchor_id, overlay_id] = widget_ids();
+        let overlay =
+            move || Box::new(clickable(overlay_id, 40., Rc::default())) as Box<dyn Widget<()>>;
+        let root = ModularWidget::new(Some(WidgetPod::new(anchor(anchor_id, overlay))))
+            .event_fn(|child, ctx, event, data, env| {
+                if let Event::Command(cmd) = event {
+                    if cmd.is(REMOVE_ANCHOR) {
+                        *child = None;
+                        ctx.children_changed();
+                        return;
+                    }
+                }
+                if let Some(child) = child {
+                    child.event(ctx, event, data, env);
+                }
+            })
+            .lifecycle_fn(|child, ctx, event, data, env| {
+                if let Some(child) = child {
+                    child.lifecycle(ctx, event, data, env);
+                }
+            })
+            .update_fn(|child, ctx, _, data, env| {
+                if let Some(child) = child {
+                    child.update(ctx, data, env);
+                }
+            })
+            .layout_fn(|child, ctx, bc, data, env| {
+                if let Some(child) = child {
+                    child.layout(ctx, &bc.loosen(), data, env);
+                    child.set_origin(ctx, data, env, Point::new(30., 30.));
+                }
+                bc.max()
+            });
+
+        Harness::create_simple((), root, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.submit_command(SHOW.with(OverlayPosition::Anchored {
+                anchor: UnitPoint::BOTTOM_LEFT,
+                overlay: UnitPoint::TOP_LEFT,
+            }));
+            harness.just_layout();
+            assert_eq!(
+                harness.get_state(overlay_id).layout_rect(),
+                Rect::new(30., 50., 70., 90.)
+            );
+
+            harness.submit_command(REMOVE_ANCHOR);
+            harness.just_layout();
+            assert!(harness.try_get_state(anchor_id).is_none());
+            assert!(harness.try_get_state(overlay_id).is_none());
+        });
+    }
+
+    #[test]
+    fn fill_overlay_covers_the_window() {
+        let [anchor_id, overlay_id] = widget_ids();
+        let overlay =
+            move || Box::new(SizedBox::empty().with_id(overlay_id)) as Box<dyn Widget<()>>;
+
+        Harness::create_simple((), anchor(anchor_id, overlay), |harness| {
+            harness.set_initial_size(Size::new(300., 200.));
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.submit_command(SHOW.with(OverlayPosition::Fill));
+            harness.just_layout();
+            assert_eq!(
+                harness.get_state(overlay_id).layout_rect(),
+                Rect::new(0., 0., 300., 200.)
+            );
+        });
+    }
+}
@@ -0,0 +1,336 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Forwarding a launch to an instance of the app that is already running.
+//!
+//! On unix, the first instance listens on a socket named after the app, in a
+//! directory that only the current user can access. A later launch connects
+//! to it, sends its arguments separated by NUL bytes, and waits for a single
+//! byte in reply before it exits.
+//!
+//! This is a plain unix socket rather than D-Bus name ownership, so that it
+//! needs no extra dependency and works the same on every unix platform.
+
+/// The result of trying to become the single instance of an app.
+pub(crate) enum Instance {
+    /// There was no other instance; we listen for later launches until the
+    /// guard is dropped.
+    Primary { _guard: PrimaryGuard },
+    /// Another instance is running, and has received our arguments.
+    Forwarded,
+}
+
+#[cfg(unix)]
+pub(crate) use unix::{acquire, PrimaryGuard};
+
+#[cfg(not(unix))]
+pub(crate) struct PrimaryGuard;
+
+/// Become the single instance of the app `id`, or forward `args` to the
+/// instance that is already running.
+#[cfg(not(unix))]
+pub(crate) fn acquire(
+    _id: &str,
+    _args: &[String],
+    _on_activate: impl Fn(Vec<String>) + Send + 'static,
+) -> std::io::Result<Instance> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Other,
+        "single instance apps are not supported on this platform",
+    ))
+}
+
+#[cfg(unix)]
+mod unix {
+    use std::env;
+    use std::fs::{self, DirBuilder};
+    use std::io::{self, Read, Write};
+    use std::net::Shutdown;
+    use std::os::unix::fs::{DirBuilderExt, MetadataExt};
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::path::{Path, PathBuf};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::thread::{self, JoinHandle};
+    use std::time::Duration;
+
+    use tracing::{debug, warn};
+
+    use super::Instance;
+
+    /// How long to wait for the running instance to acknowledge our arguments,
+    /// and for a new launch to send them.
+    const ACK_TIMEOUT: Duration = Duration::from_secs(2);
+
+    /// Stops listening for later launches, and removes the socket, when the
+    /// app quits.
+    pub(crate) struct PrimaryGuard {
+        path: PathBuf,
+        stop: Arc<AtomicBool>,
+        listener: Option<JoinHandle<()>>,
+    }
+
+    impl Drop for PrimaryGuard {
+        fn drop(&mut self) {
+            self.stop.store(true, Ordering::SeqCst);
+            // wake the listener up, so that it sees the flag
+            match UnixStream::connect(&self.path) {
+                Ok(_) => {
+                    if let Some(listener) = self.listener.take() {
+                        let _ = listener.join();
+                    }
+                }
+                Err(err) => warn!("failed to stop the single instance listener: {}", err),
+            }
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+
+    /// Become the single instance of the app `id`, or forward `args` to the
+    /// instance that is already running.
+    pub(crate) fn acquire(
+        id: &str,
+        args: &[String],
+        on_activate: impl Fn(Vec<String>) + Send + 'static,
+    ) -> io::Result<Instance> {
+        let path = socket_dir()?.join(socket_name(id));
+        match UnixStream::connect(&path) {
+            Ok(stream) => {
+                forward(stream, args)?;
+                return Ok(Instance::Forwarded);
+            }
+            // the socket was left behind by an instance that didn't quit cleanly
+            Err(err) if err.kind() == io::ErrorKind::ConnectionRefused => {
+                debug!("removing stale socket {}", path.display());
+                fs::remove_file(&path)?;
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => (),
+            Err(err) => return Err(err),
+        }
+
+        let listener = UnixListener::bind(&path)?;
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_listener = stop.clone();
+        let listener = thread::Builder::new()
+            .name(format!("{} single instance", id))
+            .spawn(move || {
+                for stream in listener.incoming() {
+                    if stop_listener.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    match stream.and_then(receive) {
+                        Ok(args) => on_activate(args),
+                        Err(err) => warn!("failed to receive arguments from a new launch: {}", err),
+                    }
+                }
+            })?;
+        let _guard = PrimaryGuard {
+            path,
+            stop,
+            listener: Some(listener),
+        };
+        Ok(Instance::Primary { _guard })
+    }
+
+    fn forward(mut stream: UnixStream, args: &[String]) -> io::Result<()> {
+        stream.set_read_timeout(Some(ACK_TIMEOUT))?;
+        for arg in args {
+            stream.write_all(arg.as_bytes())?;
+            stream.write_all(&[0])?;
+        }
+        stream.shutdown(Shutdown::Write)?;
+        let mut ack = [0];
+        stream.read_exact(&mut ack)
+    }
+
+    fn receive(mut stream: UnixStream) -> io::Result<Vec<String>> {
+        // a client that never finishes must not block the listener, or
+        // dropping the guard would wait for it forever
+        stream.set_read_timeout(Some(ACK_TIMEOUT))?;
+        stream.set_write_timeout(Some(ACK_TIMEOUT))?;
+        let mut message = Vec::new();
+        stream.read_to_end(&mut message)?;
+        // every argument is terminated by a NUL, so the last part is empty
+        let mut args: Vec<String> = message
+            .split(|b| *b == 0)
+            .map(|arg| String::from_utf8_lossy(arg).into_owned())
+            .collect();
+        args.pop();
+        stream.write_all(&[1])?;
+        Ok(args)
+    }
+
+    /// The file name of the socket for the app `id`.
+    pub(super) fn socket_name(id: &str) -> String {
+        let id: String = id
+            .chars()
+            .map(|c| match c {
+                'a'..='z' | 'A'..='Z' | '0'..='9' | '.' | '-' | '_' => c,
+                _ => '_',
+            })
+            .collect();
+        format!("{}.sock", id)
+    }
+
+    /// The directory for our sockets: the user's runtime directory, or a
+    /// directory of our own in the temporary directory.
+    ///
+    /// Other users must not be able to create files in it, or they could take
+    /// the socket's name before we do.
+    pub(super) fn socket_dir() -> io::Result<PathBuf> {
+        let uid = current_uid();
+        let dir = match env::var_os("XDG_RUNTIME_DIR") {
+            Some(dir) => PathBuf::from(dir),
+            None => {
+                let dir = env::temp_dir().join(format!("druid-{}", uid));
+                match DirBuilder::new().mode(0o700).create(&dir) {
+                    Err(err) if err.kind() != io::ErrorKind::AlreadyExists => return Err(err),
+                    _ => dir,
+                }
+            }
+        };
+        check_private(&dir, uid)?;
+        Ok(dir)
+    }
+
+    #[allow(unsafe_code)]
+    pub(super) fn current_uid() -> u32 {
+        // SAFETY: getuid has no preconditions, and always succeeds
+        unsafe { libc::getuid() }
+    }
+
+    /// Make sure `dir` is a directory that belongs to `uid`, and that no one
+    /// else can access.
+    pub(super) fn check_private(dir: &Path, uid: u32) -> io::Result<()> {
+        let metadata = fs::symlink_metadata(dir)?;
+        if !metadata.is_dir() || metadata.uid() != uid || metadata.mode() & 0o077 != 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                format!("{} is not a private directory", dir.display()),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::env;
+    use std::process::Command;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    use test_env_log::test;
+
+    use super::*;
+
+    /// Tells [`forwarded_launch`] which app to forward its arguments to.
+    const CHILD_ID_VAR: &str = "DRUID_SINGLE_INSTANCE_TEST_ID";
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    fn primary(id: &str, send: mpsc::Sender<Vec<String>>) -> PrimaryGuard {
+        match acquire(id, &[], move |args| send.send(args).unwrap()) {
+            Ok(Instance::Primary { _guard: guard }) => guard,
+            _ => panic!("the first launch should become the primary instance"),
+        }
+    }
+
+    #[test]
+    fn second_launch_forwards_its_arguments() {
+        let id = format!("org.linebender.druid.test-{}", std::process::id());
+        let (send, recv) = mpsc::channel();
+        let guard = primary(&id, send);
+
+        let second = acquire(&id, &args(&["file.txt", "", "with space"]), |_| ());
+        assert!(matches!(second, Ok(Instance::Forwarded)));
+        let received = recv.recv_timeout(Duration::from_secs(2)).unwrap();
+        assert_eq!(received, args(&["file.txt", "", "with space"]));
+
+        // once the first instance is gone, the next launch takes over
+        drop(guard);
+        let third = acquire(&id, &[], |_| ());
+        assert!(matches!(third, Ok(Instance::Primary { .. })));
+    }
+
+    #[test]
+    fn silent_client_does_not_block_the_listener() {
+        use std::os::unix::net::UnixStream;
+
+        let id = format!("org.linebender.druid.test-{}-silent", std::process::id());
+        let (send, recv) = mpsc::channel();
+        let guard = primary(&id, send);
+
+        // connect, but never send anything or hang up
+        let path = unix::socket_dir().unwrap().join(unix::socket_name(&id));
+        let _silent = UnixStream::connect(&path).unwrap();
+
+        let (dropped, was_dropped) = mpsc::channel();
+        std::thread::spawn(move || {
+            drop(guard);
+            dropped.send(()).unwrap();
+        });
+        assert!(was_dropped.recv_timeout(Duration::from_secs(10)).is_ok());
+        assert!(recv.try_recv().is_err());
+    }
+
+    #[test]
+    fn second_process_forwards_its_arguments() {
+        let id = format!("org.linebender.druid.test-{}-process", std::process::id());
+        let (send, recv) = mpsc::channel();
+        let _guard = primary(&id, send);
+
+        let output = Command::new(env::current_exe().unwrap())
+            .arg("single_instance::tests::forwarded_launch")
+            .arg("--exact")
+            .arg("--ignored")
+            .env(CHILD_ID_VAR, &id)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stdout)
+        );
+        let received = recv.recv_timeout(Duration::from_secs(2)).unwrap();
+        assert_eq!(received, args(&["file.txt", "with space"]));
+    }
+
+    /// The second launch in [`second_process_forwards_its_arguments`], run in
+    /// its own process.
+    #[test]
+    #[ignore]
+    fn forwarded_launch() {
+        let id = env::var(CHILD_ID_VAR).expect("run by second_process_forwards_its_arguments");
+        let launch = acquire(&id, &args(&["file.txt", "with space"]), |_| ());
+        assert!(matches!(launch, Ok(Instance::Forwarded)));
+    }
+
+    #[test]
+    fn socket_dir_must_be_private() {
+        use std::fs::{self, Permissions};
+        use std::os::unix::fs::PermissionsExt;
+
+        let uid = unix::current_uid();
+        let dir = tempfile::tempdir().unwrap();
+        fs::set_permissions(dir.path(), Permissions::from_mode(0o700)).unwrap();
+        assert!(unix::check_private(dir.path(), uid).is_ok());
+        assert!(unix::check_private(dir.path(), uid + 1).is_err());
+
+        fs::set_permissions(dir.path(), Permissions::from_mode(0o777)).unwrap();
+        assert!(unix::check_private(dir.path(), uid).is_err());
+    }
+}
@@ -16,11 +16,15 @@
 
 use std::path::Path;
 use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
 
 use crate::app::PendingWindow;
+use crate::app_delegate::{DelegateQueries, QueryHandler};
 use crate::core::{CommandQueue, WidgetState};
 use crate::ext_event::ExtEventHost;
 use crate::piet::{BitmapTarget, Device, Error, ImageFormat, Piet};
+use crate::text::{shared_engine, InputHandler, TextEngine};
 use crate::*;
 
 pub(crate) const DEFAULT_SIZE: Size = Size::new(400., 400.);
@@ -58,6 +62,8 @@ struct Inner<T> {
     env: Env,
     window: Window<T>,
     cmds: CommandQueue,
+    ext_host: ExtEventHost,
+    delegate: Option<Box<dyn AppDelegate<T>>>,
 }
 
 /// A way to clean up resources when our target goes out of scope.
@@ -150,13 +156,21 @@ impl<T: Data> Harness<'_, T> {
             let piet = target.0.as_mut().unwrap().render_context();
 
             let pending = PendingWindow::new(root);
-            let window = Window::new(WindowId::next(), Default::default(), pending, ext_handle);
+            #[allow(unused_mut)]
+            let mut window = Window::new(WindowId::next(), Default::default(), pending, ext_handle);
+            // so that text measures and paints the same on every platform
+            #[cfg(any(test, feature = "testing"))]
+            {
+                window.text_engine = Some(shared_engine(crate::text::MockTextEngine));
+            }
 
             let inner = Inner {
                 data,
                 env: Env::default(),
                 window,
                 cmds: Default::default(),
+                ext_host,
+                delegate: None,
             };
 
             let mut harness = Harness {
@@ -175,6 +189,34 @@ impl<T: Data> Harness<'_, T> {
         self.window_size = size;
     }
 
+    /// Build the window's [`TextLayout`]s with `engine`; intended to be used
+    /// before calling `send_initial_events`
+    ///
+    /// With the `testing` feature, the harness uses a [`MockTextEngine`] by
+    /// default, so that text measures and paints the same on every platform.
+    ///
+    /// [`TextLayout`]: crate::TextLayout
+    /// [`MockTextEngine`]: crate::text::MockTextEngine
+    pub fn set_text_engine(&mut self, engine: impl TextEngine) {
+        self.inner.window.text_engine = Some(shared_engine(engine));
+    }
+
+    /// Build the window's [`TextLayout`]s with the platform's text system,
+    /// instead of the [`MockTextEngine`] that is the default with the
+    /// `testing` feature; intended to be used
+    /// before calling `send_initial_events`
+    ///
+    /// [`TextLayout`]: crate::TextLayout
+    /// [`MockTextEngine`]: crate::text::MockTextEngine
+    pub fn use_platform_text(&mut self) {
+        self.inner.window.text_engine = None;
+    }
+
+    /// The size of the window, as sent by `send_initial_events`.
+    pub fn window_size(&self) -> Size {
+        self.window_size
+    }
+
     pub fn window(&self) -> &Window<T> {
         &self.inner.window
     }
@@ -189,6 +231,12 @@ impl<T: Data> Harness<'_, T> {
         &self.inner.data
     }
 
+    /// Describe the widget tree, starting at the root widget.
+    #[cfg(feature = "debug_state")]
+    pub fn root_debug_state(&self) -> DebugState {
+        self.window().debug_state(self.data())
+    }
+
     /// Retrieve a copy of this widget's `WidgetState`, or die trying.
     pub fn get_state(&mut self, widget: WidgetId) -> WidgetState {
         match self.try_get_state(widget) {
@@ -221,8 +269,57 @@ impl<T: Data> Harness<'_, T> {
     /// Send a command to a target.
     pub fn submit_command(&mut self, cmd: impl Into<Command>) {
         let command = cmd.into().default_to(self.inner.window.id.into());
-        let event = Event::Internal(InternalEvent::TargetedCommand(command));
-        self.event(event);
+        self.handle_cmd(command);
+    }
+
+    /// Wait for a command to be submitted through an [`ExtEventSink`], such
+    /// as by a background thread, then deliver all the pending ones.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no command arrives within two seconds.
+    ///
+    /// [`ExtEventSink`]: crate::ExtEventSink
+    pub fn wait_for_ext_events(&mut self) {
+        let deadline = Instant::now() + Duration::from_secs(2);
+        while !self.inner.ext_host.has_pending_items() {
+            assert!(Instant::now() < deadline, "no external event arrived");
+            thread::sleep(Duration::from_millis(5));
+        }
+        while let Some(cmd) = self.inner.ext_host.recv() {
+            self.submit_command(cmd);
+        }
+    }
+
+    /// Set the [`AppDelegate`] that answers queries from widgets.
+    ///
+    /// Only [`AppDelegate::query`] is called; commands and events are not
+    /// passed through the delegate.
+    ///
+    /// [`AppDelegate`]: crate::AppDelegate
+    /// [`AppDelegate::query`]: crate::AppDelegate::query
+    pub fn set_delegate(&mut self, delegate: impl AppDelegate<T> + 'static) {
+        self.inner.delegate = Some(Box::new(delegate));
+    }
+
+    /// Edit the focused text field through its [`InputHandler`], as the
+    /// platform would, and then deliver the resulting `ImeStateChange` event.
+    ///
+    /// [`InputHandler`]: crate::text::InputHandler
+    pub fn ime_edit(&mut self, f: impl FnOnce(&mut dyn InputHandler)) {
+        let window = self.window_mut();
+        let token = window
+            .ime_handlers
+            .iter()
+            .find(|(_, reg)| Some(reg.widget_id) == window.focus)
+            .map(|(token, _)| *token)
+            .expect("no focused text field");
+        let mut handler = window.get_ime_handler(token, true);
+        f(&mut *handler);
+        drop(handler);
+        if let Some(widget) = self.window_mut().release_ime_lock(token) {
+            self.event(Event::Internal(InternalEvent::RouteImeStateChange(widget)));
+        }
     }
 
     /// Send the events that would normally be sent when the app starts.
@@ -248,12 +345,25 @@ impl<T: Data> Harness<'_, T> {
         loop {
             let cmd = self.inner.cmds.pop_front();
             match cmd {
-                Some(cmd) => self.event(Event::Internal(InternalEvent::TargetedCommand(cmd))),
+                Some(cmd) => self.handle_cmd(cmd),
                 None => break,
             }
         }
     }
 
+    /// Handle the commands the app would handle itself, and send the rest to the window.
+    fn handle_cmd(&mut self, cmd: Command) {
+        if let Some(update) = cmd.get(commands::UPDATE_ENV) {
+            update.apply(&mut self.inner.env);
+            self.update();
+        } else {
+            self.event(Event::Internal(InternalEvent::TargetedCommand(cmd.clone())));
+            if let Some(cancellation) = cmd.cancellation() {
+                self.handle_cmd(cancellation);
+            }
+        }
+    }
+
     pub(crate) fn lifecycle(&mut self, event: LifeCycle) {
         self.inner.lifecycle(event)
     }
@@ -263,9 +373,16 @@ impl<T: Data> Harness<'_, T> {
         self.inner.update()
     }
 
-    /// Only do a layout pass, without painting
+    /// Only do a layout pass, without painting.
+    ///
+    /// Commands submitted during layout are dispatched afterwards, as they
+    /// would be by the running application.
     pub fn just_layout(&mut self) {
-        self.inner.layout()
+        self.inner.layout();
+        if !self.inner.cmds.is_empty() {
+            self.process_commands();
+            self.update();
+        }
     }
 
     /// Paints just the part of the window that was invalidated by calls to `request_paint` or
@@ -285,12 +402,46 @@ impl<T: Data> Harness<'_, T> {
         self.inner
             .paint_region(&mut self.piet, &self.window_size.to_rect().into());
     }
+
+    /// Paints the window into a new image of `size`, at the given scale.
+    ///
+    /// Unlike [`paint`], this doesn't touch the harness's own render target,
+    /// so it can be called as often as needed, such as to capture a widget
+    /// in several states.
+    ///
+    /// [`paint`]: Harness::paint
+    pub fn render_to_image(&mut self, size: Size, scale: f64) -> ImageBuf {
+        let mut device = Device::new().expect("harness failed to get device");
+        let size = size * scale;
+        let mut target = device
+            .bitmap_target(size.width as usize, size.height as usize, scale)
+            .expect("bitmap_target");
+        {
+            let mut piet = target.render_context();
+            self.inner
+                .paint_region(&mut piet, &self.window_size.to_rect().into());
+            piet.finish().expect("piet finish failed");
+        }
+        target
+            .to_image_buf(ImageFormat::RgbaPremul)
+            .expect("failed to read the render target")
+    }
 }
 
 impl<T: Data> Inner<T> {
     fn event(&mut self, event: Event) {
+        let (ext_event_host, env) = (&self.ext_host, &self.env);
+        let mut queries = self
+            .delegate
+            .as_deref_mut()
+            .map(|delegate| DelegateQueries {
+                delegate,
+                ext_event_host,
+                env,
+            });
+        let queries = queries.as_mut().map(|q| q as &mut dyn QueryHandler);
         self.window
-            .event(&mut self.cmds, event, &mut self.data, &self.env);
+            .event(&mut self.cmds, event, &mut self.data, &self.env, queries);
     }
 
     fn lifecycle(&mut self, event: LifeCycle) {
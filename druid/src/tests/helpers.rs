@@ -285,6 +285,7 @@ impl<T: Data, W: Widget<T>> Widget<T> for Recorder<W> {
             event,
             LifeCycle::Internal(InternalLifeCycle::DebugRequestState { .. })
                 | LifeCycle::Internal(InternalLifeCycle::DebugInspectState(_))
+                | LifeCycle::Internal(InternalLifeCycle::CollectWidgetPaths(_))
         );
 
         if should_record {
@@ -310,6 +311,11 @@ impl<T: Data, W: Widget<T>> Widget<T> for Recorder<W> {
         self.inner.paint(ctx, data, env);
         self.recording.push(Record::Paint)
     }
+
+    #[cfg(feature = "debug_state")]
+    fn debug_state(&self, data: &T) -> DebugState {
+        self.inner.debug_state(data)
+    }
 }
 
 pub fn widget_ids<const N: usize>() -> [WidgetId; N] {
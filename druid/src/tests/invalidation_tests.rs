@@ -125,4 +125,44 @@ fn invalidate_scroll() {
     });
 }
 
+#[test]
+fn invalidate_extreme_rects() {
+    const SET: Selector<Rect> = Selector::new("druid-test.invalidate-rect");
+    let id = WidgetId::next();
+    let widget = ModularWidget::new(())
+        .event_fn(|_, ctx, event, _, _| {
+            if let Event::Command(cmd) = event {
+                if let Some(rect) = cmd.get(SET) {
+                    ctx.request_paint_rect(*rect);
+                }
+            }
+        })
+        .with_id(id);
+
+    Harness::create_simple((), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        harness.window_mut().invalid_mut().clear();
+        let widget_rect = harness.get_state(id).layout_rect();
+
+        for rect in &[
+            Rect::new(f64::NAN, 0., 10., 10.),
+            Rect::new(0., 0., f64::INFINITY, 10.),
+            Rect::new(f64::NEG_INFINITY, f64::NEG_INFINITY, 0., 0.),
+        ] {
+            harness.submit_command(SET.with(*rect).to(id));
+            assert!(harness.window().invalid().is_empty());
+        }
+        // only the first one is reported
+        assert!(harness.get_state(id).warned_non_finite_paint);
+
+        // Huge rects are clipped to the widget before they get to the platform.
+        harness.submit_command(SET.with(Rect::new(-1e12, -1e12, 1e12, 1e12)).to(id));
+        assert_eq!(harness.window().invalid().rects(), &[widget_rect]);
+        harness.window_mut().invalid_mut().clear();
+        harness.submit_command(SET.with(Rect::new(1e12, 1e12, 2e12, 2e12)).to(id));
+        assert!(harness.window().invalid().is_empty());
+    });
+}
+
 // TODO: one with scroll
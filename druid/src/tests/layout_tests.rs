@@ -273,3 +273,172 @@ fn aspect_ratio() {
         assert_eq!(state.layout_rect().size(), Size::new(1000., 500.));
     });
 }
+
+#[test]
+fn dense_metrics_shrink_button() {
+    const DENSIFY: Selector = Selector::new("druid-tests.densify");
+    let id = WidgetId::next();
+    let button = EnvScope::new(
+        |env, dense: &bool| {
+            if *dense {
+                theme::metrics::DENSE.apply_to(env);
+            }
+        },
+        Button::new("Press me").with_id(id),
+    );
+    let switch = ModularWidget::new(()).event_fn(|_, _, event, dense: &mut bool, _| {
+        if let Event::Command(cmd) = event {
+            if cmd.is(DENSIFY) {
+                *dense = true;
+            }
+        }
+    });
+    let widget = Flex::column().with_child(button).with_child(switch);
+
+    Harness::create_simple(false, widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        let comfortable = harness.get_state(id).layout_rect().height();
+
+        harness.submit_command(DENSIFY);
+        harness.just_layout();
+        let dense = harness.get_state(id).layout_rect().height();
+
+        assert!(
+            dense < comfortable,
+            "dense height {} should be less than {}",
+            dense,
+            comfortable
+        );
+        assert!(dense >= theme::metrics::DENSE.bordered_widget_height);
+    });
+}
+
+#[test]
+fn dense_metrics_relayout_controls() {
+    const DENSIFY: Selector = Selector::new("druid-tests.densify-controls");
+    const DENSE: theme::metrics::Metrics = theme::metrics::DENSE;
+
+    #[derive(Clone, Data, Lens)]
+    struct Form {
+        dense: bool,
+        checked: bool,
+        value: f64,
+        text: String,
+    }
+
+    let ids = widget_ids();
+    let [checkbox, radio, slider, text_box, switch, stepper, progress, spinner] = ids;
+    let controls = Flex::column()
+        .with_child(Checkbox::new("Check").lens(Form::checked).with_id(checkbox))
+        .with_child(Radio::new("Pick", true).lens(Form::checked).with_id(radio))
+        .with_child(Slider::new().lens(Form::value).with_id(slider))
+        .with_child(TextBox::new().lens(Form::text).with_id(text_box))
+        .with_child(Switch::new().lens(Form::checked).with_id(switch))
+        .with_child(Stepper::new().lens(Form::value).with_id(stepper))
+        .with_child(ProgressBar::new().lens(Form::value).with_id(progress))
+        .with_child(WidgetExt::<Form>::with_id(Spinner::new(), spinner));
+    let switch_density = ModularWidget::new(()).event_fn(|_, _, event, form: &mut Form, _| {
+        if let Event::Command(cmd) = event {
+            if cmd.is(DENSIFY) {
+                form.dense = true;
+            }
+        }
+    });
+    let widget = EnvScope::new(
+        |env, form: &Form| {
+            if form.dense {
+                DENSE.apply_to(env);
+            }
+        },
+        Flex::column()
+            .with_child(controls)
+            .with_child(switch_density),
+    );
+    let form = Form {
+        dense: false,
+        checked: true,
+        value: 0.5,
+        text: "text".into(),
+    };
+
+    Harness::create_simple(form, widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        let comfortable: Vec<_> = ids
+            .iter()
+            .map(|id| harness.get_state(*id).layout_rect().size())
+            .collect();
+
+        harness.submit_command(DENSIFY);
+        for id in &ids {
+            assert!(harness.get_state(*id).needs_layout, "{:?} needs layout", id);
+        }
+        harness.just_layout();
+        let mut size = |id| harness.get_state(id).layout_rect().size();
+
+        assert_eq!(size(slider).height, DENSE.basic_widget_height);
+        assert_eq!(size(progress).height, DENSE.basic_widget_height);
+        assert_eq!(size(spinner).height, DENSE.basic_widget_height);
+        assert_eq!(size(switch).height, DENSE.bordered_widget_height);
+        assert_eq!(
+            size(stepper),
+            Size::new(DENSE.basic_widget_height, DENSE.bordered_widget_height)
+        );
+        // the check mark and its padding shrink by 18 + 4 - (14 + 2)
+        assert_eq!(size(checkbox).width, comfortable[0].width - 6.0);
+        assert_eq!(size(radio).width, comfortable[1].width - 6.0);
+        // vertical insets shrink from 4 + 4 to 1 + 1
+        assert_eq!(size(text_box).height, comfortable[3].height - 6.0);
+    });
+}
+
+#[test]
+fn padding_larger_than_parent() {
+    let id = WidgetId::next();
+    let widget = Label::new("squeezed")
+        .with_id(id)
+        .padding(100.)
+        .fix_size(50., 50.)
+        .center();
+
+    Harness::create_simple((), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        assert_eq!(harness.get_state(id).layout_rect().size(), Size::ZERO);
+    });
+}
+
+#[test]
+#[cfg(feature = "debug_state")]
+fn debug_paint_layout_records_constraints() {
+    let id = WidgetId::next();
+    let widget = SizedBox::empty()
+        .fix_size(30., 20.)
+        .with_id(id)
+        .center()
+        .debug_paint_layout();
+
+    Harness::create_simple((), widget, |harness| {
+        harness.set_initial_size(Size::new(100., 100.));
+        harness.send_initial_events();
+        harness.just_layout();
+
+        fn find(state: &DebugState, id: WidgetId) -> Option<&DebugState> {
+            if state.widget_id == Some(id) {
+                return Some(state);
+            }
+            state.children.iter().find_map(|child| find(child, id))
+        }
+        let root = harness.root_debug_state();
+        let state = find(&root, id).expect("widget in debug tree");
+        assert_eq!(
+            state.other_values.get("constraints").map(String::as_str),
+            Some("(0×0) ..= (100×100)")
+        );
+        assert_eq!(
+            state.other_values.get("size").map(String::as_str),
+            Some("(30×20)")
+        );
+    });
+}
@@ -23,13 +23,26 @@ pub mod helpers;
 mod invalidation_tests;
 #[cfg(test)]
 mod layout_tests;
+#[cfg(test)]
+mod pointer_capture_tests;
+#[cfg(test)]
+mod query_tests;
+#[cfg(test)]
+mod routing_tests;
+#[cfg(test)]
+pub(crate) mod snapshot;
+#[cfg(test)]
+mod text_tests;
+#[cfg(test)]
+mod update_tests;
 
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
 use std::env;
 use std::fs;
 use std::rc::Rc;
 
+use crate::command::sys::{REQUEST_FOCUS, RESIGN_FOCUS};
 use crate::widget::*;
 use crate::*;
 use harness::*;
@@ -255,6 +268,126 @@ fn take_focus() {
     })
 }
 
+#[test]
+fn focus_commands() {
+    let [text_1, text_2, label, unknown] = widget_ids();
+    let widget = Scroll::new(
+        Flex::column()
+            .with_child(TextBox::new().with_id(text_1))
+            .with_spacer(1000.)
+            .with_child(Label::new("not focusable").with_id(label))
+            .with_child(TextBox::new().with_id(text_2)),
+    )
+    .vertical();
+
+    Harness::create_simple(String::new(), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        assert_eq!(harness.window().focus, None);
+
+        harness.submit_command(REQUEST_FOCUS.with(text_1));
+        assert_eq!(harness.window().focus, Some(text_1));
+
+        // the widget is scrolled into view when it gets focus
+        assert!(harness.get_state(text_2).window_origin().y > 400.);
+        harness.submit_command(REQUEST_FOCUS.with(text_2));
+        harness.just_layout();
+        assert_eq!(harness.window().focus, Some(text_2));
+        assert!(harness.get_state(text_2).window_origin().y < 400.);
+
+        // a request for a widget that can't take focus changes nothing
+        harness.submit_command(REQUEST_FOCUS.with(label));
+        assert_eq!(harness.window().focus, Some(text_2));
+        // even when it is aimed at the widget itself
+        harness.submit_command(REQUEST_FOCUS.with(label).to(label));
+        assert_eq!(harness.window().focus, Some(text_2));
+        // or at a widget that could take focus
+        harness.submit_command(REQUEST_FOCUS.with(label).to(text_1));
+        assert_eq!(harness.window().focus, Some(text_2));
+        harness.submit_command(REQUEST_FOCUS.with(unknown));
+        assert_eq!(harness.window().focus, Some(text_2));
+
+        // only the focused widget can be told to resign
+        harness.submit_command(RESIGN_FOCUS.to(text_1));
+        assert_eq!(harness.window().focus, Some(text_2));
+        harness.submit_command(RESIGN_FOCUS.to(text_2));
+        assert_eq!(harness.window().focus, None);
+
+        harness.submit_command(REQUEST_FOCUS.with(text_1));
+        harness.submit_command(RESIGN_FOCUS);
+        assert_eq!(harness.window().focus, None);
+    })
+}
+
+#[test]
+fn single_use_command_stops_after_take() {
+    const TAKE: Selector<SingleUse<u32>> = Selector::new("druid-tests.single-use-take");
+
+    /// A widget that takes the payload, and counts the commands it sees.
+    fn taker(seen: Rc<Cell<u32>>) -> impl Widget<bool> {
+        ModularWidget::new(seen).event_fn(|seen, _ctx, event, _data, _env| {
+            if let Event::Command(cmd) = event {
+                if let Some(payload) = cmd.get(TAKE) {
+                    seen.set(seen.get() + 1);
+                    assert_eq!(payload.take(), Some(42));
+                }
+            }
+        })
+    }
+
+    let first: Rc<Cell<u32>> = Default::default();
+    let second: Rc<Cell<u32>> = Default::default();
+    let widget = Split::columns(taker(first.clone()), taker(second.clone()));
+
+    Harness::create_simple(true, widget, |harness| {
+        harness.send_initial_events();
+        harness.submit_command(TAKE.with(SingleUse::new(42)));
+        assert_eq!(first.get(), 1);
+        assert_eq!(second.get(), 0);
+    })
+}
+
+#[test]
+fn ext_single_use_command_reaches_target() {
+    const TAKE: Selector<SingleUse<u32>> = Selector::new("druid-tests.ext-single-use-take");
+    /// Start a thread that sends `TAKE` to the widget in the payload.
+    const START: Selector<WidgetId> = Selector::new("druid-tests.ext-start");
+
+    fn taker(seen: Rc<Cell<u32>>) -> impl Widget<bool> {
+        ModularWidget::new(seen).event_fn(|seen, ctx, event, _data, _env| {
+            if let Event::Command(cmd) = event {
+                if let Some(&target) = cmd.get(START) {
+                    let sink = ctx.get_external_handle();
+                    std::thread::spawn(move || {
+                        sink.submit_command(TAKE, SingleUse::new(42), target)
+                            .unwrap();
+                    });
+                }
+                if let Some(payload) = cmd.get(TAKE) {
+                    seen.set(seen.get() + 1);
+                    assert_eq!(payload.take(), Some(42));
+                }
+            }
+        })
+    }
+
+    let [first_id, second_id] = widget_ids();
+    let first: Rc<Cell<u32>> = Default::default();
+    let second: Rc<Cell<u32>> = Default::default();
+    let widget = Split::columns(
+        taker(first.clone()).with_id(first_id),
+        taker(second.clone()).with_id(second_id),
+    );
+
+    Harness::create_simple(true, widget, |harness| {
+        harness.send_initial_events();
+        harness.submit_command(START.with(second_id).to(first_id));
+        harness.wait_for_ext_events();
+        assert_eq!(first.get(), 0);
+        assert_eq!(second.get(), 1);
+    })
+}
+
 #[test]
 fn focus_changed() {
     const TAKE_FOCUS: Selector = Selector::new("druid-tests.take-focus");
@@ -961,3 +1094,154 @@ fn notifications() {
         assert!(saw_notification(&grandparent_rec));
     });
 }
+
+#[test]
+fn requests() {
+    const DOUBLE: Request<u32, u32> = Request::new("druid-tests.double");
+
+    let [responder, ignorer, requester] = widget_ids();
+    let missing = WidgetId::next();
+    let responses = Rc::new(RefCell::new(Vec::new()));
+
+    let responding = ModularWidget::new(()).event_fn(|_, ctx, event, _, _| {
+        if let Event::Command(cmd) = event {
+            if let Some(value) = cmd.get_request(DOUBLE) {
+                ctx.submit_response(cmd, DOUBLE, value * 2);
+            }
+        }
+    });
+    let requesting =
+        ModularWidget::new(responses.clone()).event_fn(move |responses, ctx, event, _, _| {
+            match event {
+                Event::WindowConnected => {
+                    ctx.submit_request(DOUBLE, 21, responder);
+                    ctx.submit_request(DOUBLE, 1, ignorer);
+                    ctx.submit_request(DOUBLE, 2, missing);
+                }
+                Event::Command(cmd) => {
+                    if let Some(response) = cmd.get_response(DOUBLE) {
+                        responses.borrow_mut().push(response.copied());
+                    }
+                }
+                _ => (),
+            }
+        });
+
+    let tree = Flex::row()
+        .with_child(responding.with_id(responder))
+        .with_child(SizedBox::empty().with_id(ignorer))
+        .with_child(requesting.with_id(requester));
+
+    Harness::create_simple((), tree, |harness| {
+        harness.send_initial_events();
+        // an ignored request and one to a missing widget are both canceled
+        assert_eq!(
+            *responses.borrow(),
+            vec![Ok(42), Err(RequestCanceled), Err(RequestCanceled)]
+        );
+    });
+}
+
+#[test]
+#[cfg(feature = "debug_state")]
+fn debug_state() {
+    use std::sync::Arc;
+
+    #[derive(Clone, Data, Lens)]
+    struct State {
+        name: String,
+        enabled: bool,
+        items: Arc<Vec<String>>,
+    }
+
+    let [button_id, list_id] = widget_ids();
+    let widget = Flex::column()
+        .with_child(TextBox::new().lens(State::name))
+        .with_child(Checkbox::new("Enabled").lens(State::enabled))
+        .with_child(
+            List::new(|| Label::dynamic(|s: &String, _| s.clone()))
+                .lens(State::items)
+                .with_id(list_id),
+        )
+        .with_child(Button::new("Save").with_id(button_id))
+        .padding(5.0);
+
+    let state = State {
+        name: "Ferris".into(),
+        enabled: true,
+        items: Arc::new(vec!["one".into(), "two".into()]),
+    };
+
+    Harness::create_simple(state, widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        let root = harness.root_debug_state();
+
+        assert_eq!(root.display_name, "Padding");
+        let flex = &root.children[0];
+        assert_eq!(flex.display_name, "Flex");
+        let names: Vec<_> = flex
+            .children
+            .iter()
+            .map(|c| c.display_name.as_str())
+            .collect();
+        assert_eq!(names, ["TextBox", "Checkbox", "List", "Button"]);
+
+        let text_box = &flex.children[0];
+        assert_eq!(text_box.main_value, "Ferris");
+        let checkbox = &flex.children[1];
+        assert_eq!(checkbox.main_value, "true");
+        assert_eq!(checkbox.children[0].main_value, "Enabled");
+
+        let list = &flex.children[2];
+        assert_eq!(list.widget_id, Some(list_id));
+        let rows: Vec<_> = list
+            .children
+            .iter()
+            .map(|c| c.main_value.as_str())
+            .collect();
+        assert_eq!(rows, ["one", "two"]);
+
+        let save = root
+            .find(|s| s.display_name == "Label" && s.main_value == "Save")
+            .is_some();
+        assert!(save);
+        let button = root.find(|s| s.widget_id == Some(button_id)).unwrap();
+        assert_eq!(button.display_name, "Button");
+        assert_eq!(
+            button.layout_rect,
+            Some(harness.get_state(button_id).layout_rect())
+        );
+    });
+}
+
+#[test]
+#[cfg(feature = "debug_state")]
+fn window_answers_debug_state_query() {
+    fn recorder(seen: Rc<RefCell<Option<DebugState>>>) -> impl Widget<()> {
+        ModularWidget::new(seen).event_fn(|seen, _ctx, event, _data, _env| {
+            if let Event::Command(cmd) = event {
+                if let Some(state) = cmd.get(commands::DEBUG_STATE) {
+                    *seen.borrow_mut() = Some(state.clone());
+                }
+            }
+        })
+    }
+
+    let seen: Rc<RefCell<Option<DebugState>>> = Default::default();
+    let widget = Flex::column()
+        .with_child(Label::new("Save"))
+        .with_child(recorder(seen.clone()));
+
+    Harness::create_simple((), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        harness.submit_command(commands::QUERY_DEBUG_STATE);
+
+        let root = seen.borrow_mut().take().expect("window answered the query");
+        assert_eq!(root, harness.root_debug_state());
+        assert!(root
+            .find(|s| s.display_name == "Label" && s.main_value == "Save")
+            .is_some());
+    });
+}
@@ -0,0 +1,141 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests related to pointer capture.
+
+use test_env_log::test;
+
+use super::*;
+
+const CAPTURE: Selector = Selector::new("druid-tests.capture-pointer");
+const RELEASE: Selector = Selector::new("druid-tests.release-pointer");
+const DISABLE: Selector = Selector::new("druid-tests.disable");
+
+/// A 100x100 widget that captures and releases the pointer on command,
+/// and sets its data on `DISABLE`.
+fn pointer_capturer(id: WidgetId, recording: &Recording) -> impl Widget<bool> {
+    ModularWidget::new(())
+        .event_fn(|_, ctx, event, data, _| {
+            if let Event::Command(cmd) = event {
+                if cmd.is(CAPTURE) {
+                    ctx.capture_pointer();
+                } else if cmd.is(RELEASE) {
+                    ctx.release_pointer();
+                } else if cmd.is(DISABLE) {
+                    *data = true;
+                }
+            }
+        })
+        .record(recording)
+        .fix_size(100.0, 100.0)
+        .with_id(id)
+}
+
+/// Drain `recording`, returning the `PointerCaptureChanged` events and
+/// the positions of mouse moves it saw.
+fn capture_changes_and_moves(recording: &Recording) -> (Vec<bool>, Vec<Point>) {
+    let mut changes = Vec::new();
+    let mut moves = Vec::new();
+    for record in recording.drain() {
+        match record {
+            Record::L(LifeCycle::PointerCaptureChanged(captured)) => changes.push(captured),
+            Record::E(Event::MouseMove(mouse)) => moves.push(mouse.pos),
+            _ => (),
+        }
+    }
+    (changes, moves)
+}
+
+#[test]
+fn pointer_capture() {
+    let [id_a, id_b] = widget_ids();
+    let rec_a = Recording::default();
+    let rec_b = Recording::default();
+    let widget = Flex::row()
+        .with_child(pointer_capturer(id_a, &rec_a))
+        .with_child(pointer_capturer(id_b, &rec_b));
+
+    Harness::create_simple(false, widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        let rect_a = harness.get_state(id_a).layout_rect();
+        let rect_b = harness.get_state(id_b).layout_rect();
+        rec_a.clear();
+        rec_b.clear();
+
+        harness.submit_command(CAPTURE.to(id_a));
+        assert_eq!(harness.window().pointer_capture, Some(id_a));
+        assert_eq!(capture_changes_and_moves(&rec_a).0, [true]);
+
+        // a move over b, and one outside of the window, only go to a, relative to its origin
+        let over_b = rect_b.center();
+        harness.event(Event::MouseMove(move_mouse(over_b)));
+        harness.event(Event::MouseMove(move_mouse((-50.0, -50.0))));
+        let origin = rect_a.origin().to_vec2();
+        let expected = [over_b - origin, Point::new(-50.0, -50.0) - origin];
+        assert_eq!(capture_changes_and_moves(&rec_a).1, expected);
+        assert!(!harness.get_state(id_a).is_hot);
+        assert!(rec_b.is_empty());
+        assert!(!harness.get_state(id_b).is_hot);
+
+        // b steals the capture
+        harness.submit_command(CAPTURE.to(id_b));
+        assert_eq!(harness.window().pointer_capture, Some(id_b));
+        assert_eq!(capture_changes_and_moves(&rec_a).0, [false]);
+        assert_eq!(capture_changes_and_moves(&rec_b).0, [true]);
+
+        // releasing a capture you don't hold does nothing
+        harness.submit_command(RELEASE.to(id_a));
+        assert_eq!(harness.window().pointer_capture, Some(id_b));
+
+        harness.submit_command(RELEASE.to(id_b));
+        assert_eq!(harness.window().pointer_capture, None);
+        assert_eq!(capture_changes_and_moves(&rec_b).0, [false]);
+
+        // without capture, events go to the widget under the pointer
+        harness.event(Event::MouseMove(move_mouse(rect_a.center())));
+        assert_eq!(capture_changes_and_moves(&rec_a).1.len(), 1);
+        assert!(capture_changes_and_moves(&rec_b).1.is_empty());
+    });
+}
+
+#[test]
+fn pointer_capture_released_on_removal_and_disable() {
+    let [id_a, id_b] = widget_ids();
+    let rec_a = Recording::default();
+    let rec_b = Recording::default();
+    let widget = Flex::row()
+        .with_child(ReplaceChild::new(
+            pointer_capturer(id_a, &rec_a),
+            SizedBox::empty,
+        ))
+        .with_child(pointer_capturer(id_b, &rec_b).disabled_if(|data, _| *data));
+
+    Harness::create_simple(false, widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+
+        harness.submit_command(CAPTURE.to(id_a));
+        assert_eq!(harness.window().pointer_capture, Some(id_a));
+        harness.submit_command(REPLACE_CHILD);
+        assert_eq!(harness.window().pointer_capture, None);
+
+        harness.submit_command(CAPTURE.to(id_b));
+        assert_eq!(harness.window().pointer_capture, Some(id_b));
+        rec_b.clear();
+        harness.submit_command(DISABLE.to(id_b));
+        assert_eq!(harness.window().pointer_capture, None);
+        assert_eq!(capture_changes_and_moves(&rec_b).0, [false]);
+    });
+}
@@ -0,0 +1,87 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests for synchronous queries from widgets to the app delegate.
+
+use test_env_log::test;
+
+use super::*;
+
+const IS_OPEN: Query<String, bool> = Query::new("druid-tests.is-open");
+const USER_NAME: Query<(), String> = Query::new("druid-tests.user-name");
+
+/// Knows which files are open, but not who the user is.
+struct OpenFiles(Vec<String>);
+
+impl AppDelegate<String> for OpenFiles {
+    fn query(&mut self, _ctx: &mut DelegateCtx, request: &mut QueryRequest, _env: &Env) {
+        if let Some(name) = request.get(IS_OPEN) {
+            let is_open = self.0.contains(name);
+            request.respond(IS_OPEN, is_open);
+        }
+    }
+}
+
+/// On mouse down, asks whether `file` is open, and sets its data to what
+/// it decided to do.
+fn opener(file: &str) -> impl Widget<String> {
+    ModularWidget::new(file.to_string()).event_fn(|file, ctx, event, data, _| {
+        if let Event::MouseDown(_) = event {
+            *data = match ctx.query(IS_OPEN, file.clone()) {
+                Some(true) => "focus existing".into(),
+                Some(false) => "open new".into(),
+                None => "unknown".into(),
+            };
+        }
+    })
+}
+
+/// Click on `widget`, optionally with a delegate, and return its data.
+fn click(widget: impl Widget<String> + 'static, mut delegate: Option<OpenFiles>) -> String {
+    let mut result = String::new();
+    Harness::create_simple(String::new(), widget, |harness| {
+        if let Some(delegate) = delegate.take() {
+            harness.set_delegate(delegate);
+        }
+        harness.send_initial_events();
+        harness.just_layout();
+        harness.event(Event::MouseDown(move_mouse((10., 10.))));
+        result = harness.data().clone();
+    });
+    result
+}
+
+fn open_files() -> Option<OpenFiles> {
+    Some(OpenFiles(vec!["notes.txt".into()]))
+}
+
+#[test]
+fn delegate_answers_query() {
+    assert_eq!(click(opener("notes.txt"), open_files()), "focus existing");
+    assert_eq!(click(opener("todo.txt"), open_files()), "open new");
+}
+
+#[test]
+fn unanswered_query() {
+    // without a delegate
+    assert_eq!(click(opener("notes.txt"), None), "unknown");
+
+    // with a delegate that doesn't know the answer
+    let widget = ModularWidget::new(()).event_fn(|_, ctx, event, data: &mut String, _| {
+        if let Event::MouseDown(_) = event {
+            *data = ctx.query(USER_NAME, ()).unwrap_or_else(|| "nobody".into());
+        }
+    });
+    assert_eq!(click(widget, open_files()), "nobody");
+}
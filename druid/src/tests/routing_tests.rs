@@ -0,0 +1,223 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests for routing commands to a specific widget.
+
+use std::cell::RefCell;
+
+use test_env_log::test;
+
+use super::*;
+
+const PING: Selector = Selector::new("druid-tests.ping");
+/// Take a child out of a stack, and hold on to it.
+const TAKE: Selector<WidgetId> = Selector::new("druid-tests.take");
+/// Put the child we are holding into a stack.
+const PUT: Selector = Selector::new("druid-tests.put");
+
+type Hand = Rc<RefCell<Option<WidgetPod<(), Box<dyn Widget<()>>>>>>;
+
+/// Widgets on top of each other, which can be moved between stacks.
+struct Stack {
+    children: ChildSet<()>,
+    hand: Hand,
+    /// How many times a command passed through this stack.
+    visits: Rc<Cell<usize>>,
+}
+
+impl Stack {
+    fn new(
+        children: impl IntoIterator<Item = Box<dyn Widget<()>>>,
+        hand: &Hand,
+        visits: &Rc<Cell<usize>>,
+    ) -> Self {
+        let mut set = ChildSet::new();
+        for child in children {
+            set.push(WidgetPod::new(child));
+        }
+        Stack {
+            children: set,
+            hand: hand.clone(),
+            visits: visits.clone(),
+        }
+    }
+}
+
+impl Widget<()> for Stack {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut (), env: &Env) {
+        match event {
+            Event::Internal(InternalEvent::TargetedCommand(_)) => {
+                self.visits.set(self.visits.get() + 1)
+            }
+            Event::Command(cmd) if cmd.is(TAKE) => {
+                let id = *cmd.get_unchecked(TAKE);
+                *self.hand.borrow_mut() = self.children.remove(id);
+                ctx.children_changed();
+                return;
+            }
+            Event::Command(cmd) if cmd.is(PUT) => {
+                if let Some(child) = self.hand.borrow_mut().take() {
+                    self.children.push(child);
+                    ctx.children_changed();
+                }
+                return;
+            }
+            _ => (),
+        }
+        for child in self.children.iter_mut() {
+            child.event(ctx, event, data, env);
+        }
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &(), env: &Env) {
+        for child in self.children.iter_mut() {
+            child.lifecycle(ctx, event, data, env);
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _: &(), data: &(), env: &Env) {
+        for child in self.children.iter_mut() {
+            child.update(ctx, data, env);
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &(), env: &Env) -> Size {
+        for child in self.children.iter_mut() {
+            child.layout(ctx, &bc.loosen(), data, env);
+            child.set_origin(ctx, data, env, Point::ORIGIN);
+        }
+        bc.max()
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &(), env: &Env) {
+        for child in self.children.iter_mut() {
+            child.paint(ctx, data, env);
+        }
+    }
+}
+
+/// A widget that counts the `PING`s it gets.
+fn leaf(id: WidgetId, pings: &Rc<Cell<usize>>) -> Box<dyn Widget<()>> {
+    let pings = pings.clone();
+    Box::new(
+        ModularWidget::new(())
+            .event_fn(move |_, _, event, _, _| {
+                if let Event::Command(cmd) = event {
+                    if cmd.is(PING) {
+                        pings.set(pings.get() + 1);
+                    }
+                }
+            })
+            .with_id(id),
+    )
+}
+
+#[test]
+fn command_goes_straight_to_target() {
+    const COLUMNS: usize = 50;
+    const ROWS: usize = 40;
+
+    let hand = Hand::default();
+    let visits = Rc::new(Cell::new(0));
+    let pings = Rc::new(Cell::new(0));
+    let ids: Vec<Vec<WidgetId>> = (0..COLUMNS)
+        .map(|_| (0..ROWS).map(|_| WidgetId::next()).collect())
+        .collect();
+    let columns = ids.iter().map(|column| {
+        let leaves = column.iter().map(|id| leaf(*id, &pings));
+        Box::new(Stack::new(leaves, &hand, &visits)) as Box<dyn Widget<()>>
+    });
+    let root = Stack::new(columns, &hand, &visits);
+
+    Harness::create_simple((), root, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+
+        for column in &ids {
+            for id in column {
+                visits.set(0);
+                harness.submit_command(PING.to(*id));
+                // With this many widgets the bloom filters are useless, so
+                // only knowing the path keeps us from visiting every column.
+                assert_eq!(visits.get(), 2, "the root and one column");
+            }
+        }
+        assert_eq!(pings.get(), COLUMNS * ROWS);
+    });
+}
+
+#[test]
+fn command_follows_moved_widget() {
+    let [first, second, target, other] = widget_ids();
+    let hand = Hand::default();
+    let visits = Rc::new(Cell::new(0));
+    let pings = Rc::new(Cell::new(0));
+    let root = Stack::new(
+        vec![
+            Box::new(Stack::new(vec![leaf(target, &pings)], &hand, &visits).with_id(first))
+                as Box<dyn Widget<()>>,
+            Box::new(Stack::new(vec![leaf(other, &pings)], &hand, &visits).with_id(second)),
+        ],
+        &hand,
+        &visits,
+    );
+
+    Harness::create_simple((), root, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        harness.submit_command(PING.to(target));
+        assert_eq!(pings.get(), 1);
+
+        harness.submit_command(TAKE.with(target).to(first));
+        harness.submit_command(PUT.to(second));
+        harness.just_layout();
+        harness.submit_command(PING.to(target));
+        assert_eq!(pings.get(), 2);
+        assert!(harness.try_get_state(target).is_some());
+    });
+}
+
+#[test]
+fn command_to_removed_widget_is_dropped() {
+    let [column, target] = widget_ids();
+    let hand = Hand::default();
+    let visits = Rc::new(Cell::new(0));
+    let pings = Rc::new(Cell::new(0));
+    let root = Stack::new(
+        vec![
+            Box::new(Stack::new(vec![leaf(target, &pings)], &hand, &visits).with_id(column))
+                as Box<dyn Widget<()>>,
+        ],
+        &hand,
+        &visits,
+    );
+
+    Harness::create_simple((), root, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+
+        harness.submit_command(TAKE.with(target).to(column));
+        harness.just_layout();
+        visits.set(0);
+        harness.submit_command(PING.to(target));
+        assert_eq!(pings.get(), 0);
+        assert_eq!(visits.get(), 0);
+
+        // once it's back, it gets commands again
+        harness.submit_command(PUT.to(column));
+        harness.just_layout();
+        harness.submit_command(PING.to(target));
+        assert_eq!(pings.get(), 1);
+    });
+}
@@ -0,0 +1,294 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Comparing what widgets paint against expected images.
+//!
+//! The expected images live in `src/tests/snapshots`, one PNG per snapshot.
+//! Run the tests with `DRUID_BLESS_SNAPSHOTS=1` to write new expected images
+//! from what is painted now. When a snapshot doesn't match, the image that
+//! was painted and an image showing the differing pixels in red are written
+//! next to the expected one, as `<name>.actual.png` and `<name>.diff.png`.
+//!
+//! The expected images are made with the cairo backend; on other platforms
+//! the comparison is skipped, since anti-aliasing differs between backends.
+
+use std::env;
+use std::fmt;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+
+use crate::piet::ImageFormat;
+use crate::ImageBuf;
+
+/// Set this environment variable to write new expected images.
+const BLESS_VAR: &str = "DRUID_BLESS_SNAPSHOTS";
+
+/// Paint the window of a [`Harness`] and compare it to the snapshot `name`.
+///
+/// An optional third argument is the largest difference allowed in any
+/// channel of a pixel; by default, the images have to match exactly.
+///
+/// [`Harness`]: crate::tests::harness::Harness
+macro_rules! assert_snapshot {
+    ($harness:expr, $name:expr) => {
+        $crate::tests::snapshot::assert_snapshot!($harness, $name, 0)
+    };
+    ($harness:expr, $name:expr, $tolerance:expr) => {{
+        let size = $harness.window_size();
+        let image = $harness.render_to_image(size, 1.0);
+        $crate::tests::snapshot::check_snapshot($name, &image, $tolerance);
+    }};
+}
+
+pub(crate) use assert_snapshot;
+
+/// How an image differs from the expected one.
+#[derive(Debug)]
+pub(crate) enum Mismatch {
+    Size {
+        expected: (usize, usize),
+        actual: (usize, usize),
+    },
+    Pixels {
+        count: usize,
+        /// The expected image, faded, with the differing pixels in red.
+        diff: ImageBuf,
+    },
+}
+
+/// Compare two images, allowing each channel of a pixel to differ by up to
+/// `tolerance`.
+pub(crate) fn compare(
+    expected: &ImageBuf,
+    actual: &ImageBuf,
+    tolerance: u8,
+) -> Result<(), Mismatch> {
+    let expected_size = (expected.width(), expected.height());
+    let actual_size = (actual.width(), actual.height());
+    if expected_size != actual_size {
+        return Err(Mismatch::Size {
+            expected: expected_size,
+            actual: actual_size,
+        });
+    }
+
+    let mut count = 0;
+    let mut diff = Vec::with_capacity(expected.raw_pixels().len());
+    for (want, got) in expected
+        .raw_pixels()
+        .chunks_exact(4)
+        .zip(actual.raw_pixels().chunks_exact(4))
+    {
+        let differs = want
+            .iter()
+            .zip(got)
+            .any(|(want, got)| want.max(got) - want.min(got) > tolerance);
+        if differs {
+            count += 1;
+            diff.extend_from_slice(&[255, 0, 0, 255]);
+        } else {
+            diff.extend(want[..3].iter().map(|c| 191 + c / 4));
+            diff.push(255);
+        }
+    }
+    if count == 0 {
+        Ok(())
+    } else {
+        let diff = ImageBuf::from_raw(
+            diff,
+            ImageFormat::RgbaPremul,
+            expected_size.0,
+            expected_size.1,
+        );
+        Err(Mismatch::Pixels { count, diff })
+    }
+}
+
+/// Compare `image` to the snapshot `name`, or save it as the snapshot if
+/// blessing is enabled.
+///
+/// # Panics
+///
+/// Panics if the snapshot doesn't exist or doesn't match.
+#[track_caller]
+pub(crate) fn check_snapshot(name: &str, image: &ImageBuf, tolerance: u8) {
+    if cfg!(any(
+        target_os = "macos",
+        target_os = "windows",
+        target_arch = "wasm32"
+    )) {
+        tracing::info!(
+            "skipping snapshot {}: the snapshots are made with cairo",
+            name
+        );
+        return;
+    }
+
+    let expected_path = snapshot_path(name, "");
+    if env::var_os(BLESS_VAR).is_some() {
+        write_png(&expected_path, image);
+        return;
+    }
+
+    let expected = match read_png(&expected_path) {
+        Ok(expected) => expected,
+        Err(err) => panic!(
+            "failed to read snapshot {}: {}\nrun the tests with {}=1 to create it",
+            expected_path.display(),
+            err,
+            BLESS_VAR
+        ),
+    };
+    if let Err(mismatch) = compare(&expected, image, tolerance) {
+        let actual_path = snapshot_path(name, ".actual");
+        write_png(&actual_path, image);
+        if let Mismatch::Pixels { diff, .. } = &mismatch {
+            write_png(&snapshot_path(name, ".diff"), diff);
+        }
+        panic!(
+            "snapshot {} doesn't match: {}\nthe new image is at {}",
+            name,
+            mismatch,
+            actual_path.display()
+        );
+    }
+}
+
+fn snapshot_path(name: &str, suffix: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("src/tests/snapshots")
+        .join(format!("{}{}.png", name, suffix))
+}
+
+fn read_png(path: &Path) -> Result<ImageBuf, Box<dyn std::error::Error>> {
+    let decoder = png::Decoder::new(BufReader::new(File::open(path)?));
+    let (info, mut reader) = decoder.read_info()?;
+    if info.color_type != png::ColorType::RGBA || info.bit_depth != png::BitDepth::Eight {
+        return Err("expected an 8-bit RGBA image".into());
+    }
+    let mut pixels = vec![0; info.buffer_size()];
+    reader.next_frame(&mut pixels)?;
+    Ok(ImageBuf::from_raw(
+        pixels,
+        ImageFormat::RgbaPremul,
+        info.width as usize,
+        info.height as usize,
+    ))
+}
+
+/// Write the pixels as they are; the window background is opaque, so
+/// premultiplying makes no difference.
+fn write_png(path: &Path, image: &ImageBuf) {
+    let file = File::create(path)
+        .unwrap_or_else(|err| panic!("failed to create {}: {}", path.display(), err));
+    let mut encoder = png::Encoder::new(
+        BufWriter::new(file),
+        image.width() as u32,
+        image.height() as u32,
+    );
+    encoder.set_color(png::ColorType::RGBA);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(image.raw_pixels()))
+        .unwrap_or_else(|err| panic!("failed to write {}: {}", path.display(), err));
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Mismatch::Size { expected, actual } => write!(
+                f,
+                "expected a {}x{} image, got {}x{}",
+                expected.0, expected.1, actual.0, actual.1
+            ),
+            Mismatch::Pixels { count, .. } => write!(f, "{} pixels differ", count),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::text::MockTextEngine;
+    use crate::widget::{Button, Checkbox, Flex, ProgressBar};
+    use crate::{Size, Widget, WidgetExt};
+    use test_env_log::test;
+
+    fn image(pixels: &[[u8; 4]]) -> ImageBuf {
+        let raw: Vec<u8> = pixels.iter().flatten().copied().collect();
+        ImageBuf::from_raw(raw, ImageFormat::RgbaPremul, pixels.len(), 1)
+    }
+
+    #[test]
+    fn compare_with_tolerance() {
+        let expected = image(&[[10, 20, 30, 255], [0, 0, 0, 255]]);
+        let close = image(&[[12, 18, 30, 255], [0, 0, 0, 255]]);
+        assert!(compare(&expected, &close, 2).is_ok());
+
+        match compare(&expected, &close, 1) {
+            Err(Mismatch::Pixels { count, diff }) => {
+                assert_eq!(count, 1);
+                assert_eq!(&diff.raw_pixels()[..4], &[255, 0, 0, 255]);
+                assert_ne!(&diff.raw_pixels()[4..], &[255, 0, 0, 255]);
+            }
+            other => panic!("expected one pixel to differ, got {:?}", other),
+        }
+
+        let wider = image(&[[0, 0, 0, 255]; 3]);
+        assert!(matches!(
+            compare(&expected, &wider, 255),
+            Err(Mismatch::Size { .. })
+        ));
+    }
+
+    fn corpus() -> impl Widget<(bool, f64)> {
+        Flex::column()
+            .with_child(Button::new("button"))
+            .with_child(Checkbox::new("checkbox").lens(crate::lens!((bool, f64), 0)))
+            .with_child(ProgressBar::new().lens(crate::lens!((bool, f64), 1)))
+    }
+
+    fn capture() -> ImageBuf {
+        let size = Size::new(200., 100.);
+        let mut image = None;
+        Harness::create_with_render(
+            (true, 0.5),
+            corpus(),
+            size,
+            |harness| {
+                // the platform's text would make the capture depend on the
+                // fonts of the machine running the test
+                harness.set_text_engine(MockTextEngine);
+                harness.send_initial_events();
+                harness.just_layout();
+                image = Some(harness.render_to_image(size, 1.0));
+            },
+            |_| {},
+        );
+        image.unwrap()
+    }
+
+    /// The point of snapshots is lost if painting the same thing twice can
+    /// give different pixels.
+    #[test]
+    fn captures_are_deterministic() {
+        let first = capture();
+        let second = capture();
+        assert!(first.raw_pixels().iter().any(|&c| c != 0));
+        assert_eq!(first.raw_pixels(), second.raw_pixels());
+    }
+}
@@ -0,0 +1,89 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests for text widgets, which the harness lays out with the fixed-width
+//! glyphs of `MockTextEngine`.
+//!
+//! At a text size of 16, every character is 8 wide, every line is 20 high,
+//! and glyphs are painted as boxes 6 wide and 12 high, ending on the baseline.
+
+use test_env_log::test;
+
+use super::*;
+
+#[test]
+fn label_measures_with_fixed_width_glyphs() {
+    let [single, wrapped] = widget_ids();
+    let widget = Flex::column()
+        .with_child(Label::new("hello").with_text_size(16.).with_id(single))
+        .with_child(
+            Label::new("hello world")
+                .with_text_size(16.)
+                .with_line_break_mode(LineBreaking::WordWrap)
+                .with_id(wrapped)
+                .fix_width(44.),
+        );
+
+    Harness::create_simple((), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        // five glyphs and the label's padding on either side
+        assert_eq!(
+            harness.get_state(single).layout_rect().size(),
+            Size::new(44., 20.)
+        );
+        // 40 points leaves room for five glyphs per line
+        assert_eq!(
+            harness.get_state(wrapped).layout_rect().size(),
+            Size::new(44., 40.)
+        );
+    });
+}
+
+#[test]
+fn label_paints_the_same_everywhere() {
+    let widget = Label::new("ab")
+        .with_text_size(16.)
+        .with_text_color(Color::WHITE);
+
+    Harness::create_with_render(
+        (),
+        widget,
+        Size::new(20., 20.),
+        |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.paint();
+        },
+        |target| {
+            let pixels = target.into_raw();
+            let row = |y: usize| &pixels[y * 20 * 4..(y + 1) * 20 * 4];
+            let background = [41, 41, 41, 255];
+            let glyph = [255, 255, 255, 255];
+
+            // the label is padded by 2, 'a' is painted from 3 to 9 and 'b'
+            // from 11 to 17
+            let mut expected = Vec::new();
+            for x in 0..20 {
+                let ink = (3..9).contains(&x) || (11..17).contains(&x);
+                expected.extend_from_slice(if ink { &glyph } else { &background });
+            }
+            for y in 4..16 {
+                assert_eq!(row(y), &expected[..], "row {}", y);
+            }
+            assert_eq!(row(3), &background.repeat(20)[..]);
+            assert_eq!(row(16), &background.repeat(20)[..]);
+        },
+    );
+}
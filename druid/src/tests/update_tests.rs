@@ -0,0 +1,356 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests that `update` only visits the parts of the tree that need it.
+
+use std::sync::Arc;
+
+use test_env_log::test;
+
+use super::*;
+
+const ROWS: usize = 10;
+const COLUMNS: usize = 100;
+
+const SET_LEAF: Selector<(usize, u32)> = Selector::new("druid-test.set-leaf");
+const SET_SCALE: Selector<f64> = Selector::new("druid-test.set-scale");
+const REQUEST_UPDATE: Selector = Selector::new("druid-test.request-update");
+const SCALE: Key<f64> = Key::new("druid-test.scale");
+
+#[derive(Clone, Data, Lens)]
+struct Leaves {
+    values: Arc<Vec<u32>>,
+    scale: f64,
+}
+
+impl Leaves {
+    fn new() -> Self {
+        Leaves {
+            values: Arc::new(vec![0; ROWS * COLUMNS]),
+            scale: 1.0,
+        }
+    }
+}
+
+/// Applies `SET_LEAF` and `SET_SCALE` to the app data.
+struct Setter;
+
+impl<W: Widget<Leaves>> Controller<Leaves, W> for Setter {
+    fn event(
+        &mut self,
+        child: &mut W,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut Leaves,
+        env: &Env,
+    ) {
+        if let Event::Command(cmd) = event {
+            if let Some((idx, value)) = cmd.get(SET_LEAF) {
+                Arc::make_mut(&mut data.values)[*idx] = *value;
+            } else if let Some(scale) = cmd.get(SET_SCALE) {
+                data.scale = *scale;
+            }
+        }
+        child.event(ctx, event, data, env)
+    }
+}
+
+/// A leaf that counts its `update` calls and asks for an update on `REQUEST_UPDATE`.
+fn leaf(updates: Rc<Cell<usize>>) -> impl Widget<u32> {
+    ModularWidget::new(updates)
+        .event_fn(|_, ctx, event, _, _| {
+            if matches!(event, Event::Command(cmd) if cmd.is(REQUEST_UPDATE)) {
+                ctx.request_update();
+            }
+        })
+        .update_fn(|updates, _, _, _, _| updates.set(updates.get() + 1))
+}
+
+/// A grid of `ROWS * COLUMNS` lensed leaves, with `SCALE` set from the data.
+fn tree(updates: &Rc<Cell<usize>>, ids: &[WidgetId]) -> impl Widget<Leaves> {
+    let mut column = Flex::column();
+    for row in 0..ROWS {
+        let mut flex = Flex::row();
+        for col in 0..COLUMNS {
+            let idx = row * COLUMNS + col;
+            let leaf = leaf(updates.clone())
+                .lens(lens::Map::new(
+                    move |values: &Arc<Vec<u32>>| values[idx],
+                    move |values: &mut Arc<Vec<u32>>, value| Arc::make_mut(values)[idx] = value,
+                ))
+                .lens(Leaves::values)
+                .with_id(ids[idx]);
+            flex.add_child(leaf);
+        }
+        column.add_child(flex);
+    }
+    EnvScope::new(|env, data: &Leaves| env.set(SCALE, data.scale), column).controller(Setter)
+}
+
+#[test]
+fn unchanged_subtrees_are_skipped() {
+    let updates: Rc<Cell<usize>> = Default::default();
+    let ids: Vec<_> = (0..ROWS * COLUMNS).map(|_| WidgetId::next()).collect();
+
+    Harness::create_simple(Leaves::new(), tree(&updates, &ids), |harness| {
+        harness.send_initial_events();
+        updates.set(0);
+
+        harness.submit_command(SET_LEAF.with((517, 3)));
+        assert_eq!(harness.data().values[517], 3);
+        assert_eq!(updates.get(), 1);
+
+        // setting the same value again is not a change
+        updates.set(0);
+        harness.submit_command(SET_LEAF.with((517, 3)));
+        assert_eq!(updates.get(), 0);
+    });
+}
+
+#[test]
+fn env_change_reaches_every_leaf() {
+    let updates: Rc<Cell<usize>> = Default::default();
+    let ids: Vec<_> = (0..ROWS * COLUMNS).map(|_| WidgetId::next()).collect();
+
+    Harness::create_simple(Leaves::new(), tree(&updates, &ids), |harness| {
+        harness.send_initial_events();
+        updates.set(0);
+
+        // no leaf's data changes, but the env they see does
+        harness.submit_command(SET_SCALE.with(2.0));
+        assert_eq!(updates.get(), ROWS * COLUMNS);
+
+        updates.set(0);
+        harness.submit_command(SET_SCALE.with(2.0));
+        assert_eq!(updates.get(), 0);
+    });
+}
+
+#[test]
+fn request_update_reaches_unchanged_leaf() {
+    let updates: Rc<Cell<usize>> = Default::default();
+    let ids: Vec<_> = (0..ROWS * COLUMNS).map(|_| WidgetId::next()).collect();
+
+    Harness::create_simple(Leaves::new(), tree(&updates, &ids), |harness| {
+        harness.send_initial_events();
+        updates.set(0);
+
+        harness.submit_command(REQUEST_UPDATE.to(ids[842]));
+        assert_eq!(updates.get(), 1);
+
+        // the request is cleared once it has been handled
+        updates.set(0);
+        harness.submit_command(SET_LEAF.with((3, 1)));
+        assert_eq!(updates.get(), 1);
+    });
+}
+
+#[test]
+fn app_env_update_reaches_every_leaf() {
+    let updates: Rc<Cell<usize>> = Default::default();
+    let ids: Vec<_> = (0..ROWS * COLUMNS).map(|_| WidgetId::next()).collect();
+
+    Harness::create_simple(Leaves::new(), tree(&updates, &ids), |harness| {
+        harness.send_initial_events();
+        updates.set(0);
+
+        harness.submit_command(
+            commands::UPDATE_ENV.with(EnvUpdate::new(|env| env.set(theme::BUTTON_PADDING_X, 2.0))),
+        );
+        assert_eq!(updates.get(), ROWS * COLUMNS);
+
+        // setting the same value again is not a change
+        updates.set(0);
+        harness.submit_command(
+            commands::UPDATE_ENV.with(EnvUpdate::new(|env| env.set(theme::BUTTON_PADDING_X, 2.0))),
+        );
+        assert_eq!(updates.get(), 0);
+    });
+}
+
+const SECTIONS: usize = 64;
+
+const SET_TITLE: Selector<u32> = Selector::new("druid-test.set-title");
+const SET_SECTION: Selector<(usize, u32)> = Selector::new("druid-test.set-section");
+
+/// A part of a document, which counts how often it is compared.
+#[derive(Clone)]
+struct Section {
+    text: u32,
+    compares: Rc<Cell<usize>>,
+}
+
+impl Data for Section {
+    fn same(&self, other: &Self) -> bool {
+        self.compares.set(self.compares.get() + 1);
+        self.text == other.text
+    }
+}
+
+/// A document that can only be compared section by section.
+#[derive(Clone)]
+struct Doc {
+    sections: Vec<Section>,
+}
+
+impl Doc {
+    fn new(compares: &Rc<Cell<usize>>) -> Self {
+        let section = Section {
+            text: 0,
+            compares: compares.clone(),
+        };
+        Doc {
+            sections: vec![section; SECTIONS],
+        }
+    }
+}
+
+impl Data for Doc {
+    fn same(&self, other: &Self) -> bool {
+        self.sections.len() == other.sections.len()
+            && self
+                .sections
+                .iter()
+                .zip(other.sections.iter())
+                .all(|(a, b)| a.same(b))
+    }
+}
+
+#[derive(Clone, Data, Lens)]
+struct PlainDoc {
+    title: u32,
+    doc: Doc,
+}
+
+#[derive(Clone, Data, Lens)]
+struct VersionedDoc {
+    title: u32,
+    doc: Versioned<Doc>,
+}
+
+fn section_text(idx: usize) -> impl Lens<Doc, u32> {
+    lens::Map::new(
+        move |doc: &Doc| doc.sections[idx].text,
+        move |doc: &mut Doc, text| doc.sections[idx].text = text,
+    )
+}
+
+/// Applies commands to the app data.
+struct Edit<F>(F);
+
+impl<D: Data, W: Widget<D>, F: Fn(&mut D, &Command)> Controller<D, W> for Edit<F> {
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut D, env: &Env) {
+        if let Event::Command(cmd) = event {
+            (self.0)(data, cmd);
+        }
+        child.event(ctx, event, data, env)
+    }
+}
+
+/// A title and `SECTIONS` sections, each shown by its own leaf.
+fn doc_tree<D: Data>(
+    updates: &Rc<Cell<usize>>,
+    title: impl Lens<D, u32> + 'static,
+    section: impl Fn(usize, Box<dyn Widget<u32>>) -> Box<dyn Widget<D>>,
+    edit: impl Fn(&mut D, &Command) + 'static,
+) -> impl Widget<D> {
+    let mut column = Flex::column().with_child(leaf(updates.clone()).lens(title));
+    for idx in 0..SECTIONS {
+        column.add_child(section(idx, Box::new(leaf(updates.clone()))));
+    }
+    column.controller(Edit(edit))
+}
+
+/// Submit `cmd`, and return the number of leaf updates and section comparisons.
+fn measure<D: Data>(
+    harness: &mut Harness<D>,
+    cmd: impl Into<Command>,
+    updates: &Rc<Cell<usize>>,
+    compares: &Rc<Cell<usize>>,
+) -> (usize, usize) {
+    updates.set(0);
+    compares.set(0);
+    harness.submit_command(cmd);
+    (updates.get(), compares.get())
+}
+
+#[test]
+fn versioned_data_skips_deep_comparisons() {
+    let updates: Rc<Cell<usize>> = Default::default();
+    let compares: Rc<Cell<usize>> = Default::default();
+
+    let plain = PlainDoc {
+        title: 0,
+        doc: Doc::new(&compares),
+    };
+    let tree = doc_tree(
+        &updates,
+        PlainDoc::title,
+        |idx, leaf| Box::new(leaf.lens(section_text(idx)).lens(PlainDoc::doc)),
+        |data: &mut PlainDoc, cmd| {
+            if let Some(title) = cmd.get(SET_TITLE) {
+                data.title = *title;
+            } else if let Some((idx, text)) = cmd.get(SET_SECTION) {
+                data.doc.sections[*idx].text = *text;
+            }
+        },
+    );
+    Harness::create_simple(plain, tree, |harness| {
+        harness.send_initial_events();
+        let (leaf_updates, section_compares) =
+            measure(harness, SET_TITLE.with(1), &updates, &compares);
+        assert_eq!(leaf_updates, 1);
+        // every leaf's pod compares the whole document
+        assert!(section_compares >= SECTIONS * SECTIONS);
+    });
+
+    let versioned = VersionedDoc {
+        title: 0,
+        doc: Versioned::new(Doc::new(&compares)),
+    };
+    let tree = doc_tree(
+        &updates,
+        VersionedDoc::title,
+        |idx, leaf| {
+            Box::new(
+                leaf.lens(section_text(idx).in_versioned())
+                    .lens(VersionedDoc::doc),
+            )
+        },
+        |data: &mut VersionedDoc, cmd| {
+            if let Some(title) = cmd.get(SET_TITLE) {
+                data.title = *title;
+            } else if let Some((idx, text)) = cmd.get(SET_SECTION) {
+                data.doc.map_inner(section_text(*idx), |old| *old = *text);
+            }
+        },
+    );
+    Harness::create_simple(versioned, tree, |harness| {
+        harness.send_initial_events();
+        let (leaf_updates, section_compares) =
+            measure(harness, SET_TITLE.with(1), &updates, &compares);
+        assert_eq!(leaf_updates, 1);
+        assert_eq!(section_compares, 0);
+
+        let (leaf_updates, _) = measure(harness, SET_SECTION.with((42, 7)), &updates, &compares);
+        assert_eq!(leaf_updates, 1);
+        assert_eq!(harness.data().doc.sections[42].text, 7);
+
+        // writing the same text again is not a change
+        let version = harness.data().doc.version();
+        let (leaf_updates, _) = measure(harness, SET_SECTION.with((42, 7)), &updates, &compares);
+        assert_eq!(leaf_updates, 0);
+        assert_eq!(harness.data().doc.version(), version);
+    });
+}
@@ -0,0 +1,296 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The text systems that [`TextLayout`] can build its layouts with.
+//!
+//! [`TextLayout`]: super::TextLayout
+
+use std::cell::RefCell;
+use std::ops::{Bound, Range, RangeBounds};
+use std::rc::Rc;
+
+use crate::kurbo::{Point, Rect, Size};
+use crate::piet::{
+    Color, HitTestPoint, HitTestPosition, LineMetric, PietText, PietTextLayout, Text as _,
+    TextAlignment, TextAttribute, TextLayout as _, TextLayoutBuilder as _,
+};
+use crate::{Env, FontDescriptor, PaintCtx, RenderContext};
+
+use super::TextStorage;
+
+/// A text system that can build [`TextLayout`]s in place of the platform's.
+///
+/// Install one for a window with [`WindowDesc::text_engine`]; every
+/// [`TextLayout`] in that window is then built by it. Widgets don't need to
+/// know about it, as long as they pass their context's [`text_factory`] to
+/// [`TextLayout::rebuild_if_needed`].
+///
+/// An engine lays out plain text: the attributes added by
+/// [`TextStorage::add_attributes`] are ignored.
+///
+/// [`TextLayout`]: super::TextLayout
+/// [`TextLayout::rebuild_if_needed`]: super::TextLayout::rebuild_if_needed
+/// [`WindowDesc::text_engine`]: crate::WindowDesc::text_engine
+/// [`text_factory`]: crate::LayoutCtx::text_factory
+pub trait TextEngine: 'static {
+    /// The layouts this engine builds.
+    type Layout: crate::piet::TextLayout + 'static;
+
+    /// Lay out `text` in the given style.
+    fn build_layout(&mut self, text: &str, style: &LayoutStyle) -> Self::Layout;
+
+    /// Paint `layout`, with the top left corner of its first line at `origin`.
+    fn draw(layout: &Self::Layout, ctx: &mut PaintCtx, origin: Point);
+}
+
+/// The style of a layout that is about to be built by a [`TextEngine`].
+#[derive(Debug, Clone)]
+pub struct LayoutStyle {
+    /// The font, resolved from the [`Env`].
+    pub font: FontDescriptor,
+    /// The color of the text.
+    pub color: Color,
+    /// The width to wrap lines at; this is infinite if lines shouldn't wrap.
+    pub wrap_width: f64,
+    /// The alignment of lines that are narrower than the layout.
+    pub alignment: TextAlignment,
+}
+
+/// Builds [`TextLayout`]s with the text engine of a window.
+///
+/// Widgets get one from their context's [`text_factory`] method and pass it
+/// to [`TextLayout::rebuild_if_needed`]. A `&mut PietText` converts into a
+/// `TextFactory` that always uses the platform text system.
+///
+/// [`TextLayout`]: super::TextLayout
+/// [`TextLayout::rebuild_if_needed`]: super::TextLayout::rebuild_if_needed
+/// [`text_factory`]: crate::LayoutCtx::text_factory
+pub struct TextFactory<'a> {
+    text: &'a mut PietText,
+    engine: Option<&'a RefCell<dyn DynTextEngine>>,
+}
+
+/// A window's custom [`TextEngine`], if it has one.
+pub(crate) type SharedTextEngine = Rc<RefCell<dyn DynTextEngine>>;
+
+/// A [`TextEngine`] with its layout type erased, so that a window can own it.
+pub(crate) trait DynTextEngine {
+    fn build_layout(&mut self, text: &str, style: &LayoutStyle) -> Rc<dyn DynLayout>;
+}
+
+/// The object-safe part of [`piet::TextLayout`], and painting.
+///
+/// [`piet::TextLayout`]: crate::piet::TextLayout
+pub(crate) trait DynLayout {
+    fn size(&self) -> Size;
+    fn trailing_whitespace_width(&self) -> f64;
+    fn image_bounds(&self) -> Rect;
+    fn text(&self) -> &str;
+    fn line_text(&self, line_number: usize) -> Option<&str>;
+    fn line_metric(&self, line_number: usize) -> Option<LineMetric>;
+    fn line_count(&self) -> usize;
+    fn hit_test_point(&self, point: Point) -> HitTestPoint;
+    fn hit_test_text_position(&self, idx: usize) -> HitTestPosition;
+    fn rects_for_range(&self, range: Range<usize>) -> Vec<Rect>;
+    fn draw(&self, ctx: &mut PaintCtx, origin: Point);
+}
+
+/// A layout built by an engine of type `E`.
+struct Erased<E: TextEngine>(E::Layout);
+
+/// A layout built by a [`TextFactory`].
+#[derive(Clone)]
+pub(crate) enum EngineLayout {
+    Piet(PietTextLayout),
+    Custom(Rc<dyn DynLayout>),
+}
+
+impl<'a> TextFactory<'a> {
+    pub(crate) fn new(
+        text: &'a mut PietText,
+        engine: Option<&'a RefCell<dyn DynTextEngine>>,
+    ) -> Self {
+        TextFactory { text, engine }
+    }
+
+    /// Lay out `text`.
+    pub(crate) fn build<T: TextStorage>(
+        &mut self,
+        text: &T,
+        style: LayoutStyle,
+        env: &Env,
+    ) -> EngineLayout {
+        if let Some(engine) = self.engine {
+            let layout = engine.borrow_mut().build_layout(text.as_str(), &style);
+            return EngineLayout::Custom(layout);
+        }
+        let builder = self
+            .text
+            .new_text_layout(text.clone())
+            .max_width(style.wrap_width)
+            .alignment(style.alignment)
+            .font(style.font.family.clone(), style.font.size)
+            .default_attribute(style.font.weight)
+            .default_attribute(style.font.style)
+            .default_attribute(TextAttribute::TextColor(style.color));
+        EngineLayout::Piet(text.add_attributes(builder, env).build().unwrap())
+    }
+}
+
+impl<'a> From<&'a mut PietText> for TextFactory<'a> {
+    fn from(text: &'a mut PietText) -> Self {
+        TextFactory::new(text, None)
+    }
+}
+
+/// Wrap `engine` so that a window can own it.
+pub(crate) fn shared_engine(engine: impl TextEngine) -> SharedTextEngine {
+    Rc::new(RefCell::new(engine))
+}
+
+impl<E: TextEngine> DynTextEngine for E {
+    fn build_layout(&mut self, text: &str, style: &LayoutStyle) -> Rc<dyn DynLayout> {
+        Rc::new(Erased::<E>(TextEngine::build_layout(self, text, style)))
+    }
+}
+
+impl<E: TextEngine> DynLayout for Erased<E> {
+    fn size(&self) -> Size {
+        self.0.size()
+    }
+
+    fn trailing_whitespace_width(&self) -> f64 {
+        self.0.trailing_whitespace_width()
+    }
+
+    fn image_bounds(&self) -> Rect {
+        self.0.image_bounds()
+    }
+
+    fn text(&self) -> &str {
+        self.0.text()
+    }
+
+    fn line_text(&self, line_number: usize) -> Option<&str> {
+        self.0.line_text(line_number)
+    }
+
+    fn line_metric(&self, line_number: usize) -> Option<LineMetric> {
+        self.0.line_metric(line_number)
+    }
+
+    fn line_count(&self) -> usize {
+        self.0.line_count()
+    }
+
+    fn hit_test_point(&self, point: Point) -> HitTestPoint {
+        self.0.hit_test_point(point)
+    }
+
+    fn hit_test_text_position(&self, idx: usize) -> HitTestPosition {
+        self.0.hit_test_text_position(idx)
+    }
+
+    fn rects_for_range(&self, range: Range<usize>) -> Vec<Rect> {
+        self.0.rects_for_range(range)
+    }
+
+    fn draw(&self, ctx: &mut PaintCtx, origin: Point) {
+        E::draw(&self.0, ctx, origin)
+    }
+}
+
+impl EngineLayout {
+    /// The layout built by the platform text system, if this is one.
+    pub(crate) fn as_piet(&self) -> Option<&PietTextLayout> {
+        match self {
+            EngineLayout::Piet(layout) => Some(layout),
+            EngineLayout::Custom(_) => None,
+        }
+    }
+
+    pub(crate) fn draw(&self, ctx: &mut PaintCtx, point: Point) {
+        match self {
+            EngineLayout::Piet(layout) => ctx.draw_text(layout, point),
+            EngineLayout::Custom(layout) => layout.draw(ctx, point),
+        }
+    }
+}
+
+/// Forwards every method to the layout of the engine that built it.
+macro_rules! forward {
+    ($self:ident, $layout:ident => $call:expr) => {
+        match $self {
+            EngineLayout::Piet($layout) => $call,
+            EngineLayout::Custom($layout) => $call,
+        }
+    };
+}
+
+impl crate::piet::TextLayout for EngineLayout {
+    fn size(&self) -> Size {
+        forward!(self, layout => layout.size())
+    }
+
+    fn trailing_whitespace_width(&self) -> f64 {
+        forward!(self, layout => layout.trailing_whitespace_width())
+    }
+
+    fn image_bounds(&self) -> Rect {
+        forward!(self, layout => layout.image_bounds())
+    }
+
+    fn text(&self) -> &str {
+        forward!(self, layout => layout.text())
+    }
+
+    fn line_text(&self, line_number: usize) -> Option<&str> {
+        forward!(self, layout => layout.line_text(line_number))
+    }
+
+    fn line_metric(&self, line_number: usize) -> Option<LineMetric> {
+        forward!(self, layout => layout.line_metric(line_number))
+    }
+
+    fn line_count(&self) -> usize {
+        forward!(self, layout => layout.line_count())
+    }
+
+    fn hit_test_point(&self, point: Point) -> HitTestPoint {
+        forward!(self, layout => layout.hit_test_point(point))
+    }
+
+    fn hit_test_text_position(&self, idx: usize) -> HitTestPosition {
+        forward!(self, layout => layout.hit_test_text_position(idx))
+    }
+
+    fn rects_for_range(&self, range: impl RangeBounds<usize>) -> Vec<Rect> {
+        match self {
+            EngineLayout::Piet(layout) => layout.rects_for_range(range),
+            EngineLayout::Custom(layout) => {
+                let start = match range.start_bound() {
+                    Bound::Included(&n) => n,
+                    Bound::Excluded(&n) => n + 1,
+                    Bound::Unbounded => 0,
+                };
+                let end = match range.end_bound() {
+                    Bound::Included(&n) => n + 1,
+                    Bound::Excluded(&n) => n,
+                    Bound::Unbounded => layout.text().len(),
+                };
+                layout.rects_for_range(start..end)
+            }
+        }
+    }
+}
@@ -17,9 +17,11 @@
 use std::cell::{Cell, Ref, RefCell, RefMut};
 use std::ops::Range;
 use std::sync::{Arc, Weak};
+use std::time::Duration;
 
 use tracing::instrument;
 
+use super::undo::UndoStack;
 use super::{
     EditableText, ImeHandlerRef, ImeInvalidation, InputHandler, Movement, Selection, TextAction,
     TextLayout, TextStorage,
@@ -27,7 +29,10 @@ use super::{
 use crate::kurbo::{Line, Point, Rect, Vec2};
 use crate::piet::TextLayout as _;
 use crate::widget::prelude::*;
-use crate::{text, theme, Cursor, Env, Modifiers, Selector, TextAlignment, UpdateCtx};
+use crate::{text, theme, Cursor, Env, Modifiers, Selector, TextAlignment, TimerToken, UpdateCtx};
+
+/// Typing after a pause this long starts a new undo group.
+const UNDO_GROUP_TIMEOUT: Duration = Duration::from_millis(1000);
 
 /// A widget that accepts text input.
 ///
@@ -65,6 +70,8 @@ pub struct TextComponent<T> {
     ///
     /// The parent should update this when handling [`LifeCycle::FocusChanged`].
     pub has_focus: bool,
+    /// Ends the current undo group when typing pauses.
+    undo_group_timer: TimerToken,
 }
 
 /// Editable text state.
@@ -105,6 +112,7 @@ pub struct EditSession<T> {
     drag_granularity: DragGranularity,
     /// The origin of the textbox, relative to the origin of the window.
     pub origin: Point,
+    undo: UndoStack,
 }
 
 /// An object that can be used to acquire an `ImeHandler`.
@@ -283,7 +291,9 @@ impl<T: TextStorage + EditableText> Widget<T> for TextComponent<T> {
                     .unwrap_or(true);
                 if needs_rebuild {
                     self.borrow_mut().layout.set_text(data.clone());
-                    self.borrow_mut().layout.rebuild_if_needed(ctx.text(), env);
+                    self.borrow_mut()
+                        .layout
+                        .rebuild_if_needed(ctx.text_factory(), env);
                     self.borrow_mut()
                         .update_pending_invalidation(ImeInvalidation::Reset);
                 }
@@ -316,6 +326,9 @@ impl<T: TextStorage + EditableText> Widget<T> for TextComponent<T> {
                 ctx.set_active(false);
                 ctx.request_paint();
             }
+            Event::Timer(token) if *token == self.undo_group_timer && self.can_write() => {
+                self.borrow_mut().undo.seal();
+            }
             Event::ImeStateChange => {
                 assert!(
                     self.can_write(),
@@ -342,13 +355,24 @@ impl<T: TextStorage + EditableText> Widget<T> for TextComponent<T> {
                 }
                 let text = self.borrow_mut().take_external_text_change();
                 let selection = self.borrow_mut().take_external_selection_change();
-                if let Some(text) = text {
-                    self.borrow_mut().layout.set_text(text.clone());
-                    *data = text;
-                }
+                let selection_before = self.borrow().selection;
                 if let Some(selection) = selection {
                     self.borrow_mut().selection = selection;
                 }
+                if let Some(text) = text {
+                    let mut session = self.borrow_mut();
+                    session.layout.set_text(text.clone());
+                    // an ongoing composition is recorded once it is committed
+                    if session.composition_range.is_none() {
+                        let selection_after = session.selection;
+                        session
+                            .undo
+                            .record(text.as_str(), selection_before, selection_after);
+                        drop(session);
+                        self.undo_group_timer = ctx.request_timer(UNDO_GROUP_TIMEOUT);
+                    }
+                    *data = text;
+                }
                 ctx.request_update();
             }
             _ => (),
@@ -368,7 +392,10 @@ impl<T: TextStorage + EditableText> Widget<T> for TextComponent<T> {
                     "ime should never be locked at WidgetAdded"
                 );
                 self.borrow_mut().layout.set_text(data.to_owned());
-                self.borrow_mut().layout.rebuild_if_needed(ctx.text(), env);
+                self.borrow_mut()
+                    .layout
+                    .rebuild_if_needed(ctx.text_factory(), env);
+                self.borrow_mut().undo.reset(data.as_str());
             }
             //FIXME: this should happen in the parent too?
             LifeCycle::Internal(crate::InternalLifeCycle::ParentWindowOrigin)
@@ -422,7 +449,9 @@ impl<T: TextStorage + EditableText> Widget<T> for TextComponent<T> {
         }
 
         self.borrow_mut().layout.set_wrap_width(bc.max().width);
-        self.borrow_mut().layout.rebuild_if_needed(ctx.text(), env);
+        self.borrow_mut()
+            .layout
+            .rebuild_if_needed(ctx.text_factory(), env);
         let metrics = self.borrow().layout.layout_metrics();
         let width = if bc.max().width.is_infinite() || bc.max().width < f64::MAX {
             metrics.trailing_whitespace_width
@@ -580,13 +609,54 @@ impl<T: TextStorage + EditableText> EditSession<T> {
     /// text state, by calling [`EventCtx::invalidate_text_input`].
     #[must_use]
     pub fn insert_text(&mut self, data: &mut T, new_text: &str) -> ImeInvalidation {
+        let selection_before = self.selection;
         let new_cursor_pos = self.selection.min() + new_text.len();
         data.edit(self.selection.range(), new_text);
         self.selection = Selection::caret(new_cursor_pos);
+        self.undo
+            .record_standalone(data.as_str(), selection_before, self.selection);
         self.scroll_to_selection_end(true);
         ImeInvalidation::Reset
     }
 
+    /// Undo the most recent edit, restoring the text and selection from before it.
+    ///
+    /// Returns `None` if there is nothing to undo. Otherwise, the caller is
+    /// responsible for notifying the platform of the change in text state,
+    /// by calling [`EventCtx::invalidate_text_input`].
+    #[must_use]
+    pub fn undo(&mut self, data: &mut T) -> Option<ImeInvalidation> {
+        let (range, text, selection) = self.undo.undo()?;
+        data.edit(range, &text);
+        self.selection = selection;
+        self.scroll_to_selection_end(true);
+        Some(ImeInvalidation::Reset)
+    }
+
+    /// Redo the most recently undone edit.
+    ///
+    /// Returns `None` if there is nothing to redo. Otherwise, the caller is
+    /// responsible for notifying the platform of the change in text state,
+    /// by calling [`EventCtx::invalidate_text_input`].
+    #[must_use]
+    pub fn redo(&mut self, data: &mut T) -> Option<ImeInvalidation> {
+        let (range, text, selection) = self.undo.redo()?;
+        data.edit(range, &text);
+        self.selection = selection;
+        self.scroll_to_selection_end(true);
+        Some(ImeInvalidation::Reset)
+    }
+
+    /// Returns `true` if there is an edit that can be undone.
+    pub fn can_undo(&self) -> bool {
+        self.undo.can_undo()
+    }
+
+    /// Returns `true` if there is an undone edit that can be redone.
+    pub fn can_redo(&self) -> bool {
+        self.undo.can_redo()
+    }
+
     /// Sets the clipboard to the contents of the current selection.
     ///
     /// Returns `true` if the clipboard was set, and `false` if not (indicating)
@@ -791,7 +861,7 @@ impl<T: TextStorage + EditableText> EditSession<T> {
     }
 
     fn word_for_pos(&self, pos: usize) -> Range<usize> {
-        let layout = match self.layout.layout() {
+        let layout = match self.layout.engine_layout() {
             Some(layout) => layout,
             None => return pos..pos,
         };
@@ -816,6 +886,10 @@ impl<T: TextStorage + EditableText> EditSession<T> {
         {
             self.update_pending_invalidation(ImeInvalidation::Reset);
             self.layout.set_text(new_data.clone());
+            // the app changed the text; our history no longer applies
+            if !self.undo.is_current(new_data.as_str()) {
+                self.undo.reset(new_data.as_str());
+            }
         }
         if self.layout.needs_rebuild_after_update(ctx) {
             ctx.request_layout();
@@ -825,7 +899,7 @@ impl<T: TextStorage + EditableText> EditSession<T> {
             self.selection = new_sel;
             self.update_pending_invalidation(ImeInvalidation::SelectionChanged);
         }
-        self.layout.rebuild_if_needed(ctx.text(), env);
+        self.layout.rebuild_if_needed(ctx.text_factory(), env);
     }
 }
 
@@ -875,14 +949,14 @@ impl<T: TextStorage + EditableText> InputHandler for EditSessionHandle<T> {
         self.inner
             .borrow()
             .layout
-            .layout()
+            .engine_layout()
             .map(|layout| layout.hit_test_point(point))
             .unwrap_or_default()
     }
 
     fn line_range(&self, index: usize, _affinity: druid_shell::text::Affinity) -> Range<usize> {
         let inner = self.inner.borrow();
-        let layout = inner.layout.layout().unwrap();
+        let layout = inner.layout.engine_layout().unwrap();
         let hit = layout.hit_test_text_position(index);
         let metric = layout.line_metric(hit.line).unwrap();
         metric.range()
@@ -898,9 +972,11 @@ impl<T: TextStorage + EditableText> InputHandler for EditSessionHandle<T> {
         let layout = &self.inner.borrow().layout;
         if range.is_empty() {
             let hit = layout
-                .layout()
+                .engine_layout()
                 .map(|l| l.hit_test_text_position(range.start))?;
-            let line = layout.layout().and_then(|l| l.line_metric(hit.line))?;
+            let line = layout
+                .engine_layout()
+                .and_then(|l| l.line_metric(hit.line))?;
             let x = hit.point.x;
             Some(Rect::new(x, line.y_offset, x, line.y_offset + line.height))
         } else {
@@ -943,12 +1019,14 @@ impl<T> Default for TextComponent<T> {
             alignment_offset: 0.0,
             drag_granularity: DragGranularity::Grapheme,
             origin: Point::ZERO,
+            undo: UndoStack::default(),
         };
 
         TextComponent {
             inner: Arc::new(RefCell::new(inner)),
             lock: Arc::new(Cell::new(ImeLock::None)),
             has_focus: false,
+            undo_group_timer: TimerToken::INVALID,
         }
     }
 }
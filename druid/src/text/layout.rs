@@ -17,13 +17,11 @@
 use std::ops::Range;
 use std::rc::Rc;
 
-use super::{Link, TextStorage};
+use super::engine::{EngineLayout, LayoutStyle};
+use super::{Link, TextFactory, TextStorage};
 use crate::kurbo::{Line, Point, Rect, Size};
-use crate::piet::{
-    Color, PietText, PietTextLayout, Text as _, TextAlignment, TextAttribute, TextLayout as _,
-    TextLayoutBuilder as _,
-};
-use crate::{Env, FontDescriptor, KeyOrValue, PaintCtx, RenderContext, UpdateCtx};
+use crate::piet::{Color, PietTextLayout, TextAlignment, TextLayout as _};
+use crate::{Env, FontDescriptor, KeyOrValue, PaintCtx, UpdateCtx};
 
 /// A component for displaying text on screen.
 ///
@@ -53,7 +51,7 @@ pub struct TextLayout<T> {
     // using a `FontDescriptor` in the `Env`.
     text_size_override: Option<KeyOrValue<f64>>,
     text_color: KeyOrValue<Color>,
-    layout: Option<PietTextLayout>,
+    layout: Option<EngineLayout>,
     wrap_width: f64,
     alignment: TextAlignment,
     links: Rc<[(Rect, usize)]>,
@@ -203,8 +201,17 @@ impl<T: TextStorage> TextLayout<T> {
 
     /// Returns the inner Piet [`TextLayout`] type.
     ///
+    /// This is `None` if the layout was built by a custom [`TextEngine`].
+    ///
+    /// [`TextEngine`]: super::TextEngine
+    ///
     /// [`TextLayout`]: ./piet/trait.TextLayout.html
     pub fn layout(&self) -> Option<&PietTextLayout> {
+        self.layout.as_ref().and_then(EngineLayout::as_piet)
+    }
+
+    /// The inner layout, whichever engine built it.
+    pub(crate) fn engine_layout(&self) -> Option<&EngineLayout> {
         self.layout.as_ref()
     }
 
@@ -366,8 +373,15 @@ impl<T: TextStorage> TextLayout<T> {
     /// A simple way to ensure this is correct is to always call this method
     /// as part of your widget's [`layout`] method.
     ///
+    /// The `factory` is usually your context's [`text_factory`], which uses
+    /// the window's [`TextEngine`]; passing [`text`] instead always uses the
+    /// platform's text system.
+    ///
     /// [`layout`]: trait.Widget.html#method.layout
-    pub fn rebuild_if_needed(&mut self, factory: &mut PietText, env: &Env) {
+    /// [`text_factory`]: crate::LayoutCtx::text_factory
+    /// [`text`]: crate::LayoutCtx::text
+    /// [`TextEngine`]: super::TextEngine
+    pub fn rebuild_if_needed<'a>(&mut self, factory: impl Into<TextFactory<'a>>, env: &Env) {
         if let Some(text) = &self.text {
             if self.layout.is_none() {
                 let font = self.font.resolve(env);
@@ -380,15 +394,13 @@ impl<T: TextStorage> TextLayout<T> {
                     font
                 };
 
-                let builder = factory
-                    .new_text_layout(text.clone())
-                    .max_width(self.wrap_width)
-                    .alignment(self.alignment)
-                    .font(descriptor.family.clone(), descriptor.size)
-                    .default_attribute(descriptor.weight)
-                    .default_attribute(descriptor.style)
-                    .default_attribute(TextAttribute::TextColor(color));
-                let layout = text.add_attributes(builder, env).build().unwrap();
+                let style = LayoutStyle {
+                    font: descriptor,
+                    color,
+                    wrap_width: self.wrap_width,
+                    alignment: self.alignment,
+                };
+                let layout = factory.into().build(text, style, env);
 
                 self.links = text
                     .links()
@@ -425,7 +437,7 @@ impl<T: TextStorage> TextLayout<T> {
                 .unwrap_or("layout is missing text")
         );
         if let Some(layout) = self.layout.as_ref() {
-            ctx.draw_text(layout, point);
+            layout.draw(ctx, point.into());
         }
     }
 }
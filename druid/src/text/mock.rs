@@ -0,0 +1,373 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A text engine with fixed-width glyphs, for tests.
+//!
+//! Every character is `font_size / 2` wide, every line is
+//! `font_size * 1.25` high, and the baseline is `font_size` below the top
+//! of the line. Glyphs are painted as solid boxes. This is enough to test
+//! layout, hit-testing and painting of text widgets without depending on
+//! the fonts installed on the machine running the tests.
+
+use crate::kurbo::{Point, Rect, Size};
+use crate::piet::{Color, HitTestPoint, HitTestPosition, LineMetric, TextAlignment, TextLayout};
+use crate::{PaintCtx, RenderContext};
+
+use super::{LayoutStyle, TextEngine};
+
+/// A [`TextEngine`] with fixed-width glyphs, so that text measures and
+/// paints the same on every platform.
+///
+/// The test [`Harness`] uses it unless a test calls
+/// [`Harness::use_platform_text`].
+///
+/// [`Harness`]: crate::tests::harness::Harness
+/// [`Harness::use_platform_text`]: crate::tests::harness::Harness::use_platform_text
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MockTextEngine;
+
+/// A layout of fixed-width glyphs, built by [`MockTextEngine`].
+#[derive(Clone)]
+pub struct MockTextLayout {
+    text: String,
+    lines: Vec<MockLine>,
+    advance: f64,
+    line_height: f64,
+    baseline: f64,
+    color: Color,
+    width: f64,
+    trailing_whitespace_width: f64,
+}
+
+#[derive(Clone)]
+struct MockLine {
+    start: usize,
+    end: usize,
+    trailing_whitespace: usize,
+    /// The offset of the line from the left edge, for alignment.
+    x: f64,
+}
+
+impl MockTextLayout {
+    fn new(text: &str, style: &LayoutStyle) -> MockTextLayout {
+        let size = style.font.size;
+        let advance = size / 2.0;
+        let max_chars = if style.wrap_width.is_finite() {
+            Some(((style.wrap_width / advance).floor() as usize).max(1))
+        } else {
+            None
+        };
+
+        let mut lines = break_lines(text, max_chars);
+        let visible_width = |line: &MockLine| {
+            text[line.start..line.end - line.trailing_whitespace]
+                .chars()
+                .count() as f64
+                * advance
+        };
+        let width = lines.iter().map(visible_width).fold(0.0, f64::max);
+        let trailing_whitespace_width = lines
+            .iter()
+            .map(|line| {
+                text[line.start..line.end]
+                    .trim_end_matches('\n')
+                    .chars()
+                    .count() as f64
+            })
+            .fold(0.0, f64::max)
+            * advance;
+        let align = match style.alignment {
+            TextAlignment::Center => 0.5,
+            TextAlignment::End => 1.0,
+            TextAlignment::Start | TextAlignment::Justified => 0.0,
+        };
+        for line in &mut lines {
+            line.x = (width - visible_width(line)) * align;
+        }
+
+        MockTextLayout {
+            text: text.to_owned(),
+            lines,
+            advance,
+            line_height: size * 1.25,
+            baseline: size,
+            color: style.color.clone(),
+            width,
+            trailing_whitespace_width,
+        }
+    }
+
+    /// Paint every non-whitespace character as a box.
+    fn draw(&self, ctx: &mut PaintCtx, point: Point) {
+        for (n, line) in self.lines.iter().enumerate() {
+            let y = point.y + n as f64 * self.line_height;
+            for (col, ch) in self.text[line.start..line.end].chars().enumerate() {
+                if ch.is_whitespace() {
+                    continue;
+                }
+                let x = point.x + line.x + col as f64 * self.advance;
+                let glyph = Rect::new(
+                    x + self.advance / 8.0,
+                    y + self.baseline / 4.0,
+                    x + self.advance * 7.0 / 8.0,
+                    y + self.baseline,
+                );
+                ctx.fill(glyph, &self.color);
+            }
+        }
+    }
+
+    /// The line containing the text position `idx`.
+    fn line_for_position(&self, idx: usize) -> usize {
+        self.lines
+            .iter()
+            .rposition(|line| line.start <= idx)
+            .unwrap_or(0)
+    }
+
+    /// The byte offset of the character at `col` in `line`, clamped to the
+    /// end of the line, not counting a hard line break.
+    fn position_in_line(&self, line: &MockLine, col: usize) -> usize {
+        let text = self.text[line.start..line.end].trim_end_matches('\n');
+        line.start
+            + text
+                .char_indices()
+                .nth(col)
+                .map(|(i, _)| i)
+                .unwrap_or(text.len())
+    }
+}
+
+/// Break `text` into lines at hard line breaks, and at whitespace so that no
+/// line has more than `max_chars` visible characters.
+///
+/// Words that are too long on their own are broken between characters.
+fn break_lines(text: &str, max_chars: Option<usize>) -> Vec<MockLine> {
+    let mut lines = Vec::new();
+    let mut push = |start: usize, end: usize| {
+        let trailing_whitespace = text[start..end].len() - text[start..end].trim_end().len();
+        lines.push(MockLine {
+            start,
+            end,
+            trailing_whitespace,
+            x: 0.0,
+        });
+    };
+
+    let mut offset = 0;
+    for paragraph in text.split_inclusive('\n') {
+        let max_chars = match max_chars {
+            Some(max_chars) => max_chars,
+            None => {
+                push(offset, offset + paragraph.len());
+                offset += paragraph.len();
+                continue;
+            }
+        };
+
+        let mut line_start = offset;
+        let mut line_chars = 0;
+        for word in words(paragraph) {
+            let word_start = offset + (word.as_ptr() as usize - paragraph.as_ptr() as usize);
+            let mut glyphs = word.trim_end();
+            if line_chars > 0 && line_chars + glyphs.chars().count() > max_chars {
+                push(line_start, word_start);
+                line_start = word_start;
+                line_chars = 0;
+            }
+            // a word that doesn't fit on a line of its own
+            while line_chars + glyphs.chars().count() > max_chars {
+                let (split, _) = glyphs.char_indices().nth(max_chars - line_chars).unwrap();
+                let line_end = glyphs[split..].as_ptr() as usize - text.as_ptr() as usize;
+                push(line_start, line_end);
+                line_start = line_end;
+                line_chars = 0;
+                glyphs = &glyphs[split..];
+            }
+            let spaces = word.len() - word.trim_end().len();
+            line_chars += glyphs.chars().count() + word[word.len() - spaces..].chars().count();
+        }
+        push(line_start, offset + paragraph.len());
+        offset += paragraph.len();
+    }
+
+    if offset == 0 || text.ends_with('\n') {
+        push(text.len(), text.len());
+    }
+    lines
+}
+
+/// Split `text` into words, each followed by its trailing whitespace.
+fn words(text: &str) -> impl Iterator<Item = &str> {
+    let mut rest = text;
+    std::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+        let word_end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        let space_end = rest[word_end..]
+            .find(|c: char| !c.is_whitespace())
+            .map(|i| word_end + i)
+            .unwrap_or(rest.len());
+        let (word, tail) = rest.split_at(space_end);
+        rest = tail;
+        Some(word)
+    })
+}
+
+impl TextEngine for MockTextEngine {
+    type Layout = MockTextLayout;
+
+    fn build_layout(&mut self, text: &str, style: &LayoutStyle) -> MockTextLayout {
+        MockTextLayout::new(text, style)
+    }
+
+    fn draw(layout: &MockTextLayout, ctx: &mut PaintCtx, origin: Point) {
+        layout.draw(ctx, origin)
+    }
+}
+
+impl TextLayout for MockTextLayout {
+    fn size(&self) -> Size {
+        Size::new(self.width, self.lines.len() as f64 * self.line_height)
+    }
+
+    fn trailing_whitespace_width(&self) -> f64 {
+        self.trailing_whitespace_width
+    }
+
+    fn image_bounds(&self) -> Rect {
+        self.size().to_rect()
+    }
+
+    fn text(&self) -> &str {
+        &self.text
+    }
+
+    fn line_text(&self, line_number: usize) -> Option<&str> {
+        let line = self.lines.get(line_number)?;
+        Some(&self.text[line.start..line.end])
+    }
+
+    fn line_metric(&self, line_number: usize) -> Option<LineMetric> {
+        let line = self.lines.get(line_number)?;
+        Some(LineMetric {
+            start_offset: line.start,
+            end_offset: line.end,
+            trailing_whitespace: line.trailing_whitespace,
+            baseline: self.baseline,
+            height: self.line_height,
+            y_offset: line_number as f64 * self.line_height,
+        })
+    }
+
+    fn line_count(&self) -> usize {
+        self.lines.len()
+    }
+
+    fn hit_test_point(&self, point: Point) -> HitTestPoint {
+        let last = self.lines.len() - 1;
+        let n = ((point.y / self.line_height).floor().max(0.0) as usize).min(last);
+        let line = &self.lines[n];
+        let col = ((point.x - line.x) / self.advance).round().max(0.0) as usize;
+        let idx = self.position_in_line(line, col);
+        let visible = (self.position_in_line(line, usize::MAX) - line.start) as f64;
+        let is_inside = self.size().to_rect().contains(point)
+            && point.x >= line.x
+            && point.x < line.x + visible * self.advance;
+        HitTestPoint::new(idx, is_inside)
+    }
+
+    fn hit_test_text_position(&self, idx: usize) -> HitTestPosition {
+        let idx = idx.min(self.text.len());
+        let n = self.line_for_position(idx);
+        let line = &self.lines[n];
+        let col = self.text[line.start..idx].chars().count();
+        let point = Point::new(
+            line.x + col as f64 * self.advance,
+            n as f64 * self.line_height + self.baseline,
+        );
+        HitTestPosition::new(point, n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::piet::FontFamily;
+    use crate::FontDescriptor;
+    use test_env_log::test;
+
+    fn build(text: &str, wrap_width: f64) -> MockTextLayout {
+        let style = LayoutStyle {
+            font: FontDescriptor::new(FontFamily::SYSTEM_UI).with_size(10.0),
+            color: Color::WHITE,
+            wrap_width,
+            alignment: TextAlignment::Start,
+        };
+        MockTextLayout::new(text, &style)
+    }
+
+    fn line_texts(layout: &MockTextLayout) -> Vec<&str> {
+        (0..layout.line_count())
+            .map(|n| layout.line_text(n).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn fixed_metrics() {
+        let layout = build("hello", f64::INFINITY);
+        assert_eq!(layout.size(), Size::new(25.0, 12.5));
+        assert_eq!(layout.line_metric(0).unwrap().baseline, 10.0);
+        assert_eq!(
+            layout.hit_test_text_position(2).point,
+            Point::new(10.0, 10.0)
+        );
+        assert_eq!(layout.hit_test_point(Point::new(11.0, 3.0)).idx, 2);
+    }
+
+    #[test]
+    fn empty_text_has_one_line() {
+        let layout = build("", f64::INFINITY);
+        assert_eq!(layout.line_count(), 1);
+        assert_eq!(layout.size(), Size::new(0.0, 12.5));
+    }
+
+    #[test]
+    fn hard_breaks() {
+        let layout = build("one\ntwo\n", f64::INFINITY);
+        assert_eq!(line_texts(&layout), vec!["one\n", "two\n", ""]);
+        assert_eq!(layout.hit_test_text_position(8).line, 2);
+        // clicking past the end of a line doesn't go past the line break
+        assert_eq!(layout.hit_test_point(Point::new(100.0, 1.0)).idx, 3);
+    }
+
+    #[test]
+    fn wraps_at_whitespace() {
+        // 6 characters fit in 30 points
+        let layout = build("one two three", 30.0);
+        assert_eq!(line_texts(&layout), vec!["one ", "two ", "three"]);
+        assert_eq!(layout.line_metric(1).unwrap().trailing_whitespace, 1);
+        assert_eq!(layout.size().width, 25.0);
+
+        let layout = build("a b c d", 30.0);
+        assert_eq!(line_texts(&layout), vec!["a b c ", "d"]);
+    }
+
+    #[test]
+    fn breaks_long_words() {
+        let layout = build("abcdefghijklmn op", 30.0);
+        assert_eq!(line_texts(&layout), vec!["abcdef", "ghijkl", "mn op"]);
+    }
+}
@@ -17,6 +17,7 @@
 mod attribute;
 mod backspace;
 mod editable_text;
+mod engine;
 mod font_descriptor;
 
 #[deprecated(since = "0.8.0", note = "use types from druid::text module instead")]
@@ -29,9 +30,12 @@ mod format_priv;
 mod input_component;
 mod input_methods;
 mod layout;
+#[cfg(any(test, feature = "testing"))]
+mod mock;
 mod movement;
 mod rich_text;
 mod storage;
+mod undo;
 
 pub use crate::piet::{FontFamily, FontStyle, FontWeight, TextAlignment};
 pub use druid_shell::text::{
@@ -42,13 +46,18 @@ pub use druid_shell::text::{
 pub use self::attribute::{Attribute, AttributeSpans, Link};
 pub use self::backspace::offset_for_delete_backwards;
 pub use self::editable_text::{EditableText, EditableTextCursor, StringCursor};
+pub use self::engine::{LayoutStyle, TextEngine, TextFactory};
 pub use self::font_descriptor::FontDescriptor;
 pub use self::format_priv::{Formatter, ParseFormatter, Validation, ValidationError};
 pub use self::layout::{LayoutMetrics, TextLayout};
+#[cfg(any(test, feature = "testing"))]
+#[cfg_attr(docsrs, doc(cfg(feature = "testing")))]
+pub use self::mock::{MockTextEngine, MockTextLayout};
 pub use self::movement::movement;
 pub use input_component::{EditSession, TextComponent};
 pub use input_methods::ImeHandlerRef;
 pub use rich_text::{AttributesAdder, RichText, RichTextBuilder};
 pub use storage::{ArcStr, TextStorage};
 
+pub(crate) use engine::{shared_engine, DynTextEngine, SharedTextEngine};
 pub(crate) use input_methods::TextFieldRegistration;
@@ -37,7 +37,7 @@ pub fn movement<T: EditableText + TextStorage>(
     layout: &TextLayout<T>,
     modify: bool,
 ) -> Selection {
-    let (text, layout) = match (layout.text(), layout.layout()) {
+    let (text, layout) = match (layout.text(), layout.engine_layout()) {
         (Some(text), Some(layout)) => (text, layout),
         _ => {
             debug_assert!(false, "movement() called before layout rebuild");
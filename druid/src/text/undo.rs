@@ -0,0 +1,360 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The edit history of a text field.
+
+use std::collections::VecDeque;
+use std::ops::Range;
+
+use super::Selection;
+
+/// The most edits we remember; older edits are forgotten.
+const MAX_UNDO_ENTRIES: usize = 100;
+
+/// A text field's undo and redo stacks.
+///
+/// Edits are recorded by comparing the new text with the text as of the
+/// previous edit, so it doesn't matter how an edit was made. Consecutive
+/// insertions, or consecutive deletions, are grouped into a single entry as
+/// long as the caret doesn't move in between; a group also ends at a word
+/// boundary, or when [`seal`] is called.
+///
+/// [`seal`]: UndoStack::seal
+#[derive(Debug, Clone, Default)]
+pub(crate) struct UndoStack {
+    undo: VecDeque<Edit>,
+    redo: Vec<Edit>,
+    /// The text after the most recent edit.
+    current: String,
+    /// `false` if the next edit must start a new entry.
+    open: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum EditKind {
+    Insert,
+    Delete,
+    /// An edit that is never grouped with others, such as a paste.
+    Standalone,
+}
+
+/// A single entry in the undo stack.
+#[derive(Debug, Clone)]
+struct Edit {
+    kind: EditKind,
+    /// The offset of the edit, which is the same before and after it.
+    start: usize,
+    removed: String,
+    inserted: String,
+    selection_before: Selection,
+    selection_after: Selection,
+}
+
+impl UndoStack {
+    /// Forget all history, and start over from `text`.
+    pub fn reset(&mut self, text: &str) {
+        self.undo.clear();
+        self.redo.clear();
+        self.current.clear();
+        self.current.push_str(text);
+        self.open = false;
+    }
+
+    /// Returns `true` if `text` is the text after the most recent edit.
+    pub fn is_current(&self, text: &str) -> bool {
+        self.current == text
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// End the current group, so that the next edit starts a new entry.
+    pub fn seal(&mut self) {
+        self.open = false;
+    }
+
+    /// Record a change to `text`, which may be grouped with the previous one.
+    pub fn record(&mut self, text: &str, selection_before: Selection, selection_after: Selection) {
+        self.push(text, selection_before, selection_after, false);
+    }
+
+    /// Record a change to `text` as its own entry.
+    pub fn record_standalone(
+        &mut self,
+        text: &str,
+        selection_before: Selection,
+        selection_after: Selection,
+    ) {
+        self.push(text, selection_before, selection_after, true);
+    }
+
+    /// Undo the most recent entry.
+    ///
+    /// Returns the range to replace, the text to replace it with, and the
+    /// selection to restore.
+    pub fn undo(&mut self) -> Option<(Range<usize>, String, Selection)> {
+        let edit = self.undo.pop_back()?;
+        let range = edit.start..edit.start + edit.inserted.len();
+        self.current.replace_range(range.clone(), &edit.removed);
+        let result = (range, edit.removed.clone(), edit.selection_before);
+        self.redo.push(edit);
+        self.open = false;
+        Some(result)
+    }
+
+    /// Redo the most recently undone entry.
+    ///
+    /// Returns the range to replace, the text to replace it with, and the
+    /// selection to restore.
+    pub fn redo(&mut self) -> Option<(Range<usize>, String, Selection)> {
+        let edit = self.redo.pop()?;
+        let range = edit.start..edit.start + edit.removed.len();
+        self.current.replace_range(range.clone(), &edit.inserted);
+        let result = (range, edit.inserted.clone(), edit.selection_after);
+        self.undo.push_back(edit);
+        self.open = false;
+        Some(result)
+    }
+
+    fn push(
+        &mut self,
+        text: &str,
+        selection_before: Selection,
+        selection_after: Selection,
+        standalone: bool,
+    ) {
+        let (start, removed, inserted) = match diff(&self.current, text, selection_before.min()) {
+            Some(diff) => diff,
+            None => return,
+        };
+        let kind = if standalone {
+            EditKind::Standalone
+        } else if inserted.is_empty() {
+            EditKind::Delete
+        } else {
+            EditKind::Insert
+        };
+        let edit = Edit {
+            kind,
+            start,
+            removed: removed.to_owned(),
+            inserted: inserted.to_owned(),
+            selection_before,
+            selection_after,
+        };
+        self.current.clear();
+        self.current.push_str(text);
+        self.redo.clear();
+
+        let merged = match self.undo.back_mut() {
+            Some(last) if self.open => last.merge(&edit),
+            _ => false,
+        };
+        if !merged {
+            self.undo.push_back(edit);
+            if self.undo.len() > MAX_UNDO_ENTRIES {
+                self.undo.pop_front();
+            }
+        }
+        self.open = kind != EditKind::Standalone;
+    }
+}
+
+impl Edit {
+    /// Try to extend this edit with the `next` one.
+    fn merge(&mut self, next: &Edit) -> bool {
+        if next.selection_before != self.selection_after || next.kind != self.kind {
+            return false;
+        }
+        match self.kind {
+            EditKind::Insert => {
+                let word_boundary = self.inserted.ends_with(char::is_whitespace)
+                    && !next.inserted.starts_with(char::is_whitespace);
+                if !next.removed.is_empty()
+                    || next.start != self.start + self.inserted.len()
+                    || word_boundary
+                {
+                    return false;
+                }
+                self.inserted.push_str(&next.inserted);
+            }
+            // backspace
+            EditKind::Delete if next.start + next.removed.len() == self.start => {
+                self.start = next.start;
+                self.removed.insert_str(0, &next.removed);
+            }
+            // forward delete
+            EditKind::Delete if next.start == self.start => {
+                self.removed.push_str(&next.removed);
+            }
+            _ => return false,
+        }
+        self.selection_after = next.selection_after;
+        true
+    }
+}
+
+/// Find the single replacement that turns `old` into `new`.
+///
+/// Returns the offset of the replacement, the removed text and the inserted
+/// text, or `None` if the strings are equal. When this is ambiguous, such as
+/// when typing a character next to the same character, the replacement is
+/// placed at `caret` or before it.
+fn diff<'a>(old: &'a str, new: &'a str, caret: usize) -> Option<(usize, &'a str, &'a str)> {
+    if old == new {
+        return None;
+    }
+    let common_prefix = old
+        .bytes()
+        .zip(new.bytes())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let mut start = common_prefix.min(caret);
+    while !old.is_char_boundary(start) || !new.is_char_boundary(start) {
+        start -= 1;
+    }
+    let mut suffix = old[start..]
+        .bytes()
+        .rev()
+        .zip(new[start..].bytes().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    while !old.is_char_boundary(old.len() - suffix) || !new.is_char_boundary(new.len() - suffix) {
+        suffix -= 1;
+    }
+    Some((
+        start,
+        &old[start..old.len() - suffix],
+        &new[start..new.len() - suffix],
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_env_log::test;
+
+    fn type_text(stack: &mut UndoStack, text: &mut String, typed: &str) {
+        for c in typed.chars() {
+            let caret = text.len();
+            text.push(c);
+            stack.record(
+                text,
+                Selection::caret(caret),
+                Selection::caret(caret + c.len_utf8()),
+            );
+        }
+    }
+
+    fn undo(stack: &mut UndoStack, text: &mut String) -> Selection {
+        let (range, replacement, selection) = stack.undo().unwrap();
+        text.replace_range(range, &replacement);
+        assert!(stack.is_current(text));
+        selection
+    }
+
+    #[test]
+    fn diffs() {
+        assert_eq!(diff("abc", "abc", 0), None);
+        assert_eq!(diff("abc", "abXc", 2), Some((2, "", "X")));
+        assert_eq!(diff("abc", "ac", 2), Some((1, "b", "")));
+        assert_eq!(diff("abc", "aXYc", 1), Some((1, "b", "XY")));
+        // ambiguous: the caret decides
+        assert_eq!(diff("aa", "aaa", 1), Some((1, "", "a")));
+        assert_eq!(diff("aa", "aaa", 2), Some((2, "", "a")));
+        // never split a character
+        assert_eq!(diff("é", "è", 0), Some((0, "é", "è")));
+        assert_eq!(diff("aé", "aéé", 3), Some((3, "", "é")));
+    }
+
+    #[test]
+    fn typing_groups_by_word() {
+        let mut stack = UndoStack::default();
+        let mut text = String::new();
+        type_text(&mut stack, &mut text, "hello world");
+
+        assert_eq!(undo(&mut stack, &mut text), Selection::caret(6));
+        assert_eq!(text, "hello ");
+        assert_eq!(undo(&mut stack, &mut text), Selection::caret(0));
+        assert_eq!(text, "");
+        assert!(!stack.can_undo());
+    }
+
+    #[test]
+    fn deletions_are_separate_from_insertions() {
+        let mut stack = UndoStack::default();
+        let mut text = String::new();
+        type_text(&mut stack, &mut text, "abcd");
+        for _ in 0..2 {
+            let caret = text.len();
+            text.pop();
+            stack.record(&text, Selection::caret(caret), Selection::caret(caret - 1));
+        }
+
+        assert_eq!(undo(&mut stack, &mut text), Selection::caret(4));
+        assert_eq!(text, "abcd");
+        assert_eq!(undo(&mut stack, &mut text), Selection::caret(0));
+        assert_eq!(text, "");
+    }
+
+    #[test]
+    fn caret_jump_and_seal_end_a_group() {
+        let mut stack = UndoStack::default();
+        let mut text = String::new();
+        type_text(&mut stack, &mut text, "ab");
+        stack.seal();
+        type_text(&mut stack, &mut text, "cd");
+        // insert at the start, without the caret continuing from the last edit
+        text.insert(0, 'X');
+        stack.record(&text, Selection::caret(0), Selection::caret(1));
+
+        undo(&mut stack, &mut text);
+        assert_eq!(text, "abcd");
+        undo(&mut stack, &mut text);
+        assert_eq!(text, "ab");
+        undo(&mut stack, &mut text);
+        assert_eq!(text, "");
+    }
+
+    #[test]
+    fn redo_and_limits() {
+        let mut stack = UndoStack::default();
+        let mut text = String::new();
+        for _ in 0..MAX_UNDO_ENTRIES + 10 {
+            stack.seal();
+            type_text(&mut stack, &mut text, "a");
+        }
+        let mut undone = 0;
+        while stack.can_undo() {
+            undo(&mut stack, &mut text);
+            undone += 1;
+        }
+        assert_eq!(undone, MAX_UNDO_ENTRIES);
+        assert_eq!(text, "a".repeat(10));
+
+        let (range, replacement, selection) = stack.redo().unwrap();
+        text.replace_range(range, &replacement);
+        assert_eq!(text, "a".repeat(11));
+        assert_eq!(selection, Selection::caret(11));
+
+        // a new edit clears the redo stack
+        type_text(&mut stack, &mut text, "b");
+        assert!(!stack.can_redo());
+    }
+}
@@ -89,6 +89,39 @@ pub const TEXTBOX_BORDER_WIDTH: Key<f64> =
     Key::new("org.linebender.druid.theme.textbox_border_width");
 pub const TEXTBOX_INSETS: Key<Insets> = Key::new("org.linebender.druid.theme.textbox_insets");
 
+/// The horizontal space between the edge of a [`Button`] and its label.
+///
+/// [`Button`]: crate::widget::Button
+pub const BUTTON_PADDING_X: Key<f64> = Key::new("org.linebender.druid.theme.button_padding_x");
+/// The vertical space between the edge of a [`Button`] and its label.
+///
+/// [`Button`]: crate::widget::Button
+pub const BUTTON_PADDING_Y: Key<f64> = Key::new("org.linebender.druid.theme.button_padding_y");
+/// The space around the label of a tab in [`Tabs`].
+///
+/// [`Tabs`]: crate::widget::Tabs
+pub const TAB_LABEL_INSETS: Key<Insets> = Key::new("org.linebender.druid.theme.tab_label_insets");
+/// The default spacing between the items of a [`List`].
+///
+/// [`List`]: crate::widget::List
+pub const LIST_ITEM_SPACING: Key<f64> = Key::new("org.linebender.druid.theme.list_item_spacing");
+/// The size of a day in a [`Calendar`].
+///
+/// [`Calendar`]: crate::widget::Calendar
+pub const CALENDAR_CELL_SIZE: Key<f64> = Key::new("org.linebender.druid.theme.calendar_cell_size");
+/// How far each level of a [`Tree`] is indented.
+///
+/// [`Tree`]: crate::widget::Tree
+pub const TREE_INDENT: Key<f64> = Key::new("org.linebender.druid.theme.tree_indent");
+/// The space between a [`Validated`] field and its error message.
+///
+/// [`Validated`]: crate::widget::Validated
+pub const FORM_ERROR_SPACING: Key<f64> = Key::new("org.linebender.druid.theme.form_error_spacing");
+/// The space between the knob of a [`Switch`] and its edge.
+///
+/// [`Switch`]: crate::widget::Switch
+pub const SWITCH_PADDING: Key<f64> = Key::new("org.linebender.druid.theme.switch_padding");
+
 /// The default horizontal spacing between widgets.
 pub const WIDGET_PADDING_HORIZONTAL: Key<f64> =
     Key::new("org.linebender.druid.theme.widget-padding-h");
@@ -116,9 +149,39 @@ pub const SCROLLBAR_EDGE_WIDTH: Key<f64> =
 /// scrollbar's primary axis.
 pub const SCROLLBAR_MIN_SIZE: Key<f64> = Key::new("org.linebender.theme.scrollbar_min_size");
 
+/// The accent color of [`ToastSeverity::Info`] toasts.
+///
+/// [`ToastSeverity::Info`]: crate::ToastSeverity::Info
+pub const TOAST_INFO_COLOR: Key<Color> = Key::new("org.linebender.druid.theme.toast_info_color");
+/// The accent color of [`ToastSeverity::Success`] toasts.
+///
+/// [`ToastSeverity::Success`]: crate::ToastSeverity::Success
+pub const TOAST_SUCCESS_COLOR: Key<Color> =
+    Key::new("org.linebender.druid.theme.toast_success_color");
+/// The accent color of [`ToastSeverity::Warning`] toasts.
+///
+/// [`ToastSeverity::Warning`]: crate::ToastSeverity::Warning
+pub const TOAST_WARNING_COLOR: Key<Color> =
+    Key::new("org.linebender.druid.theme.toast_warning_color");
+/// The accent color of [`ToastSeverity::Error`] toasts.
+///
+/// [`ToastSeverity::Error`]: crate::ToastSeverity::Error
+pub const TOAST_ERROR_COLOR: Key<Color> = Key::new("org.linebender.druid.theme.toast_error_color");
+
+/// The color of the outline and message of an invalid [`Validated`] field.
+///
+/// [`Validated`]: crate::widget::Validated
+pub const FORM_ERROR_COLOR: Key<Color> = Key::new("org.linebender.druid.theme.form_error_color");
+/// The text size of the message of an invalid [`Validated`] field.
+///
+/// [`Validated`]: crate::widget::Validated
+pub const FORM_ERROR_TEXT_SIZE: Key<f64> =
+    Key::new("org.linebender.druid.theme.form_error_text_size");
+
 /// An initial theme.
 pub(crate) fn add_to_env(env: Env) -> Env {
-    env.adding(WINDOW_BACKGROUND_COLOR, Color::rgb8(0x29, 0x29, 0x29))
+    let mut env = env
+        .adding(WINDOW_BACKGROUND_COLOR, Color::rgb8(0x29, 0x29, 0x29))
         .adding(TEXT_COLOR, Color::rgb8(0xf0, 0xf0, 0xea))
         .adding(DISABLED_TEXT_COLOR, Color::rgb8(0xa0, 0xa0, 0x9a))
         .adding(PLACEHOLDER_COLOR, Color::rgb8(0x80, 0x80, 0x80))
@@ -148,24 +211,22 @@ pub(crate) fn add_to_env(env: Env) -> Env {
         .adding(CURSOR_COLOR, Color::WHITE)
         .adding(TEXT_SIZE_NORMAL, 15.0)
         .adding(TEXT_SIZE_LARGE, 24.0)
-        .adding(BASIC_WIDGET_HEIGHT, 18.0)
         .adding(WIDE_WIDGET_WIDTH, 100.)
-        .adding(BORDERED_WIDGET_HEIGHT, 24.0)
         .adding(TEXTBOX_BORDER_RADIUS, 2.)
         .adding(TEXTBOX_BORDER_WIDTH, 1.)
-        .adding(TEXTBOX_INSETS, Insets::new(4.0, 4.0, 4.0, 4.0))
         .adding(SCROLLBAR_COLOR, Color::rgb8(0xff, 0xff, 0xff))
         .adding(SCROLLBAR_BORDER_COLOR, Color::rgb8(0x77, 0x77, 0x77))
         .adding(SCROLLBAR_MAX_OPACITY, 0.7)
         .adding(SCROLLBAR_FADE_DELAY, 1500u64)
-        .adding(SCROLLBAR_WIDTH, 8.)
-        .adding(SCROLLBAR_PAD, 2.)
         .adding(SCROLLBAR_MIN_SIZE, 45.)
         .adding(SCROLLBAR_RADIUS, 5.)
         .adding(SCROLLBAR_EDGE_WIDTH, 1.)
-        .adding(WIDGET_PADDING_VERTICAL, 10.0)
-        .adding(WIDGET_PADDING_HORIZONTAL, 8.0)
-        .adding(WIDGET_CONTROL_COMPONENT_PADDING, 4.0)
+        .adding(TOAST_INFO_COLOR, Color::rgb8(0x5c, 0xc4, 0xff))
+        .adding(TOAST_SUCCESS_COLOR, Color::rgb8(0x4c, 0xaf, 0x50))
+        .adding(TOAST_WARNING_COLOR, Color::rgb8(0xff, 0xb3, 0x00))
+        .adding(TOAST_ERROR_COLOR, Color::rgb8(0xe5, 0x39, 0x35))
+        .adding(FORM_ERROR_COLOR, Color::rgb8(0xe5, 0x39, 0x35))
+        .adding(FORM_ERROR_TEXT_SIZE, 12.0)
         .adding(
             UI_FONT,
             FontDescriptor::new(FontFamily::SYSTEM_UI).with_size(15.0),
@@ -181,7 +242,138 @@ pub(crate) fn add_to_env(env: Env) -> Env {
             FontDescriptor::new(FontFamily::SYSTEM_UI)
                 .with_style(FontStyle::Italic)
                 .with_size(15.0),
-        )
+        );
+    metrics::COMFORTABLE.apply_to(&mut env);
+    env
+}
+
+/// Presets for the sizes and spacing of the built-in widgets.
+///
+/// The metrics of the default theme are [`COMFORTABLE`]; data-heavy
+/// applications may prefer the tighter [`DENSE`]. A preset can be applied to
+/// the whole application in [`AppLauncher::configure_env`], or to part of the
+/// widget tree with an [`EnvScope`]:
+///
+/// ```
+/// use druid::theme::metrics;
+/// use druid::widget::{Button, EnvScope};
+///
+/// let dense_button: EnvScope<(), _> = EnvScope::new(
+///     |env, _| metrics::DENSE.apply_to(env),
+///     Button::new("Compact"),
+/// );
+/// ```
+///
+/// Built-in widgets lay themselves out again when these values change.
+///
+/// [`COMFORTABLE`]: metrics::COMFORTABLE
+/// [`DENSE`]: metrics::DENSE
+/// [`AppLauncher::configure_env`]: crate::AppLauncher::configure_env
+/// [`EnvScope`]: crate::widget::EnvScope
+pub mod metrics {
+    use super::*;
+
+    /// The values of the theme keys that control widget sizes and spacing.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub struct Metrics {
+        /// The value for [`BASIC_WIDGET_HEIGHT`].
+        pub basic_widget_height: f64,
+        /// The value for [`BORDERED_WIDGET_HEIGHT`].
+        pub bordered_widget_height: f64,
+        /// The value for [`BUTTON_PADDING_X`].
+        pub button_padding_x: f64,
+        /// The value for [`BUTTON_PADDING_Y`].
+        pub button_padding_y: f64,
+        /// The value for [`TEXTBOX_INSETS`].
+        pub textbox_insets: Insets,
+        /// The value for [`TAB_LABEL_INSETS`].
+        pub tab_label_insets: Insets,
+        /// The value for [`LIST_ITEM_SPACING`].
+        pub list_item_spacing: f64,
+        /// The value for [`CALENDAR_CELL_SIZE`].
+        pub calendar_cell_size: f64,
+        /// The value for [`TREE_INDENT`].
+        pub tree_indent: f64,
+        /// The value for [`FORM_ERROR_SPACING`].
+        pub form_error_spacing: f64,
+        /// The value for [`SWITCH_PADDING`].
+        pub switch_padding: f64,
+        /// The value for [`WIDGET_PADDING_HORIZONTAL`].
+        pub widget_padding_horizontal: f64,
+        /// The value for [`WIDGET_PADDING_VERTICAL`].
+        pub widget_padding_vertical: f64,
+        /// The value for [`WIDGET_CONTROL_COMPONENT_PADDING`].
+        pub widget_control_component_padding: f64,
+        /// The value for [`SCROLLBAR_WIDTH`].
+        pub scrollbar_width: f64,
+        /// The value for [`SCROLLBAR_PAD`].
+        pub scrollbar_pad: f64,
+    }
+
+    /// The metrics of the default theme.
+    pub const COMFORTABLE: Metrics = Metrics {
+        basic_widget_height: 18.0,
+        bordered_widget_height: 24.0,
+        button_padding_x: 8.0,
+        button_padding_y: 2.0,
+        textbox_insets: Insets::uniform(4.0),
+        tab_label_insets: Insets::uniform_xy(9.0, 5.0),
+        list_item_spacing: 0.0,
+        calendar_cell_size: 32.0,
+        tree_indent: 16.0,
+        form_error_spacing: 2.0,
+        switch_padding: 3.0,
+        widget_padding_horizontal: 8.0,
+        widget_padding_vertical: 10.0,
+        widget_control_component_padding: 4.0,
+        scrollbar_width: 8.0,
+        scrollbar_pad: 2.0,
+    };
+
+    /// Tighter metrics, for fitting more onto the screen.
+    pub const DENSE: Metrics = Metrics {
+        basic_widget_height: 14.0,
+        bordered_widget_height: 18.0,
+        button_padding_x: 5.0,
+        button_padding_y: 1.0,
+        textbox_insets: Insets::uniform_xy(3.0, 1.0),
+        tab_label_insets: Insets::uniform_xy(6.0, 2.0),
+        list_item_spacing: 0.0,
+        calendar_cell_size: 24.0,
+        tree_indent: 12.0,
+        form_error_spacing: 1.0,
+        switch_padding: 2.0,
+        widget_padding_horizontal: 4.0,
+        widget_padding_vertical: 4.0,
+        widget_control_component_padding: 2.0,
+        scrollbar_width: 6.0,
+        scrollbar_pad: 1.0,
+    };
+
+    impl Metrics {
+        /// Set the theme keys in `env` to these metrics.
+        pub fn apply_to(&self, env: &mut Env) {
+            env.set(BASIC_WIDGET_HEIGHT, self.basic_widget_height);
+            env.set(BORDERED_WIDGET_HEIGHT, self.bordered_widget_height);
+            env.set(BUTTON_PADDING_X, self.button_padding_x);
+            env.set(BUTTON_PADDING_Y, self.button_padding_y);
+            env.set(TEXTBOX_INSETS, self.textbox_insets);
+            env.set(TAB_LABEL_INSETS, self.tab_label_insets);
+            env.set(LIST_ITEM_SPACING, self.list_item_spacing);
+            env.set(CALENDAR_CELL_SIZE, self.calendar_cell_size);
+            env.set(TREE_INDENT, self.tree_indent);
+            env.set(FORM_ERROR_SPACING, self.form_error_spacing);
+            env.set(SWITCH_PADDING, self.switch_padding);
+            env.set(WIDGET_PADDING_HORIZONTAL, self.widget_padding_horizontal);
+            env.set(WIDGET_PADDING_VERTICAL, self.widget_padding_vertical);
+            env.set(
+                WIDGET_CONTROL_COMPONENT_PADDING,
+                self.widget_control_component_padding,
+            );
+            env.set(SCROLLBAR_WIDTH, self.scrollbar_width);
+            env.set(SCROLLBAR_PAD, self.scrollbar_pad);
+        }
+    }
 }
 
 #[deprecated(since = "0.7.0", note = "use Env::default() instead")]
@@ -0,0 +1,760 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Short notifications that are shown in a corner of a window, and go away
+//! by themselves.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::Arc;
+
+use instant::{Duration, Instant};
+use tracing::{instrument, trace};
+
+use crate::commands::SHOW_TOAST;
+use crate::widget::prelude::*;
+use crate::widget::{Button, Flex, Label, LineBreaking};
+use crate::{
+    theme, ArcStr, ChildSet, Color, Command, Insets, Key, Point, Rect, Selector, TimerToken,
+    UnitPoint, Vec2, WidgetExt, WidgetPod,
+};
+
+/// The width of a toast, if the window is wide enough.
+const TOAST_WIDTH: f64 = 320.0;
+/// The space between the toasts and the edges of the window.
+const TOAST_MARGIN: f64 = 12.0;
+/// The space between two toasts, and between the parts of a toast.
+const TOAST_SPACING: f64 = 8.0;
+/// The width of the stripe in the severity's color.
+const ACCENT_WIDTH: f64 = 4.0;
+/// How long a toast takes to slide in or out, in nanoseconds.
+const SLIDE_NANOS: f64 = 200_000_000.0;
+
+/// Sent by the buttons of a toast to dismiss it.
+const DISMISS_TOAST: Selector = Selector::new("druid-builtin.dismiss-toast");
+
+/// How important a toast is.
+///
+/// This picks the color of the toast's accent stripe from the theme.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToastSeverity {
+    /// Something the user may want to know; uses [`theme::TOAST_INFO_COLOR`].
+    Info,
+    /// Something went well; uses [`theme::TOAST_SUCCESS_COLOR`].
+    Success,
+    /// Something may need attention; uses [`theme::TOAST_WARNING_COLOR`].
+    Warning,
+    /// Something went wrong; uses [`theme::TOAST_ERROR_COLOR`].
+    Error,
+}
+
+impl ToastSeverity {
+    fn color_key(self) -> Key<Color> {
+        match self {
+            ToastSeverity::Info => theme::TOAST_INFO_COLOR,
+            ToastSeverity::Success => theme::TOAST_SUCCESS_COLOR,
+            ToastSeverity::Warning => theme::TOAST_WARNING_COLOR,
+            ToastSeverity::Error => theme::TOAST_ERROR_COLOR,
+        }
+    }
+}
+
+/// A toast to show with [`SHOW_TOAST`].
+///
+/// A toast shows a message, or a widget of its own, along with a button to
+/// close it and optionally a button for an action. It goes away by itself
+/// after its duration, which stops running while the mouse is over it.
+///
+/// A `ToastDesc` can be sent from any thread, with an [`ExtEventSink`].
+///
+/// # Examples
+///
+/// ```
+/// use druid::commands::SHOW_TOAST;
+/// use druid::{Selector, ToastDesc, ToastSeverity};
+///
+/// const UNDO: Selector = Selector::new("my-app.undo");
+///
+/// let toast = ToastDesc::new("Note deleted")
+///     .severity(ToastSeverity::Warning)
+///     .action("Undo", || UNDO.into());
+/// let command = SHOW_TOAST.with(toast);
+/// ```
+///
+/// [`SHOW_TOAST`]: crate::commands::SHOW_TOAST
+/// [`ExtEventSink`]: crate::ExtEventSink
+#[derive(Clone)]
+pub struct ToastDesc {
+    content: ToastContent,
+    severity: ToastSeverity,
+    duration: Duration,
+    action: Option<ToastAction>,
+}
+
+#[derive(Clone)]
+enum ToastContent {
+    Message(ArcStr),
+    Widget(Arc<dyn Fn() -> Box<dyn Widget<()>> + Send + Sync>),
+}
+
+#[derive(Clone)]
+struct ToastAction {
+    label: ArcStr,
+    command: Arc<dyn Fn() -> Command + Send + Sync>,
+}
+
+impl ToastDesc {
+    /// How long a toast is shown, unless a [`duration`] is set.
+    ///
+    /// [`duration`]: Self::duration
+    pub const DEFAULT_DURATION: Duration = Duration::from_secs(4);
+
+    /// A toast that shows a message.
+    pub fn new(message: impl Into<ArcStr>) -> Self {
+        ToastDesc::with_content(ToastContent::Message(message.into()))
+    }
+
+    /// A toast that shows a widget built by `make_widget`.
+    ///
+    /// The widget is built when the toast is shown.
+    pub fn with_widget(
+        make_widget: impl Fn() -> Box<dyn Widget<()>> + Send + Sync + 'static,
+    ) -> Self {
+        ToastDesc::with_content(ToastContent::Widget(Arc::new(make_widget)))
+    }
+
+    fn with_content(content: ToastContent) -> Self {
+        ToastDesc {
+            content,
+            severity: ToastSeverity::Info,
+            duration: ToastDesc::DEFAULT_DURATION,
+            action: None,
+        }
+    }
+
+    /// Set the severity of the toast. The default is [`ToastSeverity::Info`].
+    pub fn severity(mut self, severity: ToastSeverity) -> Self {
+        self.severity = severity;
+        self
+    }
+
+    /// Set how long the toast is shown, not counting the time the mouse is
+    /// over it.
+    pub fn duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    /// Add a button to the toast that submits the command built by `command`,
+    /// and then dismisses the toast.
+    ///
+    /// The command is submitted from the toast, so without a target it goes
+    /// to the toast's window.
+    pub fn action(
+        mut self,
+        label: impl Into<ArcStr>,
+        command: impl Fn() -> Command + Send + Sync + 'static,
+    ) -> Self {
+        self.action = Some(ToastAction {
+            label: label.into(),
+            command: Arc::new(command),
+        });
+        self
+    }
+}
+
+impl fmt::Debug for ToastDesc {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut s = f.debug_struct("ToastDesc");
+        match &self.content {
+            ToastContent::Message(message) => s.field("message", message),
+            ToastContent::Widget(_) => s.field("widget", &"<widget>"),
+        };
+        s.field("severity", &self.severity)
+            .field("duration", &self.duration)
+            .field("action", &self.action.as_ref().map(|action| &action.label))
+            .finish()
+    }
+}
+
+/// The time a toast has left, which only runs while the toast is shown and
+/// the mouse isn't over it.
+struct DismissTimer {
+    remaining: Duration,
+    /// When the timer was last started, if it is running.
+    running_since: Option<Instant>,
+    token: TimerToken,
+}
+
+impl DismissTimer {
+    fn new(duration: Duration) -> Self {
+        DismissTimer {
+            remaining: duration,
+            running_since: None,
+            token: TimerToken::INVALID,
+        }
+    }
+
+    /// Start the timer, if it isn't running, and return the time left.
+    fn start(&mut self, now: Instant) -> Duration {
+        self.pause(now);
+        self.running_since = Some(now);
+        self.remaining
+    }
+
+    /// Stop the timer, keeping the time left.
+    fn pause(&mut self, now: Instant) {
+        if let Some(since) = self.running_since.take() {
+            self.remaining = self.remaining.saturating_sub(now.duration_since(since));
+        }
+        self.token = TimerToken::INVALID;
+    }
+
+    /// The time left at `now`, or `None` if the timer isn't running.
+    fn remaining(&self, now: Instant) -> Option<Duration> {
+        let since = self.running_since?;
+        Some(self.remaining.saturating_sub(now.duration_since(since)))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ToastState {
+    Entering,
+    Shown,
+    Leaving,
+}
+
+/// A single toast, with its buttons.
+struct Toast {
+    severity: ToastSeverity,
+    /// The message, for debugging; empty for a toast with its own widget.
+    message: ArcStr,
+    inner: WidgetPod<(), Box<dyn Widget<()>>>,
+    state: ToastState,
+    /// How far the toast has slid in, from 0 to 1.
+    progress: f64,
+    timer: DismissTimer,
+}
+
+impl Toast {
+    fn new(desc: &ToastDesc) -> Self {
+        let (content, message): (Box<dyn Widget<()>>, _) = match &desc.content {
+            ToastContent::Message(message) => (
+                Box::new(Label::new(message.clone()).with_line_break_mode(LineBreaking::WordWrap)),
+                message.clone(),
+            ),
+            ToastContent::Widget(make_widget) => (make_widget(), "".into()),
+        };
+
+        let mut row = Flex::row().with_flex_child(content, 1.0);
+        if let Some(action) = &desc.action {
+            let command = action.command.clone();
+            row.add_spacer(TOAST_SPACING);
+            row.add_child(
+                Button::new(action.label.clone()).on_click(move |ctx, _, _| {
+                    ctx.submit_command(command());
+                    ctx.submit_notification(DISMISS_TOAST);
+                }),
+            );
+        }
+        row.add_spacer(TOAST_SPACING);
+        row.add_child(Button::new("×").on_click(|ctx, _, _| {
+            ctx.submit_notification(DISMISS_TOAST);
+        }));
+        let inner = row.padding(Insets::new(
+            ACCENT_WIDTH + TOAST_SPACING,
+            TOAST_SPACING,
+            TOAST_SPACING,
+            TOAST_SPACING,
+        ));
+
+        Toast {
+            severity: desc.severity,
+            message,
+            inner: WidgetPod::new(inner.boxed()),
+            state: ToastState::Entering,
+            progress: 0.0,
+            timer: DismissTimer::new(desc.duration),
+        }
+    }
+
+    fn dismiss(&mut self, ctx: &mut EventCtx) {
+        if self.state != ToastState::Leaving {
+            trace!("dismissing toast {:?}", self.message);
+            self.state = ToastState::Leaving;
+            self.timer.pause(Instant::now());
+            ctx.request_anim_frame();
+        }
+    }
+
+    /// Whether the toast has slid out, and can be removed.
+    fn is_gone(&self) -> bool {
+        self.state == ToastState::Leaving && self.progress == 0.0
+    }
+
+    /// How far the toast has slid in, with the motion slowing down at the end.
+    fn eased_progress(&self) -> f64 {
+        1.0 - (1.0 - self.progress).powi(3)
+    }
+}
+
+impl Widget<()> for Toast {
+    #[instrument(name = "Toast", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut (), env: &Env) {
+        match event {
+            Event::Notification(note) if note.is(DISMISS_TOAST) => {
+                self.dismiss(ctx);
+                ctx.set_handled();
+                return;
+            }
+            Event::AnimFrame(interval) => {
+                let step = *interval as f64 / SLIDE_NANOS;
+                match self.state {
+                    ToastState::Entering => {
+                        self.progress = (self.progress + step).min(1.0);
+                        if self.progress < 1.0 {
+                            ctx.request_anim_frame();
+                        } else {
+                            self.state = ToastState::Shown;
+                            if !ctx.is_hot() {
+                                let remaining = self.timer.start(Instant::now());
+                                self.timer.token = ctx.request_timer(remaining);
+                            }
+                        }
+                    }
+                    ToastState::Leaving => {
+                        self.progress = (self.progress - step).max(0.0);
+                        if self.progress > 0.0 {
+                            ctx.request_anim_frame();
+                        }
+                    }
+                    ToastState::Shown => (),
+                }
+                ctx.request_layout();
+            }
+            Event::Timer(token) if *token == self.timer.token => {
+                match self.timer.remaining(Instant::now()) {
+                    Some(remaining) if remaining == Duration::ZERO => self.dismiss(ctx),
+                    // the timer went off early
+                    Some(remaining) => self.timer.token = ctx.request_timer(remaining),
+                    None => (),
+                }
+            }
+            _ => (),
+        }
+        self.inner.event(ctx, event, data, env);
+    }
+
+    #[instrument(name = "Toast", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &(), env: &Env) {
+        match event {
+            LifeCycle::WidgetAdded => ctx.request_anim_frame(),
+            LifeCycle::HotChanged(hot) if self.state == ToastState::Shown => {
+                if *hot {
+                    self.timer.pause(Instant::now());
+                } else {
+                    let remaining = self.timer.start(Instant::now());
+                    self.timer.token = ctx.request_timer(remaining);
+                }
+            }
+            _ => (),
+        }
+        self.inner.lifecycle(ctx, event, data, env);
+    }
+
+    #[instrument(name = "Toast", level = "trace", skip(self, ctx, _old, data, env))]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old: &(), data: &(), env: &Env) {
+        if ctx.env_key_changed(&self.severity.color_key()) {
+            ctx.request_paint();
+        }
+        self.inner.update(ctx, data, env);
+    }
+
+    #[instrument(name = "Toast", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &(), env: &Env) -> Size {
+        let size = self.inner.layout(ctx, bc, data, env);
+        self.inner.set_origin(ctx, data, env, Point::ORIGIN);
+        size
+    }
+
+    #[instrument(name = "Toast", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &(), env: &Env) {
+        let rect = ctx.size().to_rect();
+        let shape = rect.to_rounded_rect(env.get(theme::BUTTON_BORDER_RADIUS));
+        ctx.fill(shape, &env.get(theme::BACKGROUND_LIGHT));
+        let accent = Rect::new(0.0, 0.0, ACCENT_WIDTH, rect.height());
+        let accent_color = env.get(self.severity.color_key());
+        ctx.with_save(|ctx| {
+            ctx.clip(shape);
+            ctx.fill(accent, &accent_color);
+        });
+        ctx.stroke(shape, &env.get(theme::BORDER_DARK), 1.0);
+        self.inner.paint(ctx, data, env);
+    }
+
+    #[cfg(feature = "debug_state")]
+    fn debug_state(&self, data: &()) -> DebugState {
+        let timer = if self.timer.running_since.is_some() {
+            "running"
+        } else {
+            "paused"
+        };
+        DebugState {
+            display_name: "Toast".to_string(),
+            main_value: self.message.to_string(),
+            other_values: [("timer".to_string(), timer.to_string())]
+                .iter()
+                .cloned()
+                .collect(),
+            children: vec![self.inner.debug_state(data)],
+            ..Default::default()
+        }
+    }
+}
+
+/// The toasts of a window, which lie above its content and overlays.
+///
+/// The stack fills the window, and shows up to `max_visible` toasts stacked
+/// at `position`, newest first. Toasts that arrive while the stack is full
+/// wait until one of the shown toasts goes away.
+pub(crate) struct ToastStack {
+    toasts: ChildSet<(), Toast>,
+    queue: VecDeque<ToastDesc>,
+    position: UnitPoint,
+    max_visible: usize,
+}
+
+impl ToastStack {
+    pub(crate) fn new(position: UnitPoint, max_visible: usize) -> Self {
+        ToastStack {
+            toasts: ChildSet::new(),
+            queue: VecDeque::new(),
+            position,
+            max_visible,
+        }
+    }
+
+    /// Whether `pos` is on one of the toasts.
+    pub(crate) fn covers(&self, pos: Point) -> bool {
+        self.toasts
+            .iter()
+            .any(|toast| toast.layout_rect().contains(pos))
+    }
+
+    /// Remove the toasts that are gone, and show waiting ones in their place.
+    fn update_toasts(&mut self, ctx: &mut EventCtx) {
+        let count = self.toasts.len();
+        self.toasts.retain(|toast| !toast.widget().is_gone());
+        let mut changed = count != self.toasts.len();
+        while self.toasts.len() < self.max_visible {
+            match self.queue.pop_front() {
+                Some(desc) => {
+                    self.toasts.push(WidgetPod::new(Toast::new(&desc)));
+                    changed = true;
+                }
+                None => break,
+            }
+        }
+        if changed {
+            ctx.children_changed();
+        }
+    }
+
+    /// The direction the toasts slide out to, towards the nearest edge.
+    fn slide_direction(&self) -> Vec2 {
+        let unit = self.position.resolve(Rect::new(0.0, 0.0, 1.0, 1.0));
+        if unit.x < 0.5 {
+            Vec2::new(-1.0, 0.0)
+        } else if unit.x > 0.5 {
+            Vec2::new(1.0, 0.0)
+        } else if unit.y < 0.5 {
+            Vec2::new(0.0, -1.0)
+        } else {
+            Vec2::new(0.0, 1.0)
+        }
+    }
+}
+
+impl Widget<()> for ToastStack {
+    #[instrument(
+        name = "ToastStack",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut (), env: &Env) {
+        if let Event::Command(cmd) = event {
+            if let Some(desc) = cmd.get(SHOW_TOAST) {
+                self.queue.push_back(desc.clone());
+                self.update_toasts(ctx);
+                ctx.set_handled();
+                return;
+            }
+        }
+        self.toasts.event(ctx, event, data, env);
+        self.update_toasts(ctx);
+    }
+
+    #[instrument(
+        name = "ToastStack",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &(), env: &Env) {
+        self.toasts.lifecycle(ctx, event, data, env)
+    }
+
+    #[instrument(name = "ToastStack", level = "trace", skip(self, ctx, _old, data, env))]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old: &(), data: &(), env: &Env) {
+        self.toasts.update(ctx, data, env)
+    }
+
+    #[instrument(name = "ToastStack", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &(), env: &Env) -> Size {
+        let size = bc.max();
+        let area = size.to_rect().inset(-TOAST_MARGIN);
+        let width = TOAST_WIDTH.min(area.width()).max(0.0);
+        let toast_bc = BoxConstraints::new(Size::new(width, 0.0), Size::new(width, f64::INFINITY));
+
+        // Each toast takes up room in the stack as far as it has slid in,
+        // so that the others move along smoothly.
+        let slots: Vec<_> = self
+            .toasts
+            .layout_each(ctx, &toast_bc, data, env)
+            .map(|(toast, toast_size)| {
+                let progress = toast.widget().eased_progress();
+                (
+                    toast_size,
+                    progress,
+                    (toast_size.height + TOAST_SPACING) * progress,
+                )
+            })
+            .collect();
+        let height = (slots.iter().map(|(_, _, slot)| slot).sum::<f64>() - TOAST_SPACING).max(0.0);
+        let stack = Size::new(width, height);
+        let stack_origin = self.position.resolve(area) - self.position.resolve(stack.to_rect());
+
+        // The newest toast is nearest to the corner.
+        let from_bottom = self.position.resolve(Rect::new(0.0, 0.0, 1.0, 1.0)).y >= 0.5;
+        let slide = self.slide_direction();
+        let mut y = if from_bottom {
+            height + TOAST_SPACING
+        } else {
+            0.0
+        };
+        for (toast, (toast_size, progress, slot)) in self.toasts.iter_mut().zip(slots).rev() {
+            if from_bottom {
+                y -= slot;
+            }
+            // far enough to be off the edge of the window
+            let distance = Vec2::new(
+                toast_size.width + TOAST_MARGIN,
+                toast_size.height + TOAST_MARGIN,
+            );
+            let offset = Vec2::new(slide.x * distance.x, slide.y * distance.y) * (1.0 - progress);
+            let origin = Point::new(stack_origin.x, stack_origin.y + y) + offset;
+            toast.set_origin(ctx, data, env, origin);
+            if !from_bottom {
+                y += slot;
+            }
+        }
+        size
+    }
+
+    #[instrument(name = "ToastStack", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &(), env: &Env) {
+        self.toasts.paint(ctx, data, env)
+    }
+
+    #[cfg(feature = "debug_state")]
+    fn debug_state(&self, data: &()) -> DebugState {
+        DebugState {
+            display_name: "ToastStack".to_string(),
+            other_values: [("queued".to_string(), self.queue.len().to_string())]
+                .iter()
+                .cloned()
+                .collect(),
+            children: self.toasts.iter().map(|t| t.debug_state(data)).collect(),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(all(test, feature = "debug_state"))]
+mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use test_env_log::test;
+
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::{widget_ids, ModularWidget};
+    use crate::widget::SizedBox;
+    use crate::MouseButton;
+
+    const UNDO: Selector = Selector::new("druid-test.undo");
+
+    /// The debug state of the whole window, including its toasts.
+    fn window_state(harness: &Harness<()>) -> DebugState {
+        harness.window().root.debug_state(harness.data())
+    }
+
+    /// The messages of the toasts that are shown, oldest first.
+    fn shown(harness: &Harness<()>) -> Vec<String> {
+        window_state(harness)
+            .iter()
+            .filter(|state| state.display_name == "Toast")
+            .map(|state| state.main_value.clone())
+            .collect()
+    }
+
+    /// The debug state of the toast with this message.
+    fn toast(harness: &Harness<()>, message: &str) -> DebugState {
+        window_state(harness)
+            .find(|state| state.display_name == "Toast" && state.main_value == message)
+            .cloned()
+            .unwrap()
+    }
+
+    /// Let every animation run to its end.
+    fn finish_animations(harness: &mut Harness<()>) {
+        harness.event(Event::AnimFrame(SLIDE_NANOS as u64));
+        harness.just_layout();
+    }
+
+    /// The window rect of the button with this label, in the toast with this
+    /// message.
+    fn button_rect(harness: &mut Harness<()>, message: &str, label: &str) -> Rect {
+        let id = toast(harness, message)
+            .find(|state| {
+                state.display_name == "Button"
+                    && state.children.iter().any(|child| child.main_value == label)
+            })
+            .and_then(|state| state.widget_id)
+            .unwrap();
+        let state = harness.get_state(id);
+        Rect::from_origin_size(state.window_origin(), state.size())
+    }
+
+    fn click(harness: &mut Harness<()>, pos: Point) {
+        let mut mouse = crate::tests::move_mouse(pos);
+        mouse.button = MouseButton::Left;
+        mouse.buttons.insert(MouseButton::Left);
+        mouse.count = 1;
+        harness.event(Event::MouseDown(mouse.clone()));
+        mouse.buttons.remove(MouseButton::Left);
+        harness.event(Event::MouseUp(mouse));
+    }
+
+    #[test]
+    fn dismiss_timer_pauses() {
+        let start = Instant::now();
+        let secs = |n| start + Duration::from_secs(n);
+        let mut timer = DismissTimer::new(Duration::from_secs(4));
+        assert_eq!(timer.remaining(start), None);
+
+        assert_eq!(timer.start(start), Duration::from_secs(4));
+        assert_eq!(timer.remaining(secs(1)), Some(Duration::from_secs(3)));
+
+        // while paused, the time left stays the same
+        timer.pause(secs(1));
+        assert_eq!(timer.remaining(secs(10)), None);
+        assert_eq!(timer.start(secs(10)), Duration::from_secs(3));
+        assert_eq!(timer.remaining(secs(12)), Some(Duration::from_secs(1)));
+        assert_eq!(timer.remaining(secs(20)), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn toasts_beyond_the_limit_wait_their_turn() {
+        Harness::create_simple((), SizedBox::empty(), |harness| {
+            harness.set_initial_size(Size::new(400., 300.));
+            harness.send_initial_events();
+            harness.just_layout();
+            for n in 0..5 {
+                harness.submit_command(SHOW_TOAST.with(ToastDesc::new(format!("toast {}", n))));
+            }
+            finish_animations(harness);
+            assert_eq!(shown(harness), ["toast 0", "toast 1", "toast 2"]);
+            let stack = window_state(harness)
+                .find(|state| state.display_name == "ToastStack")
+                .cloned()
+                .unwrap();
+            assert_eq!(stack.other_values["queued"], "2");
+
+            // the newest toast is in the bottom right corner
+            let newest = toast(harness, "toast 2").layout_rect.unwrap();
+            assert_eq!(newest.x1, 400. - TOAST_MARGIN);
+            assert_eq!(newest.y1, 300. - TOAST_MARGIN);
+            assert_eq!(newest.width(), TOAST_WIDTH);
+
+            // closing a toast makes room for the next one
+            let close = button_rect(harness, "toast 1", "×");
+            click(harness, close.center());
+            finish_animations(harness);
+            assert_eq!(shown(harness), ["toast 0", "toast 2", "toast 3"]);
+        });
+    }
+
+    #[test]
+    fn action_submits_its_command_and_dismisses() {
+        let [recorder] = widget_ids();
+        let undone = Rc::new(Cell::new(false));
+        let root = ModularWidget::new(undone.clone())
+            .event_fn(|undone, _, event, _, _| {
+                if let Event::Command(cmd) = event {
+                    if cmd.is(UNDO) {
+                        undone.set(true);
+                    }
+                }
+            })
+            .with_id(recorder);
+        let desc = ToastDesc::new("Deleted").action("Undo", move || UNDO.to(recorder));
+
+        Harness::create_simple((), root, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.submit_command(SHOW_TOAST.with(desc.clone()));
+            finish_animations(harness);
+
+            let undo = button_rect(harness, "Deleted", "Undo");
+            click(harness, undo.center());
+            assert!(undone.get());
+            finish_animations(harness);
+            assert!(shown(harness).is_empty());
+        });
+    }
+
+    #[test]
+    fn hovering_pauses_the_dismiss_timer() {
+        Harness::create_simple((), SizedBox::empty(), |harness| {
+            harness.set_initial_size(Size::new(400., 300.));
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.submit_command(SHOW_TOAST.with(ToastDesc::new("Saved")));
+            let timer =
+                |harness: &Harness<()>| toast(harness, "Saved").other_values["timer"].clone();
+
+            // the timer starts once the toast has slid in
+            assert_eq!(timer(harness), "paused");
+            finish_animations(harness);
+            assert_eq!(timer(harness), "running");
+
+            let rect = toast(harness, "Saved").layout_rect.unwrap();
+            harness.event(Event::MouseMove(crate::tests::move_mouse(rect.center())));
+            assert_eq!(timer(harness), "paused");
+            harness.event(Event::MouseMove(crate::tests::move_mouse((10., 10.))));
+            assert_eq!(timer(harness), "running");
+        });
+    }
+}
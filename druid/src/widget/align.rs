@@ -15,6 +15,7 @@
 //! A widget that aligns its child (for example, centering it).
 
 use crate::widget::prelude::*;
+use crate::widget::{Axis, Intrinsic};
 use crate::{Data, Rect, Size, UnitPoint, WidgetPod};
 use tracing::{instrument, trace};
 
@@ -137,10 +138,30 @@ impl<T: Data> Widget<T> for Align<T> {
         my_size
     }
 
+    fn compute_intrinsic_size(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        axis: Axis,
+        kind: Intrinsic,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        // The child is laid out with loosened constraints, and we are as
+        // big as the child when we aren't given more room.
+        self.child
+            .compute_intrinsic_size(ctx, axis, kind, &bc.loosen(), data, env)
+    }
+
     #[instrument(name = "Align", level = "trace", skip(self, ctx, data, env))]
     fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
         self.child.paint(ctx, data, env);
     }
+
+    #[cfg(feature = "debug_state")]
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState::with_children("Align", vec![self.child.debug_state(data)])
+    }
 }
 
 fn log_size_warnings(size: Size) {
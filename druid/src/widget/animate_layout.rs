@@ -0,0 +1,385 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that animates changes to its layout rect.
+
+use std::time::Duration;
+
+use crate::event::InternalLifeCycle;
+use crate::kurbo::{Affine, Rect};
+use crate::widget::{prelude::*, WidgetWrapper};
+use crate::{Data, Point, WidgetPod};
+
+use tracing::{instrument, trace};
+
+/// An easing curve, mapping the elapsed fraction of an animation to the
+/// fraction of the distance covered.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Curve {
+    /// Constant speed.
+    Linear,
+    /// Start slow and speed up.
+    EaseIn,
+    /// Start fast and slow down.
+    EaseOut,
+    /// Start slow, speed up, and slow down again.
+    EaseInOut,
+}
+
+impl Curve {
+    /// Evaluate the curve at `t`, which is clamped to `0.0..=1.0`.
+    ///
+    /// Every curve maps `0.0` to `0.0` and `1.0` to `1.0`, and never leaves
+    /// that range.
+    pub fn eval(self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Curve::Linear => t,
+            Curve::EaseIn => t * t * t,
+            Curve::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Curve::EaseInOut if t < 0.5 => 4.0 * t * t * t,
+            Curve::EaseInOut => 1.0 - (-2.0 * t + 2.0).powi(3) / 2.0,
+        }
+    }
+}
+
+/// A widget that animates its child from its old layout rect to its new one.
+///
+/// When a layout pass moves or resizes this widget, for instance because an
+/// item was inserted above it in a [`List`] or [`Flex`], the new layout is used
+/// immediately, but the child is painted as if it were still at the old rect,
+/// and then moved to the new one over the given duration. If the layout
+/// changes again during the animation, it continues from wherever the child
+/// was painted last.
+///
+/// Only painting is animated. Events are hit-tested against the new rect for
+/// the whole animation.
+///
+/// This is usually created with [`WidgetExt::animate_layout`].
+///
+/// [`List`]: crate::widget::List
+/// [`Flex`]: crate::widget::Flex
+/// [`WidgetExt::animate_layout`]: crate::WidgetExt::animate_layout
+pub struct AnimateLayout<T, W> {
+    child: WidgetPod<T, W>,
+    duration: Duration,
+    curve: Curve,
+    /// Our layout rect, as of the last layout pass.
+    target: Option<Rect>,
+    transition: Option<Transition>,
+}
+
+/// An animation from `from` to the current target.
+struct Transition {
+    from: Rect,
+    elapsed: Duration,
+}
+
+impl<T, W: Widget<T>> AnimateLayout<T, W> {
+    /// Create a new `AnimateLayout`, animating layout changes of `child`
+    /// over `duration`, following `curve`.
+    pub fn new(child: W, duration: Duration, curve: Curve) -> Self {
+        AnimateLayout {
+            child: WidgetPod::new(child),
+            duration,
+            curve,
+            target: None,
+            transition: None,
+        }
+    }
+
+    /// Returns `true` if the child is being animated.
+    pub fn is_animating(&self) -> bool {
+        self.transition.is_some()
+    }
+
+    /// The rect the child is currently painted at, in our parent's coordinate space.
+    fn painted_rect(&self) -> Option<Rect> {
+        let target = self.target?;
+        let transition = match &self.transition {
+            Some(transition) => transition,
+            None => return Some(target),
+        };
+        let t = if self.duration.as_nanos() == 0 {
+            1.0
+        } else {
+            transition.elapsed.as_secs_f64() / self.duration.as_secs_f64()
+        };
+        let t = self.curve.eval(t);
+        let lerp = |from: f64, to: f64| from + (to - from) * t;
+        let from = transition.from;
+        Some(Rect::new(
+            lerp(from.x0, target.x0),
+            lerp(from.y0, target.y0),
+            lerp(from.x1, target.x1),
+            lerp(from.y1, target.y1),
+        ))
+    }
+
+    /// The transform from our layout rect to the rect the child is painted at.
+    fn paint_transform(&self) -> Affine {
+        match (self.target, self.painted_rect()) {
+            (Some(target), Some(painted)) => {
+                let scale = |painted: f64, target: f64| {
+                    if target > 0.0 {
+                        painted / target
+                    } else {
+                        1.0
+                    }
+                };
+                let offset = painted.origin() - target.origin();
+                Affine::translate(offset)
+                    * Affine::scale_non_uniform(
+                        scale(painted.width(), target.width()),
+                        scale(painted.height(), target.height()),
+                    )
+            }
+            _ => Affine::IDENTITY,
+        }
+    }
+
+    /// Called once a layout pass is finished, with our new layout rect.
+    fn layout_finished(&mut self, ctx: &mut LifeCycleCtx, rect: Rect) {
+        if self.target == Some(rect) {
+            return;
+        }
+        if let Some(painted) = self.painted_rect() {
+            trace!("animating layout from {} to {}", painted, rect);
+            self.transition = Some(Transition {
+                from: painted,
+                elapsed: Duration::ZERO,
+            });
+            ctx.request_anim_frame();
+            // our paint insets need to cover the old rect
+            ctx.request_layout();
+        }
+        self.target = Some(rect);
+    }
+}
+
+impl<T, W> WidgetWrapper for AnimateLayout<T, W> {
+    widget_wrapper_pod_body!(W, child);
+}
+
+impl<T: Data, W: Widget<T>> Widget<T> for AnimateLayout<T, W> {
+    #[instrument(
+        name = "AnimateLayout",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if let (Some(transition), Event::AnimFrame(interval)) = (&mut self.transition, event) {
+            transition.elapsed += Duration::from_nanos(*interval);
+            if transition.elapsed < self.duration {
+                ctx.request_anim_frame();
+            } else {
+                self.transition = None;
+                // we no longer need to paint outside of our layout rect
+                ctx.request_layout();
+            }
+            ctx.request_paint();
+        }
+        self.child.event(ctx, event, data, env)
+    }
+
+    #[instrument(
+        name = "AnimateLayout",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        // This is sent once the window's layout pass is done, which is the
+        // first time our new origin is known.
+        if let LifeCycle::Internal(InternalLifeCycle::ParentWindowOrigin) = event {
+            let rect = ctx.widget_state.layout_rect();
+            self.layout_finished(ctx, rect);
+        }
+        self.child.lifecycle(ctx, event, data, env)
+    }
+
+    #[instrument(
+        name = "AnimateLayout",
+        level = "trace",
+        skip(self, ctx, _old, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old: &T, data: &T, env: &Env) {
+        self.child.update(ctx, data, env);
+    }
+
+    #[instrument(
+        name = "AnimateLayout",
+        level = "trace",
+        skip(self, ctx, bc, data, env)
+    )]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("AnimateLayout");
+        let size = self.child.layout(ctx, bc, data, env);
+        self.child.set_origin(ctx, data, env, Point::ORIGIN);
+
+        let child_paint_rect = self.child.paint_rect();
+        let painted = self.paint_transform().transform_rect_bbox(child_paint_rect);
+        let paint_rect = child_paint_rect.union(painted).union(size.to_rect());
+        ctx.set_paint_insets(paint_rect - size.to_rect());
+        size
+    }
+
+    #[instrument(name = "AnimateLayout", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let transform = self.paint_transform();
+        if transform == Affine::IDENTITY {
+            self.child.paint(ctx, data, env);
+        } else {
+            ctx.with_save(|ctx| {
+                ctx.transform(transform);
+                self.child.paint_always(ctx, data, env);
+            });
+        }
+    }
+
+    #[cfg(feature = "debug_state")]
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState::with_children("AnimateLayout", vec![self.child.debug_state(data)])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::ModularWidget;
+    use crate::widget::Flex;
+    use crate::{Selector, WidgetExt};
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use test_env_log::test;
+
+    const SET_HEIGHT: Selector<f64> = Selector::new("druid-test.set-height");
+    const DURATION: Duration = Duration::from_millis(100);
+
+    /// A spacer as tall as its data, followed by an animated widget that
+    /// records the window position it was painted at.
+    fn column(painted_at: Rc<Cell<Point>>) -> impl Widget<f64> {
+        let spacer = ModularWidget::new(())
+            .event_fn(|_, _, event, data: &mut f64, _| {
+                if let Event::Command(cmd) = event {
+                    if let Some(height) = cmd.get(SET_HEIGHT) {
+                        *data = *height;
+                    }
+                }
+            })
+            .update_fn(|_, ctx, old, data, _| {
+                if old != data {
+                    ctx.request_layout();
+                }
+            })
+            .layout_fn(|_, _, _, data, _| Size::new(100., *data));
+        let animated = ModularWidget::new(painted_at)
+            .layout_fn(|_, _, _, _, _| Size::new(100., 20.))
+            .paint_fn(|painted_at, ctx, _, _| {
+                painted_at.set(ctx.current_transform() * Point::ORIGIN)
+            })
+            .animate_layout(DURATION, Curve::Linear);
+        Flex::column().with_child(spacer).with_child(animated)
+    }
+
+    fn assert_painted_at(painted_at: &Cell<Point>, y: f64) {
+        let painted_y = painted_at.get().y;
+        assert!(
+            (painted_y - y).abs() < 1e-9,
+            "painted at {}, not {}",
+            painted_y,
+            y
+        );
+    }
+
+    fn frame(harness: &mut Harness<f64>, millis: u64) {
+        harness.event(Event::AnimFrame(millis * 1_000_000));
+        harness.just_layout();
+        harness.paint();
+    }
+
+    #[test]
+    fn curves() {
+        for curve in [
+            Curve::Linear,
+            Curve::EaseIn,
+            Curve::EaseOut,
+            Curve::EaseInOut,
+        ] {
+            assert_eq!(curve.eval(-1.0), 0.0);
+            assert_eq!(curve.eval(0.0), 0.0);
+            assert!((curve.eval(0.5) - 0.5).abs() <= 0.5);
+            assert_eq!(curve.eval(1.0), 1.0);
+            assert_eq!(curve.eval(2.0), 1.0);
+        }
+        assert_eq!(Curve::EaseInOut.eval(0.5), 0.5);
+    }
+
+    #[test]
+    fn move_is_animated() {
+        let painted_at: Rc<Cell<Point>> = Default::default();
+        Harness::create_simple(10., column(painted_at.clone()), |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.paint();
+            assert_painted_at(&painted_at, 10.);
+            assert!(!harness.window().wants_animation_frame());
+
+            // the layout changes immediately, but the painted position doesn't
+            harness.submit_command(SET_HEIGHT.with(60.));
+            harness.just_layout();
+            assert!(harness.window().wants_animation_frame());
+            frame(harness, 0);
+            assert_painted_at(&painted_at, 10.);
+
+            frame(harness, 25);
+            assert_painted_at(&painted_at, 22.5);
+            frame(harness, 50);
+            assert_painted_at(&painted_at, 47.5);
+
+            // moving again continues from where we were painted
+            harness.submit_command(SET_HEIGHT.with(0.));
+            harness.just_layout();
+            frame(harness, 0);
+            assert_painted_at(&painted_at, 47.5);
+            frame(harness, 50);
+            assert_painted_at(&painted_at, 23.75);
+
+            frame(harness, 50);
+            assert_painted_at(&painted_at, 0.);
+            assert!(!harness.window().wants_animation_frame());
+        });
+    }
+
+    #[test]
+    fn identical_layout_does_not_animate() {
+        let painted_at: Rc<Cell<Point>> = Default::default();
+        Harness::create_simple(10., column(painted_at.clone()), |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+
+            harness.submit_command(SET_HEIGHT.with(60.));
+            harness.just_layout();
+            frame(harness, 100);
+            assert!(!harness.window().wants_animation_frame());
+
+            // laying out again with the same result doesn't restart anything
+            harness.just_layout();
+            harness.just_layout();
+            assert!(!harness.window().wants_animation_frame());
+            harness.paint();
+            assert_painted_at(&painted_at, 60.);
+        });
+    }
+}
@@ -158,6 +158,11 @@ impl<T: Data> Widget<T> for AspectRatioBox<T> {
         self.inner.paint(ctx, data, env);
     }
 
+    #[cfg(feature = "debug_state")]
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState::with_children("AspectRatioBox", vec![self.inner.debug_state(data)])
+    }
+
     fn id(&self) -> Option<WidgetId> {
         self.inner.id()
     }
@@ -16,14 +16,9 @@
 
 use crate::widget::prelude::*;
 use crate::widget::{Click, ControllerHost, Label, LabelText};
-use crate::{theme, Affine, Data, Insets, LinearGradient, UnitPoint};
+use crate::{theme, Affine, Data, LinearGradient, UnitPoint};
 use tracing::{instrument, trace};
 
-// the minimum padding added to a button.
-// NOTE: these values are chosen to match the existing look of TextBox; these
-// should be reevaluated at some point.
-const LABEL_INSETS: Insets = Insets::uniform_xy(8., 2.);
-
 /// A button with a text label.
 pub struct Button<T> {
     label: Label<T>,
@@ -142,20 +137,29 @@ impl<T: Data> Widget<T> for Button<T> {
 
     #[instrument(name = "Button", level = "trace", skip(self, ctx, old_data, data, env))]
     fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
-        self.label.update(ctx, old_data, data, env)
+        self.label.update(ctx, old_data, data, env);
+        if ctx.env_key_changed(&theme::BUTTON_PADDING_X)
+            || ctx.env_key_changed(&theme::BUTTON_PADDING_Y)
+            || ctx.env_key_changed(&theme::BORDERED_WIDGET_HEIGHT)
+        {
+            ctx.request_layout();
+        }
     }
 
     #[instrument(name = "Button", level = "trace", skip(self, ctx, bc, data, env))]
     fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
         bc.debug_check("Button");
-        let padding = Size::new(LABEL_INSETS.x_value(), LABEL_INSETS.y_value());
+        let padding = Size::new(
+            env.get(theme::BUTTON_PADDING_X) * 2.,
+            env.get(theme::BUTTON_PADDING_Y) * 2.,
+        );
         let label_bc = bc.shrink(padding).loosen();
         self.label_size = self.label.layout(ctx, &label_bc, data, env);
         // HACK: to make sure we look okay at default sizes when beside a textbox,
         // we make sure we will have at least the same height as the default textbox.
         let min_height = env.get(theme::BORDERED_WIDGET_HEIGHT);
         let baseline = self.label.baseline_offset();
-        ctx.set_baseline_offset(baseline + LABEL_INSETS.y1);
+        ctx.set_baseline_offset(baseline + env.get(theme::BUTTON_PADDING_Y));
 
         let button_size = bc.constrain(Size::new(
             self.label_size.width + padding.width,
@@ -217,4 +221,45 @@ impl<T: Data> Widget<T> for Button<T> {
             self.label.paint(ctx, data, env);
         });
     }
+
+    #[cfg(feature = "debug_state")]
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState::with_children("Button", vec![self.label.debug_state(data)])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::move_mouse;
+    use crate::tests::snapshot::assert_snapshot;
+    use crate::{MouseButton, WidgetExt};
+    use test_env_log::test;
+
+    #[test]
+    fn paint_states() {
+        let size = Size::new(100., 40.);
+        let button = Button::<()>::new("button").padding(5.);
+        Harness::create_with_render(
+            (),
+            button,
+            size,
+            |harness| {
+                harness.send_initial_events();
+                harness.just_layout();
+                assert_snapshot!(harness, "button");
+
+                harness.event(Event::MouseMove(move_mouse((20., 20.))));
+                assert_snapshot!(harness, "button_hot");
+
+                let mut down = move_mouse((20., 20.));
+                down.button = MouseButton::Left;
+                down.buttons.insert(MouseButton::Left);
+                harness.event(Event::MouseDown(down));
+                assert_snapshot!(harness, "button_active");
+            },
+            |_| {},
+        );
+    }
 }
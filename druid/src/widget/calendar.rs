@@ -0,0 +1,956 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A calendar month view, and a date picker built on it.
+
+use std::rc::Rc;
+
+use chrono::{Datelike, Local, NaiveDate, Weekday};
+use fluent_bundle::{FluentArgs, FluentValue};
+
+use crate::commands::CLOSE_WINDOW;
+use crate::kurbo::Circle;
+use crate::text::{Formatter, Selection, TextLayout, Validation, ValidationError};
+use crate::widget::prelude::*;
+use crate::widget::{Button, Controller, Flex, TextBox, ValueTextBox};
+use crate::{
+    theme, ArcStr, Data, KbKey, Point, Rect, Selector, WidgetExt, WidgetPod, WindowConfig,
+    WindowId, WindowLevel, WindowSizePolicy,
+};
+use tracing::{instrument, trace};
+
+/// The height of the row with the month name and the arrows.
+const TITLE_HEIGHT: f64 = 32.0;
+/// The height of the row with the names of the days of the week.
+const WEEKDAY_HEIGHT: f64 = 24.0;
+/// Enough rows for any month.
+const ROWS: usize = 6;
+
+const MONTH_KEYS: [(&str, &str); 12] = [
+    ("calendar-january", "January"),
+    ("calendar-february", "February"),
+    ("calendar-march", "March"),
+    ("calendar-april", "April"),
+    ("calendar-may", "May"),
+    ("calendar-june", "June"),
+    ("calendar-july", "July"),
+    ("calendar-august", "August"),
+    ("calendar-september", "September"),
+    ("calendar-october", "October"),
+    ("calendar-november", "November"),
+    ("calendar-december", "December"),
+];
+
+/// Short weekday names, starting on Monday.
+const WEEKDAY_KEYS: [(&str, &str); 7] = [
+    ("calendar-monday-short", "Mo"),
+    ("calendar-tuesday-short", "Tu"),
+    ("calendar-wednesday-short", "We"),
+    ("calendar-thursday-short", "Th"),
+    ("calendar-friday-short", "Fr"),
+    ("calendar-saturday-short", "Sa"),
+    ("calendar-sunday-short", "Su"),
+];
+
+const SHOW_CALENDAR: Selector = Selector::new("druid-builtin.date-picker-show-calendar");
+
+type DatePredicate = Rc<dyn Fn(&NaiveDate) -> bool>;
+
+/// The dates that can be selected.
+#[derive(Clone, Default)]
+struct DateBounds {
+    min: Option<NaiveDate>,
+    max: Option<NaiveDate>,
+    disabled: Option<DatePredicate>,
+}
+
+impl DateBounds {
+    fn clamp(&self, date: NaiveDate) -> NaiveDate {
+        let date = self.min.map_or(date, |min| date.max(min));
+        self.max.map_or(date, |max| date.min(max))
+    }
+
+    fn is_selectable(&self, date: NaiveDate) -> bool {
+        self.clamp(date) == date && !matches!(&self.disabled, Some(disabled) if disabled(&date))
+    }
+}
+
+/// A month view for picking a date.
+///
+/// The calendar shows one month at a time, with buttons to show the previous
+/// and next months. Clicking a day selects it.
+///
+/// When focused, the arrow keys move the focused day, Page Up and Page Down
+/// move it by a month, Home and End move it to the start and end of the
+/// month, and Enter or Space selects it.
+///
+/// When a day is selected, it is written to the data and the calendar submits
+/// a [`Calendar::DATE_SELECTED`] notification, even if the date did not change.
+///
+/// The month and day names, and the first day of the week, come from the
+/// current locale.
+pub struct Calendar {
+    bounds: DateBounds,
+    first_weekday: Option<Weekday>,
+    /// The first day of the month that is shown.
+    month: NaiveDate,
+    /// The day that keyboard navigation starts from.
+    focused: NaiveDate,
+    today: NaiveDate,
+    title: TextLayout<ArcStr>,
+    weekdays: Vec<TextLayout<ArcStr>>,
+    days: Vec<TextLayout<ArcStr>>,
+    arrows: [TextLayout<ArcStr>; 2],
+}
+
+impl Calendar {
+    /// Notification sent when a date is selected, by clicking or with the keyboard.
+    pub const DATE_SELECTED: Selector<NaiveDate> =
+        Selector::new("druid-builtin.calendar-date-selected");
+
+    /// Create a new `Calendar`.
+    pub fn new() -> Self {
+        let today = today();
+        let days = (1..=31)
+            .map(|day| TextLayout::from_text(day.to_string()))
+            .collect();
+        Calendar {
+            bounds: DateBounds::default(),
+            first_weekday: None,
+            month: first_of_month(today),
+            focused: today,
+            today,
+            title: TextLayout::new(),
+            weekdays: (0..7).map(|_| TextLayout::new()).collect(),
+            days,
+            arrows: [TextLayout::from_text("‹"), TextLayout::from_text("›")],
+        }
+    }
+
+    /// Builder-style method to set the earliest date that can be selected.
+    pub fn with_min_date(mut self, date: NaiveDate) -> Self {
+        self.bounds.min = Some(date);
+        self
+    }
+
+    /// Builder-style method to set the latest date that can be selected.
+    pub fn with_max_date(mut self, date: NaiveDate) -> Self {
+        self.bounds.max = Some(date);
+        self
+    }
+
+    /// Builder-style method to disable dates.
+    ///
+    /// Dates for which `disabled` returns `true` are shown, but can't be
+    /// selected.
+    pub fn with_disabled_dates(mut self, disabled: impl Fn(&NaiveDate) -> bool + 'static) -> Self {
+        self.bounds.disabled = Some(Rc::new(disabled));
+        self
+    }
+
+    /// Builder-style method to set the first day of the week, instead of
+    /// using the one from the current locale.
+    pub fn with_first_weekday(mut self, weekday: Weekday) -> Self {
+        self.first_weekday = Some(weekday);
+        self
+    }
+
+    fn with_bounds(mut self, bounds: DateBounds) -> Self {
+        self.bounds = bounds;
+        self
+    }
+
+    fn first_weekday(&self, env: &Env) -> Weekday {
+        self.first_weekday.unwrap_or_else(|| {
+            localize(env, "calendar-first-weekday", "Sunday")
+                .parse()
+                .unwrap_or(Weekday::Sun)
+        })
+    }
+
+    /// The number of empty cells before the first day of the month.
+    fn leading_cells(&self, env: &Env) -> usize {
+        let first = self.first_weekday(env).num_days_from_monday();
+        let weekday = self.month.weekday().num_days_from_monday();
+        ((7 + weekday - first) % 7) as usize
+    }
+
+    fn day_rect(&self, day: u32, env: &Env) -> Rect {
+        let cell_size = env.get(theme::CALENDAR_CELL_SIZE);
+        let cell = self.leading_cells(env) + day as usize - 1;
+        let origin = Point::new(
+            (cell % 7) as f64 * cell_size,
+            TITLE_HEIGHT + WEEKDAY_HEIGHT + (cell / 7) as f64 * cell_size,
+        );
+        Rect::from_origin_size(origin, (cell_size, cell_size))
+    }
+
+    fn day_at(&self, pos: Point, env: &Env) -> Option<NaiveDate> {
+        let cell_size = env.get(theme::CALENDAR_CELL_SIZE);
+        let y = pos.y - TITLE_HEIGHT - WEEKDAY_HEIGHT;
+        if y < 0.0 || pos.x < 0.0 || pos.x >= 7.0 * cell_size {
+            return None;
+        }
+        let cell = (y / cell_size) as usize * 7 + (pos.x / cell_size) as usize;
+        let day = cell.checked_sub(self.leading_cells(env))? as u32 + 1;
+        self.month.with_day(day)
+    }
+
+    fn arrow_rects(&self, width: f64, env: &Env) -> [Rect; 2] {
+        let cell_size = env.get(theme::CALENDAR_CELL_SIZE);
+        [
+            Rect::new(0.0, 0.0, cell_size, TITLE_HEIGHT),
+            Rect::new(width - cell_size, 0.0, width, TITLE_HEIGHT),
+        ]
+    }
+
+    /// Move the focused day, keeping it within the bounds and showing its month.
+    fn focus_date(&mut self, ctx: &mut EventCtx, date: NaiveDate) {
+        self.focused = self.bounds.clamp(date);
+        let month = first_of_month(self.focused);
+        if month != self.month {
+            self.month = month;
+            ctx.request_layout();
+        }
+        ctx.request_paint();
+    }
+
+    fn select(&mut self, ctx: &mut EventCtx, date: NaiveDate, data: &mut NaiveDate) {
+        if self.bounds.is_selectable(date) {
+            self.focus_date(ctx, date);
+            *data = date;
+            ctx.submit_notification(Calendar::DATE_SELECTED.with(date));
+        }
+    }
+}
+
+impl Widget<NaiveDate> for Calendar {
+    #[instrument(name = "Calendar", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut NaiveDate, env: &Env) {
+        if ctx.is_disabled() {
+            return;
+        }
+        match event {
+            Event::MouseDown(mouse) => {
+                ctx.request_focus();
+                ctx.set_handled();
+                let [previous, next] = self.arrow_rects(ctx.size().width, env);
+                if previous.contains(mouse.pos) {
+                    self.focus_date(ctx, add_months(self.focused, -1));
+                } else if next.contains(mouse.pos) {
+                    self.focus_date(ctx, add_months(self.focused, 1));
+                } else if let Some(date) = self.day_at(mouse.pos, env) {
+                    self.select(ctx, date, data);
+                }
+            }
+            Event::KeyDown(key) if ctx.is_focused() => {
+                let focused = self.focused;
+                let target = match key.key {
+                    KbKey::ArrowLeft => add_days(focused, -1),
+                    KbKey::ArrowRight => add_days(focused, 1),
+                    KbKey::ArrowUp => add_days(focused, -7),
+                    KbKey::ArrowDown => add_days(focused, 7),
+                    KbKey::PageUp => add_months(focused, -1),
+                    KbKey::PageDown => add_months(focused, 1),
+                    KbKey::Home => first_of_month(focused),
+                    KbKey::End => add_days(add_months(first_of_month(focused), 1), -1),
+                    KbKey::Enter => {
+                        self.select(ctx, focused, data);
+                        ctx.set_handled();
+                        return;
+                    }
+                    KbKey::Character(ref c) if c == " " => {
+                        self.select(ctx, focused, data);
+                        ctx.set_handled();
+                        return;
+                    }
+                    _ => return,
+                };
+                self.focus_date(ctx, target);
+                ctx.set_handled();
+            }
+            _ => (),
+        }
+    }
+
+    #[instrument(name = "Calendar", level = "trace", skip(self, ctx, event, data, _env))]
+    fn lifecycle(
+        &mut self,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &NaiveDate,
+        _env: &Env,
+    ) {
+        match event {
+            LifeCycle::WidgetAdded => {
+                self.focused = self.bounds.clamp(*data);
+                self.month = first_of_month(self.focused);
+            }
+            LifeCycle::BuildFocusChain => ctx.register_for_focus(),
+            LifeCycle::FocusChanged(_) | LifeCycle::DisabledChanged(_) => ctx.request_paint(),
+            _ => (),
+        }
+    }
+
+    #[instrument(
+        name = "Calendar",
+        level = "trace",
+        skip(self, ctx, old_data, data, _env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &NaiveDate, data: &NaiveDate, _env: &Env) {
+        if ctx.env_key_changed(&theme::CALENDAR_CELL_SIZE) {
+            ctx.request_layout();
+        }
+        if !old_data.same(data) {
+            self.focused = self.bounds.clamp(*data);
+            self.month = first_of_month(self.focused);
+            ctx.request_layout();
+        }
+    }
+
+    #[instrument(name = "Calendar", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &NaiveDate,
+        env: &Env,
+    ) -> Size {
+        bc.debug_check("Calendar");
+        self.today = today();
+
+        let (month_key, month_fallback) = MONTH_KEYS[self.month.month0() as usize];
+        let month = localize(env, month_key, month_fallback);
+        let mut args = FluentArgs::new();
+        args.set("month", FluentValue::from(month.clone()));
+        args.set("year", FluentValue::from(self.month.year().to_string()));
+        let title = env
+            .localization_manager()
+            .localize("calendar-month-title", &args)
+            .unwrap_or_else(|| format!("{} {}", month, self.month.year()).into());
+        if self.title.text().map(|text| &**text) != Some(&*title) {
+            self.title.set_text(title);
+        }
+        self.title.set_font(theme::UI_FONT_BOLD);
+        self.title.rebuild_if_needed(ctx.text_factory(), env);
+
+        let first_weekday = self.first_weekday(env).num_days_from_monday() as usize;
+        for (i, layout) in self.weekdays.iter_mut().enumerate() {
+            let (key, fallback) = WEEKDAY_KEYS[(first_weekday + i) % 7];
+            let name = localize(env, key, fallback);
+            if layout.text().map(|text| &**text) != Some(name.as_str()) {
+                layout.set_text(name.into());
+            }
+            layout.set_text_color(theme::PLACEHOLDER_COLOR);
+            layout.rebuild_if_needed(ctx.text_factory(), env);
+        }
+
+        for (i, layout) in self.days.iter_mut().enumerate() {
+            let color = match self.month.with_day(i as u32 + 1) {
+                Some(date) if date == *data => theme::SELECTION_TEXT_COLOR,
+                Some(date) if self.bounds.is_selectable(date) => theme::TEXT_COLOR,
+                _ => theme::DISABLED_TEXT_COLOR,
+            };
+            layout.set_text_color(color);
+            layout.rebuild_if_needed(ctx.text_factory(), env);
+        }
+        for layout in self.arrows.iter_mut() {
+            layout.rebuild_if_needed(ctx.text_factory(), env);
+        }
+
+        let cell_size = env.get(theme::CALENDAR_CELL_SIZE);
+        let size = bc.constrain((
+            7.0 * cell_size,
+            TITLE_HEIGHT + WEEKDAY_HEIGHT + ROWS as f64 * cell_size,
+        ));
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    #[instrument(name = "Calendar", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &NaiveDate, env: &Env) {
+        let width = ctx.size().width;
+        let cell_size = env.get(theme::CALENDAR_CELL_SIZE);
+        let title_origin = Point::new(
+            (width - self.title.size().width) / 2.0,
+            (TITLE_HEIGHT - self.title.size().height) / 2.0,
+        );
+        self.title.draw(ctx, title_origin);
+        for (layout, rect) in self.arrows.iter().zip(self.arrow_rects(width, env).iter()) {
+            draw_centered(ctx, layout, *rect);
+        }
+
+        for (i, layout) in self.weekdays.iter().enumerate() {
+            let rect = Rect::from_origin_size(
+                (i as f64 * cell_size, TITLE_HEIGHT),
+                (cell_size, WEEKDAY_HEIGHT),
+            );
+            draw_centered(ctx, layout, rect);
+        }
+
+        let mut date = Some(self.month);
+        while let Some(day) = date.filter(|date| date.month() == self.month.month()) {
+            let rect = self.day_rect(day.day(), env);
+            let circle = Circle::new(rect.center(), cell_size / 2.0 - 2.0);
+            if day == *data {
+                ctx.fill(circle, &env.get(theme::PRIMARY_DARK));
+            }
+            if day == self.today {
+                ctx.stroke(circle, &env.get(theme::PRIMARY_LIGHT), 1.0);
+            }
+            if day == self.focused && ctx.is_focused() {
+                ctx.stroke(rect.inset(-1.0), &env.get(theme::PRIMARY_LIGHT), 2.0);
+            }
+            draw_centered(ctx, &self.days[day.day0() as usize], rect);
+            date = day.succ_opt();
+        }
+    }
+
+    #[cfg(feature = "debug_state")]
+    fn debug_state(&self, data: &NaiveDate) -> DebugState {
+        DebugState::with_value("Calendar", data.to_string())
+    }
+}
+
+impl Default for Calendar {
+    fn default() -> Self {
+        Calendar::new()
+    }
+}
+
+/// A field for entering a date, with a [`Calendar`] dropdown.
+///
+/// The date is shown in the format of the current locale, and can be edited
+/// as text; a button next to the field opens a calendar for picking a date
+/// with the mouse or keyboard.
+pub struct DatePicker {
+    bounds: DateBounds,
+    first_weekday: Option<Weekday>,
+    format: Option<String>,
+    /// Built once the locale is known, when the widget is added.
+    inner: Option<WidgetPod<NaiveDate, Box<dyn Widget<NaiveDate>>>>,
+    calendar_window: Option<WindowId>,
+}
+
+impl DatePicker {
+    /// Create a new `DatePicker`.
+    pub fn new() -> Self {
+        DatePicker {
+            bounds: DateBounds::default(),
+            first_weekday: None,
+            format: None,
+            inner: None,
+            calendar_window: None,
+        }
+    }
+
+    /// Builder-style method to set the earliest date that can be picked.
+    pub fn with_min_date(mut self, date: NaiveDate) -> Self {
+        self.bounds.min = Some(date);
+        self
+    }
+
+    /// Builder-style method to set the latest date that can be picked.
+    pub fn with_max_date(mut self, date: NaiveDate) -> Self {
+        self.bounds.max = Some(date);
+        self
+    }
+
+    /// Builder-style method to disable dates.
+    ///
+    /// Dates for which `disabled` returns `true` can't be picked.
+    pub fn with_disabled_dates(mut self, disabled: impl Fn(&NaiveDate) -> bool + 'static) -> Self {
+        self.bounds.disabled = Some(Rc::new(disabled));
+        self
+    }
+
+    /// Builder-style method to set the first day of the week in the calendar,
+    /// instead of using the one from the current locale.
+    pub fn with_first_weekday(mut self, weekday: Weekday) -> Self {
+        self.first_weekday = Some(weekday);
+        self
+    }
+
+    /// Builder-style method to set the format of the date in the text field,
+    /// instead of using the one from the current locale.
+    ///
+    /// See [`DateFormatter::new`] for the format syntax.
+    pub fn with_format(mut self, format: impl Into<String>) -> Self {
+        self.format = Some(format.into());
+        self
+    }
+
+    fn build_inner(&self, env: &Env) -> Box<dyn Widget<NaiveDate>> {
+        let format = self
+            .format
+            .clone()
+            .unwrap_or_else(|| localize(env, "date-picker-format", "%Y-%m-%d"));
+        let formatter = DateFormatter {
+            format,
+            bounds: self.bounds.clone(),
+        };
+        Flex::row()
+            .with_flex_child(ValueTextBox::new(TextBox::new(), formatter), 1.0)
+            .with_default_spacer()
+            .with_child(
+                Button::new("▾").on_click(|ctx, _, _| ctx.submit_notification(SHOW_CALENDAR)),
+            )
+            .boxed()
+    }
+
+    fn show_calendar(&mut self, ctx: &mut EventCtx, data: NaiveDate, env: &Env) {
+        if let Some(window) = self.calendar_window.take() {
+            ctx.submit_command(CLOSE_WINDOW.to(window));
+        }
+        let mut calendar = Calendar::new().with_bounds(self.bounds.clone());
+        calendar.first_weekday = self.first_weekday;
+        let calendar = calendar
+            .controller(CloseOnSelect)
+            .padding(4.0)
+            .background(theme::BACKGROUND_DARK);
+        let config = WindowConfig::default()
+            .show_titlebar(false)
+            .resizable(false)
+            .window_size_policy(WindowSizePolicy::Content)
+            .set_level(WindowLevel::DropDown)
+            .set_position(ctx.to_screen(Point::new(0.0, ctx.size().height)));
+        let window = ctx.new_sub_window(config, calendar, data, env.clone());
+        self.calendar_window = Some(window);
+    }
+}
+
+impl Widget<NaiveDate> for DatePicker {
+    #[instrument(
+        name = "DatePicker",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut NaiveDate, env: &Env) {
+        if let Event::Notification(note) = event {
+            if note.is(SHOW_CALENDAR) {
+                self.show_calendar(ctx, *data, env);
+                ctx.set_handled();
+            }
+            return;
+        }
+        if let Some(inner) = &mut self.inner {
+            inner.event(ctx, event, data, env);
+        }
+    }
+
+    #[instrument(
+        name = "DatePicker",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn lifecycle(
+        &mut self,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &NaiveDate,
+        env: &Env,
+    ) {
+        if let LifeCycle::WidgetAdded = event {
+            self.inner = Some(WidgetPod::new(self.build_inner(env)));
+        }
+        if let Some(inner) = &mut self.inner {
+            inner.lifecycle(ctx, event, data, env);
+        }
+    }
+
+    #[instrument(
+        name = "DatePicker",
+        level = "trace",
+        skip(self, ctx, old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &NaiveDate, data: &NaiveDate, env: &Env) {
+        // a date was picked in the calendar, or the date was changed elsewhere
+        if !old_data.same(data) {
+            if let Some(window) = self.calendar_window.take() {
+                ctx.submit_command(CLOSE_WINDOW.to(window));
+            }
+        }
+        if let Some(inner) = &mut self.inner {
+            inner.update(ctx, data, env);
+        }
+    }
+
+    #[instrument(name = "DatePicker", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &NaiveDate,
+        env: &Env,
+    ) -> Size {
+        bc.debug_check("DatePicker");
+        let inner = match &mut self.inner {
+            Some(inner) => inner,
+            None => return bc.min(),
+        };
+        let size = inner.layout(ctx, bc, data, env);
+        inner.set_origin(ctx, data, env, Point::ORIGIN);
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    #[instrument(name = "DatePicker", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &NaiveDate, env: &Env) {
+        if let Some(inner) = &mut self.inner {
+            inner.paint(ctx, data, env);
+        }
+    }
+
+    #[cfg(feature = "debug_state")]
+    fn debug_state(&self, data: &NaiveDate) -> DebugState {
+        DebugState {
+            display_name: "DatePicker".to_string(),
+            main_value: data.to_string(),
+            children: self
+                .inner
+                .iter()
+                .map(|inner| inner.debug_state(data))
+                .collect(),
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for DatePicker {
+    fn default() -> Self {
+        DatePicker::new()
+    }
+}
+
+/// Closes the calendar dropdown of a [`DatePicker`] when a date is picked,
+/// or when Escape is pressed.
+struct CloseOnSelect;
+
+impl Controller<NaiveDate, Calendar> for CloseOnSelect {
+    fn event(
+        &mut self,
+        child: &mut Calendar,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut NaiveDate,
+        env: &Env,
+    ) {
+        match event {
+            Event::Notification(note) if note.is(Calendar::DATE_SELECTED) => {
+                ctx.submit_command(CLOSE_WINDOW);
+                ctx.set_handled();
+            }
+            Event::KeyDown(key) if key.key == KbKey::Escape => {
+                ctx.submit_command(CLOSE_WINDOW);
+                ctx.set_handled();
+            }
+            Event::WindowConnected => {
+                ctx.request_focus();
+                child.event(ctx, event, data, env);
+            }
+            _ => child.event(ctx, event, data, env),
+        }
+    }
+}
+
+/// A [`Formatter`] for dates.
+///
+/// Dates that are outside of the bounds of the [`DatePicker`] using the
+/// formatter are rejected.
+#[derive(Clone)]
+pub struct DateFormatter {
+    format: String,
+    bounds: DateBounds,
+}
+
+impl DateFormatter {
+    /// Create a new `DateFormatter` with a [`chrono` format string].
+    ///
+    /// [`chrono` format string]: chrono::format::strftime
+    pub fn new(format: impl Into<String>) -> Self {
+        DateFormatter {
+            format: format.into(),
+            bounds: DateBounds::default(),
+        }
+    }
+}
+
+impl Formatter<NaiveDate> for DateFormatter {
+    fn format(&self, value: &NaiveDate) -> String {
+        value.format(&self.format).to_string()
+    }
+
+    fn validate_partial_input(&self, _input: &str, _sel: &Selection) -> Validation {
+        Validation::success()
+    }
+
+    fn value(&self, input: &str) -> Result<NaiveDate, ValidationError> {
+        let date =
+            NaiveDate::parse_from_str(input.trim(), &self.format).map_err(ValidationError::new)?;
+        if self.bounds.is_selectable(date) {
+            Ok(date)
+        } else {
+            Err(ValidationError::new(DateNotAllowed))
+        }
+    }
+}
+
+/// An error for a date that is outside the bounds, or disabled.
+#[derive(Debug, Clone, Copy)]
+struct DateNotAllowed;
+
+impl std::fmt::Display for DateNotAllowed {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "This date can't be picked")
+    }
+}
+
+impl std::error::Error for DateNotAllowed {}
+
+fn localize(env: &Env, key: &str, fallback: &str) -> String {
+    env.localization_manager()
+        .localize(key, None)
+        .map(|s| s.to_string())
+        .unwrap_or_else(|| fallback.to_string())
+}
+
+fn draw_centered(ctx: &mut PaintCtx, layout: &TextLayout<ArcStr>, rect: Rect) {
+    let size = layout.size();
+    let origin = rect.center() - (size.to_vec2() / 2.0);
+    layout.draw(ctx, origin);
+}
+
+fn today() -> NaiveDate {
+    Local::now().naive_local().date()
+}
+
+fn first_of_month(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).unwrap_or(date)
+}
+
+fn add_days(date: NaiveDate, days: i64) -> NaiveDate {
+    date.checked_add_signed(chrono::Duration::days(days))
+        .unwrap_or(date)
+}
+
+/// Move `date` by `months`, keeping the day of the month if it exists in the
+/// new month and using the last day of the month otherwise.
+fn add_months(date: NaiveDate, months: i32) -> NaiveDate {
+    let month0 = date.year() * 12 + date.month0() as i32 + months;
+    let (year, month) = (month0.div_euclid(12), month0.rem_euclid(12) as u32 + 1);
+    (1..=date.day())
+        .rev()
+        .find_map(|day| NaiveDate::from_ymd_opt(year, month, day))
+        .unwrap_or(date)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::widget_ids;
+    use crate::tests::move_mouse;
+    use crate::widget::{Align, EnvScope};
+    use crate::{KeyEvent, Modifiers, UnitPoint};
+    use test_env_log::test;
+
+    const CELL_SIZE: f64 = theme::metrics::COMFORTABLE.calendar_cell_size;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn click(harness: &mut Harness<NaiveDate>, pos: impl Into<Point>) {
+        let pos = pos.into();
+        harness.event(Event::MouseDown(move_mouse(pos)));
+        harness.event(Event::MouseUp(move_mouse(pos)));
+    }
+
+    fn press(harness: &mut Harness<NaiveDate>, key: KbKey) {
+        harness.event(Event::KeyDown(KeyEvent::for_test(Modifiers::empty(), key)));
+    }
+
+    /// Focuses the calendar by clicking on the month name.
+    fn focus(harness: &mut Harness<NaiveDate>) {
+        click(harness, (3.5 * CELL_SIZE, TITLE_HEIGHT / 2.0));
+    }
+
+    #[test]
+    fn month_arithmetic() {
+        assert_eq!(add_months(date(2021, 1, 31), 1), date(2021, 2, 28));
+        assert_eq!(add_months(date(2020, 1, 31), 1), date(2020, 2, 29));
+        assert_eq!(add_months(date(2021, 1, 15), -1), date(2020, 12, 15));
+        assert_eq!(add_months(date(2021, 12, 15), 13), date(2023, 1, 15));
+        assert_eq!(add_days(date(2021, 2, 28), 1), date(2021, 3, 1));
+        assert_eq!(first_of_month(date(2021, 2, 28)), date(2021, 2, 1));
+    }
+
+    #[test]
+    fn keyboard_navigation_across_months() {
+        let calendar = Calendar::new().with_first_weekday(Weekday::Mon);
+        Harness::create_simple(date(2021, 1, 31), calendar, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            focus(harness);
+
+            press(harness, KbKey::ArrowRight);
+            press(harness, KbKey::Enter);
+            assert_eq!(*harness.data(), date(2021, 2, 1));
+
+            press(harness, KbKey::ArrowUp);
+            press(harness, KbKey::Enter);
+            assert_eq!(*harness.data(), date(2021, 1, 25));
+
+            // the day is clamped to the end of the shorter month
+            press(harness, KbKey::ArrowRight);
+            press(harness, KbKey::ArrowRight);
+            press(harness, KbKey::ArrowRight);
+            press(harness, KbKey::ArrowRight);
+            press(harness, KbKey::ArrowRight);
+            press(harness, KbKey::ArrowRight);
+            press(harness, KbKey::PageDown);
+            press(harness, KbKey::Enter);
+            assert_eq!(*harness.data(), date(2021, 2, 28));
+
+            press(harness, KbKey::Home);
+            press(harness, KbKey::PageUp);
+            press(harness, KbKey::End);
+            press(harness, KbKey::Character(" ".into()));
+            assert_eq!(*harness.data(), date(2021, 1, 31));
+
+            // navigation shows the focused month: February 2021 starts on a Monday
+            press(harness, KbKey::ArrowDown);
+            harness.just_layout();
+            click(
+                harness,
+                (CELL_SIZE / 2.0, TITLE_HEIGHT + WEEKDAY_HEIGHT + 1.0),
+            );
+            assert_eq!(*harness.data(), date(2021, 2, 1));
+        });
+    }
+
+    #[test]
+    fn cells_follow_the_theme() {
+        const DENSE: f64 = theme::metrics::DENSE.calendar_cell_size;
+        let [id] = widget_ids();
+        let calendar = EnvScope::new(
+            |env, _| theme::metrics::DENSE.apply_to(env),
+            Align::new(UnitPoint::TOP_LEFT, Calendar::new().with_id(id)),
+        );
+        Harness::create_simple(date(2021, 3, 1), calendar, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            assert_eq!(
+                harness.get_state(id).layout_rect().size(),
+                Size::new(
+                    7.0 * DENSE,
+                    TITLE_HEIGHT + WEEKDAY_HEIGHT + ROWS as f64 * DENSE
+                )
+            );
+
+            // March 2021 starts on a Monday, in the second column
+            let monday = Point::new(1.5 * DENSE, TITLE_HEIGHT + WEEKDAY_HEIGHT + 1.0);
+            click(harness, monday);
+            assert_eq!(*harness.data(), date(2021, 3, 1));
+            click(harness, monday + (DENSE, 0.0));
+            assert_eq!(*harness.data(), date(2021, 3, 2));
+        });
+    }
+
+    #[test]
+    fn min_and_max_dates_are_clamped() {
+        let calendar = Calendar::new()
+            .with_min_date(date(2021, 1, 10))
+            .with_max_date(date(2021, 2, 5));
+        Harness::create_simple(date(2021, 1, 12), calendar, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            focus(harness);
+
+            press(harness, KbKey::PageUp);
+            press(harness, KbKey::Enter);
+            assert_eq!(*harness.data(), date(2021, 1, 10));
+
+            press(harness, KbKey::ArrowLeft);
+            press(harness, KbKey::Enter);
+            assert_eq!(*harness.data(), date(2021, 1, 10));
+
+            press(harness, KbKey::PageDown);
+            press(harness, KbKey::PageDown);
+            press(harness, KbKey::Enter);
+            assert_eq!(*harness.data(), date(2021, 2, 5));
+
+            press(harness, KbKey::End);
+            press(harness, KbKey::ArrowDown);
+            press(harness, KbKey::Enter);
+            assert_eq!(*harness.data(), date(2021, 2, 5));
+        });
+    }
+
+    #[test]
+    fn disabled_dates_cannot_be_selected() {
+        let calendar = Calendar::new()
+            .with_first_weekday(Weekday::Mon)
+            .with_disabled_dates(|date| date.weekday() == Weekday::Sun);
+        // a Saturday
+        Harness::create_simple(date(2021, 2, 6), calendar, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            focus(harness);
+
+            press(harness, KbKey::ArrowRight);
+            press(harness, KbKey::Enter);
+            assert_eq!(*harness.data(), date(2021, 2, 6));
+
+            // clicking Sunday the 7th does nothing either
+            let sunday = Point::new(6.5 * CELL_SIZE, TITLE_HEIGHT + WEEKDAY_HEIGHT + 1.0);
+            click(harness, sunday);
+            assert_eq!(*harness.data(), date(2021, 2, 6));
+
+            press(harness, KbKey::ArrowRight);
+            press(harness, KbKey::Enter);
+            assert_eq!(*harness.data(), date(2021, 2, 8));
+        });
+    }
+
+    #[test]
+    fn date_formatter() {
+        let formatter = DateFormatter::new("%d.%m.%Y");
+        assert_eq!(formatter.format(&date(2021, 3, 4)), "04.03.2021");
+        assert_eq!(formatter.value(" 04.03.2021 ").unwrap(), date(2021, 3, 4));
+        assert!(formatter.value("2021-03-04").is_err());
+
+        let formatter = DateFormatter {
+            bounds: DateBounds {
+                max: Some(date(2021, 3, 1)),
+                ..Default::default()
+            },
+            ..formatter
+        };
+        assert!(formatter.value("01.03.2021").is_ok());
+        assert!(formatter.value("02.03.2021").is_err());
+    }
+
+    #[test]
+    fn date_picker_smoke_test() {
+        let picker = DatePicker::new().with_format("%Y-%m-%d");
+        Harness::create_simple(date(2021, 3, 4), picker, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.paint();
+            let state = harness.root_debug_state();
+            assert_eq!(state.main_value, "2021-03-04");
+        });
+    }
+}
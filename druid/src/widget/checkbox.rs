@@ -84,6 +84,11 @@ impl Widget<bool> for Checkbox {
     )]
     fn update(&mut self, ctx: &mut UpdateCtx, old_data: &bool, data: &bool, env: &Env) {
         self.child_label.update(ctx, old_data, data, env);
+        if ctx.env_key_changed(&theme::BASIC_WIDGET_HEIGHT)
+            || ctx.env_key_changed(&theme::WIDGET_CONTROL_COMPONENT_PADDING)
+        {
+            ctx.request_layout();
+        }
         ctx.request_paint();
     }
 
@@ -159,4 +164,46 @@ impl Widget<bool> for Checkbox {
         // Paint the text label
         self.child_label.draw_at(ctx, (size + x_padding, 0.0));
     }
+
+    #[cfg(feature = "debug_state")]
+    fn debug_state(&self, data: &bool) -> DebugState {
+        DebugState {
+            main_value: data.to_string(),
+            children: vec![self.child_label.debug_state(data)],
+            ..DebugState::new("Checkbox")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::snapshot::assert_snapshot;
+    use crate::WidgetExt;
+    use test_env_log::test;
+
+    #[test]
+    fn paint() {
+        let size = Size::new(120., 30.);
+        for &checked in &[false, true] {
+            let checkbox = Checkbox::new("checkbox").padding(5.);
+            Harness::create_with_render(
+                checked,
+                checkbox,
+                size,
+                |harness| {
+                    harness.send_initial_events();
+                    harness.just_layout();
+                    let name = if checked {
+                        "checkbox_checked"
+                    } else {
+                        "checkbox"
+                    };
+                    assert_snapshot!(harness, name);
+                },
+                |_| {},
+            );
+        }
+    }
 }
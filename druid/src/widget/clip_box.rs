@@ -313,7 +313,8 @@ impl<T: Data, W: Widget<T>> Widget<T> for ClipBox<T, W> {
     #[instrument(name = "ClipBox", level = "trace", skip(self, ctx, event, data, env))]
     fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
         let viewport = ctx.size().to_rect();
-        let force_event = self.child.is_hot() || self.child.has_active();
+        let force_event =
+            self.child.is_hot() || self.child.has_active() || self.child.has_pointer_capture();
         if let Some(child_event) =
             event.transform_scroll(self.viewport_origin().to_vec2(), viewport, force_event)
         {
@@ -377,6 +378,11 @@ impl<T: Data, W: Widget<T>> Widget<T> for ClipBox<T, W> {
             ctx.with_child_ctx(visible, |ctx| self.child.paint_raw(ctx, data, env));
         });
     }
+
+    #[cfg(feature = "debug_state")]
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState::with_children("ClipBox", vec![self.child.debug_state(data)])
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,1007 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Widgets for picking a color.
+
+use crate::kurbo::{Circle, Line};
+use crate::text::{Formatter, ParseFormatter, Selection, Validation, ValidationError};
+use crate::widget::prelude::*;
+use crate::widget::{CrossAxisAlignment, Flex, Label, TextBox};
+use crate::{theme, Color, KbKey, KeyEvent, Lens, LinearGradient, Point, Rect, UnitPoint};
+use crate::{Data, WidgetExt};
+use tracing::{instrument, trace};
+
+/// The size of the squares in the checkerboard drawn behind transparent colors.
+const CHECKER_SIZE: f64 = 4.0;
+const INDICATOR_RADIUS: f64 = 5.0;
+const INDICATOR_STROKE_WIDTH: f64 = 1.5;
+/// The fraction of the range moved by an arrow key; holding shift moves ten times as far.
+const NUDGE_STEP: f64 = 0.01;
+
+/// A color in the HSV color space, with an alpha channel.
+///
+/// This is the data type of the color picker widgets. Storing the color as
+/// HSVA instead of RGBA means that the hue survives when the color passes
+/// through grey or black, and that dragging in the picker doesn't lose
+/// precision by rounding to 8-bit channels.
+///
+/// `hue` is in degrees in the range `0.0..360.0`; `saturation`, `value` and
+/// `alpha` are in the range `0.0..=1.0`.
+#[derive(Debug, Clone, Copy, PartialEq, Data)]
+pub struct HsvaColor {
+    /// The hue, in degrees.
+    pub hue: f64,
+    /// The saturation, from grey (`0.0`) to fully saturated (`1.0`).
+    pub saturation: f64,
+    /// The value, from black (`0.0`) to full brightness (`1.0`).
+    pub value: f64,
+    /// The opacity, from transparent (`0.0`) to opaque (`1.0`).
+    pub alpha: f64,
+}
+
+impl HsvaColor {
+    /// Create a new color from its components, clamping them to their ranges.
+    pub fn new(hue: f64, saturation: f64, value: f64, alpha: f64) -> HsvaColor {
+        HsvaColor {
+            hue: hue.rem_euclid(360.0),
+            saturation: saturation.clamp(0.0, 1.0),
+            value: value.clamp(0.0, 1.0),
+            alpha: alpha.clamp(0.0, 1.0),
+        }
+    }
+
+    /// Create a color from red, green, blue and alpha components in the range `0.0..=1.0`.
+    pub fn from_rgba(r: f64, g: f64, b: f64, a: f64) -> HsvaColor {
+        HsvaColor::new(0.0, 0.0, 0.0, a).with_rgb(r, g, b)
+    }
+
+    /// Return this color with its red, green and blue components replaced.
+    ///
+    /// Unlike [`from_rgba`], this keeps the current hue when the new color is
+    /// grey, and the current saturation when it is black, since those can't be
+    /// recovered from the RGB components.
+    ///
+    /// [`from_rgba`]: HsvaColor::from_rgba
+    pub fn with_rgb(self, r: f64, g: f64, b: f64) -> HsvaColor {
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let delta = max - min;
+        let hue = if delta <= 0.0 {
+            self.hue
+        } else if max == r {
+            60.0 * ((g - b) / delta)
+        } else if max == g {
+            60.0 * ((b - r) / delta + 2.0)
+        } else {
+            60.0 * ((r - g) / delta + 4.0)
+        };
+        let saturation = if max <= 0.0 {
+            self.saturation
+        } else {
+            delta / max
+        };
+        HsvaColor::new(hue, saturation, max, self.alpha)
+    }
+
+    /// The red, green, blue and alpha components, in the range `0.0..=1.0`.
+    pub fn as_rgba(&self) -> (f64, f64, f64, f64) {
+        let chroma = self.value * self.saturation;
+        let h = self.hue.rem_euclid(360.0) / 60.0;
+        let x = chroma * (1.0 - (h % 2.0 - 1.0).abs());
+        let (r, g, b) = match h as u32 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+        let m = self.value - chroma;
+        (r + m, g + m, b + m, self.alpha)
+    }
+
+    /// The red, green, blue and alpha components as bytes.
+    pub fn as_rgba8(&self) -> (u8, u8, u8, u8) {
+        let (r, g, b, a) = self.as_rgba();
+        let to_u8 = |c: f64| (c * 255.0).round() as u8;
+        (to_u8(r), to_u8(g), to_u8(b), to_u8(a))
+    }
+
+    /// Convert this color to a [`Color`].
+    pub fn to_color(&self) -> Color {
+        let (r, g, b, a) = self.as_rgba();
+        Color::rgba(r, g, b, a)
+    }
+
+    /// The fully saturated, fully bright color with this hue.
+    fn pure_hue(&self) -> Color {
+        HsvaColor::new(self.hue, 1.0, 1.0, 1.0).to_color()
+    }
+}
+
+impl Default for HsvaColor {
+    fn default() -> Self {
+        HsvaColor::new(0.0, 0.0, 0.0, 1.0)
+    }
+}
+
+impl From<Color> for HsvaColor {
+    fn from(color: Color) -> HsvaColor {
+        let (r, g, b, a) = color.as_rgba();
+        HsvaColor::from_rgba(r, g, b, a)
+    }
+}
+
+impl From<HsvaColor> for Color {
+    fn from(color: HsvaColor) -> Color {
+        color.to_color()
+    }
+}
+
+/// A [`Lens`] from an [`HsvaColor`] to one of its RGBA components as a byte.
+///
+/// This is useful for numeric entry fields. Setting a component keeps the hue
+/// and saturation where they can't be derived from the new color, so editing
+/// one field never disturbs the others.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RgbaChannel {
+    /// The red component.
+    Red,
+    /// The green component.
+    Green,
+    /// The blue component.
+    Blue,
+    /// The alpha component.
+    Alpha,
+}
+
+impl Lens<HsvaColor, u8> for RgbaChannel {
+    fn with<V, F: FnOnce(&u8) -> V>(&self, data: &HsvaColor, f: F) -> V {
+        let (r, g, b, a) = data.as_rgba8();
+        let channel = match self {
+            RgbaChannel::Red => r,
+            RgbaChannel::Green => g,
+            RgbaChannel::Blue => b,
+            RgbaChannel::Alpha => a,
+        };
+        f(&channel)
+    }
+
+    fn with_mut<V, F: FnOnce(&mut u8) -> V>(&self, data: &mut HsvaColor, f: F) -> V {
+        let (r, g, b, a) = data.as_rgba8();
+        let mut rgba = [r, g, b, a];
+        let index = *self as usize;
+        let old = rgba[index];
+        let result = f(&mut rgba[index]);
+        if rgba[index] != old {
+            let [r, g, b, a] = rgba;
+            let to_f64 = |c: u8| c as f64 / 255.0;
+            if *self == RgbaChannel::Alpha {
+                data.alpha = to_f64(a);
+            } else {
+                *data = data.with_rgb(to_f64(r), to_f64(g), to_f64(b));
+            }
+        }
+        result
+    }
+}
+
+/// A [`Lens`] from an [`HsvaColor`] to one of its components.
+///
+/// Hue is in degrees; saturation, value and alpha are percentages, which are
+/// friendlier to type than fractions. Values are clamped when set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HsvaChannel {
+    /// The hue, in degrees.
+    Hue,
+    /// The saturation, in percent.
+    Saturation,
+    /// The value, in percent.
+    Value,
+    /// The alpha, in percent.
+    Alpha,
+}
+
+impl Lens<HsvaColor, f64> for HsvaChannel {
+    fn with<V, F: FnOnce(&f64) -> V>(&self, data: &HsvaColor, f: F) -> V {
+        let channel = match self {
+            HsvaChannel::Hue => data.hue,
+            HsvaChannel::Saturation => data.saturation * 100.0,
+            HsvaChannel::Value => data.value * 100.0,
+            HsvaChannel::Alpha => data.alpha * 100.0,
+        };
+        f(&channel)
+    }
+
+    fn with_mut<V, F: FnOnce(&mut f64) -> V>(&self, data: &mut HsvaColor, f: F) -> V {
+        let mut channel = self.with(data, |c| *c);
+        let result = f(&mut channel);
+        let HsvaColor {
+            hue,
+            saturation,
+            value,
+            alpha,
+        } = *data;
+        let new = match self {
+            HsvaChannel::Hue => HsvaColor::new(channel, saturation, value, alpha),
+            HsvaChannel::Saturation => HsvaColor::new(hue, channel / 100.0, value, alpha),
+            HsvaChannel::Value => HsvaColor::new(hue, saturation, channel / 100.0, alpha),
+            HsvaChannel::Alpha => HsvaColor::new(hue, saturation, value, channel / 100.0),
+        };
+        if !new.same(data) {
+            *data = new;
+        }
+        result
+    }
+}
+
+/// A [`Formatter`] for colors as hex strings.
+///
+/// Colors are formatted as `#RRGGBB`, or `#RRGGBBAA` if they are not opaque.
+/// Accepted input is `#RGB`, `#RGBA`, `#RRGGBB` or `#RRGGBBAA`, where the `#`
+/// is optional and surrounding whitespace is ignored.
+///
+/// Because a hex string has no hue for grey colors, a grey entered this way
+/// has a hue of zero.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HexColorFormatter;
+
+impl Formatter<HsvaColor> for HexColorFormatter {
+    fn format(&self, value: &HsvaColor) -> String {
+        let (r, g, b, a) = value.as_rgba8();
+        if a == 255 {
+            format!("#{:02X}{:02X}{:02X}", r, g, b)
+        } else {
+            format!("#{:02X}{:02X}{:02X}{:02X}", r, g, b, a)
+        }
+    }
+
+    fn validate_partial_input(&self, input: &str, _sel: &Selection) -> Validation {
+        let input = input.trim();
+        let digits = input.strip_prefix('#').unwrap_or(input);
+        if digits.len() > 8 {
+            Validation::failure(ValidationError::new(HexError::TooLong))
+        } else if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            Validation::failure(ValidationError::new(HexError::NotHex))
+        } else {
+            Validation::success()
+        }
+    }
+
+    fn value(&self, input: &str) -> Result<HsvaColor, ValidationError> {
+        Color::from_hex_str(input.trim())
+            .map(HsvaColor::from)
+            .map_err(ValidationError::new)
+    }
+}
+
+/// Errors for partial hex input that can never become a valid color.
+#[derive(Debug, Clone, Copy)]
+enum HexError {
+    TooLong,
+    NotHex,
+}
+
+impl std::fmt::Display for HexError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            HexError::TooLong => write!(f, "A hex color has at most eight digits"),
+            HexError::NotHex => write!(f, "A hex color may only contain hex digits"),
+        }
+    }
+}
+
+impl std::error::Error for HexError {}
+
+/// A complete color picker.
+///
+/// This combines a [`SaturationValueSquare`], a [`HueSlider`] and an
+/// [`AlphaSlider`] with numeric RGBA and HSVA entry fields and a hex entry
+/// field. The parts are also available as separate widgets, for building a
+/// custom picker.
+#[derive(Debug, Clone)]
+pub struct ColorPicker;
+
+impl ColorPicker {
+    /// Create a new color picker.
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new() -> impl Widget<HsvaColor> {
+        let rgba = [
+            ("R", RgbaChannel::Red),
+            ("G", RgbaChannel::Green),
+            ("B", RgbaChannel::Blue),
+            ("A", RgbaChannel::Alpha),
+        ];
+        let mut rgba_row = Flex::row();
+        for (label, channel) in rgba.iter() {
+            let field = TextBox::new()
+                .with_formatter(ParseFormatter::<u8>::new())
+                .fix_width(40.0)
+                .lens(*channel);
+            rgba_row.add_child(Label::new(*label));
+            rgba_row.add_child(field);
+        }
+
+        let hsva = [
+            ("H", HsvaChannel::Hue),
+            ("S", HsvaChannel::Saturation),
+            ("V", HsvaChannel::Value),
+            ("A", HsvaChannel::Alpha),
+        ];
+        let mut hsva_row = Flex::row();
+        for (label, channel) in hsva.iter() {
+            let formatter = ParseFormatter::<f64>::with_format_fn(|v| format!("{:.0}", v));
+            let field = TextBox::new()
+                .with_formatter(formatter)
+                .fix_width(40.0)
+                .lens(*channel);
+            hsva_row.add_child(Label::new(*label));
+            hsva_row.add_child(field);
+        }
+
+        let hex_row = Flex::row()
+            .with_child(Label::new("Hex"))
+            .with_flex_child(TextBox::new().with_formatter(HexColorFormatter), 1.0);
+
+        Flex::column()
+            .cross_axis_alignment(CrossAxisAlignment::Fill)
+            .with_child(SaturationValueSquare::new())
+            .with_default_spacer()
+            .with_child(HueSlider::new())
+            .with_default_spacer()
+            .with_child(AlphaSlider::new())
+            .with_default_spacer()
+            .with_child(rgba_row)
+            .with_default_spacer()
+            .with_child(hsva_row)
+            .with_default_spacer()
+            .with_child(hex_row)
+    }
+}
+
+/// A square for picking the saturation (horizontal) and value (vertical) of a color.
+///
+/// The square is painted in the current hue. The saturation and value can be
+/// changed by clicking or dragging, or with the arrow keys when focused.
+#[derive(Debug, Clone, Default)]
+pub struct SaturationValueSquare;
+
+impl SaturationValueSquare {
+    /// Create a new `SaturationValueSquare`.
+    pub fn new() -> SaturationValueSquare {
+        SaturationValueSquare
+    }
+
+    fn set_from_pos(&self, size: Size, pos: Point, data: &mut HsvaColor) {
+        let saturation = pos.x / size.width;
+        let value = 1.0 - pos.y / size.height;
+        *data = HsvaColor::new(data.hue, saturation, value, data.alpha);
+    }
+}
+
+impl Widget<HsvaColor> for SaturationValueSquare {
+    #[instrument(
+        name = "SaturationValueSquare",
+        level = "trace",
+        skip(self, ctx, event, data, _env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut HsvaColor, _env: &Env) {
+        if let Some(pos) = drag_position(ctx, event) {
+            self.set_from_pos(ctx.size(), pos, data);
+        }
+        if let Event::KeyDown(key) = event {
+            if ctx.is_focused() && !ctx.is_disabled() {
+                let step = nudge_step(key);
+                let (ds, dv) = match key.key {
+                    KbKey::ArrowLeft => (-step, 0.0),
+                    KbKey::ArrowRight => (step, 0.0),
+                    KbKey::ArrowDown => (0.0, -step),
+                    KbKey::ArrowUp => (0.0, step),
+                    _ => return,
+                };
+                *data = HsvaColor::new(data.hue, data.saturation + ds, data.value + dv, data.alpha);
+                ctx.set_handled();
+            }
+        }
+    }
+
+    #[instrument(
+        name = "SaturationValueSquare",
+        level = "trace",
+        skip(self, ctx, event, _data, _env)
+    )]
+    fn lifecycle(
+        &mut self,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        _data: &HsvaColor,
+        _env: &Env,
+    ) {
+        picker_lifecycle(ctx, event);
+    }
+
+    #[instrument(
+        name = "SaturationValueSquare",
+        level = "trace",
+        skip(self, ctx, old_data, data, _env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &HsvaColor, data: &HsvaColor, _env: &Env) {
+        if !old_data.same(data) {
+            ctx.request_paint();
+        }
+    }
+
+    #[instrument(
+        name = "SaturationValueSquare",
+        level = "trace",
+        skip(self, _ctx, bc, _data, env)
+    )]
+    fn layout(
+        &mut self,
+        _ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        _data: &HsvaColor,
+        env: &Env,
+    ) -> Size {
+        bc.debug_check("SaturationValueSquare");
+        let side = env.get(theme::WIDE_WIDGET_WIDTH);
+        let size = bc.constrain((side, side));
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    #[instrument(
+        name = "SaturationValueSquare",
+        level = "trace",
+        skip(self, ctx, data, env)
+    )]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &HsvaColor, env: &Env) {
+        let rect = ctx.size().to_rect();
+        ctx.fill(rect, &data.pure_hue());
+        let white = LinearGradient::new(
+            UnitPoint::LEFT,
+            UnitPoint::RIGHT,
+            (Color::WHITE, Color::WHITE.with_alpha(0.0)),
+        );
+        ctx.fill(rect, &white);
+        let black = LinearGradient::new(
+            UnitPoint::TOP,
+            UnitPoint::BOTTOM,
+            (Color::BLACK.with_alpha(0.0), Color::BLACK),
+        );
+        ctx.fill(rect, &black);
+
+        let center = Point::new(
+            data.saturation * rect.width(),
+            (1.0 - data.value) * rect.height(),
+        );
+        paint_indicator(ctx, Circle::new(center, INDICATOR_RADIUS));
+        paint_focus(ctx, rect, env);
+    }
+}
+
+/// A horizontal slider for picking the hue of a color.
+///
+/// The hue can be changed by clicking or dragging, or with the arrow keys
+/// when focused.
+#[derive(Debug, Clone, Default)]
+pub struct HueSlider;
+
+impl HueSlider {
+    /// Create a new `HueSlider`.
+    pub fn new() -> HueSlider {
+        HueSlider
+    }
+}
+
+impl Widget<HsvaColor> for HueSlider {
+    #[instrument(
+        name = "HueSlider",
+        level = "trace",
+        skip(self, ctx, event, data, _env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut HsvaColor, _env: &Env) {
+        // the end of the bar is 360°, but hue wraps around to 0°; stop just short
+        let max_hue = 359.99;
+        if let Some(pos) = drag_position(ctx, event) {
+            data.hue = (pos.x / ctx.size().width * 360.0).clamp(0.0, max_hue);
+        }
+        if let Event::KeyDown(key) = event {
+            if ctx.is_focused() && !ctx.is_disabled() {
+                if let Some(delta) = horizontal_nudge(key) {
+                    data.hue = (data.hue + delta * 360.0).clamp(0.0, max_hue);
+                    ctx.set_handled();
+                }
+            }
+        }
+    }
+
+    #[instrument(
+        name = "HueSlider",
+        level = "trace",
+        skip(self, ctx, event, _data, _env)
+    )]
+    fn lifecycle(
+        &mut self,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        _data: &HsvaColor,
+        _env: &Env,
+    ) {
+        picker_lifecycle(ctx, event);
+    }
+
+    #[instrument(
+        name = "HueSlider",
+        level = "trace",
+        skip(self, ctx, old_data, data, _env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &HsvaColor, data: &HsvaColor, _env: &Env) {
+        if old_data.hue != data.hue {
+            ctx.request_paint();
+        }
+    }
+
+    #[instrument(name = "HueSlider", level = "trace", skip(self, _ctx, bc, _data, env))]
+    fn layout(
+        &mut self,
+        _ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        _data: &HsvaColor,
+        env: &Env,
+    ) -> Size {
+        bc.debug_check("HueSlider");
+        slider_layout(bc, env)
+    }
+
+    #[instrument(name = "HueSlider", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &HsvaColor, env: &Env) {
+        let rect = ctx.size().to_rect();
+        let stops: Vec<Color> = (0..=6)
+            .map(|i| HsvaColor::new(i as f64 * 60.0, 1.0, 1.0, 1.0).to_color())
+            .collect();
+        let gradient = LinearGradient::new(UnitPoint::LEFT, UnitPoint::RIGHT, stops.as_slice());
+        ctx.fill(rect, &gradient);
+        paint_slider_indicator(ctx, data.hue / 360.0);
+        paint_focus(ctx, rect, env);
+    }
+}
+
+/// A horizontal slider for picking the opacity of a color.
+///
+/// The current color is painted over a checkerboard, from transparent to
+/// opaque. The alpha can be changed by clicking or dragging, or with the
+/// arrow keys when focused.
+#[derive(Debug, Clone, Default)]
+pub struct AlphaSlider;
+
+impl AlphaSlider {
+    /// Create a new `AlphaSlider`.
+    pub fn new() -> AlphaSlider {
+        AlphaSlider
+    }
+}
+
+impl Widget<HsvaColor> for AlphaSlider {
+    #[instrument(
+        name = "AlphaSlider",
+        level = "trace",
+        skip(self, ctx, event, data, _env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut HsvaColor, _env: &Env) {
+        if let Some(pos) = drag_position(ctx, event) {
+            data.alpha = (pos.x / ctx.size().width).clamp(0.0, 1.0);
+        }
+        if let Event::KeyDown(key) = event {
+            if ctx.is_focused() && !ctx.is_disabled() {
+                if let Some(delta) = horizontal_nudge(key) {
+                    data.alpha = (data.alpha + delta).clamp(0.0, 1.0);
+                    ctx.set_handled();
+                }
+            }
+        }
+    }
+
+    #[instrument(
+        name = "AlphaSlider",
+        level = "trace",
+        skip(self, ctx, event, _data, _env)
+    )]
+    fn lifecycle(
+        &mut self,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        _data: &HsvaColor,
+        _env: &Env,
+    ) {
+        picker_lifecycle(ctx, event);
+    }
+
+    #[instrument(
+        name = "AlphaSlider",
+        level = "trace",
+        skip(self, ctx, old_data, data, _env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &HsvaColor, data: &HsvaColor, _env: &Env) {
+        if !old_data.same(data) {
+            ctx.request_paint();
+        }
+    }
+
+    #[instrument(
+        name = "AlphaSlider",
+        level = "trace",
+        skip(self, _ctx, bc, _data, env)
+    )]
+    fn layout(
+        &mut self,
+        _ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        _data: &HsvaColor,
+        env: &Env,
+    ) -> Size {
+        bc.debug_check("AlphaSlider");
+        slider_layout(bc, env)
+    }
+
+    #[instrument(name = "AlphaSlider", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &HsvaColor, env: &Env) {
+        let rect = ctx.size().to_rect();
+        paint_checkerboard(ctx, rect);
+        let color = HsvaColor {
+            alpha: 1.0,
+            ..*data
+        }
+        .to_color();
+        let gradient = LinearGradient::new(
+            UnitPoint::LEFT,
+            UnitPoint::RIGHT,
+            (color.clone().with_alpha(0.0), color),
+        );
+        ctx.fill(rect, &gradient);
+        paint_slider_indicator(ctx, data.alpha);
+        paint_focus(ctx, rect, env);
+    }
+}
+
+/// Handles clicking and dragging, returning the mouse position if the value
+/// should follow it.
+fn drag_position(ctx: &mut EventCtx, event: &Event) -> Option<Point> {
+    if ctx.is_disabled() {
+        ctx.set_active(false);
+        return None;
+    }
+    let size = ctx.size();
+    let clamp =
+        |pos: Point| Point::new(pos.x.clamp(0.0, size.width), pos.y.clamp(0.0, size.height));
+    match event {
+        Event::MouseDown(mouse) => {
+            ctx.set_active(true);
+            ctx.request_focus();
+            Some(clamp(mouse.pos))
+        }
+        Event::MouseMove(mouse) if ctx.is_active() => Some(clamp(mouse.pos)),
+        Event::MouseUp(mouse) if ctx.is_active() => {
+            ctx.set_active(false);
+            Some(clamp(mouse.pos))
+        }
+        _ => None,
+    }
+}
+
+/// The size of an arrow key step, as a fraction of the range.
+fn nudge_step(key: &KeyEvent) -> f64 {
+    if key.mods.shift() {
+        NUDGE_STEP * 10.0
+    } else {
+        NUDGE_STEP
+    }
+}
+
+/// The change for a left or right arrow key, as a fraction of the range.
+fn horizontal_nudge(key: &KeyEvent) -> Option<f64> {
+    match key.key {
+        KbKey::ArrowLeft | KbKey::ArrowDown => Some(-nudge_step(key)),
+        KbKey::ArrowRight | KbKey::ArrowUp => Some(nudge_step(key)),
+        _ => None,
+    }
+}
+
+fn picker_lifecycle(ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+    match event {
+        LifeCycle::BuildFocusChain => ctx.register_for_focus(),
+        LifeCycle::FocusChanged(_) | LifeCycle::DisabledChanged(_) => ctx.request_paint(),
+        _ => (),
+    }
+}
+
+fn slider_layout(bc: &BoxConstraints, env: &Env) -> Size {
+    let height = env.get(theme::BASIC_WIDGET_HEIGHT);
+    let width = env.get(theme::WIDE_WIDGET_WIDTH);
+    let size = bc.constrain((width, height));
+    trace!("Computed size: {}", size);
+    size
+}
+
+/// Paints an indicator that shows up on both light and dark colors.
+fn paint_indicator(ctx: &mut PaintCtx, shape: impl crate::kurbo::Shape) {
+    ctx.stroke(&shape, &Color::BLACK, INDICATOR_STROKE_WIDTH * 2.0);
+    ctx.stroke(&shape, &Color::WHITE, INDICATOR_STROKE_WIDTH);
+}
+
+/// Paints the indicator of a horizontal slider at `fraction` of its width.
+fn paint_slider_indicator(ctx: &mut PaintCtx, fraction: f64) {
+    let size = ctx.size();
+    let x = fraction * size.width;
+    paint_indicator(ctx, Line::new((x, 0.0), (x, size.height)));
+}
+
+fn paint_focus(ctx: &mut PaintCtx, rect: Rect, env: &Env) {
+    if ctx.is_focused() {
+        ctx.stroke(rect.inset(-1.0), &env.get(theme::PRIMARY_LIGHT), 2.0);
+    }
+}
+
+fn paint_checkerboard(ctx: &mut PaintCtx, rect: Rect) {
+    ctx.fill(rect, &Color::grey(0.8));
+    let columns = (rect.width() / CHECKER_SIZE).ceil() as usize;
+    let rows = (rect.height() / CHECKER_SIZE).ceil() as usize;
+    ctx.with_save(|ctx| {
+        ctx.clip(rect);
+        for row in 0..rows {
+            for column in (row % 2..columns).step_by(2) {
+                let origin = Point::new(column as f64 * CHECKER_SIZE, row as f64 * CHECKER_SIZE);
+                let square = Rect::from_origin_size(origin, (CHECKER_SIZE, CHECKER_SIZE));
+                ctx.fill(square, &Color::grey(0.6));
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::move_mouse;
+    use crate::widget::Align;
+    use crate::Modifiers;
+    use float_cmp::approx_eq;
+    use test_env_log::test;
+
+    fn hex(input: &str) -> Option<(u8, u8, u8, u8)> {
+        HexColorFormatter
+            .value(input)
+            .ok()
+            .map(|color| color.as_rgba8())
+    }
+
+    fn accepts_partial(input: &str) -> bool {
+        !HexColorFormatter
+            .validate_partial_input(input, &Selection::caret(0))
+            .is_err()
+    }
+
+    #[test]
+    fn hex_parsing() {
+        assert_eq!(hex("#fff"), Some((255, 255, 255, 255)));
+        assert_eq!(hex("abc"), Some((0xaa, 0xbb, 0xcc, 255)));
+        assert_eq!(hex("#12345678"), Some((0x12, 0x34, 0x56, 0x78)));
+        assert_eq!(hex("#A0B1C2"), Some((0xa0, 0xb1, 0xc2, 255)));
+        assert_eq!(hex("  #000000 "), Some((0, 0, 0, 255)));
+        assert_eq!(hex(""), None);
+        assert_eq!(hex("#"), None);
+        assert_eq!(hex("#12345"), None);
+        assert_eq!(hex("#ggg"), None);
+        assert_eq!(hex("##fff"), None);
+        assert_eq!(hex("#123456789"), None);
+    }
+
+    #[test]
+    fn hex_formatting_round_trips() {
+        for color in &[
+            HsvaColor::new(200.0, 0.5, 0.25, 1.0),
+            HsvaColor::new(0.0, 0.0, 1.0, 0.5),
+            HsvaColor::default(),
+        ] {
+            let text = HexColorFormatter.format(color);
+            assert_eq!(hex(&text), Some(color.as_rgba8()), "{}", text);
+        }
+        assert_eq!(
+            HexColorFormatter.format(&Color::rgb8(1, 2, 3).into()),
+            "#010203"
+        );
+        assert_eq!(
+            HexColorFormatter.format(&Color::rgba8(1, 2, 3, 4).into()),
+            "#01020304"
+        );
+    }
+
+    #[test]
+    fn hex_partial_input() {
+        assert!(accepts_partial(""));
+        assert!(accepts_partial("#"));
+        assert!(accepts_partial("#12"));
+        assert!(accepts_partial("12345"));
+        assert!(accepts_partial("#12345678"));
+        assert!(!accepts_partial("#123456789"));
+        assert!(!accepts_partial("#12g"));
+        assert!(!accepts_partial("##"));
+    }
+
+    #[test]
+    fn rgb_conversion() {
+        let cases = [
+            ((255, 0, 0), (0.0, 1.0, 1.0)),
+            ((0, 255, 0), (120.0, 1.0, 1.0)),
+            ((0, 0, 255), (240.0, 1.0, 1.0)),
+            ((255, 0, 255), (300.0, 1.0, 1.0)),
+            ((128, 128, 128), (0.0, 0.0, 128.0 / 255.0)),
+        ];
+        for &((r, g, b), (h, s, v)) in &cases {
+            let color = HsvaColor::from(Color::rgb8(r, g, b));
+            assert!(approx_eq!(f64, color.hue, h, epsilon = 1e-9), "{:?}", color);
+            assert!(approx_eq!(f64, color.saturation, s, epsilon = 1e-9));
+            assert!(approx_eq!(f64, color.value, v, epsilon = 1e-9));
+            assert_eq!(color.as_rgba8(), (r, g, b, 255));
+        }
+    }
+
+    #[test]
+    fn channel_lenses_keep_hue() {
+        // a grey doesn't have a hue of its own, so changing it keeps the old one
+        let grey = HsvaColor::new(210.0, 0.0, 0.5, 1.0);
+        assert_eq!(grey.with_rgb(0.8, 0.8, 0.8).hue, 210.0);
+        let black = HsvaColor::new(210.0, 0.6, 1.0, 1.0).with_rgb(0.0, 0.0, 0.0);
+        assert_eq!((black.hue, black.saturation), (210.0, 0.6));
+
+        let mut color = grey;
+        RgbaChannel::Alpha.with_mut(&mut color, |a| *a = 51);
+        assert!(approx_eq!(f64, color.alpha, 0.2));
+        assert_eq!(color.hue, 210.0);
+
+        // setting a channel to its current value leaves the color untouched
+        let before = color;
+        RgbaChannel::Red.with_mut(&mut color, |_| ());
+        assert!(before.same(&color));
+
+        // changing one channel leaves the others alone
+        let mut color = HsvaColor::from(Color::rgba8(10, 120, 230, 40));
+        RgbaChannel::Green.with_mut(&mut color, |g| *g = 0);
+        assert_eq!(color.as_rgba8(), (10, 0, 230, 40));
+        RgbaChannel::Red.with_mut(&mut color, |r| *r = 255);
+        assert_eq!(color.as_rgba8(), (255, 0, 230, 40));
+
+        HsvaChannel::Saturation.with_mut(&mut color, |s| *s = 150.0);
+        assert_eq!(color.saturation, 1.0);
+        HsvaChannel::Hue.with_mut(&mut color, |h| *h = 370.0);
+        assert!(approx_eq!(f64, color.hue, 10.0));
+    }
+
+    #[test]
+    fn sliders_change_only_their_component() {
+        let data = HsvaColor::new(90.0, 0.5, 0.5, 0.5);
+        let column = Flex::column()
+            .cross_axis_alignment(CrossAxisAlignment::Start)
+            .with_child(HueSlider::new().fix_size(360.0, 20.0))
+            .with_child(AlphaSlider::new().fix_size(100.0, 20.0))
+            .with_child(SaturationValueSquare::new().fix_size(100.0, 100.0));
+        let widget = Align::new(UnitPoint::TOP_LEFT, column);
+
+        Harness::create_simple(data, widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+
+            harness.event(Event::MouseDown(move_mouse((180.0, 10.0))));
+            harness.event(Event::MouseMove(move_mouse((270.0, 10.0))));
+            harness.event(Event::MouseUp(move_mouse((270.0, 10.0))));
+            assert_eq!(*harness.data(), HsvaColor::new(270.0, 0.5, 0.5, 0.5));
+
+            // dragging past the end clamps
+            harness.event(Event::MouseDown(move_mouse((50.0, 30.0))));
+            harness.event(Event::MouseUp(move_mouse((500.0, 30.0))));
+            assert_eq!(*harness.data(), HsvaColor::new(270.0, 0.5, 0.5, 1.0));
+
+            harness.event(Event::MouseDown(move_mouse((25.0, 65.0))));
+            harness.event(Event::MouseUp(move_mouse((25.0, 65.0))));
+            assert_eq!(*harness.data(), HsvaColor::new(270.0, 0.25, 0.75, 1.0));
+
+            // the square took focus when clicked, so arrow keys nudge it
+            harness.event(Event::KeyDown(KeyEvent::for_test(
+                Modifiers::empty(),
+                KbKey::ArrowUp,
+            )));
+            harness.event(Event::KeyDown(KeyEvent::for_test(
+                Modifiers::SHIFT,
+                KbKey::ArrowLeft,
+            )));
+            let data = harness.data();
+            assert!(approx_eq!(f64, data.saturation, 0.15));
+            assert!(approx_eq!(f64, data.value, 0.76));
+            assert_eq!(data.hue, 270.0);
+        });
+    }
+
+    #[test]
+    fn color_picker_smoke_test() {
+        let data = HsvaColor::from(Color::rgb8(255, 0, 0));
+        Harness::create_simple(data, ColorPicker::new(), |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.paint();
+
+            // clicking the square focuses it, so arrow keys nudge it
+            harness.event(Event::MouseDown(move_mouse((0.0, 0.0))));
+            harness.event(Event::MouseUp(move_mouse((0.0, 0.0))));
+            harness.event(Event::KeyDown(KeyEvent::for_test(
+                Modifiers::empty(),
+                KbKey::ArrowDown,
+            )));
+            let data = *harness.data();
+            assert_eq!(data.hue, 0.0);
+            assert_eq!(data.saturation, 0.0);
+            assert!(approx_eq!(f64, data.value, 0.99));
+            harness.just_layout();
+            harness.paint();
+        });
+    }
+
+    /// Renders a `SaturationValueSquare` filling a window of `size`, returning the pixels.
+    fn render_square(hue: f64, size: Size, check: impl Fn(&dyn Fn(f64, f64) -> [u8; 4])) {
+        // keep the indicator in the bottom left corner, out of the way
+        let data = HsvaColor::new(hue, 0.0, 0.0, 1.0);
+        Harness::create_with_render(
+            data,
+            SaturationValueSquare::new(),
+            size,
+            |harness| {
+                harness.send_initial_events();
+                harness.just_layout();
+                harness.paint();
+            },
+            |target| {
+                let pixels = target.into_raw();
+                let width = size.width as usize;
+                let pixel = |x: f64, y: f64| {
+                    let x = (x * (size.width - 1.0)).round() as usize;
+                    let y = (y * (size.height - 1.0)).round() as usize;
+                    let i = (y * width + x) * 4;
+                    [pixels[i], pixels[i + 1], pixels[i + 2], pixels[i + 3]]
+                };
+                check(&pixel);
+            },
+        );
+    }
+
+    fn assert_close(actual: [u8; 4], expected: [u8; 4]) {
+        let close = actual
+            .iter()
+            .zip(expected.iter())
+            .all(|(a, e)| (*a as i32 - *e as i32).abs() <= 8);
+        assert!(close, "expected {:?}, found {:?}", expected, actual);
+    }
+
+    #[test]
+    fn saturation_value_square_paint() {
+        for &size in &[Size::new(100.0, 100.0), Size::new(240.0, 160.0)] {
+            render_square(120.0, size, |pixel| {
+                assert_close(pixel(0.0, 0.0), [255, 255, 255, 255]);
+                assert_close(pixel(1.0, 0.0), [0, 255, 0, 255]);
+                assert_close(pixel(1.0, 1.0), [0, 0, 0, 255]);
+                assert_close(pixel(0.5, 1.0), [0, 0, 0, 255]);
+                // half saturation, half value
+                assert_close(pixel(0.5, 0.5), [64, 128, 64, 255]);
+            });
+        }
+    }
+}
@@ -16,6 +16,7 @@
 
 use super::BackgroundBrush;
 use crate::widget::prelude::*;
+use crate::widget::{Axis, Intrinsic};
 use crate::{Color, Data, KeyOrValue, Point, WidgetPod};
 use tracing::{instrument, trace, trace_span};
 
@@ -24,11 +25,26 @@ struct BorderStyle {
     color: KeyOrValue<Color>,
 }
 
+/// The interaction state a [`Container`] styles itself by.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct StyleState {
+    /// The mouse is over the container.
+    hot: bool,
+    /// The container or one of its descendants is active.
+    active: bool,
+    /// The container or one of its descendants has focus.
+    focus: bool,
+}
+
 /// A widget that provides simple visual styling options to a child.
 pub struct Container<T> {
     background: Option<BackgroundBrush<T>>,
+    hover_background: Option<BackgroundBrush<T>>,
+    active_background: Option<BackgroundBrush<T>>,
     border: Option<BorderStyle>,
+    focus_border: Option<BorderStyle>,
     corner_radius: KeyOrValue<f64>,
+    style_state: StyleState,
 
     inner: WidgetPod<T, Box<dyn Widget<T>>>,
 }
@@ -38,8 +54,12 @@ impl<T: Data> Container<T> {
     pub fn new(inner: impl Widget<T> + 'static) -> Self {
         Self {
             background: None,
+            hover_background: None,
+            active_background: None,
             border: None,
+            focus_border: None,
             corner_radius: 0.0.into(),
+            style_state: StyleState::default(),
             inner: WidgetPod::new(inner).boxed(),
         }
     }
@@ -80,6 +100,40 @@ impl<T: Data> Container<T> {
         self.background = None;
     }
 
+    /// Builder-style method for setting the background used while the mouse is
+    /// over this widget.
+    ///
+    /// This takes the same arguments as [`background`], and replaces that
+    /// background while the widget is hot.
+    ///
+    /// [`background`]: #method.background
+    pub fn hover_background(mut self, brush: impl Into<BackgroundBrush<T>>) -> Self {
+        self.set_hover_background(brush);
+        self
+    }
+
+    /// Set the background used while the mouse is over this widget.
+    pub fn set_hover_background(&mut self, brush: impl Into<BackgroundBrush<T>>) {
+        self.hover_background = Some(brush.into());
+    }
+
+    /// Builder-style method for setting the background used while this widget
+    /// or one of its descendants is active, for instance while a child button
+    /// is pressed.
+    ///
+    /// This takes precedence over the [`hover_background`].
+    ///
+    /// [`hover_background`]: #method.hover_background
+    pub fn active_background(mut self, brush: impl Into<BackgroundBrush<T>>) -> Self {
+        self.set_active_background(brush);
+        self
+    }
+
+    /// Set the background used while this widget or one of its descendants is active.
+    pub fn set_active_background(&mut self, brush: impl Into<BackgroundBrush<T>>) {
+        self.active_background = Some(brush.into());
+    }
+
     /// Builder-style method for painting a border around the widget with a color and width.
     ///
     /// Arguments can be either concrete values, or a [`Key`] of the respective
@@ -117,6 +171,35 @@ impl<T: Data> Container<T> {
         self.border = None;
     }
 
+    /// Builder-style method for painting a border while this widget or one of
+    /// its descendants has focus.
+    ///
+    /// This replaces the regular [`border`] while focused. The child is laid
+    /// out inside the wider of the two borders, so that gaining or losing
+    /// focus does not change the layout.
+    ///
+    /// [`border`]: #method.border
+    pub fn focus_border(
+        mut self,
+        color: impl Into<KeyOrValue<Color>>,
+        width: impl Into<KeyOrValue<f64>>,
+    ) -> Self {
+        self.set_focus_border(color, width);
+        self
+    }
+
+    /// Paint a border while this widget or one of its descendants has focus.
+    pub fn set_focus_border(
+        &mut self,
+        color: impl Into<KeyOrValue<Color>>,
+        width: impl Into<KeyOrValue<f64>>,
+    ) {
+        self.focus_border = Some(BorderStyle {
+            color: color.into(),
+            width: width.into(),
+        });
+    }
+
     /// Builder style method for rounding off corners of this container by setting a corner radius
     pub fn rounded(mut self, radius: impl Into<KeyOrValue<f64>>) -> Self {
         self.set_rounded(radius);
@@ -128,6 +211,51 @@ impl<T: Data> Container<T> {
         self.corner_radius = radius.into();
     }
 
+    /// Whether any of the styling depends on the hot, active or focus state.
+    fn is_stateful(&self) -> bool {
+        self.hover_background.is_some()
+            || self.active_background.is_some()
+            || self.focus_border.is_some()
+    }
+
+    /// Record the current interaction state, requesting a repaint if it changed
+    /// and we style by it.
+    fn update_style_state(&mut self, state: StyleState) -> bool {
+        let changed = state != self.style_state;
+        self.style_state = state;
+        changed && self.is_stateful()
+    }
+
+    /// The background for the current interaction state.
+    fn current_background(&mut self) -> Option<&mut BackgroundBrush<T>> {
+        let state = self.style_state;
+        if state.active && self.active_background.is_some() {
+            self.active_background.as_mut()
+        } else if state.hot && self.hover_background.is_some() {
+            self.hover_background.as_mut()
+        } else {
+            self.background.as_mut()
+        }
+    }
+
+    /// The border for the current interaction state.
+    fn current_border(&self) -> Option<&BorderStyle> {
+        match &self.focus_border {
+            Some(border) if self.style_state.focus => Some(border),
+            _ => self.border.as_ref(),
+        }
+    }
+
+    /// The space the child is inset by; this is the widest of the borders,
+    /// so that the child doesn't move when the focus border appears.
+    fn border_width(&self, env: &Env) -> f64 {
+        self.border
+            .iter()
+            .chain(self.focus_border.iter())
+            .map(|border| border.width.resolve(env))
+            .fold(0.0, f64::max)
+    }
+
     #[cfg(test)]
     pub(crate) fn background_is_some(&self) -> bool {
         self.background.is_some()
@@ -143,11 +271,27 @@ impl<T: Data> Widget<T> for Container<T> {
     #[instrument(name = "Container", level = "trace", skip(self, ctx, event, data, env))]
     fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
         self.inner.event(ctx, event, data, env);
+        let state = StyleState {
+            hot: ctx.is_hot(),
+            active: self.inner.has_active(),
+            focus: ctx.has_focus(),
+        };
+        if self.update_style_state(state) {
+            ctx.request_paint();
+        }
     }
 
     #[instrument(name = "Container", level = "trace", skip(self, ctx, event, data, env))]
     fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
-        self.inner.lifecycle(ctx, event, data, env)
+        self.inner.lifecycle(ctx, event, data, env);
+        let state = StyleState {
+            hot: ctx.is_hot(),
+            active: self.inner.has_active(),
+            focus: ctx.has_focus(),
+        };
+        if self.update_style_state(state) {
+            ctx.request_paint();
+        }
     }
 
     #[instrument(
@@ -156,10 +300,17 @@ impl<T: Data> Widget<T> for Container<T> {
         skip(self, ctx, old_data, data, env)
     )]
     fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
-        if let Some(BackgroundBrush::Painter(p)) = self.background.as_mut() {
-            trace_span!("update background").in_scope(|| {
-                p.update(ctx, old_data, data, env);
-            });
+        let backgrounds = self
+            .background
+            .iter_mut()
+            .chain(self.hover_background.iter_mut())
+            .chain(self.active_background.iter_mut());
+        for background in backgrounds {
+            if let BackgroundBrush::Painter(p) = background {
+                trace_span!("update background").in_scope(|| {
+                    p.update(ctx, old_data, data, env);
+                });
+            }
         }
         self.inner.update(ctx, data, env);
     }
@@ -169,10 +320,7 @@ impl<T: Data> Widget<T> for Container<T> {
         bc.debug_check("Container");
 
         // Shrink constraints by border offset
-        let border_width = match &self.border {
-            Some(border) => border.width.resolve(env),
-            None => 0.0,
-        };
+        let border_width = self.border_width(env);
         let child_bc = bc.shrink((2.0 * border_width, 2.0 * border_width));
         let size = self.inner.layout(ctx, &child_bc, data, env);
         let origin = Point::new(border_width, border_width);
@@ -189,11 +337,28 @@ impl<T: Data> Widget<T> for Container<T> {
         my_size
     }
 
+    fn compute_intrinsic_size(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        axis: Axis,
+        kind: Intrinsic,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        let border_width = self.border_width(env);
+        let child_bc = bc.shrink((2.0 * border_width, 2.0 * border_width));
+        let child_size = self
+            .inner
+            .compute_intrinsic_size(ctx, axis, kind, &child_bc, data, env);
+        child_size + 2.0 * border_width
+    }
+
     #[instrument(name = "Container", level = "trace", skip(self, ctx, data, env))]
     fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
         let corner_radius = self.corner_radius.resolve(env);
 
-        if let Some(background) = self.background.as_mut() {
+        if let Some(background) = self.current_background() {
             let panel = ctx.size().to_rounded_rect(corner_radius);
 
             trace_span!("paint background").in_scope(|| {
@@ -204,7 +369,7 @@ impl<T: Data> Widget<T> for Container<T> {
             });
         }
 
-        if let Some(border) = &self.border {
+        if let Some(border) = self.current_border() {
             let border_width = border.width.resolve(env);
             let border_rect = ctx
                 .size()
@@ -216,4 +381,122 @@ impl<T: Data> Widget<T> for Container<T> {
 
         self.inner.paint(ctx, data, env);
     }
+
+    #[cfg(feature = "debug_state")]
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState::with_children("Container", vec![self.inner.debug_state(data)])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::{widget_ids, ModularWidget};
+    use crate::tests::move_mouse;
+    use crate::widget::{Align, Painter, SizedBox};
+    use crate::{Rect, Selector, UnitPoint, WidgetExt};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use test_env_log::test;
+
+    const FOCUS: Selector = Selector::new("druid-test.focus");
+
+    type Painted = Rc<RefCell<Vec<&'static str>>>;
+
+    fn recorder(painted: &Painted, name: &'static str) -> Painter<()> {
+        let painted = painted.clone();
+        Painter::new(move |_, _, _| painted.borrow_mut().push(name))
+    }
+
+    /// A 100x100 child that is active while the mouse is down and takes focus on `FOCUS`.
+    fn child() -> impl Widget<()> {
+        let inner = ModularWidget::new(()).event_fn(|_, ctx, event, _, _| match event {
+            Event::MouseDown(_) => ctx.set_active(true),
+            Event::MouseUp(_) => ctx.set_active(false),
+            Event::Command(cmd) if cmd.is(FOCUS) => ctx.request_focus(),
+            _ => (),
+        });
+        SizedBox::new(inner).width(100.).height(100.)
+    }
+
+    #[test]
+    fn background_follows_state() {
+        let painted: Painted = Default::default();
+        let container = Container::new(child())
+            .background(recorder(&painted, "normal"))
+            .hover_background(recorder(&painted, "hover"))
+            .active_background(recorder(&painted, "active"));
+        let widget = Align::new(UnitPoint::TOP_LEFT, container);
+
+        Harness::create_simple((), widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.paint();
+            assert_eq!(painted.borrow().last(), Some(&"normal"));
+
+            harness.event(Event::MouseMove(move_mouse((10., 10.))));
+            assert_eq!(
+                harness.window().invalid().rects(),
+                &[Rect::new(0., 0., 100., 100.)]
+            );
+            harness.paint_invalid();
+            assert_eq!(painted.borrow().last(), Some(&"hover"));
+
+            harness.event(Event::MouseDown(move_mouse((10., 10.))));
+            harness.paint_invalid();
+            assert_eq!(painted.borrow().last(), Some(&"active"));
+
+            harness.event(Event::MouseUp(move_mouse((10., 10.))));
+            harness.paint_invalid();
+            assert_eq!(painted.borrow().last(), Some(&"hover"));
+
+            harness.event(Event::MouseMove(move_mouse((300., 300.))));
+            assert!(!harness.window().invalid().is_empty());
+            harness.paint_invalid();
+            assert_eq!(painted.borrow().last(), Some(&"normal"));
+        });
+    }
+
+    #[test]
+    fn stateless_container_does_not_repaint() {
+        let widget = Align::new(
+            UnitPoint::TOP_LEFT,
+            Container::new(child()).background(Color::WHITE),
+        );
+
+        Harness::create_simple((), widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.paint();
+
+            harness.event(Event::MouseMove(move_mouse((10., 10.))));
+            harness.event(Event::MouseDown(move_mouse((10., 10.))));
+            assert!(harness.window().invalid().is_empty());
+        });
+    }
+
+    #[test]
+    fn focus_border() {
+        let [container_id] = widget_ids();
+        let container = Container::new(child())
+            .border(Color::BLACK, 1.)
+            .focus_border(Color::WHITE, 3.)
+            .with_id(container_id);
+        let widget = Align::new(UnitPoint::TOP_LEFT, container);
+
+        Harness::create_simple((), widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.paint();
+            let rect = harness.get_state(container_id).layout_rect();
+            // the child is laid out inside the wider border
+            assert_eq!(rect.size(), Size::new(106., 106.));
+
+            harness.submit_command(FOCUS);
+            assert_eq!(harness.window().invalid().rects(), &[rect]);
+            harness.just_layout();
+            assert_eq!(harness.get_state(container_id).layout_rect(), rect);
+        });
+    }
 }
@@ -15,7 +15,7 @@
 //! A widget-controlling widget.
 
 use crate::widget::prelude::*;
-use crate::widget::WidgetWrapper;
+use crate::widget::{Axis, Intrinsic, WidgetWrapper};
 
 /// A trait for types that modify behaviour of a child widget.
 ///
@@ -126,10 +126,28 @@ impl<T, W: Widget<T>, C: Controller<T, W>> Widget<T> for ControllerHost<W, C> {
         self.widget.layout(ctx, bc, data, env)
     }
 
+    fn compute_intrinsic_size(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        axis: Axis,
+        kind: Intrinsic,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        self.widget
+            .compute_intrinsic_size(ctx, axis, kind, bc, data, env)
+    }
+
     fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
         self.widget.paint(ctx, data, env)
     }
 
+    #[cfg(feature = "debug_state")]
+    fn debug_state(&self, data: &T) -> DebugState {
+        self.widget.debug_state(data)
+    }
+
     fn id(&self) -> Option<WidgetId> {
         self.widget.id()
     }
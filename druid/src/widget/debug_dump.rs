@@ -0,0 +1,78 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that shows a dump of its data.
+
+use std::fmt::Debug;
+
+use crate::widget::prelude::*;
+use crate::widget::Label;
+use crate::{Data, FontDescriptor, FontFamily};
+
+/// A widget that shows a dump of its data, in a monospace font.
+///
+/// This is meant for debugging, when there is no widget yet for some data,
+/// or to see everything in it at once.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use druid::widget::DebugDump;
+///
+/// // the same as `DebugDump::pretty()`
+/// let dump: DebugDump<Arc<Vec<u32>>> = DebugDump::new(|data| format!("{:#?}", data));
+/// ```
+pub struct DebugDump<T> {
+    label: Label<T>,
+}
+
+impl<T: Data> DebugDump<T> {
+    /// Create a widget that shows the text `dump` returns for the data.
+    pub fn new(dump: impl Fn(&T) -> String + 'static) -> Self {
+        let label = Label::dynamic(move |data, _| dump(data))
+            .with_font(FontDescriptor::new(FontFamily::MONOSPACE));
+        DebugDump { label }
+    }
+
+    /// Create a widget that shows the pretty-printed `Debug` output of the data.
+    pub fn pretty() -> Self
+    where
+        T: Debug,
+    {
+        DebugDump::new(|data| format!("{:#?}", data))
+    }
+}
+
+impl<T: Data> Widget<T> for DebugDump<T> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        self.label.event(ctx, event, data, env)
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        self.label.lifecycle(ctx, event, data, env)
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        self.label.update(ctx, old_data, data, env)
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        self.label.layout(ctx, bc, data, env)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.label.paint(ctx, data, env)
+    }
+}
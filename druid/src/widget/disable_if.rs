@@ -12,6 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::widget::{Axis, Intrinsic};
+#[cfg(feature = "debug_state")]
+use crate::DebugState;
 use crate::{
     BoxConstraints, Data, Env, Event, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx,
     Point, Size, UpdateCtx, Widget, WidgetPod,
@@ -65,7 +68,25 @@ impl<T: Data, W: Widget<T>> Widget<T> for DisabledIf<T, W> {
         size
     }
 
+    fn compute_intrinsic_size(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        axis: Axis,
+        kind: Intrinsic,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        self.inner
+            .compute_intrinsic_size(ctx, axis, kind, bc, data, env)
+    }
+
     fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
         self.inner.paint(ctx, data, env);
     }
+
+    #[cfg(feature = "debug_state")]
+    fn debug_state(&self, data: &T) -> DebugState {
+        self.inner.debug_state(data)
+    }
 }
@@ -93,6 +93,16 @@ impl<T: Data> Widget<T> for Either<T> {
     fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
         self.current_widget().paint(ctx, data, env)
     }
+
+    #[cfg(feature = "debug_state")]
+    fn debug_state(&self, data: &T) -> DebugState {
+        let child = if self.current {
+            &self.true_branch
+        } else {
+            &self.false_branch
+        };
+        DebugState::with_children("Either", vec![child.debug_state(data)])
+    }
 }
 
 impl<T> Either<T> {
@@ -15,7 +15,7 @@
 //! A widget that accepts a closure to update the environment for its child.
 
 use crate::widget::prelude::*;
-use crate::widget::WidgetWrapper;
+use crate::widget::{Axis, Intrinsic, WidgetWrapper};
 use crate::{Data, Point, WidgetPod};
 use tracing::instrument;
 
@@ -97,6 +97,21 @@ impl<T: Data, W: Widget<T>> Widget<T> for EnvScope<T, W> {
         size
     }
 
+    fn compute_intrinsic_size(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        axis: Axis,
+        kind: Intrinsic,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        let mut new_env = env.clone();
+        (self.f)(&mut new_env, data);
+        self.child
+            .compute_intrinsic_size(ctx, axis, kind, bc, data, &new_env)
+    }
+
     #[instrument(name = "EnvScope", level = "trace", skip(self, ctx, data, env))]
     fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
         let mut new_env = env.clone();
@@ -104,6 +119,11 @@ impl<T: Data, W: Widget<T>> Widget<T> for EnvScope<T, W> {
 
         self.child.paint(ctx, data, &new_env);
     }
+
+    #[cfg(feature = "debug_state")]
+    fn debug_state(&self, data: &T) -> DebugState {
+        self.child.debug_state(data)
+    }
 }
 
 impl<T, W: Widget<T>> WidgetWrapper for EnvScope<T, W> {
@@ -0,0 +1,638 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Recording the events that reach a widget, and showing them.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::Duration;
+
+use instant::Instant;
+
+use crate::widget::prelude::*;
+use crate::widget::{
+    Button, Checkbox, Controller, CrossAxisAlignment, DebugDump, Flex, Label, List, Scroll,
+};
+use crate::{Data, KeyEvent, Lens, Modifiers, MouseButton, MouseEvent, Point, Selector};
+use crate::{WidgetExt, WidgetPod};
+
+/// Tells an [`EventViewer`] that entries were added to its log.
+const LOG_CHANGED: Selector = Selector::new("druid-builtin.event-log-changed");
+
+const NUMBER_WIDTH: f64 = 50.0;
+const TIME_WIDTH: f64 = 70.0;
+const NAME_WIDTH: f64 = 140.0;
+const NEWEST_ENTRY_HEIGHT: f64 = 160.0;
+
+/// A log of the events and lifecycle events that reach a widget.
+///
+/// Widgets are wrapped with [`WidgetExt::debug_event_log`] to record into
+/// a log, and an [`EventViewer`] shows it. Clones of an `EventLog` share
+/// the same entries.
+///
+/// The log keeps a bounded number of entries; once it is full, the oldest
+/// entry is dropped for every new one.
+#[derive(Clone)]
+pub struct EventLog(Rc<RefCell<LogState>>);
+
+struct LogState {
+    entries: VecDeque<LogEntry>,
+    capacity: usize,
+    /// The number of entries recorded so far, including the dropped ones.
+    recorded: usize,
+    /// Changes every time an entry is added or the log is cleared.
+    version: u64,
+    start: Instant,
+    paused: bool,
+    viewer: Option<WidgetId>,
+    /// Whether the viewer has been told about changes it hasn't seen yet.
+    viewer_notified: bool,
+}
+
+/// One entry in an [`EventLog`].
+#[derive(Clone, Data)]
+pub struct LogEntry {
+    /// The number of this entry, counting from the first entry recorded.
+    pub number: usize,
+    /// The time between creating the log and recording this entry.
+    pub time: Duration,
+    /// What was recorded.
+    #[data(ignore)]
+    pub event: LoggedEvent,
+}
+
+/// Something that was recorded in an [`EventLog`].
+#[derive(Debug, Clone)]
+pub enum LoggedEvent {
+    /// An [`Event`].
+    Event(Event),
+    /// A [`LifeCycle`] event.
+    LifeCycle(LifeCycle),
+}
+
+/// A [`Controller`] that records the events and lifecycle events reaching
+/// its child in an [`EventLog`].
+///
+/// This is usually created with [`WidgetExt::debug_event_log`]. Internal
+/// events are not recorded, since they are only routed through the child.
+pub struct EventLogger {
+    log: EventLog,
+}
+
+/// A widget that shows the contents of an [`EventLog`].
+///
+/// The viewer has buttons to pause recording and to clear the log, and
+/// checkboxes to hide mouse moves and lifecycle events, which would
+/// otherwise drown out everything else. Below the list, the newest entry is
+/// shown in full, with its `Debug` output.
+///
+/// The viewer doesn't use the app data, so it can be placed anywhere, but
+/// it must not be inside the widgets whose events it shows: showing a new
+/// entry would then record more events, without end.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{EventLog, EventViewer, Flex, TextBox};
+/// use druid::WidgetExt;
+///
+/// let log = EventLog::new();
+/// let root = Flex::column()
+///     .with_child(TextBox::new().debug_event_log(log.clone()))
+///     .with_flex_child(EventViewer::new(log), 1.0);
+/// # let _: Flex<String> = root;
+/// ```
+pub struct EventViewer {
+    state: ViewerState,
+    /// The version of the log that `state.entries` was made from.
+    version: u64,
+    inner: WidgetPod<ViewerState, Box<dyn Widget<ViewerState>>>,
+}
+
+#[derive(Clone, Data, Lens)]
+struct ViewerState {
+    #[data(ignore)]
+    log: EventLog,
+    /// The entries of the log that are shown, oldest first.
+    entries: Arc<Vec<LogEntry>>,
+    paused: bool,
+    show_mouse_moves: bool,
+    show_lifecycle: bool,
+}
+
+impl EventLog {
+    /// The number of entries a log created with [`EventLog::new`] keeps.
+    pub const DEFAULT_CAPACITY: usize = 1000;
+
+    /// Create a log that keeps the last [`DEFAULT_CAPACITY`] entries.
+    ///
+    /// [`DEFAULT_CAPACITY`]: EventLog::DEFAULT_CAPACITY
+    pub fn new() -> EventLog {
+        EventLog::with_capacity(EventLog::DEFAULT_CAPACITY)
+    }
+
+    /// Create a log that keeps the last `capacity` entries.
+    pub fn with_capacity(capacity: usize) -> EventLog {
+        EventLog(Rc::new(RefCell::new(LogState {
+            entries: VecDeque::with_capacity(capacity.min(EventLog::DEFAULT_CAPACITY)),
+            capacity,
+            recorded: 0,
+            version: 0,
+            start: Instant::now(),
+            paused: false,
+            viewer: None,
+            viewer_notified: false,
+        })))
+    }
+
+    /// The entries in the log, oldest first.
+    pub fn entries(&self) -> Vec<LogEntry> {
+        self.0.borrow().entries.iter().cloned().collect()
+    }
+
+    /// The number of entries this log keeps.
+    pub fn capacity(&self) -> usize {
+        self.0.borrow().capacity
+    }
+
+    /// Stop recording new entries, until [`resume`] is called.
+    ///
+    /// [`resume`]: EventLog::resume
+    pub fn pause(&self) {
+        self.0.borrow_mut().paused = true;
+    }
+
+    /// Record new entries again, after [`pause`].
+    ///
+    /// [`pause`]: EventLog::pause
+    pub fn resume(&self) {
+        self.0.borrow_mut().paused = false;
+    }
+
+    /// Returns `true` if recording is paused.
+    pub fn is_paused(&self) -> bool {
+        self.0.borrow().paused
+    }
+
+    /// Remove all entries.
+    ///
+    /// Entries recorded later keep counting from where the log was.
+    pub fn clear(&self) {
+        let mut state = self.0.borrow_mut();
+        state.entries.clear();
+        state.version += 1;
+    }
+
+    /// Add an entry, and return the viewer that should be told about it.
+    fn record(&self, event: LoggedEvent) -> Option<WidgetId> {
+        let mut state = self.0.borrow_mut();
+        if state.paused {
+            return None;
+        }
+        if state.entries.len() == state.capacity {
+            state.entries.pop_front();
+        }
+        let entry = LogEntry {
+            number: state.recorded,
+            time: state.start.elapsed(),
+            event,
+        };
+        if state.capacity > 0 {
+            state.entries.push_back(entry);
+        }
+        state.recorded += 1;
+        state.version += 1;
+        match state.viewer {
+            Some(viewer) if !state.viewer_notified => {
+                state.viewer_notified = true;
+                Some(viewer)
+            }
+            _ => None,
+        }
+    }
+
+    fn set_viewer(&self, viewer: WidgetId) {
+        let mut state = self.0.borrow_mut();
+        state.viewer = Some(viewer);
+        state.viewer_notified = false;
+    }
+
+    fn version(&self) -> u64 {
+        self.0.borrow().version
+    }
+
+    /// The current version; the viewer is told about the next change again.
+    fn take_version(&self) -> u64 {
+        let mut state = self.0.borrow_mut();
+        state.viewer_notified = false;
+        state.version
+    }
+}
+
+impl Default for EventLog {
+    fn default() -> Self {
+        EventLog::new()
+    }
+}
+
+impl LogEntry {
+    /// The name of the event, like `MouseDown` or `WidgetAdded`.
+    pub fn name(&self) -> String {
+        let debug = self.debug_string();
+        variant_name(&debug).to_string()
+    }
+
+    /// A summary of the interesting parts of the event.
+    ///
+    /// Mouse and keyboard events show their position, buttons, keys and
+    /// modifiers; other events show their contents.
+    pub fn details(&self) -> String {
+        match &self.event {
+            LoggedEvent::Event(Event::MouseDown(mouse))
+            | LoggedEvent::Event(Event::MouseUp(mouse))
+            | LoggedEvent::Event(Event::MouseMove(mouse)) => mouse_details(mouse, false),
+            LoggedEvent::Event(Event::Wheel(mouse)) => mouse_details(mouse, true),
+            LoggedEvent::Event(Event::KeyDown(key)) | LoggedEvent::Event(Event::KeyUp(key)) => {
+                key_details(key)
+            }
+            LoggedEvent::Event(Event::WindowSize(size)) => format!("{:.1}", size),
+            _ => {
+                let debug = self.debug_string();
+                let contents = &debug[variant_name(&debug).len()..];
+                contents
+                    .strip_prefix('(')
+                    .and_then(|contents| contents.strip_suffix(')'))
+                    .unwrap_or(contents)
+                    .trim()
+                    .to_string()
+            }
+        }
+    }
+
+    fn debug_string(&self) -> String {
+        match &self.event {
+            LoggedEvent::Event(event) => format!("{:?}", event),
+            LoggedEvent::LifeCycle(event) => format!("{:?}", event),
+        }
+    }
+}
+
+fn variant_name(debug: &str) -> &str {
+    let end = debug
+        .find(|c: char| !c.is_alphanumeric() && c != '_')
+        .unwrap_or(debug.len());
+    &debug[..end]
+}
+
+fn mouse_details(mouse: &MouseEvent, wheel: bool) -> String {
+    let mut details = format!("pos {:.2}", mouse.pos);
+    if mouse.button != MouseButton::None {
+        details.push_str(&format!(" button {:?}", mouse.button));
+    }
+    if mouse.count > 0 {
+        details.push_str(&format!(" count {}", mouse.count));
+    }
+    if wheel {
+        details.push_str(&format!(
+            " delta ({:.1}, {:.1})",
+            mouse.wheel_delta.x, mouse.wheel_delta.y
+        ));
+    }
+    details + &modifiers_details(mouse.mods)
+}
+
+fn key_details(key: &KeyEvent) -> String {
+    let mut details = format!(
+        "key {} code {} location {:?}",
+        key.key, key.code, key.location
+    );
+    if key.repeat {
+        details.push_str(" repeat");
+    }
+    details + &modifiers_details(key.mods)
+}
+
+fn modifiers_details(mods: Modifiers) -> String {
+    let mut details = String::new();
+    for (pressed, name) in &[
+        (mods.shift(), " Shift"),
+        (mods.ctrl(), " Ctrl"),
+        (mods.alt(), " Alt"),
+        (mods.meta(), " Meta"),
+    ] {
+        if *pressed {
+            details.push_str(name);
+        }
+    }
+    details
+}
+
+impl EventLogger {
+    /// Create a controller that records into `log`.
+    pub fn new(log: EventLog) -> Self {
+        EventLogger { log }
+    }
+
+    fn record(&self, event: LoggedEvent) -> Option<WidgetId> {
+        match &event {
+            LoggedEvent::Event(Event::Internal(_))
+            | LoggedEvent::LifeCycle(LifeCycle::Internal(_)) => None,
+            _ => self.log.record(event),
+        }
+    }
+}
+
+impl<T, W: Widget<T>> Controller<T, W> for EventLogger {
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if let Some(viewer) = self.record(LoggedEvent::Event(event.clone())) {
+            ctx.submit_command(LOG_CHANGED.to(viewer));
+        }
+        child.event(ctx, event, data, env)
+    }
+
+    fn lifecycle(
+        &mut self,
+        child: &mut W,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &T,
+        env: &Env,
+    ) {
+        if let Some(viewer) = self.record(LoggedEvent::LifeCycle(event.clone())) {
+            ctx.submit_command(LOG_CHANGED.to(viewer));
+        }
+        child.lifecycle(ctx, event, data, env)
+    }
+}
+
+impl EventViewer {
+    /// Create a viewer showing `log`.
+    ///
+    /// A log is shown by one viewer at a time.
+    pub fn new(log: EventLog) -> Self {
+        let state = ViewerState {
+            paused: log.is_paused(),
+            log,
+            entries: Default::default(),
+            show_mouse_moves: false,
+            show_lifecycle: false,
+        };
+        EventViewer {
+            state,
+            version: 0,
+            inner: WidgetPod::new(Box::new(viewer_ui())),
+        }
+    }
+
+    /// Rebuild the shown entries from the log.
+    // `List` wants an `Arc`, but the entries never leave the UI thread
+    #[allow(clippy::arc_with_non_send_sync)]
+    fn refresh(&mut self) {
+        self.version = self.state.log.take_version();
+        self.state.paused = self.state.log.is_paused();
+        let state = &self.state;
+        let entries = state
+            .log
+            .entries()
+            .into_iter()
+            .filter(|entry| state.shows(entry))
+            .collect();
+        self.state.entries = Arc::new(entries);
+    }
+}
+
+impl ViewerState {
+    fn shows(&self, entry: &LogEntry) -> bool {
+        match entry.event {
+            LoggedEvent::Event(Event::MouseMove(_)) => self.show_mouse_moves,
+            LoggedEvent::LifeCycle(_) => self.show_lifecycle,
+            _ => true,
+        }
+    }
+}
+
+impl<T: Data> Widget<T> for EventViewer {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, _data: &mut T, env: &Env) {
+        let old = self.state.clone();
+        match event {
+            Event::Command(cmd) if cmd.is(LOG_CHANGED) => ctx.set_handled(),
+            _ => {
+                self.inner.event(ctx, event, &mut self.state, env);
+                if self.state.paused != self.state.log.is_paused() {
+                    if self.state.paused {
+                        self.state.log.pause();
+                    } else {
+                        self.state.log.resume();
+                    }
+                }
+            }
+        }
+        if !old.same(&self.state) || self.state.log.version() != self.version {
+            self.refresh();
+            ctx.request_update();
+        }
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, _data: &T, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            self.state.log.set_viewer(ctx.widget_id());
+            self.refresh();
+        }
+        self.inner.lifecycle(ctx, event, &self.state, env)
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, _data: &T, env: &Env) {
+        self.inner.update(ctx, &self.state, env)
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, _data: &T, env: &Env) -> Size {
+        let size = self.inner.layout(ctx, bc, &self.state, env);
+        self.inner.set_origin(ctx, &self.state, env, Point::ORIGIN);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, _data: &T, env: &Env) {
+        self.inner.paint(ctx, &self.state, env)
+    }
+}
+
+fn viewer_ui() -> impl Widget<ViewerState> {
+    let pause = Button::dynamic(|state: &ViewerState, _| {
+        if state.paused { "Resume" } else { "Pause" }.to_string()
+    })
+    .on_click(|_, state: &mut ViewerState, _| state.paused = !state.paused);
+    let clear = Button::new("Clear").on_click(|_, state: &mut ViewerState, _| state.log.clear());
+
+    let controls = Flex::row()
+        .with_child(pause)
+        .with_default_spacer()
+        .with_child(clear)
+        .with_default_spacer()
+        .with_child(Checkbox::new("Mouse moves").lens(ViewerState::show_mouse_moves))
+        .with_default_spacer()
+        .with_child(Checkbox::new("Lifecycle").lens(ViewerState::show_lifecycle));
+
+    let header = Flex::row()
+        .with_child(Label::new("#").fix_width(NUMBER_WIDTH))
+        .with_child(Label::new("Time").fix_width(TIME_WIDTH))
+        .with_child(Label::new("Event").fix_width(NAME_WIDTH))
+        .with_child(Label::new("Details"));
+
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(controls)
+        .with_default_spacer()
+        .with_child(header)
+        .with_flex_child(
+            Scroll::new(List::new(entry_row).lens(ViewerState::entries)).vertical(),
+            1.0,
+        )
+        .with_default_spacer()
+        .with_child(
+            Scroll::new(newest_entry())
+                .vertical()
+                .fix_height(NEWEST_ENTRY_HEIGHT)
+                .expand_width(),
+        )
+}
+
+fn newest_entry() -> impl Widget<ViewerState> {
+    DebugDump::new(|state: &ViewerState| {
+        state
+            .entries
+            .last()
+            .map(|entry| format!("{:#?}", entry.event))
+            .unwrap_or_default()
+    })
+}
+
+fn entry_row() -> impl Widget<LogEntry> {
+    Flex::row()
+        .with_child(
+            Label::dynamic(|entry: &LogEntry, _| entry.number.to_string()).fix_width(NUMBER_WIDTH),
+        )
+        .with_child(
+            Label::dynamic(|entry: &LogEntry, _| format!("{:.3}", entry.time.as_secs_f64()))
+                .fix_width(TIME_WIDTH),
+        )
+        .with_child(Label::dynamic(|entry: &LogEntry, _| entry.name()).fix_width(NAME_WIDTH))
+        .with_child(Label::dynamic(|entry: &LogEntry, _| entry.details()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::move_mouse;
+    use crate::widget::SizedBox;
+    use test_env_log::test;
+
+    fn names(log: &EventLog) -> Vec<String> {
+        log.entries().iter().map(LogEntry::name).collect()
+    }
+
+    #[test]
+    fn keeps_the_newest_entries() {
+        let log = EventLog::with_capacity(3);
+        for _ in 0..5 {
+            log.record(LoggedEvent::LifeCycle(LifeCycle::WidgetAdded));
+        }
+        let numbers: Vec<_> = log.entries().iter().map(|entry| entry.number).collect();
+        assert_eq!(numbers, vec![2, 3, 4]);
+
+        log.clear();
+        assert!(log.entries().is_empty());
+        log.record(LoggedEvent::LifeCycle(LifeCycle::WidgetAdded));
+        assert_eq!(log.entries()[0].number, 5);
+    }
+
+    #[test]
+    fn pause_stops_recording() {
+        let log = EventLog::new();
+        log.pause();
+        log.record(LoggedEvent::LifeCycle(LifeCycle::WidgetAdded));
+        assert!(log.entries().is_empty());
+        log.resume();
+        log.record(LoggedEvent::LifeCycle(LifeCycle::WidgetAdded));
+        assert_eq!(names(&log), vec!["WidgetAdded"]);
+    }
+
+    #[test]
+    fn details() {
+        let mut mouse = move_mouse((10., 20.));
+        mouse.mods.set(Modifiers::SHIFT, true);
+        let entry = LogEntry {
+            number: 0,
+            time: Duration::default(),
+            event: LoggedEvent::Event(Event::MouseMove(mouse)),
+        };
+        assert_eq!(entry.name(), "MouseMove");
+        assert_eq!(entry.details(), "pos (10.00, 20.00) Shift");
+
+        let entry = LogEntry {
+            event: LoggedEvent::LifeCycle(LifeCycle::HotChanged(true)),
+            ..entry
+        };
+        assert_eq!(entry.name(), "HotChanged");
+        assert_eq!(entry.details(), "true");
+    }
+
+    #[test]
+    fn viewer_shows_the_log() {
+        let log = EventLog::new();
+        let widget = Flex::column()
+            .with_child(
+                SizedBox::empty()
+                    .fix_size(100., 100.)
+                    .debug_event_log(log.clone()),
+            )
+            .with_flex_child(EventViewer::new(log.clone()), 1.0);
+
+        Harness::create_simple((), widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            let recorded = names(&log);
+            assert!(recorded.iter().any(|name| name == "WidgetAdded"));
+            assert!(recorded.iter().any(|name| name == "WindowConnected"));
+
+            harness.event(Event::MouseMove(move_mouse((200., 50.))));
+            assert!(names(&log).iter().any(|name| name == "MouseMove"));
+            // the viewer has handled the notification, and shown the new entries
+            let state = log.0.borrow();
+            assert!(state.viewer.is_some());
+            assert!(!state.viewer_notified);
+        });
+    }
+
+    #[test]
+    fn viewer_filters_entries() {
+        let log = EventLog::new();
+        let mut viewer = EventViewer::new(log.clone());
+        log.record(LoggedEvent::LifeCycle(LifeCycle::WidgetAdded));
+        log.record(LoggedEvent::Event(Event::MouseMove(move_mouse((1., 1.)))));
+        log.record(LoggedEvent::Event(Event::WindowConnected));
+
+        // mouse moves and lifecycle events are hidden by default
+        viewer.refresh();
+        let shown: Vec<_> = viewer.state.entries.iter().map(LogEntry::name).collect();
+        assert_eq!(shown, vec!["WindowConnected"]);
+
+        viewer.state.show_mouse_moves = true;
+        viewer.state.show_lifecycle = true;
+        viewer.refresh();
+        assert_eq!(viewer.state.entries.len(), 3);
+    }
+}
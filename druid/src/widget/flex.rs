@@ -16,7 +16,8 @@
 
 use crate::kurbo::{common::FloatExt, Vec2};
 use crate::widget::prelude::*;
-use crate::{Data, KeyOrValue, Point, Rect, WidgetPod};
+use crate::widget::Intrinsic;
+use crate::{ChildList, Data, KeyOrValue, ListChild, Point, Rect, WidgetPod};
 use tracing::{instrument, trace};
 
 /// A container with either horizontal or vertical layout.
@@ -78,6 +79,16 @@ use tracing::{instrument, trace};
 /// available to it. If you want to force a widget to use all available space,
 /// you should expand it, with [`expand_width`] or [`expand_height`].
 ///
+/// ## Shrinking
+///
+/// If the non-flex children don't fit on the main axis, they are shrunk
+/// before anything overflows, but never below their minimum intrinsic size
+/// (see [`Widget::compute_intrinsic_size`]); a label that wraps, for instance,
+/// can get as narrow as its longest word. Children with a lower
+/// [`shrink_priority`] are shrunk first, and only once they are as small as
+/// they can get are children with a higher priority shrunk. Children with
+/// the same priority shrink in proportion to how much they can shrink.
+///
 ///
 /// # Options
 ///
@@ -135,12 +146,14 @@ use tracing::{instrument, trace};
 /// [`expand_width`]: ../trait.WidgetExt.html#method.expand_width
 /// [`TextBox`]: struct.TextBox.html
 /// [`SizedBox`]: struct.SizedBox.html
+/// [`Widget::compute_intrinsic_size`]: ../trait.Widget.html#method.compute_intrinsic_size
+/// [`shrink_priority`]: struct.FlexParams.html#method.shrink_priority
 pub struct Flex<T> {
     direction: Axis,
     cross_alignment: CrossAxisAlignment,
     main_alignment: MainAxisAlignment,
     fill_major_axis: bool,
-    children: Vec<Child<T>>,
+    children: ChildList<T, Child<T>>,
 }
 
 /// Optional parameters for an item in a [`Flex`] container (row or column).
@@ -168,6 +181,10 @@ pub struct Flex<T> {
 /// // you can construct FlexParams if needed:
 /// let params = FlexParams::new(2.0, CrossAxisAlignment::End);
 /// row.add_flex_child(child_2, params);
+/// // a flex factor of zero gives a non-flex child, which is useful
+/// // to set its other parameters:
+/// let child_3 = Label::new("I'm tired");
+/// row.add_flex_child(child_3, FlexParams::default().shrink_priority(1));
 /// ```
 ///
 /// [`CrossAxisAlignment`]: enum.CrossAxisAlignment.html
@@ -178,6 +195,7 @@ pub struct Flex<T> {
 pub struct FlexParams {
     flex: f64,
     alignment: Option<CrossAxisAlignment>,
+    shrink_priority: i32,
 }
 
 /// An axis in visual space.
@@ -264,6 +282,9 @@ impl Axis {
     }
 
     /// Generate constraints with new values on the major axis.
+    ///
+    /// This goes through [`BoxConstraints::new`], so an overflowing row or
+    /// column clamps its children's constraints to zero.
     pub(crate) fn constraints(
         self,
         bc: &BoxConstraints,
@@ -361,8 +382,23 @@ impl FlexParams {
         FlexParams {
             flex,
             alignment: alignment.into(),
+            shrink_priority: 0,
         }
     }
+
+    /// Builder-style method to set how reluctant a non-flex child is to shrink.
+    ///
+    /// When the non-flex children of a [`Flex`] don't fit, the ones with the
+    /// lowest priority are shrunk first. The default priority is `0`.
+    ///
+    /// This has no effect on flex children, which always share whatever
+    /// space is left after the non-flex children have been laid out.
+    ///
+    /// [`Flex`]: struct.Flex.html
+    pub fn shrink_priority(mut self, priority: i32) -> Self {
+        self.shrink_priority = priority;
+        self
+    }
 }
 
 impl<T: Data> Flex<T> {
@@ -370,7 +406,7 @@ impl<T: Data> Flex<T> {
     pub fn for_axis(axis: Axis) -> Self {
         Flex {
             direction: axis,
-            children: Vec::new(),
+            children: ChildList::new(),
             cross_alignment: CrossAxisAlignment::Center,
             main_alignment: MainAxisAlignment::Start,
             fill_major_axis: false,
@@ -525,6 +561,7 @@ impl<T: Data> Flex<T> {
         let child = Child::Fixed {
             widget: WidgetPod::new(Box::new(child)),
             alignment: None,
+            shrink_priority: 0,
         };
         self.children.push(child);
     }
@@ -565,11 +602,20 @@ impl<T: Data> Flex<T> {
                 alignment: params.alignment,
                 flex: params.flex,
             }
+        } else if params.flex == 0.0 {
+            // `FlexParams::default()`, used to set the other parameters
+            // of a non-flex child.
+            Child::Fixed {
+                widget: WidgetPod::new(Box::new(child)),
+                alignment: params.alignment,
+                shrink_priority: params.shrink_priority,
+            }
         } else {
             tracing::warn!("Flex value should be > 0.0. To add a non-flex child use the add_child or with_child methods.\nSee the docs for more information: https://docs.rs/druid/0.7.0/druid/widget/struct.Flex.html");
             Child::Fixed {
                 widget: WidgetPod::new(Box::new(child)),
                 alignment: None,
+                shrink_priority: 0,
             }
         };
         self.children.push(child);
@@ -622,28 +668,104 @@ impl<T: Data> Flex<T> {
         let new_child = Child::FlexedSpacer(flex, 0.0);
         self.children.push(new_child);
     }
+
+    /// Shrink the non-flex children, which have been laid out at their
+    /// natural size, to get rid of `overflow` on the main axis.
+    ///
+    /// Returns the space the non-flex children and fixed spacers take now.
+    fn shrink_non_flex(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        loosened_bc: &BoxConstraints,
+        mut overflow: f64,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        let direction = self.direction;
+        // (child index, shrink priority, natural size, minimum size)
+        let mut children: Vec<(usize, i32, f64, f64)> = Vec::new();
+        for (i, child) in self.children.iter_mut().enumerate() {
+            if let Child::Fixed {
+                widget,
+                shrink_priority,
+                ..
+            } = child
+            {
+                let natural = direction.major(widget.layout_rect().size());
+                let min = widget.compute_intrinsic_size(
+                    ctx,
+                    direction,
+                    Intrinsic::Min,
+                    loosened_bc,
+                    data,
+                    env,
+                );
+                children.push((i, *shrink_priority, natural, min.min(natural)));
+            }
+        }
+        // the sort is stable, so children keep their order within a priority
+        children.sort_by_key(|child| child.1);
+
+        let mut group_start = 0;
+        while group_start < children.len() {
+            let priority = children[group_start].1;
+            let group_end = children[group_start..]
+                .iter()
+                .position(|child| child.1 != priority)
+                .map_or(children.len(), |len| group_start + len);
+            let group = &children[group_start..group_end];
+
+            let available: f64 = group.iter().map(|child| child.2 - child.3).sum();
+            let fraction = if overflow > 0.0 && available > 0.0 {
+                (overflow / available).min(1.0)
+            } else {
+                0.0
+            };
+            overflow -= available * fraction;
+
+            // Computing the intrinsic sizes may have changed the layout of
+            // the children, so all of them are laid out again.
+            for &(i, _, natural, min) in group {
+                let major = if fraction > 0.0 {
+                    natural - (natural - min) * fraction
+                } else {
+                    f64::INFINITY
+                };
+                let child_bc = direction.constraints(loosened_bc, 0.0, major);
+                if let Some(widget) = self.children.get_mut(i).and_then(Child::widget_mut) {
+                    widget.layout(ctx, &child_bc, data, env);
+                }
+            }
+            group_start = group_end;
+        }
+
+        self.children
+            .iter()
+            .map(|child| match child {
+                Child::Fixed { widget, .. } => {
+                    direction.major(widget.layout_rect().size()).expand()
+                }
+                Child::FixedSpacer(_, calculated_size) => *calculated_size,
+                _ => 0.0,
+            })
+            .sum()
+    }
 }
 
 impl<T: Data> Widget<T> for Flex<T> {
     #[instrument(name = "Flex", level = "trace", skip(self, ctx, event, data, env))]
     fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
-        for child in self.children.iter_mut().filter_map(|x| x.widget_mut()) {
-            child.event(ctx, event, data, env);
-        }
+        self.children.event(ctx, event, data, env);
     }
 
     #[instrument(name = "Flex", level = "trace", skip(self, ctx, event, data, env))]
     fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
-        for child in self.children.iter_mut().filter_map(|x| x.widget_mut()) {
-            child.lifecycle(ctx, event, data, env);
-        }
+        self.children.lifecycle(ctx, event, data, env);
     }
 
     #[instrument(name = "Flex", level = "trace", skip(self, ctx, _old_data, data, env))]
     fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
-        for child in self.children.iter_mut().filter_map(|x| x.widget_mut()) {
-            child.update(ctx, data, env);
-        }
+        self.children.update(ctx, data, env);
     }
 
     #[instrument(name = "Flex", level = "trace", skip(self, ctx, bc, data, env))]
@@ -662,16 +784,13 @@ impl<T: Data> Widget<T> for Flex<T> {
         // Measure non-flex children.
         let mut major_non_flex = 0.0;
         let mut flex_sum = 0.0;
-        for child in &mut self.children {
+        for child in self.children.iter_mut() {
             match child {
-                Child::Fixed { widget, alignment } => {
-                    any_use_baseline &= *alignment == Some(CrossAxisAlignment::Baseline);
-
+                Child::Fixed { widget, .. } => {
                     let child_bc =
                         self.direction
                             .constraints(&loosened_bc, 0.0, std::f64::INFINITY);
                     let child_size = widget.layout(ctx, &child_bc, data, env);
-                    let baseline_offset = widget.baseline_offset();
 
                     if child_size.width.is_infinite() {
                         tracing::warn!("A non-Flex child has an infinite width.");
@@ -682,10 +801,6 @@ impl<T: Data> Widget<T> for Flex<T> {
                     }
 
                     major_non_flex += self.direction.major(child_size).expand();
-                    minor = minor.max(self.direction.minor(child_size).expand());
-                    max_above_baseline =
-                        max_above_baseline.max(child_size.height - baseline_offset);
-                    max_below_baseline = max_below_baseline.max(baseline_offset);
                 }
                 Child::FixedSpacer(kv, calculated_siz) => {
                     *calculated_siz = kv.resolve(env);
@@ -700,13 +815,33 @@ impl<T: Data> Widget<T> for Flex<T> {
         }
 
         let total_major = self.direction.major(bc.max());
+        if major_non_flex > total_major {
+            major_non_flex =
+                self.shrink_non_flex(ctx, &loosened_bc, major_non_flex - total_major, data, env);
+        }
+
+        for child in self.children.iter_mut() {
+            if let Child::Fixed {
+                widget, alignment, ..
+            } = child
+            {
+                any_use_baseline &= *alignment == Some(CrossAxisAlignment::Baseline);
+
+                let child_size = widget.layout_rect().size();
+                let baseline_offset = widget.baseline_offset();
+                minor = minor.max(self.direction.minor(child_size).expand());
+                max_above_baseline = max_above_baseline.max(child_size.height - baseline_offset);
+                max_below_baseline = max_below_baseline.max(baseline_offset);
+            }
+        }
+
         let remaining = (total_major - major_non_flex).max(0.0);
         let mut remainder: f64 = 0.0;
 
         let mut major_flex: f64 = 0.0;
         let px_per_flex = remaining / flex_sum;
         // Measure flex children.
-        for child in &mut self.children {
+        for child in self.children.iter_mut() {
             match child {
                 Child::Flex { widget, flex, .. } => {
                     let desired_major = (*flex) * px_per_flex + remainder;
@@ -756,9 +891,11 @@ impl<T: Data> Widget<T> for Flex<T> {
         let mut major = spacing.next().unwrap_or(0.);
         let mut child_paint_rect = Rect::ZERO;
 
-        for child in &mut self.children {
+        for child in self.children.iter_mut() {
             match child {
-                Child::Fixed { widget, alignment }
+                Child::Fixed {
+                    widget, alignment, ..
+                }
                 | Child::Flex {
                     widget, alignment, ..
                 } => {
@@ -828,7 +965,9 @@ impl<T: Data> Widget<T> for Flex<T> {
 
         let baseline_offset = match self.direction {
             Axis::Horizontal => max_below_baseline,
-            Axis::Vertical => (&self.children)
+            Axis::Vertical => self
+                .children
+                .iter()
                 .last()
                 .map(|last| {
                     let child = last.widget();
@@ -853,11 +992,35 @@ impl<T: Data> Widget<T> for Flex<T> {
         my_size
     }
 
+    fn compute_intrinsic_size(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        axis: Axis,
+        kind: Intrinsic,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        // the children get loosened constraints, as in layout
+        let loosened_bc = bc.loosen();
+        let direction = self.direction;
+        let sizes = self.children.iter_mut().map(|child| match child {
+            Child::Fixed { widget, .. } | Child::Flex { widget, .. } => {
+                widget.compute_intrinsic_size(ctx, axis, kind, &loosened_bc, data, env)
+            }
+            Child::FixedSpacer(kv, _) if axis == direction => kv.resolve(env).max(0.0),
+            Child::FixedSpacer(..) | Child::FlexedSpacer(..) => 0.0,
+        });
+        if axis == direction {
+            sizes.sum()
+        } else {
+            sizes.fold(0.0, f64::max)
+        }
+    }
+
     #[instrument(name = "Flex", level = "trace", skip(self, ctx, data, env))]
     fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
-        for child in self.children.iter_mut().filter_map(|x| x.widget_mut()) {
-            child.paint(ctx, data, env);
-        }
+        self.children.paint(ctx, data, env);
 
         // paint the baseline if we're debugging layout
         if env.get(Env::DEBUG_PAINT) && ctx.widget_state.baseline_offset != 0.0 {
@@ -868,6 +1031,11 @@ impl<T: Data> Widget<T> for Flex<T> {
             ctx.stroke_styled(line, &color, 1.0, &stroke_style);
         }
     }
+
+    #[cfg(feature = "debug_state")]
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState::with_children("Flex", self.children.debug_state(data))
+    }
 }
 
 impl CrossAxisAlignment {
@@ -990,6 +1158,7 @@ enum Child<T> {
     Fixed {
         widget: WidgetPod<T, Box<dyn Widget<T>>>,
         alignment: Option<CrossAxisAlignment>,
+        shrink_priority: i32,
     },
     Flex {
         widget: WidgetPod<T, Box<dyn Widget<T>>>,
@@ -1000,14 +1169,17 @@ enum Child<T> {
     FlexedSpacer(f64, f64),
 }
 
-impl<T> Child<T> {
-    fn widget_mut(&mut self) -> Option<&mut WidgetPod<T, Box<dyn Widget<T>>>> {
+impl<T> ListChild<T> for Child<T> {
+    type Widget = Box<dyn Widget<T>>;
+
+    fn widget(&self) -> Option<&WidgetPod<T, Box<dyn Widget<T>>>> {
         match self {
             Child::Fixed { widget, .. } | Child::Flex { widget, .. } => Some(widget),
             _ => None,
         }
     }
-    fn widget(&self) -> Option<&WidgetPod<T, Box<dyn Widget<T>>>> {
+
+    fn widget_mut(&mut self) -> Option<&mut WidgetPod<T, Box<dyn Widget<T>>>> {
         match self {
             Child::Fixed { widget, .. } | Child::Flex { widget, .. } => Some(widget),
             _ => None,
@@ -1018,6 +1190,12 @@ impl<T> Child<T> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::{widget_ids, ModularWidget};
+    use crate::widget::{Label, LineBreaking, SizedBox};
+    use crate::WidgetExt;
+    use std::cell::Cell;
+    use std::rc::Rc;
     use test_env_log::test;
 
     #[test]
@@ -1103,4 +1281,171 @@ mod tests {
         let params = FlexParams::new(-1.0, None);
         approx_eq!(f64, params.flex, 1.0, ulps = 2);
     }
+
+    const WORDS: &str = "one two three four five";
+
+    fn wrapping(text: &str) -> Label<()> {
+        Label::new(text).with_line_break_mode(LineBreaking::WordWrap)
+    }
+
+    /// The widths of the labels in a row of `labels`, in a window of `width`.
+    fn label_widths(labels: &[(&str, i32)], width: f64) -> Vec<f64> {
+        let ids: Vec<_> = labels.iter().map(|_| WidgetId::next()).collect();
+        let mut row = Flex::row();
+        for (&(text, priority), &id) in labels.iter().zip(&ids) {
+            let params = FlexParams::default().shrink_priority(priority);
+            row.add_flex_child(wrapping(text).with_id(id), params);
+        }
+
+        let mut widths = Vec::new();
+        Harness::create_with_render(
+            (),
+            row,
+            Size::new(width, 400.),
+            |harness| {
+                harness.send_initial_events();
+                harness.just_layout();
+                for &id in &ids {
+                    widths.push(harness.get_state(id).layout_rect().width());
+                }
+            },
+            |_| {},
+        );
+        widths
+    }
+
+    #[test]
+    fn shrink_lowest_priority_first() {
+        let labels = [(WORDS, 1), (WORDS, 0), (WORDS, 1)];
+        let natural = label_widths(&labels, 10_000.)[0];
+        // too little room for anything but the longest word
+        let min = label_widths(&labels, 1.)[0];
+        assert!(min < natural);
+        let range = natural - min;
+
+        // only the label with the lowest priority shrinks
+        let widths = label_widths(&labels, 3. * natural - range / 2.);
+        assert_eq!(widths[0], natural);
+        assert!(widths[1] < natural && widths[1] > min);
+        assert_eq!(widths[2], natural);
+
+        // once it's as small as it gets, the others shrink as well, evenly
+        let widths = label_widths(&labels, 2. * natural + min - range / 2.);
+        assert_eq!(widths[1], min);
+        assert!(widths[0] < natural && widths[0] > min);
+        assert_eq!(widths[0], widths[2]);
+    }
+
+    #[test]
+    fn never_shrink_below_minimum() {
+        let labels = [("unbreakable", 0), (WORDS, 0)];
+        let natural = label_widths(&labels, 10_000.);
+
+        let widths = label_widths(&labels, natural[1]);
+        // a single word can't wrap, so that label keeps its width
+        assert_eq!(widths[0], natural[0]);
+        assert!(widths[1] < natural[1]);
+
+        // when nothing fits, everything is at its minimum, and the row overflows
+        let widths = label_widths(&labels, 1.);
+        assert_eq!(widths[0], natural[0]);
+        assert!(widths[1] > 0.);
+        assert_eq!(widths[1], label_widths(&[(WORDS, 0)], 1.)[0]);
+    }
+
+    #[test]
+    fn over_subtraction_clamps_to_zero() {
+        let [flexed, padded, after_spacer] = widget_ids();
+        let window = Size::new(200., 100.);
+
+        // a spacer wider than the row leaves nothing for the flex child
+        let row = Flex::row()
+            .with_spacer(300.)
+            .with_flex_child(SizedBox::empty().expand().with_id(flexed), 1.0);
+        Harness::create_simple((), row, |harness| {
+            harness.set_initial_size(window);
+            harness.send_initial_events();
+            harness.just_layout();
+            assert_eq!(harness.get_state(flexed).layout_rect().width(), 0.);
+        });
+
+        // padding wider than the window leaves the row itself no room
+        let row = Flex::row().with_flex_child(SizedBox::empty().expand().with_id(padded), 1.0);
+        Harness::create_simple((), row.padding(150.), |harness| {
+            harness.set_initial_size(window);
+            harness.send_initial_events();
+            harness.just_layout();
+            assert_eq!(harness.get_state(padded).layout_rect().size(), Size::ZERO);
+        });
+
+        // a negative spacer takes no space
+        let row = Flex::row()
+            .with_spacer(-50.)
+            .with_child(SizedBox::empty().width(20.).with_id(after_spacer))
+            .main_axis_alignment(MainAxisAlignment::Start);
+        Harness::create_simple((), row, |harness| {
+            harness.set_initial_size(window);
+            harness.send_initial_events();
+            harness.just_layout();
+            assert_eq!(harness.get_state(after_spacer).layout_rect().x0, 0.);
+        });
+    }
+
+    /// A widget that asks its child for its intrinsic sizes before laying it out.
+    fn measure<W: Widget<()> + 'static>(
+        child: W,
+        measured: Rc<Cell<(f64, f64)>>,
+    ) -> impl Widget<()> {
+        ModularWidget::new(WidgetPod::new(child))
+            .lifecycle_fn(|child, ctx, event, data, env| child.lifecycle(ctx, event, data, env))
+            .layout_fn(move |child, ctx, bc, data, env| {
+                let kind = Intrinsic::Preferred;
+                let width =
+                    child.compute_intrinsic_size(ctx, Axis::Horizontal, kind, bc, data, env);
+                // asking again is free
+                child.compute_intrinsic_size(ctx, Axis::Horizontal, kind, bc, data, env);
+                let kind = Intrinsic::Min;
+                let height = child.compute_intrinsic_size(ctx, Axis::Vertical, kind, bc, data, env);
+                measured.set((width, height));
+                let size = child.layout(ctx, bc, data, env);
+                child.set_origin(ctx, data, env, Point::ZERO);
+                size
+            })
+    }
+
+    #[test]
+    fn intrinsic_sizes_of_a_row() {
+        let row = Flex::row()
+            .with_child(SizedBox::empty().fix_size(30., 10.))
+            .with_spacer(5.)
+            .with_flex_child(SizedBox::empty().fix_size(20., 40.), 1.0);
+        let measured = Rc::new(Cell::new((0., 0.)));
+        let widget = measure(row, measured.clone());
+        Harness::create_simple((), widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+        });
+        assert_eq!(measured.get(), (55., 40.));
+    }
+
+    #[test]
+    fn intrinsic_sizes_are_cached() {
+        let layouts = Rc::new(Cell::new(0));
+        let layouts_ = layouts.clone();
+        // this uses the default implementation, which lays the widget out
+        let leaf = ModularWidget::new(()).layout_fn(move |_, _, _, _, _| {
+            layouts_.set(layouts_.get() + 1);
+            Size::new(10., 10.)
+        });
+        let column = Flex::column().with_child(Flex::row().with_child(leaf));
+        let measured = Rc::new(Cell::new((0., 0.)));
+        let widget = measure(column, measured.clone());
+        Harness::create_simple((), widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+        });
+        assert_eq!(measured.get(), (10., 10.));
+        // once for each kind of intrinsic size, and once for the real layout
+        assert_eq!(layouts.get(), 3);
+    }
 }
@@ -0,0 +1,529 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Validation of form fields.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use tracing::{instrument, trace};
+
+use crate::kurbo::RoundedRect;
+use crate::text::TextLayout;
+use crate::widget::prelude::*;
+use crate::widget::LensWrap;
+use crate::{theme, ArcStr, Data, Lens, Point, Selector, TimerToken, WidgetPod};
+
+type Validator<T, V> = dyn Fn(&V, &T) -> Result<(), String>;
+
+/// Sent by a [`Validated`] to itself, to report its state from `event`.
+const REPORT: Selector = Selector::new("druid-builtin.validated-report");
+
+/// Submitted as a notification by a [`Validated`] whenever it is validated,
+/// or added to the tree; the payload is whether the field is valid.
+const FIELD_VALIDITY: Selector<bool> = Selector::new("druid-builtin.validated-validity");
+
+#[derive(Debug, Clone, PartialEq)]
+enum FieldState {
+    /// The field hasn't been edited or left yet.
+    Unvalidated,
+    Valid,
+    Invalid(ArcStr),
+}
+
+/// A wrapper for an input widget that checks its value with a list of
+/// validators.
+///
+/// The validators run when the value changes, and when the focus leaves the
+/// field. Until then, the field is neither valid nor invalid. While the
+/// field is invalid, it is outlined in [`theme::FORM_ERROR_COLOR`] and the
+/// message of the first failing validator is shown below it.
+///
+/// Validators added with [`with_form_validator`] also get the whole form
+/// data, for checks across fields; once a field has been validated, it is
+/// checked again whenever the form data changes.
+///
+/// Put fields in a [`FormScope`] to find out whether all of them are valid.
+///
+/// [`with_form_validator`]: Validated::with_form_validator
+pub struct Validated<T, V, L, W> {
+    lens: L,
+    child: WidgetPod<T, LensWrap<T, V, L, W>>,
+    validators: Vec<Box<Validator<T, V>>>,
+    debounce: Option<Duration>,
+    timer: TimerToken,
+    state: FieldState,
+    error: TextLayout<ArcStr>,
+    had_focus: bool,
+}
+
+impl<T: Data, V: Data, L: Lens<T, V> + Clone, W: Widget<V>> Validated<T, V, L, W> {
+    /// Create a new `Validated` for `child`, which edits the part of the data
+    /// that `lens` points to.
+    pub fn new(child: W, lens: L) -> Self {
+        let mut error = TextLayout::new();
+        error.set_text_color(theme::FORM_ERROR_COLOR);
+        error.set_text_size(theme::FORM_ERROR_TEXT_SIZE);
+        Validated {
+            child: WidgetPod::new(LensWrap::new(child, lens.clone())),
+            lens,
+            validators: Vec::new(),
+            debounce: None,
+            timer: TimerToken::INVALID,
+            state: FieldState::Unvalidated,
+            error,
+            had_focus: false,
+        }
+    }
+
+    /// Builder-style method to add a validator.
+    ///
+    /// Validators run in the order they were added, and the first error is
+    /// the one that is shown.
+    pub fn with_validator(self, validator: impl Fn(&V) -> Result<(), String> + 'static) -> Self {
+        self.with_form_validator(move |value, _| validator(value))
+    }
+
+    /// Builder-style method to add a validator that also gets the form data,
+    /// for instance to check that a password was repeated correctly.
+    pub fn with_form_validator(
+        mut self,
+        validator: impl Fn(&V, &T) -> Result<(), String> + 'static,
+    ) -> Self {
+        self.validators.push(Box::new(validator));
+        self
+    }
+
+    /// Builder-style method to wait until the value hasn't changed for
+    /// `delay` before validating it, so that errors don't flash up while
+    /// the user is still typing.
+    ///
+    /// Leaving the field still validates it immediately.
+    pub fn with_debounce(mut self, delay: Duration) -> Self {
+        self.debounce = Some(delay);
+        self
+    }
+
+    /// Whether the field has been validated, and passed.
+    pub fn is_valid(&self) -> bool {
+        self.state == FieldState::Valid
+    }
+
+    /// The current error message, if the field is invalid.
+    pub fn error(&self) -> Option<&str> {
+        match &self.state {
+            FieldState::Invalid(message) => Some(message),
+            _ => None,
+        }
+    }
+
+    /// Run the validators, returning `true` if the state changed.
+    fn validate(&mut self, data: &T) -> bool {
+        let validators = &self.validators;
+        let result = self.lens.with(data, |value| {
+            validators
+                .iter()
+                .try_for_each(|validator| validator(value, data))
+        });
+        let state = match result {
+            Ok(()) => FieldState::Valid,
+            Err(message) => FieldState::Invalid(message.into()),
+        };
+        if state == self.state {
+            return false;
+        }
+        trace!("field is now {:?}", state);
+        if let FieldState::Invalid(message) = &state {
+            self.error.set_text(message.clone());
+        }
+        self.state = state;
+        true
+    }
+}
+
+macro_rules! validate_and_report {
+    ($self:ident, $ctx:ident, $data:ident) => {
+        $self.timer = TimerToken::INVALID;
+        if $self.validate($data) {
+            $ctx.request_layout();
+        }
+        $ctx.submit_command(REPORT.to($ctx.widget_id()));
+    };
+}
+
+impl<T: Data, V: Data, L: Lens<T, V> + Clone, W: Widget<V>> Widget<T> for Validated<T, V, L, W> {
+    #[instrument(name = "Validated", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::Command(cmd) if cmd.is(REPORT) => {
+                ctx.submit_notification(FIELD_VALIDITY.with(self.is_valid()));
+                ctx.set_handled();
+                return;
+            }
+            Event::Timer(token) if *token == self.timer && *token != TimerToken::INVALID => {
+                validate_and_report!(self, ctx, data);
+                ctx.set_handled();
+                return;
+            }
+            _ => (),
+        }
+        self.child.event(ctx, event, data, env);
+    }
+
+    #[instrument(name = "Validated", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            // let the form know about us
+            ctx.submit_command(REPORT.to(ctx.widget_id()));
+        }
+        self.child.lifecycle(ctx, event, data, env);
+
+        let has_focus = ctx.has_focus();
+        if self.had_focus && !has_focus {
+            validate_and_report!(self, ctx, data);
+        }
+        self.had_focus = has_focus;
+    }
+
+    #[instrument(
+        name = "Validated",
+        level = "trace",
+        skip(self, ctx, old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        self.child.update(ctx, data, env);
+
+        let lens = &self.lens;
+        let value_changed = !lens.with(old_data, |old| lens.with(data, |new| old.same(new)));
+        if value_changed {
+            match self.debounce {
+                Some(delay) => self.timer = ctx.request_timer(delay),
+                None => {
+                    validate_and_report!(self, ctx, data);
+                }
+            }
+        } else if self.state != FieldState::Unvalidated && !old_data.same(data) {
+            // form validators may depend on the other fields
+            validate_and_report!(self, ctx, data);
+        }
+
+        if ctx.env_changed()
+            && (self.error.needs_rebuild_after_update(ctx)
+                || ctx.env_key_changed(&theme::FORM_ERROR_SPACING))
+        {
+            ctx.request_layout();
+        }
+    }
+
+    #[instrument(name = "Validated", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("Validated");
+        if self.error().is_none() {
+            let size = self.child.layout(ctx, bc, data, env);
+            self.child.set_origin(ctx, data, env, Point::ORIGIN);
+            ctx.set_baseline_offset(self.child.baseline_offset());
+            return size;
+        }
+
+        self.error.set_wrap_width(bc.max().width);
+        self.error.rebuild_if_needed(ctx.text(), env);
+        let error_size = self.error.size();
+        let error_height = error_size.height + env.get(theme::FORM_ERROR_SPACING);
+        let child_bc = bc.shrink((0.0, error_height));
+        let child_size = self.child.layout(ctx, &child_bc, data, env);
+        self.child.set_origin(ctx, data, env, Point::ORIGIN);
+
+        let size = bc.constrain(Size::new(
+            child_size.width.max(error_size.width),
+            child_size.height + error_height,
+        ));
+        ctx.set_baseline_offset(self.child.baseline_offset() + size.height - child_size.height);
+        trace!("Computed layout: size={}", size);
+        size
+    }
+
+    #[instrument(name = "Validated", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.child.paint(ctx, data, env);
+        if self.error().is_some() {
+            let color = env.get(theme::FORM_ERROR_COLOR);
+            let child_rect = self.child.layout_rect().inset(-0.5);
+            let outline = RoundedRect::from_rect(child_rect, env.get(theme::TEXTBOX_BORDER_RADIUS));
+            ctx.stroke(outline, &color, 1.0);
+            let spacing = env.get(theme::FORM_ERROR_SPACING);
+            let origin = Point::new(0.0, self.child.layout_rect().y1 + spacing);
+            self.error.draw(ctx, origin);
+        }
+    }
+
+    #[cfg(feature = "debug_state")]
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState {
+            display_name: "Validated".to_string(),
+            main_value: self.error().unwrap_or_default().to_string(),
+            children: vec![self.child.debug_state(data)],
+            ..Default::default()
+        }
+    }
+}
+
+/// A container that keeps track of whether all the [`Validated`] fields in
+/// it are valid.
+///
+/// The result is written to the `bool` that the lens points to, which is
+/// `false` until every field has been validated at least once. A submit
+/// button can then be disabled with [`WidgetExt::disabled_if`].
+///
+/// Fields that are removed from the tree are still counted, with the last
+/// state they reported.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{Button, Flex, FormScope, TextBox, Validated};
+/// use druid::{Data, Lens, WidgetExt};
+///
+/// #[derive(Clone, Data, Lens)]
+/// struct Signup {
+///     name: String,
+///     is_valid: bool,
+/// }
+///
+/// let name = Validated::new(TextBox::new(), Signup::name)
+///     .with_validator(|name: &String| match name.is_empty() {
+///         true => Err("Please enter a name".into()),
+///         false => Ok(()),
+///     });
+/// let submit = Button::new("Sign up").disabled_if(|form: &Signup, _| !form.is_valid);
+/// let form = FormScope::new(Signup::is_valid, Flex::column().with_child(name).with_child(submit));
+/// ```
+///
+/// [`WidgetExt::disabled_if`]: crate::WidgetExt::disabled_if
+pub struct FormScope<T, L, W> {
+    is_valid: L,
+    child: WidgetPod<T, W>,
+    fields: HashMap<WidgetId, bool>,
+}
+
+impl<T: Data, L: Lens<T, bool>, W: Widget<T>> FormScope<T, L, W> {
+    /// Create a new `FormScope` that writes whether the fields in `child`
+    /// are valid to the `bool` that `is_valid` points to.
+    pub fn new(is_valid: L, child: W) -> Self {
+        FormScope {
+            is_valid,
+            child: WidgetPod::new(child),
+            fields: HashMap::new(),
+        }
+    }
+}
+
+impl<T: Data, L: Lens<T, bool>, W: Widget<T>> Widget<T> for FormScope<T, L, W> {
+    #[instrument(name = "FormScope", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if let Event::Notification(note) = event {
+            if let Some(valid) = note.get(FIELD_VALIDITY) {
+                self.fields.insert(note.source(), *valid);
+                let all_valid = self.fields.values().all(|valid| *valid);
+                self.is_valid.with_mut(data, |is_valid| {
+                    if *is_valid != all_valid {
+                        trace!(
+                            "form is now {}",
+                            if all_valid { "valid" } else { "invalid" }
+                        );
+                        *is_valid = all_valid;
+                    }
+                });
+                ctx.set_handled();
+            }
+            return;
+        }
+        self.child.event(ctx, event, data, env);
+    }
+
+    #[instrument(name = "FormScope", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        self.child.lifecycle(ctx, event, data, env);
+    }
+
+    #[instrument(name = "FormScope", level = "trace", skip(self, ctx, _old, data, env))]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old: &T, data: &T, env: &Env) {
+        self.child.update(ctx, data, env);
+    }
+
+    #[instrument(name = "FormScope", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        let size = self.child.layout(ctx, bc, data, env);
+        self.child.set_origin(ctx, data, env, Point::ORIGIN);
+        ctx.set_paint_insets(self.child.paint_insets());
+        ctx.set_baseline_offset(self.child.baseline_offset());
+        size
+    }
+
+    #[instrument(name = "FormScope", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.child.paint(ctx, data, env);
+    }
+
+    #[cfg(feature = "debug_state")]
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState::with_children("FormScope", vec![self.child.debug_state(data)])
+    }
+}
+
+#[cfg(all(test, feature = "debug_state"))]
+mod tests {
+    use test_env_log::test;
+
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::{widget_ids, ModularWidget};
+    use crate::widget::{Button, Flex};
+    use crate::WidgetExt;
+
+    const SET: Selector<String> = Selector::new("druid-test.set");
+    const FOCUS: Selector = Selector::new("druid-test.focus");
+
+    #[derive(Clone, Data, Lens)]
+    struct Signup {
+        password: String,
+        confirm: String,
+        is_valid: bool,
+    }
+
+    /// An input that takes its new value from commands, and can be focused.
+    fn input(id: WidgetId) -> impl Widget<String> {
+        ModularWidget::new(())
+            .event_fn(|_, ctx, event, data: &mut String, _| {
+                if let Event::Command(cmd) = event {
+                    if let Some(value) = cmd.get(SET) {
+                        *data = value.clone();
+                    } else if cmd.is(FOCUS) {
+                        ctx.request_focus();
+                    }
+                }
+            })
+            .lifecycle_fn(|_, ctx, event, _, _| {
+                if let LifeCycle::BuildFocusChain = event {
+                    ctx.register_for_focus();
+                }
+            })
+            .with_id(id)
+    }
+
+    fn form(ids: [WidgetId; 5]) -> impl Widget<Signup> {
+        let [password_field, password, confirm_field, confirm, submit] = ids;
+        let password_input = Validated::new(input(password), Signup::password)
+            .with_validator(|password: &String| match password.len() {
+                0 => Err("Please enter a password".into()),
+                1..=7 => Err("Too short".into()),
+                _ => Ok(()),
+            })
+            .with_id(password_field);
+        let confirm_input = Validated::new(input(confirm), Signup::confirm)
+            .with_form_validator(|confirm, form: &Signup| match *confirm == form.password {
+                true => Ok(()),
+                false => Err("Passwords don't match".into()),
+            })
+            .with_id(confirm_field);
+        let submit_button = Button::new("Sign up")
+            .disabled_if(|form: &Signup, _| !form.is_valid)
+            .with_id(submit);
+        FormScope::new(
+            Signup::is_valid,
+            Flex::column()
+                .with_child(password_input)
+                .with_child(confirm_input)
+                .with_child(submit_button),
+        )
+    }
+
+    fn signup() -> Signup {
+        Signup {
+            password: String::new(),
+            confirm: String::new(),
+            is_valid: false,
+        }
+    }
+
+    fn error(harness: &Harness<Signup>, id: WidgetId) -> String {
+        harness
+            .root_debug_state()
+            .find(|state| state.widget_id == Some(id))
+            .map(|state| state.main_value.clone())
+            .unwrap()
+    }
+
+    #[test]
+    fn errors_show_and_clear() {
+        let ids = widget_ids();
+        let [password_field, password, _, _, _] = ids;
+
+        Harness::create_simple(signup(), form(ids), |harness| {
+            harness.send_initial_events();
+            assert_eq!(error(harness, password_field), "");
+
+            harness.submit_command(SET.with("hunter".into()).to(password));
+            assert_eq!(error(harness, password_field), "Too short");
+            assert!(!harness.data().is_valid);
+
+            harness.submit_command(SET.with("hunter22".into()).to(password));
+            assert_eq!(error(harness, password_field), "");
+        });
+    }
+
+    #[test]
+    fn form_validators_see_other_fields() {
+        let ids = widget_ids();
+        let [_, password, confirm_field, confirm, _] = ids;
+
+        Harness::create_simple(signup(), form(ids), |harness| {
+            harness.send_initial_events();
+            harness.submit_command(SET.with("hunter22".into()).to(password));
+            harness.submit_command(SET.with("hunter22".into()).to(confirm));
+            assert_eq!(error(harness, confirm_field), "");
+            assert!(harness.data().is_valid);
+
+            harness.submit_command(SET.with("hunter23".into()).to(password));
+            assert_eq!(error(harness, confirm_field), "Passwords don't match");
+            // fields report from `update`, so the form hears about it after the next pass
+            harness.just_layout();
+            assert!(!harness.data().is_valid);
+        });
+    }
+
+    #[test]
+    fn submit_waits_for_every_field() {
+        let ids = widget_ids();
+        let [_, password, confirm_field, confirm, submit] = ids;
+
+        Harness::create_simple(signup(), form(ids), |harness| {
+            harness.send_initial_events();
+            assert!(harness.get_state(submit).is_disabled());
+
+            // leaving a field validates it, even if it wasn't edited
+            harness.submit_command(FOCUS.to(confirm));
+            harness.submit_command(FOCUS.to(password));
+            assert_eq!(error(harness, confirm_field), "");
+            assert!(!harness.data().is_valid);
+
+            harness.submit_command(SET.with("hunter22".into()).to(password));
+            assert_eq!(error(harness, confirm_field), "Passwords don't match");
+            assert!(harness.get_state(submit).is_disabled());
+
+            harness.submit_command(SET.with("hunter22".into()).to(confirm));
+            assert!(harness.data().is_valid);
+            assert!(!harness.get_state(submit).is_disabled());
+        });
+    }
+}
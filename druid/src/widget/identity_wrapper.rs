@@ -16,7 +16,7 @@
 
 use crate::kurbo::Size;
 use crate::widget::prelude::*;
-use crate::widget::WidgetWrapper;
+use crate::widget::{Axis, Intrinsic, WidgetWrapper};
 use crate::Data;
 use tracing::instrument;
 
@@ -70,11 +70,29 @@ impl<T: Data, W: Widget<T>> Widget<T> for IdentityWrapper<W> {
         self.inner.layout(ctx, bc, data, env)
     }
 
+    fn compute_intrinsic_size(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        axis: Axis,
+        kind: Intrinsic,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        self.inner
+            .compute_intrinsic_size(ctx, axis, kind, bc, data, env)
+    }
+
     #[instrument(name = "IdentityWrapper", level = "trace", skip(self, ctx, data, env))]
     fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
         self.inner.paint(ctx, data, env);
     }
 
+    #[cfg(feature = "debug_state")]
+    fn debug_state(&self, data: &T) -> DebugState {
+        self.inner.debug_state(data)
+    }
+
     fn id(&self) -> Option<WidgetId> {
         Some(self.id)
     }
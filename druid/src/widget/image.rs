@@ -16,11 +16,13 @@
 //! Please consider using SVG and the SVG widget as it scales much better.
 
 use crate::{
+    commands,
     kurbo::Rect,
     piet::{Image as _, ImageBuf, InterpolationMode, PietImage},
     widget::common::FillStrat,
     widget::prelude::*,
-    Data,
+    widget::{Axis, Intrinsic},
+    AssetKey, AssetState, Data,
 };
 use tracing::{instrument, trace};
 
@@ -67,10 +69,23 @@ use tracing::{instrument, trace};
 /// image_widget.set_interpolation_mode(InterpolationMode::Bilinear);
 /// ```
 ///
+/// Images can also come from the app's asset cache, which loads them in the
+/// background and shares them between widgets:
+/// ```
+/// use druid::{widget::Image, AssetKey, ImageBuf};
+///
+/// let thumbnail = Image::from_asset(AssetKey::Path("thumbnails/cat.png".into()))
+///     .fallback(ImageBuf::empty());
+/// ```
+///
 /// [scaling a bitmap image]: ../struct.Scale.html#pixels-and-display-points
 /// [SVG files]: https://en.wikipedia.org/wiki/Scalable_Vector_Graphics
 pub struct Image {
     image_data: ImageBuf,
+    /// The key of the image in the asset cache, if that is where it comes from.
+    asset: Option<AssetKey>,
+    /// Shown if the asset fails to load.
+    fallback: Option<ImageBuf>,
     paint_data: Option<PietImage>,
     fill: FillStrat,
     interpolation: InterpolationMode,
@@ -91,6 +106,8 @@ impl Image {
     pub fn new(image_data: ImageBuf) -> Self {
         Image {
             image_data,
+            asset: None,
+            fallback: None,
             paint_data: None,
             fill: FillStrat::default(),
             interpolation: InterpolationMode::Bilinear,
@@ -98,6 +115,29 @@ impl Image {
         }
     }
 
+    /// Create an image widget that shows an image from the app's asset cache.
+    ///
+    /// The image is loaded in the background if it isn't cached yet; until
+    /// then, nothing is shown.
+    ///
+    /// See [`EventCtx::get_image`].
+    ///
+    /// [`EventCtx::get_image`]: crate::EventCtx::get_image
+    pub fn from_asset(key: AssetKey) -> Self {
+        Image {
+            asset: Some(key),
+            ..Image::new(ImageBuf::empty())
+        }
+    }
+
+    /// Builder-style method for setting the image that is shown if the asset
+    /// fails to load.
+    #[inline]
+    pub fn fallback(mut self, image_data: ImageBuf) -> Self {
+        self.fallback = Some(image_data);
+        self
+    }
+
     /// Builder-style method for specifying the fill strategy.
     #[inline]
     pub fn fill_mode(mut self, mode: FillStrat) -> Self {
@@ -154,6 +194,19 @@ impl Image {
         self.invalidate();
     }
 
+    /// Show the image from the asset cache, if it is ready.
+    fn set_asset_state(&mut self, state: AssetState) {
+        match state {
+            AssetState::Loaded(image_data) => self.set_image_data(image_data),
+            AssetState::Loading => (),
+            AssetState::Failed(_) => {
+                if let Some(fallback) = self.fallback.clone() {
+                    self.set_image_data(fallback);
+                }
+            }
+        }
+    }
+
     /// Invalidate the image cache, forcing it to be recreated.
     #[inline]
     fn invalidate(&mut self) {
@@ -162,11 +215,27 @@ impl Image {
 }
 
 impl<T: Data> Widget<T> for Image {
-    #[instrument(name = "Image", level = "trace", skip(self, _ctx, _event, _data, _env))]
-    fn event(&mut self, _ctx: &mut EventCtx, _event: &Event, _data: &mut T, _env: &Env) {}
+    #[instrument(name = "Image", level = "trace", skip(self, ctx, event, _data, _env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, _data: &mut T, _env: &Env) {
+        if let Event::Command(cmd) = event {
+            if let (Some(key), Some(asset)) = (cmd.get(commands::ASSET_LOADED), &self.asset) {
+                if key == asset {
+                    let state = ctx.get_image(key);
+                    self.set_asset_state(state);
+                    ctx.request_layout();
+                    ctx.set_handled();
+                }
+            }
+        }
+    }
 
-    #[instrument(name = "Image", level = "trace", skip(self, _ctx, _event, _data, _env))]
-    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle, _data: &T, _env: &Env) {}
+    #[instrument(name = "Image", level = "trace", skip(self, ctx, event, _data, _env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, _data: &T, _env: &Env) {
+        if let (LifeCycle::WidgetAdded, Some(key)) = (event, &self.asset) {
+            let state = ctx.get_image(key);
+            self.set_asset_state(state);
+        }
+    }
 
     #[instrument(
         name = "Image",
@@ -207,6 +276,22 @@ impl<T: Data> Widget<T> for Image {
         size
     }
 
+    fn compute_intrinsic_size(
+        &mut self,
+        _ctx: &mut LayoutCtx,
+        axis: Axis,
+        kind: Intrinsic,
+        _bc: &BoxConstraints,
+        _data: &T,
+        _env: &Env,
+    ) -> f64 {
+        // an image can be scaled down as far as needed
+        match kind {
+            Intrinsic::Min => 0.0,
+            Intrinsic::Preferred => axis.major(self.image_data.size()),
+        }
+    }
+
     #[instrument(name = "Image", level = "trace", skip(self, ctx, _data, _env))]
     fn paint(&mut self, ctx: &mut PaintCtx, _data: &T, _env: &Env) {
         let offset_matrix = self.fill.affine_to_fill(ctx.size(), self.image_data.size());
@@ -335,6 +420,52 @@ mod tests {
         )
     }
 
+    /// An asset is painted once it is loaded, and a failed one shows the fallback.
+    #[test]
+    fn asset_paint() {
+        use crate::tests::harness::Harness;
+        use std::sync::Arc;
+
+        let checker = ImageBuf::from_raw(
+            vec![255, 255, 255, 0, 0, 0, 0, 0, 0, 255, 255, 255],
+            ImageFormat::Rgb,
+            2,
+            2,
+        );
+        let loaded = checker.clone();
+        let loader = Arc::new(move |name: &str| match name {
+            "checker" => Ok(loaded.clone()),
+            _ => Err(format!("no asset called {}", name)),
+        });
+
+        let loaded_widget = Image::from_asset(AssetKey::Custom("checker".into()));
+        let failed_widget = Image::from_asset(AssetKey::Custom("missing".into())).fallback(checker);
+        for widget in [loaded_widget, failed_widget] {
+            let widget = widget.interpolation_mode(InterpolationMode::NearestNeighbor);
+            let loader = loader.clone();
+            Harness::create_with_render(
+                (),
+                widget,
+                Size::new(400., 600.),
+                |harness| {
+                    harness.window_mut().asset_cache.set_loader(loader.clone());
+                    harness.send_initial_events();
+                    harness.just_layout();
+                    harness.wait_for_ext_events();
+                    harness.just_layout();
+                    harness.paint();
+                },
+                |target| {
+                    let raw_pixels = target.into_raw();
+                    // the same middle row as in `tall_paint`
+                    let expecting: Vec<u8> =
+                        [[0, 0, 0, 255].repeat(200), [255, 255, 255, 255].repeat(200)].concat();
+                    assert_eq!(raw_pixels[400 * 300 * 4..400 * 301 * 4], expecting[..]);
+                },
+            );
+        }
+    }
+
     #[test]
     fn wide_paint() {
         use crate::{tests::harness::Harness, WidgetId};
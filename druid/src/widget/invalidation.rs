@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use crate::widget::prelude::*;
+use crate::widget::{Axis, Intrinsic};
 use crate::Data;
 use tracing::instrument;
 
@@ -72,6 +73,19 @@ impl<T: Data, W: Widget<T>> Widget<T> for DebugInvalidation<T, W> {
         self.inner.layout(ctx, bc, data, env)
     }
 
+    fn compute_intrinsic_size(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        axis: Axis,
+        kind: Intrinsic,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        self.inner
+            .compute_intrinsic_size(ctx, axis, kind, bc, data, env)
+    }
+
     #[instrument(
         name = "DebugInvalidation",
         level = "trace",
@@ -90,6 +104,11 @@ impl<T: Data, W: Widget<T>> Widget<T> for DebugInvalidation<T, W> {
         self.debug_color += 1;
     }
 
+    #[cfg(feature = "debug_state")]
+    fn debug_state(&self, data: &T) -> DebugState {
+        self.inner.debug_state(data)
+    }
+
     fn id(&self) -> Option<WidgetId> {
         self.inner.id()
     }
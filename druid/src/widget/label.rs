@@ -21,11 +21,13 @@ use druid_shell::Cursor;
 use crate::kurbo::Vec2;
 use crate::text::TextStorage;
 use crate::widget::prelude::*;
+use crate::widget::{Axis, Intrinsic};
 use crate::{
     ArcStr, Color, Data, FontDescriptor, KeyOrValue, LocalizedString, Point, TextAlignment,
     TextLayout,
 };
 use tracing::{instrument, trace};
+use xi_unicode::LineBreakIterator;
 
 // added padding between the edges of the widget and the text.
 const LABEL_X_PADDING: f64 = 2.0;
@@ -287,6 +289,38 @@ impl<T: TextStorage> RawLabel<T> {
         let text_metrics = self.layout.layout_metrics();
         text_metrics.size.height - text_metrics.first_baseline
     }
+
+    /// The width of the widest run of text that can't be wrapped, with the
+    /// padding.
+    ///
+    /// We can't just wrap the text at zero width, because words that don't
+    /// fit on a line get broken up.
+    fn longest_word_width(&mut self, ctx: &mut LayoutCtx, env: &Env) -> f64 {
+        let mut widest = 0.0f64;
+        self.text_size(ctx, f64::INFINITY, env);
+        if let Some(text) = self.layout.text() {
+            let text = text.as_str();
+            let mut start = 0;
+            for (end, _) in LineBreakIterator::new(text) {
+                let end = start + text[start..end].trim_end().len();
+                let x0 = self.layout.point_for_text_position(start).x;
+                let x1 = self.layout.point_for_text_position(end).x;
+                widest = widest.max((x1 - x0).abs());
+                start = end;
+            }
+        }
+        widest + 2. * LABEL_X_PADDING
+    }
+
+    /// The size of the text wrapped at `wrap_width`, with the padding.
+    ///
+    /// This leaves the text wrapped at `wrap_width`; `layout` sets it back.
+    fn text_size(&mut self, ctx: &mut LayoutCtx, wrap_width: f64, env: &Env) -> Size {
+        self.layout.set_wrap_width(wrap_width);
+        self.layout.rebuild_if_needed(ctx.text_factory(), env);
+        let size = self.layout.layout_metrics().size;
+        Size::new(size.width + 2. * LABEL_X_PADDING, size.height)
+    }
 }
 
 impl<T: TextStorage> Label<T> {
@@ -519,6 +553,19 @@ impl<T: Data> Widget<T> for Label<T> {
         self.label.layout(ctx, bc, &self.current_text, env)
     }
 
+    fn compute_intrinsic_size(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        axis: Axis,
+        kind: Intrinsic,
+        bc: &BoxConstraints,
+        _data: &T,
+        env: &Env,
+    ) -> f64 {
+        self.label
+            .compute_intrinsic_size(ctx, axis, kind, bc, &self.current_text, env)
+    }
+
     #[instrument(name = "Label", level = "trace", skip(self, ctx, _data, env))]
     fn paint(&mut self, ctx: &mut PaintCtx, _data: &T, env: &Env) {
         if self.text_should_be_updated {
@@ -526,6 +573,11 @@ impl<T: Data> Widget<T> for Label<T> {
         }
         self.label.paint(ctx, &self.current_text, env)
     }
+
+    #[cfg(feature = "debug_state")]
+    fn debug_state(&self, _data: &T) -> DebugState {
+        DebugState::with_value("Label", self.text.display_text().to_string())
+    }
 }
 
 impl<T: TextStorage> Widget<T> for RawLabel<T> {
@@ -601,7 +653,7 @@ impl<T: TextStorage> Widget<T> for RawLabel<T> {
         };
 
         self.layout.set_wrap_width(width);
-        self.layout.rebuild_if_needed(ctx.text(), env);
+        self.layout.rebuild_if_needed(ctx.text_factory(), env);
 
         let text_metrics = self.layout.layout_metrics();
         ctx.set_baseline_offset(text_metrics.size.height - text_metrics.first_baseline);
@@ -613,6 +665,32 @@ impl<T: TextStorage> Widget<T> for RawLabel<T> {
         size
     }
 
+    fn compute_intrinsic_size(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        axis: Axis,
+        kind: Intrinsic,
+        bc: &BoxConstraints,
+        _data: &T,
+        env: &Env,
+    ) -> f64 {
+        match (axis, kind, self.line_break_mode) {
+            // A clipped label can be as narrow as it has to be.
+            (Axis::Horizontal, Intrinsic::Min, LineBreaking::Clip) => 0.0,
+            // The narrowest a wrapping label gets is the width of its
+            // longest word.
+            (Axis::Horizontal, Intrinsic::Min, LineBreaking::WordWrap) => {
+                self.longest_word_width(ctx, env)
+            }
+            (Axis::Horizontal, _, _) => self.text_size(ctx, f64::INFINITY, env).width,
+            (Axis::Vertical, _, LineBreaking::WordWrap) => {
+                let wrap_width = bc.max().width - LABEL_X_PADDING * 2.0;
+                self.text_size(ctx, wrap_width, env).height
+            }
+            (Axis::Vertical, _, _) => self.text_size(ctx, f64::INFINITY, env).height,
+        }
+    }
+
     #[instrument(name = "RawLabel", level = "trace", skip(self, ctx, _data, _env))]
     fn paint(&mut self, ctx: &mut PaintCtx, _data: &T, _env: &Env) {
         let origin = Point::new(LABEL_X_PADDING, 0.0);
@@ -623,6 +701,11 @@ impl<T: TextStorage> Widget<T> for RawLabel<T> {
         }
         self.draw_at(ctx, origin)
     }
+
+    #[cfg(feature = "debug_state")]
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState::with_value("RawLabel", data.as_str())
+    }
 }
 
 impl<T: TextStorage> Default for RawLabel<T> {
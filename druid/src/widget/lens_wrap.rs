@@ -21,7 +21,7 @@
 use std::marker::PhantomData;
 
 use crate::widget::prelude::*;
-use crate::widget::WidgetWrapper;
+use crate::widget::{Axis, Intrinsic, WidgetWrapper};
 use crate::{Data, Lens};
 
 use tracing::{instrument, trace};
@@ -128,12 +128,32 @@ where
             .with(data, |data| inner.layout(ctx, bc, data, env))
     }
 
+    fn compute_intrinsic_size(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        axis: Axis,
+        kind: Intrinsic,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        let inner = &mut self.inner;
+        self.lens.with(data, |data| {
+            inner.compute_intrinsic_size(ctx, axis, kind, bc, data, env)
+        })
+    }
+
     #[instrument(name = "LensWrap", level = "trace", skip(self, ctx, data, env))]
     fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
         let inner = &mut self.inner;
         self.lens.with(data, |data| inner.paint(ctx, data, env));
     }
 
+    #[cfg(feature = "debug_state")]
+    fn debug_state(&self, data: &T) -> DebugState {
+        self.lens.with(data, |data| self.inner.debug_state(data))
+    }
+
     fn id(&self) -> Option<WidgetId> {
         self.inner.id()
     }
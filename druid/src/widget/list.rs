@@ -26,9 +26,11 @@ use crate::im::{OrdMap, Vector};
 
 use crate::kurbo::{Point, Rect, Size};
 
+#[cfg(feature = "debug_state")]
+use crate::DebugState;
 use crate::{
-    widget::Axis, BoxConstraints, Data, Env, Event, EventCtx, KeyOrValue, LayoutCtx, LifeCycle,
-    LifeCycleCtx, PaintCtx, UpdateCtx, Widget, WidgetPod,
+    theme, widget::Axis, BoxConstraints, Data, Env, Event, EventCtx, KeyOrValue, LayoutCtx,
+    LifeCycle, LifeCycleCtx, PaintCtx, UpdateCtx, Widget, WidgetPod,
 };
 
 /// A list widget for a variable-size collection of items.
@@ -47,7 +49,7 @@ impl<T: Data> List<T> {
             closure: Box::new(move || Box::new(closure())),
             children: Vec::new(),
             axis: Axis::Vertical,
-            spacing: KeyOrValue::Concrete(0.),
+            spacing: theme::LIST_ITEM_SPACING.into(),
         }
     }
 
@@ -58,6 +60,8 @@ impl<T: Data> List<T> {
     }
 
     /// Set the spacing between elements.
+    ///
+    /// The default is [`theme::LIST_ITEM_SPACING`].
     pub fn with_spacing(mut self, spacing: impl Into<KeyOrValue<f64>>) -> Self {
         self.spacing = spacing.into();
         self
@@ -69,6 +73,21 @@ impl<T: Data> List<T> {
         self
     }
 
+    /// The axis along which children are laid out.
+    pub(crate) fn axis(&self) -> Axis {
+        self.axis
+    }
+
+    /// The number of children currently instantiated.
+    pub(crate) fn child_count(&self) -> usize {
+        self.children.len()
+    }
+
+    /// The layout rect of the child at `index`, in the list's coordinate space.
+    pub(crate) fn child_layout_rect(&self, index: usize) -> Option<Rect> {
+        self.children.get(index).map(|child| child.layout_rect())
+    }
+
     /// When the widget is created or the data changes, create or remove children as needed
     ///
     /// Returns `true` if children were added or removed.
@@ -362,6 +381,10 @@ impl<C: Data, T: ListIter<C>> Widget<T> for List<C> {
         if self.update_child_count(data, env) {
             ctx.children_changed();
         }
+
+        if ctx.env_key_changed(&self.spacing) {
+            ctx.request_layout();
+        }
     }
 
     #[instrument(name = "List", level = "trace", skip(self, ctx, bc, data, env))]
@@ -407,4 +430,16 @@ impl<C: Data, T: ListIter<C>> Widget<T> for List<C> {
             }
         });
     }
+
+    #[cfg(feature = "debug_state")]
+    fn debug_state(&self, data: &T) -> DebugState {
+        let mut children = Vec::with_capacity(self.children.len());
+        let mut pods = self.children.iter();
+        data.for_each(|child_data, _| {
+            if let Some(child) = pods.next() {
+                children.push(child.debug_state(child_data));
+            }
+        });
+        DebugState::with_children("List", children)
+    }
 }
@@ -0,0 +1,892 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A list with a keyboard and mouse driven selection.
+
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use instant::{Duration, Instant};
+use tracing::{instrument, trace};
+
+use crate::widget::prelude::*;
+use crate::widget::{Axis, List, ListIter};
+use crate::{theme, KbKey, KeyEvent, Modifiers, Point, Rect, Selector};
+
+/// How long the type-ahead buffer is kept between key presses.
+const TYPE_AHEAD_TIMEOUT: Duration = Duration::from_millis(1000);
+
+/// The number of rows moved by PageUp and PageDown, unless configured otherwise.
+const DEFAULT_PAGE_SIZE: usize = 10;
+
+/// Produces the text that type-ahead matches a row against.
+type RowText<T> = dyn Fn(&T) -> String;
+
+/// The selection state of a [`SelectableList`].
+///
+/// This is plain [`Data`], and is meant to live in your application state
+/// next to the collection it refers to. Selected rows are stored as indices
+/// into that collection.
+///
+/// Besides the set of selected indices, a selection tracks a *cursor*, which
+/// is the row that keyboard navigation moves from, and an *anchor*, which is
+/// the row that range selection (Shift+click, Shift+arrow) extends from.
+#[derive(Debug, Clone, Default, PartialEq, Data)]
+pub struct ListSelection {
+    selected: Arc<BTreeSet<usize>>,
+    cursor: Option<usize>,
+    anchor: Option<usize>,
+}
+
+/// A [`List`] that maintains a [`ListSelection`].
+///
+/// The data for this widget is a tuple of the collection and its selection;
+/// you will generally build it from two lenses into your own state:
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use druid::widget::{Label, ListSelection, SelectableList};
+/// # use druid::{Data, Lens, Widget, WidgetExt};
+/// #[derive(Clone, Data, Lens)]
+/// struct AppState {
+///     songs: Arc<Vec<String>>,
+///     selection: ListSelection,
+/// }
+///
+/// fn playlist() -> impl Widget<AppState> {
+///     SelectableList::new(|| Label::dynamic(|song: &String, _| song.clone()))
+///         .multi_select(true)
+///         .with_type_ahead(|song: &String| song.clone())
+///         .lens((AppState::songs, AppState::selection))
+///         .scroll()
+/// }
+/// ```
+///
+/// Rows are selected by clicking them; with [`multi_select`] enabled, Ctrl
+/// (Cmd on macOS) toggles a single row and Shift selects a range. When the
+/// list is focused, the arrow keys, Home, End, PageUp and PageDown move the
+/// selection, and the closest [`Scroll`] ancestor follows it. Double clicking
+/// a row or pressing Enter sends an [`ACTIVATE`] notification.
+///
+/// [`multi_select`]: SelectableList::multi_select
+/// [`Scroll`]: crate::widget::Scroll
+/// [`ACTIVATE`]: SelectableList::ACTIVATE
+pub struct SelectableList<T> {
+    list: List<T>,
+    multi_select: bool,
+    page_size: usize,
+    row_text: Option<Box<RowText<T>>>,
+    type_ahead: String,
+    last_type_ahead: Option<Instant>,
+}
+
+impl ListSelection {
+    /// Create a new, empty selection.
+    pub fn new() -> ListSelection {
+        ListSelection::default()
+    }
+
+    /// Returns `true` if no rows are selected.
+    pub fn is_empty(&self) -> bool {
+        self.selected.is_empty()
+    }
+
+    /// The number of selected rows.
+    pub fn len(&self) -> usize {
+        self.selected.len()
+    }
+
+    /// Returns `true` if the row at `index` is selected.
+    pub fn is_selected(&self, index: usize) -> bool {
+        self.selected.contains(&index)
+    }
+
+    /// An iterator over the selected indices, in ascending order.
+    pub fn selected(&self) -> impl Iterator<Item = usize> + '_ {
+        self.selected.iter().copied()
+    }
+
+    /// The row that keyboard navigation starts from.
+    pub fn cursor(&self) -> Option<usize> {
+        self.cursor
+    }
+
+    /// The row that range selections extend from.
+    pub fn anchor(&self) -> Option<usize> {
+        self.anchor
+    }
+
+    /// Select only the row at `index`, moving the cursor and anchor to it.
+    pub fn select(&mut self, index: usize) {
+        if self.selected.len() != 1 || !self.is_selected(index) {
+            let selected = Arc::make_mut(&mut self.selected);
+            selected.clear();
+            selected.insert(index);
+        }
+        self.cursor = Some(index);
+        self.anchor = Some(index);
+    }
+
+    /// Toggle the row at `index`, leaving the rest of the selection alone.
+    ///
+    /// The cursor and anchor are moved to `index`.
+    pub fn toggle(&mut self, index: usize) {
+        let selected = Arc::make_mut(&mut self.selected);
+        if !selected.remove(&index) {
+            selected.insert(index);
+        }
+        self.cursor = Some(index);
+        self.anchor = Some(index);
+    }
+
+    /// Select every row between the anchor and `index`, inclusive, replacing
+    /// the current selection.
+    ///
+    /// The cursor moves to `index`; the anchor is unchanged, unless there
+    /// was no anchor, in which case this behaves like [`select`].
+    ///
+    /// [`select`]: ListSelection::select
+    pub fn select_range(&mut self, index: usize) {
+        let anchor = match self.anchor {
+            Some(anchor) => anchor,
+            None => return self.select(index),
+        };
+        let (start, end) = if anchor <= index {
+            (anchor, index)
+        } else {
+            (index, anchor)
+        };
+        self.selected = Arc::new((start..=end).collect());
+        self.cursor = Some(index);
+    }
+
+    /// Select every row in a collection of length `len`.
+    pub fn select_all(&mut self, len: usize) {
+        self.selected = Arc::new((0..len).collect());
+        if len > 0 {
+            self.anchor = self.anchor.or(Some(0));
+            self.cursor = self.cursor.or(Some(0));
+        }
+    }
+
+    /// Move the cursor to `index` without changing which rows are selected.
+    pub fn set_cursor(&mut self, index: usize) {
+        self.cursor = Some(index);
+    }
+
+    /// Deselect everything and reset the cursor and anchor.
+    pub fn clear(&mut self) {
+        if !self.selected.is_empty() {
+            self.selected = Arc::default();
+        }
+        self.cursor = None;
+        self.anchor = None;
+    }
+
+    /// Update the selection after the item at `index` was removed from the
+    /// collection.
+    ///
+    /// The removed row is deselected, and every index after it is shifted
+    /// down by one, so that the selection keeps referring to the same items.
+    pub fn remove_index(&mut self, index: usize) {
+        if self.selected.iter().any(|&i| i >= index) {
+            self.selected = Arc::new(
+                self.selected
+                    .iter()
+                    .filter(|&&i| i != index)
+                    .map(|&i| if i > index { i - 1 } else { i })
+                    .collect(),
+            );
+        }
+        let shift = |i: usize| if i > index { i - 1 } else { i };
+        self.cursor = self.cursor.map(shift);
+        self.anchor = self.anchor.map(shift);
+    }
+
+    /// Update the selection after an item was inserted at `index`.
+    ///
+    /// Every index at or after `index` is shifted up by one.
+    pub fn insert_index(&mut self, index: usize) {
+        if self.selected.iter().any(|&i| i >= index) {
+            self.selected = Arc::new(
+                self.selected
+                    .iter()
+                    .map(|&i| if i >= index { i + 1 } else { i })
+                    .collect(),
+            );
+        }
+        let shift = |i: usize| if i >= index { i + 1 } else { i };
+        self.cursor = self.cursor.map(shift);
+        self.anchor = self.anchor.map(shift);
+    }
+
+    /// Make the selection valid for a collection of length `len`.
+    ///
+    /// Selected indices past the end are dropped, and the cursor and anchor
+    /// are clamped to the last row. This does nothing if the selection is
+    /// already valid, so it is cheap to call whenever the collection may
+    /// have shrunk.
+    pub fn truncate(&mut self, len: usize) {
+        if matches!(self.selected.iter().next_back(), Some(&i) if i >= len) {
+            self.selected = Arc::new(self.selected.range(..len).copied().collect());
+        }
+        let clamp = |i: usize| if len == 0 { None } else { Some(i.min(len - 1)) };
+        self.cursor = self.cursor.and_then(clamp);
+        self.anchor = self.anchor.and_then(clamp);
+    }
+}
+
+impl SelectableList<()> {
+    /// A notification sent when the user activates the selection, by double
+    /// clicking a row or pressing Enter.
+    ///
+    /// The payload is the list of selected indices, in ascending order.
+    pub const ACTIVATE: Selector<Vec<usize>> = Selector::new("druid-builtin.list-activate");
+}
+
+impl<T: Data> SelectableList<T> {
+    /// Create a new selectable list. The closure will be called every time
+    /// a new row needs to be constructed, as with [`List::new`].
+    pub fn new<W: Widget<T> + 'static>(closure: impl Fn() -> W + 'static) -> Self {
+        SelectableList::from_list(List::new(closure))
+    }
+
+    /// Add selection behaviour to an existing [`List`].
+    pub fn from_list(list: List<T>) -> Self {
+        SelectableList {
+            list,
+            multi_select: false,
+            page_size: DEFAULT_PAGE_SIZE,
+            row_text: None,
+            type_ahead: String::new(),
+            last_type_ahead: None,
+        }
+    }
+
+    /// Builder-style method to set whether more than one row can be selected.
+    pub fn multi_select(mut self, multi_select: bool) -> Self {
+        self.multi_select = multi_select;
+        self
+    }
+
+    /// Builder-style method to set the number of rows that PageUp and
+    /// PageDown move the selection by.
+    pub fn page_size(mut self, rows: usize) -> Self {
+        self.page_size = rows.max(1);
+        self
+    }
+
+    /// Builder-style method to enable type-ahead selection.
+    ///
+    /// When the list is focused, typing selects the next row whose text, as
+    /// returned by the closure, starts with the typed characters. Matching
+    /// ignores case.
+    pub fn with_type_ahead(mut self, row_text: impl Fn(&T) -> String + 'static) -> Self {
+        self.row_text = Some(Box::new(row_text));
+        self
+    }
+
+    /// The rect of the row at `index`, stretched to fill the list's cross axis.
+    fn row_rect(&self, index: usize, size: Size) -> Option<Rect> {
+        let rect = self.list.child_layout_rect(index)?;
+        Some(match self.list.axis() {
+            Axis::Vertical => Rect::new(0.0, rect.y0, size.width.max(rect.x1), rect.y1),
+            Axis::Horizontal => Rect::new(rect.x0, 0.0, rect.x1, size.height.max(rect.y1)),
+        })
+    }
+
+    /// The index of the row under `pos`, if any.
+    fn row_at(&self, pos: Point, size: Size) -> Option<usize> {
+        (0..self.list.child_count())
+            .find(|&i| matches!(self.row_rect(i, size), Some(rect) if rect.contains(pos)))
+    }
+
+    /// Apply a click on `index` to the selection, respecting modifiers.
+    fn click_row(&self, selection: &mut ListSelection, index: usize, mods: Modifiers) {
+        if self.multi_select && mods.shift() {
+            selection.select_range(index);
+        } else if self.multi_select && toggle_modifier(mods) {
+            selection.toggle(index);
+        } else {
+            selection.select(index);
+        }
+    }
+
+    /// Move the cursor to `index`, updating the selection as a keyboard
+    /// navigation would.
+    fn move_cursor(&self, selection: &mut ListSelection, index: usize, mods: Modifiers) {
+        if self.multi_select && mods.shift() {
+            selection.select_range(index);
+        } else if self.multi_select && toggle_modifier(mods) {
+            selection.set_cursor(index);
+        } else {
+            selection.select(index);
+        }
+    }
+
+    /// Find the row to select for the typed character `c`.
+    fn type_ahead<I: ListIter<T>>(
+        &mut self,
+        items: &I,
+        cursor: Option<usize>,
+        c: &str,
+    ) -> Option<usize> {
+        let row_text = self.row_text.as_ref()?;
+        let now = Instant::now();
+        let expired = match self.last_type_ahead {
+            Some(last) => now.duration_since(last) > TYPE_AHEAD_TIMEOUT,
+            None => true,
+        };
+        if expired {
+            self.type_ahead.clear();
+        }
+        self.last_type_ahead = Some(now);
+        self.type_ahead.push_str(&c.to_lowercase());
+
+        // A single character cycles through matching rows; a longer prefix
+        // refines the current match, so it may stay on the current row.
+        let start = match cursor {
+            Some(cursor) if self.type_ahead.chars().count() == 1 => cursor + 1,
+            Some(cursor) => cursor,
+            None => 0,
+        };
+        let len = items.data_len();
+        let mut matches = Vec::new();
+        items.for_each(|item, i| {
+            if row_text(item).to_lowercase().starts_with(&self.type_ahead) {
+                matches.push(i);
+            }
+        });
+        matches
+            .iter()
+            .find(|&&i| i >= start % len.max(1))
+            .or_else(|| matches.first())
+            .copied()
+    }
+
+    fn activate(&self, ctx: &mut EventCtx, selection: &ListSelection) {
+        if !selection.is_empty() {
+            let indices = selection.selected().collect();
+            ctx.submit_notification(SelectableList::ACTIVATE.with(indices));
+        }
+    }
+
+    fn handle_key<I: ListIter<T>>(
+        &mut self,
+        ctx: &mut EventCtx,
+        key: &KeyEvent,
+        items: &I,
+        selection: &mut ListSelection,
+    ) -> bool {
+        let len = items.data_len();
+        if len == 0 {
+            return false;
+        }
+        let last = len - 1;
+        let cursor = selection.cursor();
+        let (prev_key, next_key) = match self.list.axis() {
+            Axis::Vertical => (KbKey::ArrowUp, KbKey::ArrowDown),
+            Axis::Horizontal => (KbKey::ArrowLeft, KbKey::ArrowRight),
+        };
+
+        let target = match &key.key {
+            k if *k == prev_key => Some(cursor.map_or(last, |c| c.saturating_sub(1))),
+            k if *k == next_key => Some(cursor.map_or(0, |c| (c + 1).min(last))),
+            KbKey::Home => Some(0),
+            KbKey::End => Some(last),
+            KbKey::PageUp => Some(cursor.map_or(0, |c| c.saturating_sub(self.page_size))),
+            KbKey::PageDown => Some(cursor.map_or(0, |c| (c + self.page_size).min(last))),
+            KbKey::Enter => {
+                self.activate(ctx, selection);
+                return true;
+            }
+            KbKey::Character(c) if c == " " && toggle_modifier(key.mods) && self.multi_select => {
+                if let Some(cursor) = cursor {
+                    selection.toggle(cursor);
+                }
+                return true;
+            }
+            KbKey::Character(c) if c == "a" && toggle_modifier(key.mods) && self.multi_select => {
+                selection.select_all(len);
+                return true;
+            }
+            KbKey::Character(c) if !key.mods.ctrl() && !key.mods.meta() && !key.mods.alt() => {
+                match self.type_ahead(items, cursor, c) {
+                    Some(index) => {
+                        selection.select(index);
+                        Some(index)
+                    }
+                    None => return self.row_text.is_some(),
+                }
+            }
+            _ => None,
+        };
+
+        match target {
+            Some(index) => {
+                if !matches!(key.key, KbKey::Character(_)) {
+                    self.move_cursor(selection, index, key.mods);
+                }
+                if let Some(rect) = self.row_rect(index, ctx.size()) {
+                    ctx.scroll_area_to_view(rect);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn paint_selection(&self, ctx: &mut PaintCtx, selection: &ListSelection, env: &Env) {
+        let color = if ctx.has_focus() {
+            env.get(theme::SELECTED_TEXT_BACKGROUND_COLOR)
+        } else {
+            env.get(theme::SELECTED_TEXT_INACTIVE_BACKGROUND_COLOR)
+        };
+        for index in selection.selected() {
+            if let Some(rect) = self.row_rect(index, ctx.size()) {
+                ctx.fill(rect, &color);
+            }
+        }
+
+        if ctx.is_focused() {
+            if let Some(rect) = selection
+                .cursor()
+                .and_then(|index| self.row_rect(index, ctx.size()))
+            {
+                let rect = rect.inset(-0.5);
+                ctx.stroke(rect, &env.get(theme::PRIMARY_LIGHT), 1.0);
+            }
+        }
+    }
+}
+
+impl<T: Data, I: ListIter<T>> Widget<(I, ListSelection)> for SelectableList<T> {
+    #[instrument(
+        name = "SelectableList",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn event(
+        &mut self,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut (I, ListSelection),
+        env: &Env,
+    ) {
+        let (items, selection) = data;
+        self.list.event(ctx, event, items, env);
+        // The collection may have shrunk since we last saw it.
+        selection.truncate(items.data_len());
+        if ctx.is_handled() || ctx.is_disabled() {
+            return;
+        }
+
+        match event {
+            Event::MouseDown(mouse) if mouse.button.is_left() => {
+                if let Some(index) = self.row_at(mouse.pos, ctx.size()) {
+                    ctx.request_focus();
+                    if mouse.count == 2 {
+                        self.activate(ctx, selection);
+                    } else {
+                        self.click_row(selection, index, mouse.mods);
+                    }
+                    ctx.request_paint();
+                    ctx.set_handled();
+                }
+            }
+            Event::KeyDown(key) if ctx.is_focused() => {
+                let changed = self.handle_key(ctx, key, items, selection);
+                if changed {
+                    trace!("selection changed to {:?}", selection);
+                    ctx.request_paint();
+                    ctx.set_handled();
+                }
+            }
+            _ => (),
+        }
+    }
+
+    #[instrument(
+        name = "SelectableList",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn lifecycle(
+        &mut self,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &(I, ListSelection),
+        env: &Env,
+    ) {
+        match event {
+            LifeCycle::BuildFocusChain => ctx.register_for_focus(),
+            LifeCycle::FocusChanged(_) => ctx.request_paint(),
+            _ => (),
+        }
+        self.list.lifecycle(ctx, event, &data.0, env);
+    }
+
+    #[instrument(
+        name = "SelectableList",
+        level = "trace",
+        skip(self, ctx, old_data, data, env)
+    )]
+    fn update(
+        &mut self,
+        ctx: &mut UpdateCtx,
+        old_data: &(I, ListSelection),
+        data: &(I, ListSelection),
+        env: &Env,
+    ) {
+        if !old_data.1.same(&data.1) {
+            ctx.request_paint();
+        }
+        self.list.update(ctx, &old_data.0, &data.0, env);
+    }
+
+    #[instrument(
+        name = "SelectableList",
+        level = "trace",
+        skip(self, ctx, bc, data, env)
+    )]
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &(I, ListSelection),
+        env: &Env,
+    ) -> Size {
+        self.list.layout(ctx, bc, &data.0, env)
+    }
+
+    #[instrument(name = "SelectableList", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &(I, ListSelection), env: &Env) {
+        self.paint_selection(ctx, &data.1, env);
+        self.list.paint(ctx, &data.0, env);
+    }
+
+    #[cfg(feature = "debug_state")]
+    fn debug_state(&self, data: &(I, ListSelection)) -> DebugState {
+        let (items, selection) = data;
+        let mut children = self.list.debug_state(items).children;
+        for (i, child) in children.iter_mut().enumerate() {
+            let selected = selection.is_selected(i).to_string();
+            child.other_values.insert("selected".into(), selected);
+        }
+        DebugState {
+            main_value: format!("{:?}", selection.selected().collect::<Vec<_>>()),
+            children,
+            ..DebugState::new("SelectableList")
+        }
+    }
+}
+
+/// The modifier that toggles single rows: Cmd on macOS, Ctrl elsewhere.
+fn toggle_modifier(mods: Modifiers) -> bool {
+    if cfg!(target_os = "macos") {
+        mods.meta()
+    } else {
+        mods.ctrl()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::{widget_ids, ModularWidget};
+    use crate::widget::{Label, Scroll};
+    use crate::{MouseButton, MouseButtons, MouseEvent, Vec2, WidgetExt, WidgetPod};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use test_env_log::test;
+
+    type Items = Arc<Vec<String>>;
+
+    const REMOVE_LAST: Selector = Selector::new("druid-test.remove-last");
+
+    fn items(names: &[&str]) -> (Items, ListSelection) {
+        let items = names.iter().map(|s| s.to_string()).collect();
+        (Arc::new(items), ListSelection::new())
+    }
+
+    fn selected(data: &(Items, ListSelection)) -> Vec<usize> {
+        data.1.selected().collect()
+    }
+
+    fn click(pos: impl Into<Point>, mods: Modifiers, count: u8) -> Event {
+        let pos = pos.into();
+        Event::MouseDown(MouseEvent {
+            pos,
+            window_pos: pos,
+            buttons: MouseButtons::default().with(MouseButton::Left),
+            mods,
+            count,
+            focus: false,
+            button: MouseButton::Left,
+            wheel_delta: Vec2::ZERO,
+        })
+    }
+
+    fn key(mods: Modifiers, key: KbKey) -> Event {
+        Event::KeyDown(KeyEvent::for_test(mods, key))
+    }
+
+    fn char_key(c: &str) -> Event {
+        key(Modifiers::empty(), KbKey::Character(c.into()))
+    }
+
+    /// Each row is 20px high, so row `i` is at y = 20 * i + 10.
+    fn list() -> SelectableList<String> {
+        SelectableList::new(|| Label::dynamic(|s: &String, _| s.clone()).fix_height(20.))
+    }
+
+    /// Wrap the list so that tests can observe notifications and remove items.
+    fn with_observer(
+        inner: impl Widget<(Items, ListSelection)> + 'static,
+        activations: Rc<RefCell<Vec<Vec<usize>>>>,
+    ) -> impl Widget<(Items, ListSelection)> {
+        let inner = WidgetPod::new(inner);
+        ModularWidget::new(inner)
+            .event_fn(
+                move |inner, ctx, event, data: &mut (Items, ListSelection), env| match event {
+                    Event::Command(cmd) if cmd.is(REMOVE_LAST) => {
+                        Arc::make_mut(&mut data.0).pop();
+                    }
+                    Event::Notification(note) => {
+                        if let Some(indices) = note.get(SelectableList::ACTIVATE) {
+                            activations.borrow_mut().push(indices.clone());
+                            ctx.set_handled();
+                        }
+                    }
+                    _ => inner.event(ctx, event, data, env),
+                },
+            )
+            .lifecycle_fn(|inner, ctx, event, data, env| inner.lifecycle(ctx, event, data, env))
+            .update_fn(|inner, ctx, _, data, env| inner.update(ctx, data, env))
+            .layout_fn(|inner, ctx, bc, data, env| {
+                let size = inner.layout(ctx, bc, data, env);
+                inner.set_origin(ctx, data, env, Point::ORIGIN);
+                size
+            })
+            .paint_fn(|inner, ctx, data, env| inner.paint(ctx, data, env))
+    }
+
+    #[test]
+    fn selection_ranges() {
+        let mut sel = ListSelection::new();
+        sel.select(2);
+        sel.select_range(5);
+        assert_eq!(sel.selected().collect::<Vec<_>>(), vec![2, 3, 4, 5]);
+        assert_eq!(sel.anchor(), Some(2));
+        assert_eq!(sel.cursor(), Some(5));
+
+        // the range is relative to the anchor, and replaces the old range
+        sel.select_range(0);
+        assert_eq!(sel.selected().collect::<Vec<_>>(), vec![0, 1, 2]);
+
+        sel.toggle(7);
+        assert_eq!(sel.selected().collect::<Vec<_>>(), vec![0, 1, 2, 7]);
+        assert_eq!(sel.anchor(), Some(7));
+        sel.select_range(5);
+        assert_eq!(sel.selected().collect::<Vec<_>>(), vec![5, 6, 7]);
+
+        sel.toggle(6);
+        assert_eq!(sel.selected().collect::<Vec<_>>(), vec![5, 7]);
+    }
+
+    #[test]
+    fn selection_follows_removal() {
+        let mut sel = ListSelection::new();
+        sel.select(1);
+        sel.select_range(4);
+        sel.remove_index(2);
+        assert_eq!(sel.selected().collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert_eq!(sel.cursor(), Some(3));
+        assert_eq!(sel.anchor(), Some(1));
+
+        sel.insert_index(0);
+        assert_eq!(sel.selected().collect::<Vec<_>>(), vec![2, 3, 4]);
+        assert_eq!(sel.cursor(), Some(4));
+
+        sel.truncate(3);
+        assert_eq!(sel.selected().collect::<Vec<_>>(), vec![2]);
+        assert_eq!(sel.cursor(), Some(2));
+        assert_eq!(sel.anchor(), Some(2));
+
+        sel.truncate(0);
+        assert!(sel.is_empty());
+        assert_eq!(sel.cursor(), None);
+    }
+
+    #[test]
+    fn truncate_is_noop_when_valid() {
+        let mut sel = ListSelection::new();
+        sel.select(1);
+        let before = sel.clone();
+        sel.truncate(5);
+        assert!(sel.same(&before));
+    }
+
+    #[test]
+    fn click_and_modifiers() {
+        let data = items(&["a", "b", "c", "d", "e"]);
+        let widget = list().multi_select(true);
+        Harness::create_simple(data, widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+
+            harness.event(click((5., 30.), Modifiers::empty(), 1));
+            assert_eq!(selected(harness.data()), vec![1]);
+
+            harness.event(click((5., 70.), Modifiers::SHIFT, 1));
+            assert_eq!(selected(harness.data()), vec![1, 2, 3]);
+
+            let toggle = if cfg!(target_os = "macos") {
+                Modifiers::META
+            } else {
+                Modifiers::CONTROL
+            };
+            harness.event(click((5., 50.), toggle, 1));
+            assert_eq!(selected(harness.data()), vec![1, 3]);
+
+            // a plain click replaces the whole selection
+            harness.event(click((5., 90.), Modifiers::empty(), 1));
+            assert_eq!(selected(harness.data()), vec![4]);
+        });
+    }
+
+    #[test]
+    fn single_select_ignores_modifiers() {
+        let data = items(&["a", "b", "c"]);
+        Harness::create_simple(data, list(), |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+
+            harness.event(click((5., 10.), Modifiers::empty(), 1));
+            harness.event(click((5., 50.), Modifiers::SHIFT, 1));
+            assert_eq!(selected(harness.data()), vec![2]);
+        });
+    }
+
+    #[test]
+    fn keyboard_navigation() {
+        let data = items(&["a", "b", "c", "d", "e", "f", "g"]);
+        let widget = list().multi_select(true).page_size(3);
+        Harness::create_simple(data, widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.event(click((5., 10.), Modifiers::empty(), 1));
+            assert_eq!(selected(harness.data()), vec![0]);
+
+            harness.event(key(Modifiers::empty(), KbKey::ArrowDown));
+            assert_eq!(selected(harness.data()), vec![1]);
+            harness.event(key(Modifiers::empty(), KbKey::PageDown));
+            assert_eq!(selected(harness.data()), vec![4]);
+            harness.event(key(Modifiers::empty(), KbKey::End));
+            assert_eq!(selected(harness.data()), vec![6]);
+            harness.event(key(Modifiers::empty(), KbKey::ArrowDown));
+            assert_eq!(selected(harness.data()), vec![6]);
+            harness.event(key(Modifiers::SHIFT, KbKey::ArrowUp));
+            harness.event(key(Modifiers::SHIFT, KbKey::ArrowUp));
+            assert_eq!(selected(harness.data()), vec![4, 5, 6]);
+            harness.event(key(Modifiers::SHIFT, KbKey::Home));
+            assert_eq!(selected(harness.data()), vec![0, 1, 2, 3, 4, 5, 6]);
+            harness.event(key(Modifiers::empty(), KbKey::PageUp));
+            assert_eq!(selected(harness.data()), vec![0]);
+        });
+    }
+
+    #[test]
+    fn activation() {
+        let activations = Rc::new(RefCell::new(Vec::new()));
+        let data = items(&["a", "b", "c"]);
+        let widget = with_observer(list().multi_select(true), activations.clone());
+        Harness::create_simple(data, widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+
+            harness.event(click((5., 30.), Modifiers::empty(), 1));
+            harness.event(click((5., 30.), Modifiers::empty(), 2));
+            assert_eq!(*activations.borrow(), vec![vec![1]]);
+
+            harness.event(key(Modifiers::SHIFT, KbKey::ArrowDown));
+            harness.event(key(Modifiers::empty(), KbKey::Enter));
+            assert_eq!(activations.borrow()[1], vec![1, 2]);
+        });
+    }
+
+    #[test]
+    fn selection_valid_after_removal() {
+        let data = items(&["a", "b", "c", "d"]);
+        let widget = with_observer(list(), Default::default());
+        Harness::create_simple(data, widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+
+            harness.event(click((5., 70.), Modifiers::empty(), 1));
+            assert_eq!(selected(harness.data()), vec![3]);
+
+            harness.submit_command(REMOVE_LAST);
+            harness.just_layout();
+            // the next event sees the shorter collection and fixes the selection
+            harness.event(key(Modifiers::empty(), KbKey::ArrowUp));
+            assert_eq!(selected(harness.data()), vec![1]);
+            assert_eq!(harness.data().1.cursor(), Some(1));
+        });
+    }
+
+    #[test]
+    fn type_ahead() {
+        let data = items(&["Apple", "Banana", "blueberry", "Cherry", "Avocado"]);
+        let widget = list().with_type_ahead(|s: &String| s.clone());
+        Harness::create_simple(data, widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.event(click((5., 10.), Modifiers::empty(), 1));
+
+            harness.event(char_key("b"));
+            assert_eq!(selected(harness.data()), vec![1]);
+            harness.event(char_key("l"));
+            assert_eq!(selected(harness.data()), vec![2]);
+            harness.event(char_key("x"));
+            assert_eq!(selected(harness.data()), vec![2]);
+        });
+    }
+
+    #[test]
+    fn keyboard_scrolls_selection_into_view() {
+        let [list_id] = widget_ids();
+        let names: Vec<String> = (0..50).map(|i| i.to_string()).collect();
+        let data = (Arc::new(names), ListSelection::new());
+        let list = WidgetExt::<(Items, ListSelection)>::with_id(list(), list_id);
+        let widget = Scroll::new(list).vertical();
+        Harness::create_simple(data, widget, |harness| {
+            harness.set_initial_size(Size::new(100., 100.));
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.event(click((5., 10.), Modifiers::empty(), 1));
+
+            harness.event(key(Modifiers::empty(), KbKey::End));
+            harness.just_layout();
+            let state = harness.get_state(list_id);
+            // the last row (980..1000) must be inside the 100px viewport
+            assert_eq!(state.viewport_offset, Vec2::new(0., 900.));
+
+            harness.event(key(Modifiers::empty(), KbKey::Home));
+            let state = harness.get_state(list_id);
+            assert_eq!(state.viewport_offset, Vec2::ZERO);
+        });
+    }
+}
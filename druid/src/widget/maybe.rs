@@ -14,6 +14,8 @@
 
 //! A widget for optional data, with different `Some` and `None` children.
 
+#[cfg(feature = "debug_state")]
+use druid::DebugState;
 use druid::{
     BoxConstraints, Data, Env, Event, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx, Size,
     UpdateCtx, Widget, WidgetExt, WidgetPod,
@@ -141,6 +143,16 @@ impl<T: Data> Widget<Option<T>> for Maybe<T> {
             None => self.widget.with_none(|w| w.paint(ctx, &(), env)),
         };
     }
+
+    #[cfg(feature = "debug_state")]
+    fn debug_state(&self, data: &Option<T>) -> DebugState {
+        let child = match (&self.widget, data.as_ref()) {
+            (MaybeWidget::Some(w), Some(d)) => w.debug_state(d),
+            (MaybeWidget::None(w), None) => w.debug_state(&()),
+            _ => return DebugState::new("Maybe"),
+        };
+        DebugState::with_children("Maybe", vec![child])
+    }
 }
 
 impl<T> MaybeWidget<T> {
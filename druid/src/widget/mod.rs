@@ -20,26 +20,42 @@ mod widget_wrapper;
 
 mod added;
 mod align;
+mod animate_layout;
 mod aspect_ratio_box;
 mod button;
+#[cfg(feature = "chrono")]
+#[cfg_attr(docsrs, doc(cfg(feature = "chrono")))]
+mod calendar;
 mod checkbox;
 mod click;
 mod clip_box;
+mod color_picker;
 mod common;
 mod container;
 mod controller;
+mod debug_dump;
 mod disable_if;
 mod either;
 mod env_scope;
+#[cfg(feature = "diagnostics")]
+#[cfg_attr(docsrs, doc(cfg(feature = "diagnostics")))]
+mod event_viewer;
 mod flex;
+mod form;
 mod identity_wrapper;
 mod image;
 mod invalidation;
 mod label;
 mod lens_wrap;
 mod list;
+mod list_selection;
 mod maybe;
+#[cfg(feature = "im")]
+#[cfg_attr(docsrs, doc(cfg(feature = "im")))]
+mod navigator;
+mod on_change;
 mod padding;
+mod paged_list;
 mod painter;
 mod parse;
 mod progress_bar;
@@ -57,6 +73,8 @@ mod svg;
 mod switch;
 mod tabs;
 mod textbox;
+mod transform;
+mod tree;
 mod value_textbox;
 mod view_switcher;
 #[allow(clippy::module_inception)]
@@ -66,30 +84,49 @@ mod widget_ext;
 pub use self::image::Image;
 pub use added::Added;
 pub use align::Align;
+pub use animate_layout::{AnimateLayout, Curve};
 pub use aspect_ratio_box::AspectRatioBox;
 pub use button::Button;
+#[cfg(feature = "chrono")]
+pub use calendar::{Calendar, DateFormatter, DatePicker};
 pub use checkbox::Checkbox;
 pub use click::Click;
 pub use clip_box::{ClipBox, Viewport};
+pub use color_picker::{
+    AlphaSlider, ColorPicker, HexColorFormatter, HsvaChannel, HsvaColor, HueSlider, RgbaChannel,
+    SaturationValueSquare,
+};
 pub use common::FillStrat;
 pub use container::Container;
 pub use controller::{Controller, ControllerHost};
+pub use debug_dump::DebugDump;
 pub use disable_if::DisabledIf;
 pub use either::Either;
 pub use env_scope::EnvScope;
+#[cfg(feature = "diagnostics")]
+pub use event_viewer::{EventLog, EventLogger, EventViewer, LogEntry, LoggedEvent};
 pub use flex::{Axis, CrossAxisAlignment, Flex, FlexParams, MainAxisAlignment};
+pub use form::{FormScope, Validated};
 pub use identity_wrapper::IdentityWrapper;
 pub use label::{Label, LabelText, LineBreaking, RawLabel};
 pub use lens_wrap::LensWrap;
 pub use list::{List, ListIter};
+pub use list_selection::{ListSelection, SelectableList};
 pub use maybe::Maybe;
+#[cfg(feature = "im")]
+pub use navigator::{Navigator, NavigatorTransition, Route};
+pub use on_change::OnChange;
 pub use padding::Padding;
+pub use paged_list::PagedList;
 pub use painter::{BackgroundBrush, Painter};
 pub use parse::Parse;
 pub use progress_bar::ProgressBar;
 pub use radio::{Radio, RadioGroup};
-pub use scope::{DefaultScopePolicy, LensScopeTransfer, Scope, ScopePolicy, ScopeTransfer};
-pub use scroll::Scroll;
+pub use scope::{
+    DefaultScopePolicy, IndependentScopePolicy, IndependentScopeTransfer, LensScopeTransfer, Scope,
+    ScopePolicy, ScopeTransfer,
+};
+pub use scroll::{Scroll, ScrollGroup, SyncAxes};
 pub use sized_box::SizedBox;
 pub use slider::Slider;
 pub use spinner::Spinner;
@@ -100,10 +137,12 @@ pub use svg::{Svg, SvgData};
 pub use switch::Switch;
 pub use tabs::{TabInfo, Tabs, TabsEdge, TabsPolicy, TabsState, TabsTransition};
 pub use textbox::TextBox;
+pub use transform::Transform;
+pub use tree::{Tree, TreeNode};
 pub use value_textbox::{TextBoxEvent, ValidationDelegate, ValueTextBox};
 pub use view_switcher::ViewSwitcher;
 #[doc(hidden)]
-pub use widget::{Widget, WidgetId};
+pub use widget::{Intrinsic, Widget, WidgetId};
 #[doc(hidden)]
 pub use widget_ext::WidgetExt;
 pub use widget_wrapper::WidgetWrapper;
@@ -130,6 +169,9 @@ pub use widget_wrapper::WidgetWrapper;
 /// [`Widget`](../../trait.Widget.html)
 // NOTE: \ at the end works as a line break, but skip on last line!
 pub mod prelude {
+    #[cfg(feature = "debug_state")]
+    #[doc(hidden)]
+    pub use crate::DebugState;
     #[doc(hidden)]
     pub use crate::{
         BoxConstraints, Data, Env, Event, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx,
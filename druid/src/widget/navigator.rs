@@ -0,0 +1,652 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that shows the top of a stack of routes, for drill-down navigation.
+
+use std::any::Any;
+use std::fmt;
+use std::sync::Arc;
+
+use instant::Duration;
+use tracing::{instrument, trace, warn};
+
+use crate::im::Vector;
+use crate::widget::prelude::*;
+use crate::{Affine, Data, HotKey, KbKey, Lens, Point, RawMods, Selector, Target, WidgetPod};
+
+type Nanos = u64;
+type RouteBuilder<T, R> = dyn Fn(&R, &T, &Env) -> Box<dyn Widget<T>>;
+type ViewPod<T> = WidgetPod<T, Box<dyn Widget<T>>>;
+
+/// A route, as the payload of [`Navigator::NAVIGATE_PUSH`] and
+/// [`Navigator::NAVIGATE_REPLACE`].
+///
+/// A navigator only accepts routes of the type it was created with.
+#[derive(Clone)]
+pub struct Route(Arc<dyn Any + Send + Sync>);
+
+impl Route {
+    /// Wrap a route.
+    pub fn new<R: Any + Send + Sync>(route: R) -> Self {
+        Route(Arc::new(route))
+    }
+
+    fn get<R: Any>(&self) -> Option<&R> {
+        self.0.downcast_ref()
+    }
+}
+
+impl fmt::Debug for Route {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Route")
+    }
+}
+
+/// Whether a [`Navigator`] animates the change when a route is pushed or
+/// popped.
+#[derive(Data, Copy, Clone, Debug, PartialEq)]
+pub enum NavigatorTransition {
+    /// Change views instantly, with no animation.
+    Instant,
+    /// Slide the new view in from the right when a route is pushed, and the
+    /// old view out to the right when it is popped. The argument is the
+    /// duration in nanoseconds.
+    Slide(Nanos),
+}
+
+impl Default for NavigatorTransition {
+    fn default() -> Self {
+        NavigatorTransition::Slide(Duration::from_millis(250).as_nanos() as Nanos)
+    }
+}
+
+/// The view being navigated away from, while the transition runs.
+struct TransitionState<T> {
+    /// The old view, if it is no longer in the stack.
+    removed: Option<ViewPod<T>>,
+    /// The index of the old view, if it is still in the stack.
+    covered: Option<usize>,
+    forward: bool,
+    current_time: Nanos,
+    duration: Nanos,
+}
+
+impl<T> TransitionState<T> {
+    fn live(&self) -> bool {
+        self.current_time < self.duration
+    }
+
+    fn fraction(&self) -> f64 {
+        (self.current_time as f64) / (self.duration as f64)
+    }
+
+    /// The offsets of the old and the new view.
+    fn offsets(&self, width: f64) -> (f64, f64) {
+        let moved = width * self.fraction();
+        if self.forward {
+            (-moved, width - moved)
+        } else {
+            (moved, moved - width)
+        }
+    }
+}
+
+/// A change to the route stack, from a command or a notification.
+enum Navigation<'a> {
+    Push(&'a Route),
+    Pop,
+    Replace(&'a Route),
+}
+
+macro_rules! navigation {
+    ($message:expr) => {
+        if let Some(route) = $message.get(Navigator::NAVIGATE_PUSH) {
+            Some(Navigation::Push(route))
+        } else if $message.is(Navigator::NAVIGATE_POP) {
+            Some(Navigation::Pop)
+        } else if let Some(route) = $message.get(Navigator::NAVIGATE_REPLACE) {
+            Some(Navigation::Replace(route))
+        } else {
+            None
+        }
+    };
+}
+
+/// A widget that shows the view for the route at the top of a stack.
+///
+/// The stack of routes is part of the app data, as an [`im::Vector`] that the
+/// navigator reaches through a lens, and each route is turned into a view by
+/// the closure passed to [`Navigator::new`]. Pushing and popping routes is
+/// done by changing the stack, or with the [`NAVIGATE_PUSH`], [`NAVIGATE_POP`]
+/// and [`NAVIGATE_REPLACE`] commands. These can be sent to the navigator's
+/// [`WidgetId`], or submitted as notifications by any widget inside the
+/// navigator; a notification is handled by the nearest navigator that can
+/// handle it.
+///
+/// The views under the top one are kept, so that they are as the user left
+/// them when they go back, unless [`with_retain_covered`] says otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use druid::im::Vector;
+/// use druid::widget::{Button, Label, Navigator, Route};
+/// use druid::{Data, Lens, Widget, WidgetExt};
+///
+/// #[derive(Clone, Copy, Data, PartialEq)]
+/// enum Screen {
+///     Settings,
+///     Network,
+/// }
+///
+/// #[derive(Clone, Data, Lens)]
+/// struct AppState {
+///     screens: Vector<Screen>,
+/// }
+///
+/// let navigator = Navigator::new(AppState::screens, |screen, _, _| match screen {
+///     Screen::Settings => Button::new("Network")
+///         .on_click(|ctx, _, _| {
+///             let route = Route::new(Screen::Network);
+///             ctx.submit_notification(Navigator::NAVIGATE_PUSH.with(route));
+///         })
+///         .boxed(),
+///     Screen::Network => Label::new("Network settings").boxed(),
+/// });
+/// ```
+///
+/// [`im::Vector`]: crate::im::Vector
+/// [`NAVIGATE_PUSH`]: Navigator::NAVIGATE_PUSH
+/// [`NAVIGATE_POP`]: Navigator::NAVIGATE_POP
+/// [`NAVIGATE_REPLACE`]: Navigator::NAVIGATE_REPLACE
+/// [`with_retain_covered`]: Navigator::with_retain_covered
+pub struct Navigator<T, R, L> {
+    routes: L,
+    builder: Box<RouteBuilder<T, R>>,
+    /// The routes on the stack, from the bottom, and their views. The views
+    /// of covered routes are `None` if they aren't retained, or haven't been
+    /// built yet.
+    views: Vec<(R, Option<ViewPod<T>>)>,
+    retain_covered: bool,
+    back_key: Option<HotKey>,
+    transition: NavigatorTransition,
+    transition_state: Option<TransitionState<T>>,
+}
+
+impl Navigator<(), (), ()> {
+    /// Push a route onto the stack.
+    pub const NAVIGATE_PUSH: Selector<Route> = Selector::new("druid-builtin.navigator-push");
+
+    /// Pop the top route off the stack.
+    ///
+    /// The last route is never popped; a notification is passed on to the
+    /// next navigator up instead.
+    pub const NAVIGATE_POP: Selector = Selector::new("druid-builtin.navigator-pop");
+
+    /// Replace the top route of the stack.
+    pub const NAVIGATE_REPLACE: Selector<Route> = Selector::new("druid-builtin.navigator-replace");
+}
+
+impl<T: Data, R: Data, L: Lens<T, Vector<R>>> Navigator<T, R, L> {
+    /// Create a navigator for the stack of routes that `routes` points to.
+    ///
+    /// The `builder` closure creates the view for a route.
+    pub fn new(routes: L, builder: impl Fn(&R, &T, &Env) -> Box<dyn Widget<T>> + 'static) -> Self {
+        Navigator {
+            routes,
+            builder: Box::new(builder),
+            views: Vec::new(),
+            retain_covered: true,
+            back_key: Some(HotKey::new(RawMods::Alt, KbKey::ArrowLeft)),
+            transition: NavigatorTransition::default(),
+            transition_state: None,
+        }
+    }
+
+    /// Builder-style method to set whether the views under the top one are
+    /// kept, or rebuilt when they are uncovered.
+    ///
+    /// Kept views remember their state, like where they were scrolled to;
+    /// rebuilding them saves memory when the stack gets deep.
+    pub fn with_retain_covered(mut self, retain: bool) -> Self {
+        self.retain_covered = retain;
+        self
+    }
+
+    /// Builder-style method to set the key that pops a route, when the focus
+    /// is inside the navigator. The default is Alt+Left; `None` disables it.
+    pub fn with_back_key(mut self, key: impl Into<Option<HotKey>>) -> Self {
+        self.back_key = key.into();
+        self
+    }
+
+    /// Builder-style method to set the transition between views.
+    pub fn with_transition(mut self, transition: NavigatorTransition) -> Self {
+        self.transition = transition;
+        self
+    }
+
+    /// Apply a change to the stack, returning `false` if it doesn't apply to
+    /// this navigator.
+    fn navigate(&mut self, data: &mut T, navigation: Navigation) -> bool {
+        self.routes.with_mut(data, |routes| match navigation {
+            Navigation::Push(route) => match route.get::<R>() {
+                Some(route) => {
+                    routes.push_back(route.clone());
+                    true
+                }
+                None => false,
+            },
+            Navigation::Pop if routes.len() > 1 => {
+                routes.pop_back();
+                true
+            }
+            Navigation::Pop => false,
+            Navigation::Replace(route) => match route.get::<R>() {
+                Some(route) => {
+                    routes.pop_back();
+                    routes.push_back(route.clone());
+                    true
+                }
+                None => false,
+            },
+        })
+    }
+
+    /// Make the views match the stack, returning `true` if they changed.
+    ///
+    /// Views that were built here have not been added to the widget tree yet.
+    fn sync_views(&mut self, data: &T, env: &Env) -> bool {
+        let routes = self.routes.with(data, |routes| routes.clone());
+        let common = self
+            .views
+            .iter()
+            .zip(routes.iter())
+            .take_while(|((old, _), new)| old.same(new))
+            .count();
+        let old_len = self.views.len();
+        if common == old_len && common == routes.len() {
+            return false;
+        }
+        trace!("navigating from depth {} to {}", old_len, routes.len());
+
+        // the old top view stays if it is now covered, and covered views are kept
+        let old_top_covered = old_len > 0 && old_len <= common && self.retain_covered;
+        let removed = match self.views.last_mut() {
+            Some((_, view)) if !old_top_covered => view.take(),
+            _ => None,
+        };
+        self.views.truncate(common);
+        self.views.extend(
+            routes
+                .iter()
+                .skip(common)
+                .map(|route| (route.clone(), None)),
+        );
+        if !self.retain_covered {
+            for (_, view) in self.views.iter_mut().rev().skip(1) {
+                *view = None;
+            }
+        }
+        if let Some((route, view)) = self.views.last_mut() {
+            if view.is_none() {
+                *view = Some(WidgetPod::new((self.builder)(route, data, env)));
+            }
+        }
+
+        self.transition_state = match self.transition {
+            NavigatorTransition::Slide(duration)
+                if (removed.is_some() || old_top_covered) && !self.views.is_empty() =>
+            {
+                Some(TransitionState {
+                    removed,
+                    covered: if old_top_covered {
+                        Some(old_len - 1)
+                    } else {
+                        None
+                    },
+                    forward: routes.len() >= old_len,
+                    current_time: 0,
+                    duration,
+                })
+            }
+            _ => None,
+        };
+        true
+    }
+
+    fn top_view(&mut self) -> Option<&mut ViewPod<T>> {
+        self.views.last_mut().and_then(|(_, view)| view.as_mut())
+    }
+
+    /// The views that are in the widget tree: the top view, and the retained
+    /// ones under it.
+    fn views(&mut self) -> impl Iterator<Item = &mut ViewPod<T>> {
+        self.views.iter_mut().filter_map(|(_, view)| view.as_mut())
+    }
+}
+
+impl<T: Data, R: Data, L: Lens<T, Vector<R>>> Widget<T> for Navigator<T, R, L> {
+    #[instrument(name = "Navigator", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::Command(cmd) if cmd.target() == Target::Widget(ctx.widget_id()) => {
+                if let Some(navigation) = navigation!(cmd) {
+                    if !self.navigate(data, navigation) {
+                        warn!("{:?} can't be applied to this navigator", cmd);
+                    }
+                    ctx.set_handled();
+                    return;
+                }
+            }
+            Event::Notification(note) => {
+                if let Some(navigation) = navigation!(note) {
+                    if self.navigate(data, navigation) {
+                        ctx.set_handled();
+                    }
+                }
+                return;
+            }
+            _ => (),
+        }
+
+        if event.should_propagate_to_hidden() {
+            for view in self.views() {
+                view.event(ctx, event, data, env);
+            }
+        } else if let Some(view) = self.top_view() {
+            view.event(ctx, event, data, env);
+        }
+
+        if let Event::KeyDown(key) = event {
+            let is_back = matches!(&self.back_key, Some(back) if back.matches(key));
+            if !ctx.is_handled() && is_back && self.navigate(data, Navigation::Pop) {
+                ctx.set_handled();
+            }
+        }
+
+        if let (Some(state), Event::AnimFrame(interval)) = (&mut self.transition_state, event) {
+            state.current_time += *interval;
+            if state.live() {
+                ctx.request_anim_frame();
+            } else {
+                self.transition_state = None;
+            }
+            ctx.request_paint();
+        }
+    }
+
+    #[instrument(name = "Navigator", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            self.sync_views(data, env);
+            // nothing to animate from
+            self.transition_state = None;
+        }
+
+        if event.should_propagate_to_hidden() {
+            for view in self.views() {
+                view.lifecycle(ctx, event, data, env);
+            }
+        } else if let Some(view) = self.top_view() {
+            view.lifecycle(ctx, event, data, env);
+        }
+    }
+
+    #[instrument(name = "Navigator", level = "trace", skip(self, ctx, _old, data, env))]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old: &T, data: &T, env: &Env) {
+        if self.sync_views(data, env) {
+            ctx.children_changed();
+            if self.transition_state.is_some() {
+                ctx.request_anim_frame();
+            }
+        }
+        // Views that were just built get their first update after they are added.
+        for view in self.views().filter(|view| view.is_initialized()) {
+            view.update(ctx, data, env);
+        }
+    }
+
+    #[instrument(name = "Navigator", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        // The covered views are laid out too, so that they can get events.
+        let mut size = bc.max();
+        for view in self.views() {
+            size = view.layout(ctx, bc, data, env);
+            view.set_origin(ctx, data, env, Point::ORIGIN);
+        }
+        size
+    }
+
+    #[instrument(name = "Navigator", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let width = ctx.size().width;
+        if let Some(state) = &mut self.transition_state {
+            let (old_x, new_x) = state.offsets(width);
+            let clip = ctx.size().to_rect();
+            ctx.clip(clip);
+            let old_view = match (&mut state.removed, state.covered) {
+                (Some(view), _) => Some(view),
+                (None, Some(idx)) => self.views.get_mut(idx).and_then(|(_, v)| v.as_mut()),
+                (None, None) => None,
+            };
+            if let Some(view) = old_view {
+                ctx.with_save(|ctx| {
+                    ctx.transform(Affine::translate((old_x, 0.0)));
+                    view.paint_raw(ctx, data, env);
+                });
+            }
+            if let Some((_, Some(view))) = self.views.last_mut() {
+                ctx.with_save(|ctx| {
+                    ctx.transform(Affine::translate((new_x, 0.0)));
+                    view.paint_raw(ctx, data, env);
+                });
+            }
+        } else if let Some(view) = self.top_view() {
+            view.paint_raw(ctx, data, env);
+        }
+    }
+
+    #[cfg(feature = "debug_state")]
+    fn debug_state(&self, data: &T) -> DebugState {
+        let top = self.views.last().and_then(|(_, view)| view.as_ref());
+        let children = top.map(|view| view.debug_state(data));
+        DebugState {
+            display_name: "Navigator".to_string(),
+            main_value: format!("depth {}", self.views.len()),
+            children: children.into_iter().collect(),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use test_env_log::test;
+
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::{widget_ids, ModularWidget};
+    use crate::{KeyEvent, Modifiers, WidgetExt};
+
+    /// Sent to a view, to make it ask for a route to be pushed.
+    const ASK_PUSH: Selector<u32> = Selector::new("druid-test.ask-push");
+    /// Sent to a view, to make it ask for its route to be popped.
+    const ASK_POP: Selector = Selector::new("druid-test.ask-pop");
+    const FOCUS: Selector = Selector::new("druid-test.focus");
+
+    #[derive(Clone, Data, Lens)]
+    struct State {
+        routes: Vector<u32>,
+    }
+
+    fn state(routes: &[u32]) -> State {
+        State {
+            routes: routes.iter().copied().collect(),
+        }
+    }
+
+    fn routes(harness: &Harness<State>) -> Vec<u32> {
+        harness.data().routes.iter().copied().collect()
+    }
+
+    /// A view with the id `ids[route]`, which submits notifications on
+    /// request, and counts how often it was built.
+    fn view(id: WidgetId) -> Box<dyn Widget<State>> {
+        ModularWidget::new(())
+            .event_fn(|_, ctx, event, _, _| {
+                if let Event::Command(cmd) = event {
+                    if let Some(route) = cmd.get(ASK_PUSH) {
+                        ctx.submit_notification(Navigator::NAVIGATE_PUSH.with(Route::new(*route)));
+                    } else if cmd.is(ASK_POP) {
+                        ctx.submit_notification(Navigator::NAVIGATE_POP);
+                    } else if cmd.is(FOCUS) {
+                        ctx.request_focus();
+                    }
+                }
+            })
+            .with_id(id)
+            .boxed()
+    }
+
+    fn navigator(
+        ids: [WidgetId; 3],
+        builds: Rc<Cell<usize>>,
+    ) -> Navigator<State, u32, impl Lens<State, Vector<u32>>> {
+        Navigator::new(State::routes, move |route, _, _| {
+            builds.set(builds.get() + 1);
+            view(ids[*route as usize])
+        })
+        .with_transition(NavigatorTransition::Instant)
+    }
+
+    #[test]
+    fn push_and_pop_with_commands() {
+        let [nav_id, a, b, c] = widget_ids();
+        let builds = Rc::new(Cell::new(0));
+        let widget = navigator([a, b, c], builds.clone()).with_id(nav_id);
+
+        Harness::create_simple(state(&[0]), widget, |harness| {
+            harness.send_initial_events();
+            assert_eq!(builds.get(), 1);
+            assert!(harness.try_get_state(a).is_some());
+
+            harness.submit_command(Navigator::NAVIGATE_PUSH.with(Route::new(1u32)).to(nav_id));
+            harness.submit_command(Navigator::NAVIGATE_PUSH.with(Route::new(2u32)).to(nav_id));
+            assert_eq!(routes(harness), [0, 1, 2]);
+            assert!(harness.try_get_state(c).is_some());
+            // covered views are kept
+            assert!(harness.try_get_state(a).is_some());
+            assert!(harness.try_get_state(b).is_some());
+
+            harness.submit_command(Navigator::NAVIGATE_POP.to(nav_id));
+            assert_eq!(routes(harness), [0, 1]);
+            assert!(harness.try_get_state(c).is_none());
+
+            harness.submit_command(
+                Navigator::NAVIGATE_REPLACE
+                    .with(Route::new(2u32))
+                    .to(nav_id),
+            );
+            assert_eq!(routes(harness), [0, 2]);
+            assert!(harness.try_get_state(b).is_none());
+            assert!(harness.try_get_state(c).is_some());
+            assert_eq!(builds.get(), 4);
+
+            // the root route can't be popped
+            harness.submit_command(Navigator::NAVIGATE_POP.to(nav_id));
+            harness.submit_command(Navigator::NAVIGATE_POP.to(nav_id));
+            assert_eq!(routes(harness), [0]);
+            assert!(harness.try_get_state(a).is_some());
+            assert_eq!(builds.get(), 4);
+        });
+    }
+
+    #[test]
+    fn views_navigate_with_notifications() {
+        let [a, b, c] = widget_ids();
+        let widget = navigator([a, b, c], Rc::default());
+
+        Harness::create_simple(state(&[0]), widget, |harness| {
+            harness.send_initial_events();
+            harness.submit_command(ASK_PUSH.with(1).to(a));
+            assert_eq!(routes(harness), [0, 1]);
+            harness.submit_command(ASK_PUSH.with(2).to(b));
+            assert_eq!(routes(harness), [0, 1, 2]);
+            harness.submit_command(ASK_POP.to(c));
+            assert_eq!(routes(harness), [0, 1]);
+            assert!(harness.try_get_state(c).is_none());
+        });
+    }
+
+    #[test]
+    fn covered_views_can_be_rebuilt() {
+        let [a, b, c] = widget_ids();
+        let builds = Rc::new(Cell::new(0));
+        let widget = navigator([a, b, c], builds.clone()).with_retain_covered(false);
+
+        Harness::create_simple(state(&[0, 1]), widget, |harness| {
+            harness.send_initial_events();
+            // only the top view is built
+            assert_eq!(builds.get(), 1);
+            assert!(harness.try_get_state(a).is_none());
+            assert!(harness.try_get_state(b).is_some());
+
+            harness.submit_command(ASK_PUSH.with(2).to(b));
+            assert!(harness.try_get_state(b).is_none());
+
+            harness.submit_command(ASK_POP.to(c));
+            assert!(harness.try_get_state(b).is_some());
+            assert_eq!(builds.get(), 3);
+        });
+    }
+
+    #[test]
+    fn back_key_pops() {
+        let [a, b, c] = widget_ids();
+        let widget = navigator([a, b, c], Rc::default());
+
+        Harness::create_simple(state(&[0, 1]), widget, |harness| {
+            harness.send_initial_events();
+            harness.submit_command(FOCUS.to(b));
+            let back = KeyEvent::for_test(Modifiers::ALT, KbKey::ArrowLeft);
+            harness.event(Event::KeyDown(back));
+            assert_eq!(routes(harness), [0]);
+        });
+    }
+
+    #[test]
+    fn slide_transition_finishes() {
+        let [a, b, c] = widget_ids();
+        let widget =
+            navigator([a, b, c], Rc::default()).with_transition(NavigatorTransition::Slide(1_000));
+
+        Harness::create_simple(state(&[0, 1]), widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.submit_command(ASK_POP.to(b));
+            assert_eq!(routes(harness), [0]);
+            // the popped view is out of the tree, but still painted while it
+            // slides out
+            assert!(harness.try_get_state(b).is_none());
+            harness.paint();
+            harness.event(Event::AnimFrame(1_000));
+            harness.paint();
+            assert!(harness.try_get_state(a).is_some());
+        });
+    }
+}
@@ -0,0 +1,231 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A [`Controller`] that calls a closure when its data changes.
+//!
+//! [`Controller`]: crate::widget::Controller
+
+use std::time::Duration;
+
+use tracing::{instrument, trace};
+
+use crate::widget::prelude::*;
+use crate::widget::Controller;
+use crate::{Data, TimerToken};
+
+type ChangeFn<T> = dyn Fn(&mut UpdateCtx, &T, &T, &Env);
+
+/// A [`Controller`] that calls a closure whenever the data of its child changes.
+///
+/// The closure receives the old and the new data. It can't mutate the data
+/// directly, but it can submit commands through the [`UpdateCtx`] that will do
+/// so in a later cycle.
+///
+/// This is also available, for convenience, as the [`on_change`] and
+/// [`on_change_debounced`] methods via [`WidgetExt`].
+///
+/// [`Controller`]: crate::widget::Controller
+/// [`WidgetExt`]: crate::widget::WidgetExt
+/// [`on_change`]: crate::widget::WidgetExt::on_change
+/// [`on_change_debounced`]: crate::widget::WidgetExt::on_change_debounced
+pub struct OnChange<T> {
+    action: Box<ChangeFn<T>>,
+    debounce: Option<Duration>,
+    timer: TimerToken,
+    /// The data from before the first change in the current debounce window.
+    pending: Option<T>,
+    /// Set when the debounce timer has fired and the next `update` should call the closure.
+    ready: bool,
+}
+
+impl<T: Data> OnChange<T> {
+    /// Create a new controller that calls `action` after every data change.
+    pub fn new(action: impl Fn(&mut UpdateCtx, &T, &T, &Env) + 'static) -> Self {
+        OnChange {
+            action: Box::new(action),
+            debounce: None,
+            timer: TimerToken::INVALID,
+            pending: None,
+            ready: false,
+        }
+    }
+
+    /// Create a new controller that calls `action` once the data has stopped
+    /// changing for `delay`.
+    ///
+    /// Every change restarts the timer. When it finally fires, `action` is
+    /// called with the data from before the first change and the current data;
+    /// if the data has returned to where it started, `action` is not called.
+    pub fn debounced(
+        delay: Duration,
+        action: impl Fn(&mut UpdateCtx, &T, &T, &Env) + 'static,
+    ) -> Self {
+        OnChange {
+            debounce: Some(delay),
+            ..OnChange::new(action)
+        }
+    }
+}
+
+impl<T: Data, W: Widget<T>> Controller<T, W> for OnChange<T> {
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::Timer(token) if *token == self.timer && self.pending.is_some() => {
+                trace!("debounce timer fired");
+                self.timer = TimerToken::INVALID;
+                self.ready = true;
+                ctx.request_update();
+                ctx.set_handled();
+            }
+            _ => child.event(ctx, event, data, env),
+        }
+    }
+
+    #[instrument(
+        name = "OnChange",
+        level = "trace",
+        skip(self, child, ctx, old_data, data, env)
+    )]
+    fn update(&mut self, child: &mut W, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        let changed = !old_data.same(data);
+        if changed {
+            match self.debounce {
+                None => (self.action)(ctx, old_data, data, env),
+                Some(delay) => {
+                    if self.pending.is_none() {
+                        self.pending = Some(old_data.clone());
+                    }
+                    self.timer = ctx.request_timer(delay);
+                }
+            }
+        }
+        // a change in the same cycle as the timer restarted it, so only fire if stable
+        if std::mem::take(&mut self.ready) && !changed {
+            if let Some(old) = self.pending.take() {
+                if !old.same(data) {
+                    (self.action)(ctx, &old, data, env);
+                }
+            }
+        }
+        child.update(ctx, old_data, data, env)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::ModularWidget;
+    use crate::{Selector, WidgetExt};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use test_env_log::test;
+
+    const SET: Selector<i32> = Selector::new("druid-test.set");
+    const TRIGGER: Selector = Selector::new("druid-test.trigger");
+
+    type Calls = Rc<RefCell<Vec<(i32, i32)>>>;
+
+    /// A widget that sets its data in response to `SET`.
+    fn setter() -> ModularWidget<(), i32> {
+        ModularWidget::new(()).event_fn(|_, _, event, data, _| {
+            if let Event::Command(cmd) = event {
+                if let Some(value) = cmd.get(SET) {
+                    *data = *value;
+                }
+            }
+        })
+    }
+
+    fn fire_timer(harness: &mut Harness<i32>) {
+        let token = *harness.window().timers.keys().next().expect("no timer");
+        harness.event(Event::Timer(token));
+    }
+
+    #[test]
+    fn on_change() {
+        let calls: Calls = Default::default();
+        let calls_2 = calls.clone();
+        let widget = setter().on_change(move |ctx, old, new, _| {
+            calls_2.borrow_mut().push((*old, *new));
+            ctx.submit_command(TRIGGER);
+        });
+
+        Harness::create_simple(1, widget, |harness| {
+            harness.send_initial_events();
+            assert!(calls.borrow().is_empty());
+
+            harness.submit_command(SET.with(2));
+            harness.submit_command(SET.with(2));
+            harness.submit_command(SET.with(5));
+            assert_eq!(*calls.borrow(), vec![(1, 2), (2, 5)]);
+        });
+    }
+
+    #[test]
+    fn on_change_debounced() {
+        let calls: Calls = Default::default();
+        let calls_2 = calls.clone();
+        let widget = setter()
+            .on_change_debounced(Duration::from_millis(100), move |_, old, new, _| {
+                calls_2.borrow_mut().push((*old, *new))
+            });
+
+        Harness::create_simple(1, widget, |harness| {
+            harness.send_initial_events();
+
+            harness.submit_command(SET.with(2));
+            harness.submit_command(SET.with(3));
+            assert!(calls.borrow().is_empty());
+            fire_timer(harness);
+            assert_eq!(*calls.borrow(), vec![(1, 3)]);
+
+            // the timer has been consumed; nothing pending
+            assert!(harness.window().timers.is_empty());
+            harness.submit_command(SET.with(4));
+            fire_timer(harness);
+            assert_eq!(*calls.borrow(), vec![(1, 3), (3, 4)]);
+        });
+    }
+
+    #[test]
+    fn debounce_resets_timer() {
+        let calls: Calls = Default::default();
+        let calls_2 = calls.clone();
+        let widget = setter()
+            .on_change_debounced(Duration::from_millis(100), move |_, old, new, _| {
+                calls_2.borrow_mut().push((*old, *new))
+            });
+
+        Harness::create_simple(1, widget, |harness| {
+            harness.send_initial_events();
+
+            harness.submit_command(SET.with(2));
+            // a timer that isn't ours is ignored
+            harness.event(Event::Timer(TimerToken::next()));
+            assert!(calls.borrow().is_empty());
+
+            // a change restarts the timer, so only the final state is reported
+            harness.submit_command(SET.with(7));
+            fire_timer(harness);
+            assert_eq!(*calls.borrow(), vec![(1, 7)]);
+
+            // changing back to the starting value before the timer fires is not a change
+            harness.submit_command(SET.with(8));
+            harness.submit_command(SET.with(7));
+            fire_timer(harness);
+            assert_eq!(*calls.borrow(), vec![(1, 7)]);
+        });
+    }
+}
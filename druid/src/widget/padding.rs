@@ -14,7 +14,7 @@
 
 //! A widget that just adds padding during layout.
 
-use crate::widget::{prelude::*, WidgetWrapper};
+use crate::widget::{prelude::*, Axis, Intrinsic, WidgetWrapper};
 use crate::{Data, Insets, KeyOrValue, Point, WidgetPod};
 
 use tracing::{instrument, trace};
@@ -102,15 +102,38 @@ impl<T: Data, W: Widget<T>> Widget<T> for Padding<T, W> {
         let origin = Point::new(insets.x0, insets.y0);
         self.child.set_origin(ctx, data, env, origin);
 
-        let my_size = Size::new(size.width + hpad, size.height + vpad);
+        let my_size = bc.constrain((size.width + hpad, size.height + vpad));
         let my_insets = self.child.compute_parent_paint_insets(my_size);
         ctx.set_paint_insets(my_insets);
         trace!("Computed layout: size={}, insets={:?}", my_size, my_insets);
         my_size
     }
 
+    fn compute_intrinsic_size(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        axis: Axis,
+        kind: Intrinsic,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        let insets = self.insets.resolve(env);
+        let pad = Size::new(insets.x0 + insets.x1, insets.y0 + insets.y1);
+        let child_bc = bc.shrink(pad);
+        let child_size = self
+            .child
+            .compute_intrinsic_size(ctx, axis, kind, &child_bc, data, env);
+        child_size + axis.major(pad)
+    }
+
     #[instrument(name = "Padding", level = "trace", skip(self, ctx, data, env))]
     fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
         self.child.paint(ctx, data, env);
     }
+
+    #[cfg(feature = "debug_state")]
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState::with_children("Padding", vec![self.child.debug_state(data)])
+    }
 }
@@ -0,0 +1,579 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A scrolling list that asks for more items when it gets near the end.
+
+use std::marker::PhantomData;
+
+use tracing::{instrument, trace};
+
+use crate::widget::prelude::*;
+use crate::widget::{List, ListIter, Scope, ScopePolicy, ScopeTransfer, Scroll, Spinner};
+use crate::{Data, Lens, Point, Selector, WidgetExt, WidgetPod};
+
+/// The number of rows from the end at which more items are requested,
+/// unless configured otherwise.
+const DEFAULT_THRESHOLD: usize = 5;
+
+/// Sent by a [`PagedList`] to itself, when layout finds that it is near the end.
+const CHECK_NEAR_END: Selector = Selector::new("druid-builtin.paged-list-check-near-end");
+
+/// A vertically scrolling [`List`] for collections that are loaded a page at
+/// a time.
+///
+/// When the user scrolls to within a few rows of the end of the items that
+/// are loaded, the list submits a [`LOAD_MORE`] command with the current
+/// number of items. Until the collection grows, no more requests are made and
+/// a placeholder is shown after the last item. Loading the items, for instance
+/// from a delegate that handles the command, is up to the application.
+///
+/// Whether a request is outstanding is kept in the widget, not in your data;
+/// it is reset by the collection getting longer. If the source has no more
+/// items, the placeholder stays visible; in that case you may want to swap
+/// in a plain list.
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use druid::widget::{Label, PagedList};
+/// # use druid::{Data, Lens, Widget, WidgetExt};
+/// #[derive(Clone, Data, Lens)]
+/// struct AppState {
+///     messages: Arc<Vec<String>>,
+/// }
+///
+/// fn inbox() -> impl Widget<AppState> {
+///     PagedList::new(|| Label::dynamic(|message: &String, _| message.clone()))
+///         .lens(AppState::messages)
+/// }
+/// ```
+///
+/// [`LOAD_MORE`]: PagedList::LOAD_MORE
+pub struct PagedList<T, C> {
+    list: Option<List<T>>,
+    placeholder: Option<Box<dyn Widget<()>>>,
+    load_more: Selector<usize>,
+    threshold: usize,
+    scope: Option<WidgetPod<C, Box<dyn Widget<C>>>>,
+}
+
+impl PagedList<(), ()> {
+    /// The command a [`PagedList`] submits when it needs more items, unless
+    /// configured otherwise with [`with_load_more`].
+    ///
+    /// The payload is the number of items in the collection when the request
+    /// was made. The command is sent to the window.
+    ///
+    /// [`with_load_more`]: PagedList::with_load_more
+    pub const LOAD_MORE: Selector<usize> = Selector::new("druid-builtin.paged-list-load-more");
+}
+
+impl<T: Data, C: ListIter<T>> PagedList<T, C> {
+    /// Create a new paged list. The closure will be called every time a new
+    /// row needs to be constructed, as with [`List::new`].
+    pub fn new<W: Widget<T> + 'static>(closure: impl Fn() -> W + 'static) -> Self {
+        PagedList::from_list(List::new(closure))
+    }
+
+    /// Add paging to an existing [`List`].
+    pub fn from_list(list: List<T>) -> Self {
+        PagedList {
+            list: Some(list),
+            placeholder: None,
+            load_more: PagedList::LOAD_MORE,
+            threshold: DEFAULT_THRESHOLD,
+            scope: None,
+        }
+    }
+
+    /// Builder-style method to set the command submitted to ask for more items.
+    pub fn with_load_more(mut self, selector: Selector<usize>) -> Self {
+        self.load_more = selector;
+        self
+    }
+
+    /// Builder-style method to set how close to the end, in rows, the list
+    /// has to be scrolled before more items are requested.
+    ///
+    /// With a threshold of `0`, more items are requested when the last row
+    /// becomes visible.
+    pub fn with_threshold(mut self, rows: usize) -> Self {
+        self.threshold = rows;
+        self
+    }
+
+    /// Builder-style method to set the widget shown after the last item
+    /// while more items are being loaded.
+    ///
+    /// The default is a [`Spinner`].
+    pub fn with_placeholder(mut self, placeholder: impl Widget<()> + 'static) -> Self {
+        self.placeholder = Some(Box::new(placeholder));
+        self
+    }
+}
+
+impl<T: Data, C: ListIter<T>> PagedList<T, C> {
+    fn make_scope(&mut self) -> WidgetPod<C, Box<dyn Widget<C>>> {
+        let list = self.list.take().expect("PagedList built twice");
+        let placeholder = self
+            .placeholder
+            .take()
+            .unwrap_or_else(|| Box::new(Spinner::new().padding(4.)));
+        let content = PagedContent {
+            list: WidgetPod::new(list),
+            placeholder: WidgetPod::new(placeholder),
+        };
+        let body = PagedBody {
+            scroll: Scroll::new(content).vertical(),
+            load_more: self.load_more,
+            threshold: self.threshold,
+            check_requested: false,
+        };
+        WidgetPod::new(Box::new(Scope::new(PagedScopePolicy::default(), body)))
+    }
+}
+
+/// The state inside the [`Scope`] of a [`PagedList`].
+#[derive(Clone, Data, Lens)]
+struct PagedState<C> {
+    items: C,
+    /// `true` while we are waiting for more items.
+    pending: bool,
+}
+
+struct PagedScopePolicy<T, C>(PhantomData<(T, C)>);
+
+impl<T, C> Default for PagedScopePolicy<T, C> {
+    fn default() -> Self {
+        PagedScopePolicy(PhantomData)
+    }
+}
+
+impl<T: Data, C: ListIter<T>> ScopePolicy for PagedScopePolicy<T, C> {
+    type In = C;
+    type State = PagedState<C>;
+    type Transfer = PagedScopeTransfer<T, C>;
+
+    fn create(self, items: &C) -> (Self::State, Self::Transfer) {
+        let state = PagedState {
+            items: items.clone(),
+            pending: false,
+        };
+        (state, PagedScopeTransfer(PhantomData))
+    }
+}
+
+/// Keeps the items in sync, and ends the pending request when more arrive.
+struct PagedScopeTransfer<T, C>(PhantomData<(T, C)>);
+
+impl<T: Data, C: ListIter<T>> ScopeTransfer for PagedScopeTransfer<T, C> {
+    type In = C;
+    type State = PagedState<C>;
+
+    fn read_input(&self, state: &mut PagedState<C>, items: &C) {
+        if !state.items.same(items) {
+            if items.data_len() > state.items.data_len() {
+                state.pending = false;
+            }
+            state.items = items.clone();
+        }
+    }
+
+    fn write_back_input(&self, state: &PagedState<C>, items: &mut C) {
+        if !state.items.same(items) {
+            *items = state.items.clone();
+        }
+    }
+}
+
+/// Scrolls the content, and decides when to ask for more.
+struct PagedBody<T, C> {
+    scroll: Scroll<PagedState<C>, PagedContent<T, C>>,
+    load_more: Selector<usize>,
+    threshold: usize,
+    /// Whether we have sent ourselves a `CHECK_NEAR_END` that we haven't seen yet.
+    check_requested: bool,
+}
+
+impl<T: Data, C: ListIter<T>> PagedBody<T, C> {
+    /// Returns `true` if the row `threshold` rows from the end is in view.
+    fn near_end(&self, items: &C) -> bool {
+        let list = self.scroll.child().list.widget();
+        let len = items.data_len();
+        // the new rows haven't been laid out yet; we'll check again in layout.
+        if list.child_count() != len {
+            return false;
+        }
+        if len == 0 {
+            return true;
+        }
+        let row = len.saturating_sub(self.threshold + 1);
+        match list.child_layout_rect(row) {
+            Some(rect) => rect.y0 < self.scroll.viewport_rect().y1,
+            None => false,
+        }
+    }
+
+    fn check_near_end(&mut self, ctx: &mut EventCtx, data: &mut PagedState<C>) {
+        if !data.pending && self.near_end(&data.items) {
+            let len = data.items.data_len();
+            trace!("requesting more items after {}", len);
+            data.pending = true;
+            ctx.submit_command(self.load_more.with(len));
+        }
+    }
+}
+
+impl<T: Data, C: ListIter<T>> Widget<PagedState<C>> for PagedBody<T, C> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut PagedState<C>, env: &Env) {
+        if let Event::Command(cmd) = event {
+            if cmd.is(CHECK_NEAR_END) {
+                self.check_requested = false;
+                self.check_near_end(ctx, data);
+                ctx.set_handled();
+                return;
+            }
+        }
+        self.scroll.event(ctx, event, data, env);
+        self.check_near_end(ctx, data);
+    }
+
+    fn lifecycle(
+        &mut self,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &PagedState<C>,
+        env: &Env,
+    ) {
+        self.scroll.lifecycle(ctx, event, data, env)
+    }
+
+    fn update(
+        &mut self,
+        ctx: &mut UpdateCtx,
+        old_data: &PagedState<C>,
+        data: &PagedState<C>,
+        env: &Env,
+    ) {
+        self.scroll.update(ctx, old_data, data, env)
+    }
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &PagedState<C>,
+        env: &Env,
+    ) -> Size {
+        let size = self.scroll.layout(ctx, bc, data, env);
+        // we can't change our data here, so we ask to be called back.
+        if !data.pending && !self.check_requested && self.near_end(&data.items) {
+            self.check_requested = true;
+            ctx.submit_command(CHECK_NEAR_END.to(ctx.widget_id()));
+        }
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &PagedState<C>, env: &Env) {
+        self.scroll.paint(ctx, data, env)
+    }
+}
+
+/// The list, followed by the placeholder while more items are pending.
+struct PagedContent<T, C> {
+    list: WidgetPod<C, List<T>>,
+    placeholder: WidgetPod<(), Box<dyn Widget<()>>>,
+}
+
+impl<T: Data, C: ListIter<T>> Widget<PagedState<C>> for PagedContent<T, C> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut PagedState<C>, env: &Env) {
+        self.list.event(ctx, event, &mut data.items, env);
+        if data.pending {
+            self.placeholder.event(ctx, event, &mut (), env);
+        }
+    }
+
+    fn lifecycle(
+        &mut self,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &PagedState<C>,
+        env: &Env,
+    ) {
+        self.list.lifecycle(ctx, event, &data.items, env);
+        self.placeholder.lifecycle(ctx, event, &(), env);
+    }
+
+    fn update(
+        &mut self,
+        ctx: &mut UpdateCtx,
+        old_data: &PagedState<C>,
+        data: &PagedState<C>,
+        env: &Env,
+    ) {
+        self.list.update(ctx, &data.items, env);
+        self.placeholder.update(ctx, &(), env);
+        if old_data.pending != data.pending {
+            ctx.request_layout();
+        }
+    }
+
+    #[instrument(name = "PagedList", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &PagedState<C>,
+        env: &Env,
+    ) -> Size {
+        let list_size = self.list.layout(ctx, bc, &data.items, env);
+        self.list.set_origin(ctx, &data.items, env, Point::ORIGIN);
+        if !data.pending {
+            return list_size;
+        }
+
+        let placeholder_bc = bc.shrink((0., list_size.height)).loosen();
+        let placeholder_size = self.placeholder.layout(ctx, &placeholder_bc, &(), env);
+        let x = ((list_size.width - placeholder_size.width) / 2.).max(0.);
+        let origin = Point::new(x, list_size.height);
+        self.placeholder.set_origin(ctx, &(), env, origin);
+        bc.constrain(Size::new(
+            list_size.width.max(placeholder_size.width),
+            list_size.height + placeholder_size.height,
+        ))
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &PagedState<C>, env: &Env) {
+        self.list.paint(ctx, &data.items, env);
+        if data.pending {
+            self.placeholder.paint(ctx, &(), env);
+        }
+    }
+}
+
+impl<T: Data, C: ListIter<T>> Widget<C> for PagedList<T, C> {
+    #[instrument(name = "PagedList", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut C, env: &Env) {
+        if let Some(scope) = &mut self.scope {
+            scope.event(ctx, event, data, env);
+        }
+    }
+
+    #[instrument(name = "PagedList", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &C, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            if self.scope.is_none() {
+                self.scope = Some(self.make_scope());
+                ctx.children_changed();
+            }
+        }
+        if let Some(scope) = &mut self.scope {
+            scope.lifecycle(ctx, event, data, env);
+        }
+    }
+
+    #[instrument(
+        name = "PagedList",
+        level = "trace",
+        skip(self, ctx, _old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &C, data: &C, env: &Env) {
+        if let Some(scope) = &mut self.scope {
+            scope.update(ctx, data, env);
+        }
+    }
+
+    #[instrument(name = "PagedList", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &C, env: &Env) -> Size {
+        match &mut self.scope {
+            Some(scope) => {
+                let size = scope.layout(ctx, bc, data, env);
+                scope.set_origin(ctx, data, env, Point::ORIGIN);
+                size
+            }
+            None => bc.min(),
+        }
+    }
+
+    #[instrument(name = "PagedList", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &C, env: &Env) {
+        if let Some(scope) = &mut self.scope {
+            scope.paint(ctx, data, env);
+        }
+    }
+
+    #[cfg(feature = "debug_state")]
+    fn debug_state(&self, data: &C) -> DebugState {
+        let children = match &self.scope {
+            Some(scope) => vec![scope.debug_state(data)],
+            None => vec![],
+        };
+        DebugState::with_children("PagedList", children)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::ModularWidget;
+    use crate::widget::{Controller, SizedBox};
+    use crate::{MouseButton, MouseButtons, MouseEvent, Vec2};
+    use std::cell::{Cell, RefCell};
+    use std::rc::Rc;
+    use std::sync::Arc;
+    use test_env_log::test;
+
+    const ROW_HEIGHT: f64 = 20.;
+    const APPEND: Selector<usize> = Selector::new("druid-test.append");
+
+    type Requests = Rc<RefCell<Vec<usize>>>;
+
+    /// A collection the tests can append items to.
+    trait Items: ListIter<u32> {
+        /// Append `count` items, continuing the numbering.
+        fn append(&mut self, count: usize);
+    }
+
+    impl Items for Arc<Vec<u32>> {
+        fn append(&mut self, count: usize) {
+            let items = Arc::make_mut(self);
+            let start = items.len() as u32;
+            items.extend(start..start + count as u32);
+        }
+    }
+
+    #[cfg(feature = "im")]
+    impl Items for crate::im::Vector<u32> {
+        fn append(&mut self, count: usize) {
+            let start = self.len() as u32;
+            self.extend(start..start + count as u32);
+        }
+    }
+
+    /// Records `LOAD_MORE` requests, and appends items on `APPEND`.
+    struct Source(Requests);
+
+    impl<C: Items, W: Widget<C>> Controller<C, W> for Source {
+        fn event(
+            &mut self,
+            child: &mut W,
+            ctx: &mut EventCtx,
+            event: &Event,
+            data: &mut C,
+            env: &Env,
+        ) {
+            if let Event::Command(cmd) = event {
+                if let Some(len) = cmd.get(PagedList::LOAD_MORE) {
+                    self.0.borrow_mut().push(*len);
+                } else if let Some(count) = cmd.get(APPEND) {
+                    data.append(*count);
+                }
+            }
+            child.event(ctx, event, data, env)
+        }
+    }
+
+    fn paged_list<C: Items>(requests: &Requests) -> impl Widget<C> {
+        PagedList::new(|| SizedBox::empty().height(ROW_HEIGHT))
+            .with_threshold(2)
+            .with_placeholder(SizedBox::empty().height(ROW_HEIGHT))
+            .controller(Source(requests.clone()))
+    }
+
+    fn scroll_to_end<C: Data>(harness: &mut Harness<C>) {
+        let pos = Point::new(10., 10.);
+        harness.event(Event::Wheel(MouseEvent {
+            pos,
+            window_pos: pos,
+            buttons: MouseButtons::default(),
+            mods: Default::default(),
+            count: 0,
+            focus: false,
+            button: MouseButton::None,
+            wheel_delta: Vec2::new(0., 1.0e6),
+        }));
+    }
+
+    /// Starting from ten items, scroll to the end twice, with a page of ten
+    /// items arriving in between.
+    fn check_load_more_once_per_page<C: Items>(data: C) {
+        let requests = Requests::default();
+        let widget = paged_list(&requests);
+
+        Harness::create_simple(data, widget, |harness| {
+            harness.set_initial_size(Size::new(100., 5. * ROW_HEIGHT));
+            harness.send_initial_events();
+            harness.just_layout();
+            assert!(requests.borrow().is_empty());
+
+            scroll_to_end(harness);
+            scroll_to_end(harness);
+            harness.just_layout();
+            assert_eq!(*requests.borrow(), vec![10]);
+
+            harness.submit_command(APPEND.with(10));
+            harness.just_layout();
+            assert_eq!(*requests.borrow(), vec![10]);
+
+            scroll_to_end(harness);
+            harness.just_layout();
+            scroll_to_end(harness);
+            assert_eq!(*requests.borrow(), vec![10, 20]);
+        });
+    }
+
+    #[test]
+    fn load_more_once_per_page() {
+        check_load_more_once_per_page(Arc::new((0..10).collect::<Vec<u32>>()));
+    }
+
+    #[test]
+    #[cfg(feature = "im")]
+    fn load_more_once_per_page_im_vector() {
+        check_load_more_once_per_page((0..10).collect::<crate::im::Vector<u32>>());
+    }
+
+    #[test]
+    fn appending_removes_placeholder() {
+        let requests = Requests::default();
+        let placeholder = WidgetId::next();
+        let layouts = Rc::new(Cell::new(0));
+        let layouts_2 = layouts.clone();
+        let spinner = ModularWidget::new(()).layout_fn(move |_, _, bc, _, _| {
+            layouts_2.set(layouts_2.get() + 1);
+            bc.constrain((10., ROW_HEIGHT))
+        });
+        let widget = PagedList::new(|| SizedBox::empty().height(ROW_HEIGHT))
+            .with_placeholder(spinner.with_id(placeholder))
+            .controller(Source(requests.clone()));
+
+        Harness::create_simple(Arc::new(vec![0, 1]), widget, |harness| {
+            harness.set_initial_size(Size::new(100., 5. * ROW_HEIGHT));
+            harness.send_initial_events();
+            harness.just_layout();
+            // a short list asks for more right away
+            assert_eq!(*requests.borrow(), vec![2]);
+            harness.just_layout();
+            let rect = harness.get_state(placeholder).layout_rect();
+            assert_eq!(rect.origin(), Point::new(0., 2. * ROW_HEIGHT));
+            assert_eq!(rect.height(), ROW_HEIGHT);
+
+            harness.submit_command(APPEND.with(10));
+            layouts.set(0);
+            harness.just_layout();
+            assert_eq!(*requests.borrow(), vec![2]);
+            assert_eq!(layouts.get(), 0, "placeholder should be gone");
+        });
+    }
+}
@@ -84,6 +84,11 @@ impl<T: FromStr + Display + Data, W: Widget<String>> Widget<Option<T>> for Parse
         self.widget.paint(ctx, &self.state, env)
     }
 
+    #[cfg(feature = "debug_state")]
+    fn debug_state(&self, _data: &Option<T>) -> DebugState {
+        self.widget.debug_state(&self.state)
+    }
+
     fn id(&self) -> Option<WidgetId> {
         self.widget.id()
     }
@@ -52,6 +52,11 @@ impl Widget<f64> for ProgressBar {
         skip(self, ctx, _old_data, _data, _env)
     )]
     fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &f64, _data: &f64, _env: &Env) {
+        if ctx.env_key_changed(&theme::BASIC_WIDGET_HEIGHT)
+            || ctx.env_key_changed(&theme::WIDE_WIDGET_WIDTH)
+        {
+            ctx.request_layout();
+        }
         ctx.request_paint();
     }
 
@@ -118,4 +123,35 @@ impl Widget<f64> for ProgressBar {
         );
         ctx.fill(rounded_rect, &bar_gradient);
     }
+
+    #[cfg(feature = "debug_state")]
+    fn debug_state(&self, data: &f64) -> DebugState {
+        DebugState::with_value("ProgressBar", data.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::snapshot::assert_snapshot;
+    use crate::WidgetExt;
+    use test_env_log::test;
+
+    #[test]
+    fn paint() {
+        let size = Size::new(120., 30.);
+        let bar = ProgressBar::new().padding(5.);
+        Harness::create_with_render(
+            0.4,
+            bar,
+            size,
+            |harness| {
+                harness.send_initial_events();
+                harness.just_layout();
+                assert_snapshot!(harness, "progress_bar");
+            },
+            |_| {},
+        );
+    }
 }
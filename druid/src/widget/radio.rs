@@ -97,6 +97,11 @@ impl<T: Data + PartialEq> Widget<T> for Radio<T> {
     #[instrument(name = "Radio", level = "trace", skip(self, ctx, old_data, data, env))]
     fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
         self.child_label.update(ctx, old_data, data, env);
+        if ctx.env_key_changed(&theme::BASIC_WIDGET_HEIGHT)
+            || ctx.env_key_changed(&theme::WIDGET_CONTROL_COMPONENT_PADDING)
+        {
+            ctx.request_layout();
+        }
         if !old_data.same(data) {
             ctx.request_paint();
         }
@@ -162,4 +167,13 @@ impl<T: Data + PartialEq> Widget<T> for Radio<T> {
         // Paint the text label
         self.child_label.draw_at(ctx, (size + x_padding, 0.0));
     }
+
+    #[cfg(feature = "debug_state")]
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState {
+            main_value: (*data == self.variant).to_string(),
+            children: vec![self.child_label.debug_state(data)],
+            ..DebugState::new("Radio")
+        }
+    }
 }
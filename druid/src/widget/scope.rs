@@ -130,6 +130,60 @@ impl<L: Lens<State, In>, In: Data, State: Data> ScopeTransfer for LensScopeTrans
     }
 }
 
+/// A [`ScopePolicy`] for a `Scope` whose state does not depend on its input
+/// at all.
+///
+/// The input is never read into the state, and nothing is ever written back.
+/// See [`Scope::independent`].
+pub struct IndependentScopePolicy<In, State> {
+    state: State,
+    phantom_in: PhantomData<In>,
+}
+
+impl<In, State> IndependentScopePolicy<In, State> {
+    /// Create a `ScopePolicy` that starts out with `state`.
+    pub fn new(state: State) -> Self {
+        IndependentScopePolicy {
+            state,
+            phantom_in: PhantomData,
+        }
+    }
+}
+
+impl<In: Data, State: Data> ScopePolicy for IndependentScopePolicy<In, State> {
+    type In = In;
+    type State = State;
+    type Transfer = IndependentScopeTransfer<In, State>;
+
+    fn create(self, _inner: &In) -> (State, Self::Transfer) {
+        (self.state, IndependentScopeTransfer::default())
+    }
+}
+
+/// A `ScopeTransfer` that never synchronises the input and the state.
+pub struct IndependentScopeTransfer<In, State> {
+    phantom_in: PhantomData<In>,
+    phantom_state: PhantomData<State>,
+}
+
+impl<In, State> Default for IndependentScopeTransfer<In, State> {
+    fn default() -> Self {
+        IndependentScopeTransfer {
+            phantom_in: PhantomData,
+            phantom_state: PhantomData,
+        }
+    }
+}
+
+impl<In: Data, State: Data> ScopeTransfer for IndependentScopeTransfer<In, State> {
+    type In = In;
+    type State = State;
+
+    fn read_input(&self, _state: &mut State, _data: &In) {}
+
+    fn write_back_input(&self, _state: &State, _data: &mut In) {}
+}
+
 enum ScopeContent<SP: ScopePolicy> {
     Policy {
         policy: Option<SP>,
@@ -168,7 +222,15 @@ enum ScopeContent<SP: ScopePolicy> {
 ///
 /// Convenience methods are provided to make a policy from a function and a lens.
 /// It may sometimes be advisable to implement ScopePolicy directly if you need to
-/// mention the type of a Scope.
+/// mention the type of a Scope. For state that has nothing to do with the
+/// input, like whether a panel is expanded, use [`Scope::independent`].
+///
+/// The state is created the first time the scope sees its input, and then
+/// lives as long as the `Scope` widget does: it is kept when the input
+/// changes, but a newly constructed `Scope` (for instance, when a
+/// [`ViewSwitcher`] rebuilds its child) starts again from the policy.
+///
+/// [`ViewSwitcher`]: crate::widget::ViewSwitcher
 ///
 /// # Examples
 /// ```
@@ -270,6 +332,26 @@ impl<In: Data, State: Data, F: Fn(In) -> State, L: Lens<State, In>, W: Widget<St
     }
 }
 
+impl<In: Data, State: Data, W: Widget<State>> Scope<IndependentScopePolicy<In, State>, W> {
+    /// Create a scope that gives `inner` its own state, starting with `state`,
+    /// which is not connected to the input in any way.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use druid::widget::{Scope, TextBox};
+    /// use druid::Widget;
+    ///
+    /// // A search field whose contents the rest of the app doesn't care about.
+    /// fn search_field<T: druid::Data>() -> impl Widget<T> {
+    ///     Scope::independent(String::new(), TextBox::new())
+    /// }
+    /// ```
+    pub fn independent(state: State, inner: W) -> Self {
+        Self::new(IndependentScopePolicy::new(state), inner)
+    }
+}
+
 impl<SP: ScopePolicy, W: Widget<SP::State>> Widget<SP::In> for Scope<SP, W> {
     #[instrument(name = "Scope", level = "trace", skip(self, ctx, event, data, env))]
     fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut SP::In, env: &Env) {
@@ -307,8 +389,147 @@ impl<SP: ScopePolicy, W: Widget<SP::State>> Widget<SP::In> for Scope<SP, W> {
     fn paint(&mut self, ctx: &mut PaintCtx, data: &SP::In, env: &Env) {
         self.with_state(data, |state, inner| inner.paint_raw(ctx, state, env));
     }
+
+    #[cfg(feature = "debug_state")]
+    fn debug_state(&self, _data: &SP::In) -> DebugState {
+        let children = match &self.content {
+            ScopeContent::Transfer { state, .. } => vec![self.inner.debug_state(state)],
+            ScopeContent::Policy { .. } => vec![],
+        };
+        DebugState::with_children("Scope", children)
+    }
 }
 
 impl<SP: ScopePolicy, W: Widget<SP::State>> WidgetWrapper for Scope<SP, W> {
     widget_wrapper_pod_body!(W, inner);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::ModularWidget;
+    use crate::text::Selection;
+    use crate::widget::{CrossAxisAlignment, Flex, TextBox};
+    use crate::{MouseButton, MouseButtons, MouseEvent, Selector, Vec2, WidgetExt};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use test_env_log::test;
+
+    const SET_OUTER: Selector<String> = Selector::new("druid-test.set-outer");
+    const SET_INNER: Selector<String> = Selector::new("druid-test.set-inner");
+
+    #[derive(Clone, Data, Lens)]
+    struct Local {
+        name: String,
+        edits: u32,
+    }
+
+    /// A widget that sets its data in response to `selector`.
+    fn setter<T: Data>(
+        selector: Selector<String>,
+        set: impl Fn(&mut T, &str) + 'static,
+    ) -> ModularWidget<(), T> {
+        ModularWidget::new(()).event_fn(move |_, _, event, data, _| {
+            if let Event::Command(cmd) = event {
+                if let Some(value) = cmd.get(selector) {
+                    set(data, value);
+                }
+            }
+        })
+    }
+
+    /// A widget that records the data of each update it gets.
+    fn probe<T: Data>(seen: &Rc<RefCell<Vec<T>>>) -> ModularWidget<(), T> {
+        let seen = seen.clone();
+        ModularWidget::new(()).update_fn(move |_, _, _, data: &T, _| {
+            seen.borrow_mut().push(data.clone());
+        })
+    }
+
+    fn click(harness: &mut Harness<String>, pos: Point) {
+        let mouse = MouseEvent {
+            pos,
+            window_pos: pos,
+            buttons: MouseButtons::new().with(MouseButton::Left),
+            mods: Default::default(),
+            count: 1,
+            focus: false,
+            button: MouseButton::Left,
+            wheel_delta: Vec2::ZERO,
+        };
+        harness.event(Event::MouseDown(mouse.clone()));
+        harness.event(Event::MouseUp(mouse));
+    }
+
+    #[test]
+    fn independent_state_stays_local() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let scope = Scope::independent(
+            String::new(),
+            Flex::column()
+                .cross_axis_alignment(CrossAxisAlignment::Start)
+                .with_child(TextBox::new().fix_width(200.))
+                .with_child(probe(&seen)),
+        );
+        let widget = Flex::column()
+            .cross_axis_alignment(CrossAxisAlignment::Start)
+            .with_child(scope)
+            .with_child(setter(SET_OUTER, |data: &mut String, value| {
+                *data = value.into()
+            }));
+
+        Harness::create_simple("outer".to_string(), widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            click(harness, Point::new(5., 5.));
+            for c in "hi".chars() {
+                harness.ime_edit(|handler| {
+                    let selection = handler.selection();
+                    handler.replace_range(selection.range(), &c.to_string());
+                    handler.set_selection(Selection::caret(selection.min() + 1));
+                });
+            }
+            assert_eq!(seen.borrow().last().map(String::as_str), Some("hi"));
+            assert_eq!(harness.data(), "outer");
+
+            // the local state survives changes to the outer data
+            harness.submit_command(SET_OUTER.with("changed".into()));
+            assert_eq!(harness.data(), "changed");
+            assert_eq!(seen.borrow().last().map(String::as_str), Some("hi"));
+        });
+    }
+
+    #[test]
+    fn lens_scope_syncs_both_ways() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let inner = Flex::column()
+            .with_child(setter(SET_INNER, |data: &mut Local, value| {
+                data.name = value.into();
+                data.edits += 1;
+            }))
+            .with_child(probe(&seen));
+        let scope = Scope::from_lens(|name| Local { name, edits: 0 }, Local::name, inner);
+        let widget = Flex::column()
+            .with_child(scope)
+            .with_child(setter(SET_OUTER, |data: &mut String, value| {
+                *data = value.into()
+            }));
+
+        Harness::create_simple("outer".to_string(), widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+
+            harness.submit_command(SET_INNER.with("inner".into()));
+            assert_eq!(harness.data(), "inner");
+            seen.borrow_mut().clear();
+
+            harness.submit_command(SET_OUTER.with("outer again".into()));
+            // reading the new input does not bounce back and forth
+            assert_eq!(seen.borrow().len(), 1);
+            let local = &seen.borrow()[0];
+            assert_eq!(local.name, "outer again");
+            assert_eq!(local.edits, 1);
+        });
+    }
+}
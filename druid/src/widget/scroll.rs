@@ -14,11 +14,16 @@
 
 //! A container that scrolls its contents.
 
+use crate::commands::SCROLL_TO_VIEW;
+use crate::shell::Counter;
 use crate::widget::prelude::*;
 use crate::widget::{Axis, ClipBox};
-use crate::{scroll_component::*, Data, Rect, Vec2};
+use crate::{scroll_component::*, Command, Data, Rect, Selector, Vec2};
 use tracing::{instrument, trace};
 
+/// Sent to the window by a member of a [`ScrollGroup`] whose offset changed.
+const SYNC_OFFSET: Selector<SyncOffset> = Selector::new("druid-builtin.scroll-sync-offset");
+
 /// A container that scrolls its contents.
 ///
 /// This container holds a single child, and uses the wheel to scroll it
@@ -34,6 +39,75 @@ use tracing::{instrument, trace};
 pub struct Scroll<T, W> {
     clip: ClipBox<T, W>,
     scroll_component: ScrollComponent,
+    sync: Option<SyncState>,
+}
+
+/// Identifies a group of [`Scroll`]s that keep their offsets in sync.
+///
+/// This can be used to keep the headers of a table aligned with its body:
+/// the body scrolls on both axes, and the header row and header column
+/// follow it on one axis each.
+///
+/// Groups work within a single window.
+///
+/// # Examples
+/// ```
+/// use druid::widget::{Flex, Label, Scroll, ScrollGroup, SyncAxes};
+///
+/// let group = ScrollGroup::next();
+/// let header = Scroll::new(Label::<()>::new("header"))
+///     .horizontal()
+///     .sync_scroll(group, SyncAxes::Horizontal);
+/// let body = Scroll::new(Label::new("body")).sync_scroll(group, SyncAxes::Both);
+/// let table = Flex::column().with_child(header).with_flex_child(body, 1.0);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ScrollGroup(u64);
+
+/// The axes on which a [`Scroll`] follows the other members of its
+/// [`ScrollGroup`].
+///
+/// Two members follow each other on the axes they have in common.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SyncAxes {
+    /// Only the horizontal offset is shared.
+    Horizontal,
+    /// Only the vertical offset is shared.
+    Vertical,
+    /// Both offsets are shared.
+    Both,
+}
+
+struct SyncState {
+    group: ScrollGroup,
+    axes: SyncAxes,
+    /// The latest update to the group that this member has sent or followed.
+    seq: u64,
+}
+
+struct SyncOffset {
+    group: ScrollGroup,
+    axes: SyncAxes,
+    offset: Vec2,
+    seq: u64,
+}
+
+impl ScrollGroup {
+    /// Allocate a new, unique `ScrollGroup`.
+    pub fn next() -> ScrollGroup {
+        static GROUP_COUNTER: Counter = Counter::new();
+        ScrollGroup(GROUP_COUNTER.next())
+    }
+}
+
+impl SyncAxes {
+    fn includes(self, axis: Axis) -> bool {
+        match self {
+            SyncAxes::Horizontal => axis == Axis::Horizontal,
+            SyncAxes::Vertical => axis == Axis::Vertical,
+            SyncAxes::Both => true,
+        }
+    }
 }
 
 impl<T, W: Widget<T>> Scroll<T, W> {
@@ -46,6 +120,7 @@ impl<T, W: Widget<T>> Scroll<T, W> {
         Scroll {
             clip: ClipBox::new(child),
             scroll_component: ScrollComponent::new(),
+            sync: None,
         }
     }
 
@@ -99,6 +174,21 @@ impl<T, W> Scroll<T, W> {
         self
     }
 
+    /// Builder-style method to keep this `Scroll`'s offset in sync with the
+    /// other members of `group`, on the given axes.
+    ///
+    /// When any member is scrolled, the others follow. If the content of one
+    /// member is too small for the shared offset, all members are clamped to
+    /// the offset it can show.
+    pub fn sync_scroll(mut self, group: ScrollGroup, axes: SyncAxes) -> Self {
+        self.sync = Some(SyncState {
+            group,
+            axes,
+            seq: 0,
+        });
+        self
+    }
+
     /// Disable both scrollbars
     pub fn disable_scrollbars(mut self) -> Self {
         self.scroll_component.enabled = ScrollbarsEnabled::None;
@@ -175,6 +265,29 @@ impl<T, W> Scroll<T, W> {
 impl<T: Data, W: Widget<T>> Widget<T> for Scroll<T, W> {
     #[instrument(name = "Scroll", level = "trace", skip(self, ctx, event, data, env))]
     fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        let old_offset = self.offset();
+        match event {
+            Event::Notification(note) => {
+                if let Some(&area) = note.get(SCROLL_TO_VIEW) {
+                    ctx.set_handled();
+                    self.handle_scroll_to_view(ctx, area, env);
+                    if let Some(update) = self.sync_update(old_offset) {
+                        ctx.submit_command(update);
+                    }
+                    return;
+                }
+            }
+            // this goes to every widget in the window, so it is never handled
+            Event::Command(cmd) => {
+                if let Some(sync) = cmd.get(SYNC_OFFSET) {
+                    self.follow(ctx, sync);
+                    self.clip.event(ctx, event, data, env);
+                    return;
+                }
+            }
+            _ => (),
+        }
+
         let scroll_component = &mut self.scroll_component;
         self.clip.with_port(|port| {
             scroll_component.event(port, ctx, event, env);
@@ -186,6 +299,9 @@ impl<T: Data, W: Widget<T>> Widget<T> for Scroll<T, W> {
         self.clip.with_port(|port| {
             scroll_component.handle_scroll(port, ctx, event, env);
         });
+        if let Some(update) = self.sync_update(old_offset) {
+            ctx.submit_command(update);
+        }
     }
 
     #[instrument(name = "Scroll", level = "trace", skip(self, ctx, event, data, env))]
@@ -204,6 +320,7 @@ impl<T: Data, W: Widget<T>> Widget<T> for Scroll<T, W> {
         bc.debug_check("Scroll");
 
         let old_size = self.clip.viewport().view_size;
+        let old_offset = self.offset();
         let child_size = self.clip.layout(ctx, &bc, data, env);
         log_size_warnings(child_size);
 
@@ -211,6 +328,10 @@ impl<T: Data, W: Widget<T>> Widget<T> for Scroll<T, W> {
         // The new size might have made the current scroll offset invalid. This makes it valid
         // again.
         let _ = self.scroll_by(Vec2::ZERO);
+        // if we had to clamp, the rest of the group should clamp as well
+        if let Some(update) = self.sync_update(old_offset) {
+            ctx.submit_command(update);
+        }
         if old_size != self_size {
             self.scroll_component
                 .reset_scrollbar_fade(|d| ctx.request_timer(d), env);
@@ -226,6 +347,90 @@ impl<T: Data, W: Widget<T>> Widget<T> for Scroll<T, W> {
         self.scroll_component
             .draw_bars(ctx, &self.clip.viewport(), env);
     }
+
+    #[cfg(feature = "debug_state")]
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState::with_children("Scroll", vec![self.clip.debug_state(data)])
+    }
+}
+
+impl<T, W: Widget<T>> Scroll<T, W> {
+    /// Scroll so that `area`, given in window coordinates, is visible.
+    ///
+    /// The part of `area` that is now visible is then forwarded to our own
+    /// ancestors, so that nested scroll views all move as needed.
+    fn handle_scroll_to_view(&mut self, ctx: &mut EventCtx, area: Rect, env: &Env) {
+        let content_origin = ctx.window_origin() - self.offset();
+        let content_area = area - content_origin.to_vec2();
+        if self.scroll_to(content_area) {
+            ctx.request_paint();
+            self.scroll_component
+                .reset_scrollbar_fade(|d| ctx.request_timer(d), env);
+        }
+
+        let visible = (content_area - self.offset()).intersect(ctx.size().to_rect());
+        if visible.area() > 0.0 {
+            ctx.scroll_area_to_view(visible);
+        }
+    }
+}
+
+impl<T, W: Widget<T>> Scroll<T, W> {
+    /// The update for the rest of our [`ScrollGroup`], if our offset changed
+    /// on the axes we share with it.
+    ///
+    /// Every update gets a sequence number, so that if several members move
+    /// before the updates are delivered, they all settle on the latest one.
+    fn sync_update(&mut self, old_offset: Vec2) -> Option<Command> {
+        static SEQ_COUNTER: Counter = Counter::new();
+        let offset = self.offset();
+        let sync = self.sync.as_mut()?;
+        let moved = [Axis::Horizontal, Axis::Vertical].iter().any(|&axis| {
+            sync.axes.includes(axis) && axis.major_vec(offset) != axis.major_vec(old_offset)
+        });
+        if !moved {
+            return None;
+        }
+        sync.seq = SEQ_COUNTER.next();
+        Some(SYNC_OFFSET.with(SyncOffset {
+            group: sync.group,
+            axes: sync.axes,
+            offset,
+            seq: sync.seq,
+        }))
+    }
+
+    /// Move to the offset another member of our group has moved to.
+    fn follow(&mut self, ctx: &mut EventCtx, update: &SyncOffset) {
+        let axes = match &mut self.sync {
+            Some(sync) if sync.group == update.group && update.seq > sync.seq => {
+                sync.seq = update.seq;
+                sync.axes
+            }
+            _ => return,
+        };
+        let shared = |axis| axes.includes(axis) && update.axes.includes(axis);
+        let old_offset = self.offset();
+        let mut target = old_offset;
+        if shared(Axis::Horizontal) {
+            target.x = update.offset.x;
+        }
+        if shared(Axis::Vertical) {
+            target.y = update.offset.y;
+        }
+        if self.clip.pan_to(target.to_point()) {
+            ctx.request_paint();
+        }
+        // we couldn't go as far, so the others have to come back
+        let offset = self.offset();
+        if (shared(Axis::Horizontal) && offset.x != target.x)
+            || (shared(Axis::Vertical) && offset.y != target.y)
+        {
+            if let Some(update) = self.sync_update(target) {
+                ctx.submit_command(update);
+            }
+        }
+    }
 }
 
 fn log_size_warnings(size: Size) {
@@ -237,3 +442,122 @@ fn log_size_warnings(size: Size) {
         tracing::warn!("Scroll widget's child has an infinite height.");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::{widget_ids, ModularWidget};
+    use crate::tests::scroll_mouse;
+    use crate::widget::{Controller, Flex, SizedBox};
+    use crate::WidgetExt;
+    use test_env_log::test;
+
+    const SET_WIDTH: Selector<f64> = Selector::new("druid-tests.set-width");
+
+    struct SetWidth;
+
+    impl<W: Widget<f64>> Controller<f64, W> for SetWidth {
+        fn event(
+            &mut self,
+            child: &mut W,
+            ctx: &mut EventCtx,
+            event: &Event,
+            data: &mut f64,
+            env: &Env,
+        ) {
+            match event {
+                Event::Command(cmd) if cmd.is(SET_WIDTH) => *data = *cmd.get_unchecked(SET_WIDTH),
+                _ => child.event(ctx, event, data, env),
+            }
+        }
+    }
+
+    /// A widget as wide as the data, minus `less`.
+    fn content(less: f64, height: f64) -> impl Widget<f64> {
+        ModularWidget::new(())
+            .layout_fn(move |_, _ctx, _bc, width, _env| Size::new(width - less, height))
+    }
+
+    /// A table with a header row that is 200 narrower than the body.
+    fn table(row: WidgetId, column: WidgetId, body: WidgetId) -> impl Widget<f64> {
+        let group = ScrollGroup::next();
+        let header_row = Scroll::new(content(200., 20.).with_id(row))
+            .horizontal()
+            .sync_scroll(group, SyncAxes::Horizontal);
+        let header_column = Scroll::new(SizedBox::empty().width(50.).height(1000.).with_id(column))
+            .vertical()
+            .sync_scroll(group, SyncAxes::Vertical);
+        let body = Scroll::new(content(0., 1000.).with_id(body)).sync_scroll(group, SyncAxes::Both);
+
+        Flex::column()
+            .with_child(
+                Flex::row()
+                    .with_child(SizedBox::empty().width(50.).height(20.))
+                    .with_child(header_row.fix_width(300.).fix_height(20.)),
+            )
+            .with_child(
+                Flex::row()
+                    .with_child(header_column.fix_width(50.).fix_height(300.))
+                    .with_child(body.fix_width(300.).fix_height(300.)),
+            )
+            .controller(SetWidth)
+    }
+
+    #[test]
+    fn headers_follow_body() {
+        let [row, column, body] = widget_ids();
+        Harness::create_simple(1200., table(row, column, body), |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            let origin = |harness: &mut Harness<f64>, id| harness.get_state(id).window_origin();
+            let (row_0, column_0, body_0) = (
+                origin(harness, row),
+                origin(harness, column),
+                origin(harness, body),
+            );
+
+            harness.event(Event::Wheel(scroll_mouse((200., 150.), (30., 40.))));
+            harness.just_layout();
+            assert_eq!(origin(harness, body) - body_0, Vec2::new(-30., -40.));
+            assert_eq!(origin(harness, row) - row_0, Vec2::new(-30., 0.));
+            assert_eq!(origin(harness, column) - column_0, Vec2::new(0., -40.));
+
+            // and the body follows a header, on the axis they share
+            harness.event(Event::Wheel(scroll_mouse((40., 150.), (0., 60.))));
+            harness.just_layout();
+            assert_eq!(origin(harness, body) - body_0, Vec2::new(-30., -100.));
+            assert_eq!(origin(harness, row) - row_0, Vec2::new(-30., 0.));
+        })
+    }
+
+    #[test]
+    fn shrinking_content_clamps_the_group() {
+        let [row, column, body] = widget_ids();
+        Harness::create_simple(1200., table(row, column, body), |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            let origin = |harness: &mut Harness<f64>, id| harness.get_state(id).window_origin();
+            let (row_0, body_0) = (origin(harness, row), origin(harness, body));
+
+            harness.event(Event::Wheel(scroll_mouse((200., 150.), (600., 0.))));
+            harness.just_layout();
+            assert_eq!(origin(harness, body).x - body_0.x, -600.);
+            assert_eq!(origin(harness, row).x - row_0.x, -600.);
+
+            // both members have to clamp in the same layout pass, to different
+            // offsets; the one that can show the least wins
+            harness.submit_command(SET_WIDTH.with(800.));
+            harness.just_layout();
+            // the offsets are synced after layout; the next one moves the content
+            harness.just_layout();
+            assert_eq!(origin(harness, row).x - row_0.x, -300.);
+            assert_eq!(origin(harness, body).x - body_0.x, -300.);
+
+            // and it stays there
+            harness.just_layout();
+            assert_eq!(origin(harness, row).x - row_0.x, -300.);
+            assert_eq!(origin(harness, body).x - body_0.x, -300.);
+        })
+    }
+}
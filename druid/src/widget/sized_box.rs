@@ -18,6 +18,7 @@ use std::f64::INFINITY;
 use tracing::{instrument, trace, warn};
 
 use crate::widget::prelude::*;
+use crate::widget::{Axis, Intrinsic};
 use crate::Data;
 
 /// A widget with predefined size.
@@ -104,19 +105,14 @@ impl<T> SizedBox<T> {
     fn child_constraints(&self, bc: &BoxConstraints) -> BoxConstraints {
         // if we don't have a width/height, we don't change that axis.
         // if we have a width/height, we clamp it on that axis.
+        let fixed = bc.constrain((self.width.unwrap_or(0.), self.height.unwrap_or(0.)));
         let (min_width, max_width) = match self.width {
-            Some(width) => {
-                let w = width.max(bc.min().width).min(bc.max().width);
-                (w, w)
-            }
+            Some(_) => (fixed.width, fixed.width),
             None => (bc.min().width, bc.max().width),
         };
 
         let (min_height, max_height) = match self.height {
-            Some(height) => {
-                let h = height.max(bc.min().height).min(bc.max().height);
-                (h, h)
-            }
+            Some(_) => (fixed.height, fixed.height),
             None => (bc.min().height, bc.max().height),
         };
 
@@ -180,6 +176,29 @@ impl<T: Data> Widget<T> for SizedBox<T> {
         size
     }
 
+    fn compute_intrinsic_size(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        axis: Axis,
+        kind: Intrinsic,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        let fixed = match axis {
+            Axis::Horizontal => self.width,
+            Axis::Vertical => self.height,
+        };
+        let child_bc = self.child_constraints(bc);
+        match (fixed, self.inner.as_mut()) {
+            // an expanded box takes whatever it gets, so it doesn't have
+            // a fixed size to report.
+            (Some(size), _) if size.is_finite() => size,
+            (_, Some(inner)) => inner.compute_intrinsic_size(ctx, axis, kind, &child_bc, data, env),
+            (_, None) => 0.0,
+        }
+    }
+
     #[instrument(name = "SizedBox", level = "trace", skip(self, ctx, data, env))]
     fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
         if let Some(ref mut inner) = self.inner {
@@ -187,6 +206,12 @@ impl<T: Data> Widget<T> for SizedBox<T> {
         }
     }
 
+    #[cfg(feature = "debug_state")]
+    fn debug_state(&self, data: &T) -> DebugState {
+        let children = self.inner.iter().map(|inner| inner.debug_state(data));
+        DebugState::with_children("SizedBox", children.collect())
+    }
+
     fn id(&self) -> Option<WidgetId> {
         self.inner.as_ref().and_then(|inner| inner.id())
     }
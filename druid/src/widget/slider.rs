@@ -136,6 +136,11 @@ impl Widget<f64> for Slider {
         skip(self, ctx, _old_data, _data, _env)
     )]
     fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &f64, _data: &f64, _env: &Env) {
+        if ctx.env_key_changed(&theme::BASIC_WIDGET_HEIGHT)
+            || ctx.env_key_changed(&theme::WIDE_WIDGET_WIDTH)
+        {
+            ctx.request_layout();
+        }
         ctx.request_paint();
     }
 
@@ -231,4 +236,9 @@ impl Widget<f64> for Slider {
         //Actually paint the knob
         ctx.fill(knob_circle, &knob_gradient);
     }
+
+    #[cfg(feature = "debug_state")]
+    fn debug_state(&self, data: &f64) -> DebugState {
+        DebugState::with_value("Slider", data.to_string())
+    }
 }
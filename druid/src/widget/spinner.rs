@@ -91,9 +91,13 @@ impl<T: Data> Widget<T> for Spinner {
     #[instrument(
         name = "Spinner",
         level = "trace",
-        skip(self, _ctx, _old_data, _data, _env)
+        skip(self, ctx, _old_data, _data, _env)
     )]
-    fn update(&mut self, _ctx: &mut UpdateCtx, _old_data: &T, _data: &T, _env: &Env) {}
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, _data: &T, _env: &Env) {
+        if ctx.env_key_changed(&theme::BASIC_WIDGET_HEIGHT) {
+            ctx.request_layout();
+        }
+    }
 
     #[instrument(
         name = "Spinner",
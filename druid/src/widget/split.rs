@@ -501,4 +501,10 @@ impl<T: Data> Widget<T> for Split<T> {
         self.child1.paint(ctx, &data, env);
         self.child2.paint(ctx, &data, env);
     }
+
+    #[cfg(feature = "debug_state")]
+    fn debug_state(&self, data: &T) -> DebugState {
+        let children = vec![self.child1.debug_state(data), self.child2.debug_state(data)];
+        DebugState::with_children("Split", children)
+    }
 }
@@ -198,6 +198,11 @@ impl Widget<f64> for Stepper {
         ctx.fill(arrows, &color);
     }
 
+    #[cfg(feature = "debug_state")]
+    fn debug_state(&self, data: &f64) -> DebugState {
+        DebugState::with_value("Stepper", data.to_string())
+    }
+
     #[instrument(
         name = "Stepper",
         level = "trace",
@@ -278,6 +283,11 @@ impl Widget<f64> for Stepper {
         skip(self, ctx, old_data, data, _env)
     )]
     fn update(&mut self, ctx: &mut UpdateCtx, old_data: &f64, data: &f64, _env: &Env) {
+        if ctx.env_key_changed(&theme::BASIC_WIDGET_HEIGHT)
+            || ctx.env_key_changed(&theme::BORDERED_WIDGET_HEIGHT)
+        {
+            ctx.request_layout();
+        }
         if (*data - old_data).abs() > EPSILON {
             ctx.request_paint();
         }
@@ -20,18 +20,27 @@ use tracing::{instrument, trace};
 use crate::kurbo::{Circle, Shape};
 use crate::piet::{LinearGradient, RenderContext, UnitPoint};
 use crate::widget::prelude::*;
-use crate::{theme, ArcStr, Point, TextLayout};
+use crate::{theme, ArcStr, KbKey, Point, TextLayout};
 
 const SWITCH_CHANGE_TIME: f64 = 0.2;
-const SWITCH_PADDING: f64 = 3.;
 const SWITCH_WIDTH_RATIO: f64 = 2.75;
+/// How far the mouse has to move sideways before a press becomes a drag.
+const SWITCH_DRAG_THRESHOLD: f64 = 4.;
 
 /// A switch that toggles a `bool`.
+///
+/// Clicking the switch, or pressing space or enter while it has focus,
+/// toggles the value. The knob can also be dragged; the value changes when
+/// it is released past the middle of the switch, and otherwise the knob
+/// goes back to where it was.
 #[derive(Debug, Clone)]
 pub struct Switch {
     knob_pos: Point,
     knob_hovered: bool,
     knob_dragged: bool,
+    // where the mouse and the knob were when the mouse was pressed
+    press_x: f64,
+    press_knob_x: f64,
     animation_in_progress: bool,
     on_text: TextLayout<ArcStr>,
     off_text: TextLayout<ArcStr>,
@@ -43,6 +52,8 @@ impl Default for Switch {
             knob_pos: Point::ZERO,
             knob_hovered: false,
             knob_dragged: false,
+            press_x: 0.0,
+            press_knob_x: 0.0,
             animation_in_progress: false,
             //TODO: use localized strings, also probably make these configurable?
             on_text: TextLayout::from_text("ON"),
@@ -63,26 +74,26 @@ impl Switch {
     }
 
     fn paint_labels(&mut self, ctx: &mut PaintCtx, env: &Env, switch_width: f64) {
-        self.on_text.rebuild_if_needed(ctx.text(), env);
-        self.off_text.rebuild_if_needed(ctx.text(), env);
+        self.on_text.rebuild_if_needed(ctx.text_factory(), env);
+        self.off_text.rebuild_if_needed(ctx.text_factory(), env);
 
         let switch_height = env.get(theme::BORDERED_WIDGET_HEIGHT);
-        let knob_size = switch_height - 2. * SWITCH_PADDING;
+        let padding = env.get(theme::SWITCH_PADDING);
+        let knob_size = switch_height - 2. * padding;
 
         let on_size = self.on_text.size();
         let off_size = self.off_text.size();
 
         let label_y = (switch_height - on_size.height).max(0.0) / 2.0;
-        let label_x_space = switch_width - knob_size - SWITCH_PADDING * 2.0;
-        let off_pos = knob_size / 2. + SWITCH_PADDING;
+        let label_x_space = switch_width - knob_size - padding * 2.0;
+        let off_pos = knob_size / 2. + padding;
         let knob_delta = self.knob_pos.x - off_pos;
 
         let on_label_width = on_size.width;
-        let on_base_x_pos =
-            -on_label_width - (label_x_space - on_label_width) / 2.0 + SWITCH_PADDING;
+        let on_base_x_pos = -on_label_width - (label_x_space - on_label_width) / 2.0 + padding;
         let on_label_origin = Point::new(on_base_x_pos + knob_delta, label_y);
 
-        let off_base_x_pos = knob_size + (label_x_space - off_size.width) / 2.0 + SWITCH_PADDING;
+        let off_base_x_pos = knob_size + (label_x_space - off_size.width) / 2.0 + padding;
         let off_label_origin = Point::new(off_base_x_pos + knob_delta, label_y);
 
         self.on_text.draw(ctx, on_label_origin);
@@ -95,25 +106,37 @@ impl Widget<bool> for Switch {
     fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut bool, env: &Env) {
         let switch_height = env.get(theme::BORDERED_WIDGET_HEIGHT);
         let switch_width = switch_height * SWITCH_WIDTH_RATIO;
-        let knob_size = switch_height - 2. * SWITCH_PADDING;
-        let on_pos = switch_width - knob_size / 2. - SWITCH_PADDING;
-        let off_pos = knob_size / 2. + SWITCH_PADDING;
+        let padding = env.get(theme::SWITCH_PADDING);
+        let knob_size = switch_height - 2. * padding;
+        let on_pos = switch_width - knob_size / 2. - padding;
+        let off_pos = knob_size / 2. + padding;
 
         match event {
-            Event::MouseDown(_) => {
+            Event::MouseDown(mouse) => {
                 if !ctx.is_disabled() {
                     ctx.set_active(true);
+                    ctx.request_focus();
+                    if !self.animation_in_progress {
+                        self.knob_pos.x = if *data { on_pos } else { off_pos };
+                    }
+                    self.press_x = mouse.pos.x;
+                    self.press_knob_x = self.knob_pos.x;
                     ctx.request_paint();
                 }
             }
             Event::MouseUp(_) => {
-                if !ctx.is_disabled() {
-                    if self.knob_dragged {
+                if ctx.is_active() && !ctx.is_disabled() {
+                    let value = if self.knob_dragged {
                         // toggle value when dragging if knob has been moved far enough
-                        *data = self.knob_pos.x > switch_width / 2.;
-                    } else if ctx.is_active() {
+                        self.knob_pos.x > switch_width / 2.
+                    } else if ctx.is_hot() {
                         // toggle value on click
-                        *data = !*data;
+                        !*data
+                    } else {
+                        *data
+                    };
+                    if value != *data {
+                        *data = value;
                     }
                 }
 
@@ -126,8 +149,17 @@ impl Widget<bool> for Switch {
             Event::MouseMove(mouse) => {
                 if !ctx.is_disabled() {
                     if ctx.is_active() {
-                        self.knob_pos.x = mouse.pos.x.min(on_pos).max(off_pos);
-                        self.knob_dragged = true;
+                        // Only take over the gesture once it is clearly a
+                        // sideways drag, so small movements are still clicks,
+                        // and don't get in the way of an enclosing scroll.
+                        let delta = mouse.pos.x - self.press_x;
+                        if delta.abs() > SWITCH_DRAG_THRESHOLD {
+                            self.knob_dragged = true;
+                        }
+                        if self.knob_dragged {
+                            self.knob_pos.x = (self.press_knob_x + delta).min(on_pos).max(off_pos);
+                            ctx.set_handled();
+                        }
                     }
                     if ctx.is_hot() {
                         self.knob_hovered = self.knob_hit_test(knob_size, mouse.pos)
@@ -137,13 +169,25 @@ impl Widget<bool> for Switch {
                     ctx.set_active(false);
                 }
             }
+            Event::KeyDown(key) if ctx.is_focused() && !ctx.is_disabled() => match &key.key {
+                KbKey::Enter => {
+                    *data = !*data;
+                    ctx.set_handled();
+                }
+                KbKey::Character(c) if c == " " => {
+                    *data = !*data;
+                    ctx.set_handled();
+                }
+                _ => (),
+            },
             Event::AnimFrame(interval) => {
                 let delta = Duration::from_nanos(*interval).as_secs_f64();
                 let switch_height = env.get(theme::BORDERED_WIDGET_HEIGHT);
                 let switch_width = switch_height * SWITCH_WIDTH_RATIO;
-                let knob_size = switch_height - 2. * SWITCH_PADDING;
-                let on_pos = switch_width - knob_size / 2. - SWITCH_PADDING;
-                let off_pos = knob_size / 2. + SWITCH_PADDING;
+                let padding = env.get(theme::SWITCH_PADDING);
+                let knob_size = switch_height - 2. * padding;
+                let on_pos = switch_width - knob_size / 2. - padding;
+                let off_pos = knob_size / 2. + padding;
 
                 // move knob to right position depending on the value
                 if self.animation_in_progress {
@@ -172,9 +216,11 @@ impl Widget<bool> for Switch {
     fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, _data: &bool, env: &Env) {
         match event {
             LifeCycle::WidgetAdded => {
-                self.on_text.rebuild_if_needed(ctx.text(), env);
-                self.off_text.rebuild_if_needed(ctx.text(), env);
+                self.on_text.rebuild_if_needed(ctx.text_factory(), env);
+                self.off_text.rebuild_if_needed(ctx.text_factory(), env);
             }
+            LifeCycle::BuildFocusChain => ctx.register_for_focus(),
+            LifeCycle::FocusChanged(_) => ctx.request_paint(),
             LifeCycle::DisabledChanged(true) if self.knob_dragged => {
                 self.knob_dragged = false;
                 self.animation_in_progress = true;
@@ -201,6 +247,12 @@ impl Widget<bool> for Switch {
         skip(self, ctx, old_data, data, _env)
     )]
     fn update(&mut self, ctx: &mut UpdateCtx, old_data: &bool, data: &bool, _env: &Env) {
+        if ctx.env_key_changed(&theme::BORDERED_WIDGET_HEIGHT) {
+            ctx.request_layout();
+        }
+        if ctx.env_key_changed(&theme::SWITCH_PADDING) {
+            ctx.request_paint();
+        }
         if old_data != data {
             self.animation_in_progress = true;
             ctx.request_anim_frame();
@@ -215,7 +267,7 @@ impl Widget<bool> for Switch {
         _data: &bool,
         env: &Env,
     ) -> Size {
-        self.on_text.rebuild_if_needed(ctx.text(), env);
+        self.on_text.rebuild_if_needed(ctx.text_factory(), env);
 
         let text_metrics = self.on_text.layout_metrics();
         let height = env.get(theme::BORDERED_WIDGET_HEIGHT);
@@ -235,9 +287,10 @@ impl Widget<bool> for Switch {
     fn paint(&mut self, ctx: &mut PaintCtx, data: &bool, env: &Env) {
         let switch_height = env.get(theme::BORDERED_WIDGET_HEIGHT);
         let switch_width = switch_height * SWITCH_WIDTH_RATIO;
-        let knob_size = switch_height - 2. * SWITCH_PADDING;
-        let on_pos = switch_width - knob_size / 2. - SWITCH_PADDING;
-        let off_pos = knob_size / 2. + SWITCH_PADDING;
+        let padding = env.get(theme::SWITCH_PADDING);
+        let knob_size = switch_height - 2. * padding;
+        let on_pos = switch_width - knob_size / 2. - padding;
+        let off_pos = knob_size / 2. + padding;
         let stroke_width = 2.0;
 
         let background_rect = Size::new(switch_width, switch_height)
@@ -254,7 +307,7 @@ impl Widget<bool> for Switch {
             }
         };
 
-        self.knob_pos = Point::new(self.knob_pos.x, knob_size / 2. + SWITCH_PADDING);
+        self.knob_pos = Point::new(self.knob_pos.x, knob_size / 2. + padding);
         let knob_circle = Circle::new(self.knob_pos, knob_size / 2.);
 
         // paint different background for on and off state
@@ -283,7 +336,12 @@ impl Widget<bool> for Switch {
             ),
         );
 
-        ctx.stroke(background_rect, &env.get(theme::BORDER_DARK), stroke_width);
+        let border_color = if ctx.is_focused() {
+            env.get(theme::PRIMARY_LIGHT)
+        } else {
+            env.get(theme::BORDER_DARK)
+        };
+        ctx.stroke(background_rect, &border_color, stroke_width);
         ctx.fill(background_rect, &background_gradient_on_state);
         ctx.fill(background_rect, &background_gradient_off_state);
         ctx.clip(background_rect);
@@ -334,4 +392,123 @@ impl Widget<bool> for Switch {
         // paint on/off label
         self.paint_labels(ctx, env, switch_width);
     }
+
+    #[cfg(feature = "debug_state")]
+    fn debug_state(&self, data: &bool) -> DebugState {
+        DebugState::with_value("Switch", data.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::move_mouse;
+    use crate::widget::Align;
+    use crate::{KeyEvent, Modifiers};
+    use test_env_log::test;
+
+    // With the default theme the switch is 66 by 24; the knob rests at
+    // x = 12 when off and x = 54 when on.
+    const MIDDLE_Y: f64 = 12.;
+
+    fn switch_harness(data: bool, mut test: impl FnMut(&mut Harness<bool>)) {
+        let switch = Align::new(UnitPoint::TOP_LEFT, Switch::new());
+        Harness::create_simple(data, switch, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            test(harness);
+        });
+    }
+
+    fn press(harness: &mut Harness<bool>, x: f64) {
+        harness.event(Event::MouseMove(move_mouse((x, MIDDLE_Y))));
+        harness.event(Event::MouseDown(move_mouse((x, MIDDLE_Y))));
+    }
+
+    fn drag(harness: &mut Harness<bool>, x: f64) {
+        harness.event(Event::MouseMove(move_mouse((x, MIDDLE_Y))));
+    }
+
+    fn release(harness: &mut Harness<bool>, x: f64) {
+        harness.event(Event::MouseUp(move_mouse((x, MIDDLE_Y))));
+    }
+
+    fn key(harness: &mut Harness<bool>, key: KbKey) {
+        harness.event(Event::KeyDown(KeyEvent::for_test(Modifiers::empty(), key)));
+    }
+
+    #[test]
+    fn click_toggles() {
+        switch_harness(false, |harness| {
+            press(harness, 30.);
+            release(harness, 30.);
+            assert!(*harness.data());
+            press(harness, 30.);
+            release(harness, 30.);
+            assert!(!*harness.data());
+
+            // a small movement is still a click
+            press(harness, 30.);
+            drag(harness, 32.);
+            release(harness, 32.);
+            assert!(*harness.data());
+        });
+    }
+
+    #[test]
+    fn drag_past_middle_commits() {
+        switch_harness(false, |harness| {
+            press(harness, 12.);
+            drag(harness, 30.);
+            drag(harness, 50.);
+            // nothing is written until the knob is released
+            assert!(!*harness.data());
+            release(harness, 50.);
+            assert!(*harness.data());
+
+            press(harness, 54.);
+            drag(harness, 20.);
+            release(harness, 20.);
+            assert!(!*harness.data());
+        });
+    }
+
+    #[test]
+    fn drag_snaps_back() {
+        switch_harness(false, |harness| {
+            press(harness, 12.);
+            drag(harness, 25.);
+            release(harness, 25.);
+            // not past the middle, and not a click either
+            assert!(!*harness.data());
+
+            // dragging back to where it started doesn't toggle either
+            press(harness, 12.);
+            drag(harness, 40.);
+            drag(harness, 12.);
+            release(harness, 12.);
+            assert!(!*harness.data());
+        });
+    }
+
+    #[test]
+    fn keyboard_toggles_when_focused() {
+        switch_harness(false, |harness| {
+            key(harness, KbKey::Enter);
+            assert!(!*harness.data());
+
+            // clicking focuses the switch
+            press(harness, 30.);
+            release(harness, 30.);
+            assert!(*harness.data());
+
+            key(harness, KbKey::Character(" ".into()));
+            assert!(!*harness.data());
+            key(harness, KbKey::Enter);
+            assert!(*harness.data());
+            key(harness, KbKey::Tab);
+            assert!(*harness.data());
+        });
+    }
 }
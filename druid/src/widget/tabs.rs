@@ -25,7 +25,7 @@ use tracing::{instrument, trace};
 use crate::kurbo::{Circle, Line};
 use crate::widget::prelude::*;
 use crate::widget::{Axis, Flex, Label, LabelText, LensScopeTransfer, Painter, Scope, ScopePolicy};
-use crate::{theme, Affine, Data, Insets, Lens, Point, SingleUse, WidgetExt, WidgetPod};
+use crate::{theme, Affine, Data, Lens, Point, SingleUse, WidgetExt, WidgetPod};
 
 type TabsScope<TP> = Scope<TabsScopePolicy<TP>, Box<dyn Widget<TabsState<TP>>>>;
 type TabBodyPod<TP> = WidgetPod<<TP as TabsPolicy>::Input, <TP as TabsPolicy>::BodyWidget>;
@@ -281,7 +281,7 @@ impl<TP: TabsPolicy> TabBar<TP> {
                 .policy
                 .tab_label(key.clone(), info, &data.inner)
                 .lens(TabsState::<TP>::inner)
-                .padding(Insets::uniform_xy(9., 5.));
+                .padding(theme::TAB_LABEL_INSETS);
 
             if can_close {
                 let close_button = Painter::new(|ctx, _, env| {
@@ -464,6 +464,12 @@ impl<TP: TabsPolicy> Widget<TabsState<TP>> for TabBar<TP> {
             }
         }
     }
+
+    #[cfg(feature = "debug_state")]
+    fn debug_state(&self, data: &TabsState<TP>) -> DebugState {
+        let children = self.tabs.iter().map(|(_, tab)| tab.debug_state(data));
+        DebugState::with_children("TabBar", children.collect())
+    }
 }
 
 struct TabsTransitionState {
@@ -712,6 +718,13 @@ impl<TP: TabsPolicy> Widget<TabsState<TP>> for TabsBody<TP> {
             child.paint_raw(ctx, &data.inner, env);
         }
     }
+
+    #[cfg(feature = "debug_state")]
+    fn debug_state(&self, data: &TabsState<TP>) -> DebugState {
+        let children = self.children.get(data.selected);
+        let children = children.map(|(_, child)| child.debug_state(&data.inner));
+        DebugState::with_children("TabsBody", children.into_iter().collect())
+    }
 }
 
 // This only needs to exist to be able to give a reasonable type to the TabScope
@@ -803,7 +816,7 @@ enum TabsContent<TP: TabsPolicy> {
         tabs: TP,
     },
     Running {
-        scope: WidgetPod<TP::Input, TabsScope<TP>>,
+        scope: Box<WidgetPod<TP::Input, TabsScope<TP>>>,
     },
     Swapping,
 }
@@ -974,13 +987,13 @@ impl<TP: TabsPolicy> Widget<TP::Input> for Tabs<TP> {
                 TabsContent::Building { tabs } => {
                     ctx.children_changed();
                     TabsContent::Running {
-                        scope: self.make_scope(TP::build(tabs)),
+                        scope: Box::new(self.make_scope(TP::build(tabs))),
                     }
                 }
                 TabsContent::Complete { tabs } => {
                     ctx.children_changed();
                     TabsContent::Running {
-                        scope: self.make_scope(tabs),
+                        scope: Box::new(self.make_scope(tabs)),
                     }
                 }
                 _ => content,
@@ -1021,4 +1034,13 @@ impl<TP: TabsPolicy> Widget<TP::Input> for Tabs<TP> {
             scope.paint(ctx, data, env)
         }
     }
+
+    #[cfg(feature = "debug_state")]
+    fn debug_state(&self, data: &TP::Input) -> DebugState {
+        let children = match &self.content {
+            TabsContent::Running { scope } => vec![scope.debug_state(data)],
+            _ => vec![],
+        };
+        DebugState::with_children("Tabs", children)
+    }
 }
@@ -37,6 +37,12 @@ const SCROLL_TO_INSETS: Insets = Insets::uniform_xy(40.0, 0.0);
 
 /// A widget that allows user text input.
 ///
+/// # Undo
+///
+/// Each `TextBox` keeps its own edit history, separate from any undo in the
+/// application. While it is focused, the [`UNDO`] and [`REDO`] commands, which
+/// the standard key bindings send, undo and redo its edits.
+///
 /// # Editing values
 ///
 /// If the text you are editing represents a value of some other type, such
@@ -46,6 +52,8 @@ const SCROLL_TO_INSETS: Insets = Insets::uniform_xy(40.0, 0.0);
 ///
 /// [`Formatter`]: crate::text::format::Formatter
 /// [`ValueTextBox`]: super::ValueTextBox
+/// [`UNDO`]: crate::commands::UNDO
+/// [`REDO`]: crate::commands::REDO
 pub struct TextBox<T> {
     placeholder: TextLayout<String>,
     inner: Scroll<T, Padding<T, TextComponent<T>>>,
@@ -444,6 +452,23 @@ impl<T: TextStorage + EditableText> Widget<T> for TextBox<T> {
                 }
                 ctx.set_handled();
             }
+            Event::Command(cmd)
+                if !self.text().is_composing()
+                    && ctx.is_focused()
+                    && (cmd.is(crate::commands::UNDO) || cmd.is(crate::commands::REDO)) =>
+            {
+                let mut session = self.text_mut().borrow_mut();
+                let inval = if cmd.is(crate::commands::UNDO) {
+                    session.undo(data)
+                } else {
+                    session.redo(data)
+                };
+                drop(session);
+                if let Some(inval) = inval {
+                    ctx.invalidate_text_input(inval);
+                }
+                ctx.set_handled();
+            }
             Event::Paste(ref item) if self.text().can_write() => {
                 if let Some(string) = item.get_string() {
                     let text = if self.multiline {
@@ -509,6 +534,11 @@ impl<T: TextStorage + EditableText> Widget<T> for TextBox<T> {
         if ctx.env_changed() && self.placeholder.needs_rebuild_after_update(ctx) {
             ctx.request_layout();
         }
+        if ctx.env_key_changed(&theme::WIDE_WIDGET_WIDTH)
+            || ctx.env_key_changed(&theme::TEXTBOX_INSETS)
+        {
+            ctx.request_layout();
+        }
         if self.text().can_write() {
             if let Some(ime_invalidation) = self.text_mut().borrow_mut().pending_ime_invalidation()
             {
@@ -525,7 +555,7 @@ impl<T: TextStorage + EditableText> Widget<T> for TextBox<T> {
         let min_width = env.get(theme::WIDE_WIDGET_WIDTH);
         let textbox_insets = env.get(theme::TEXTBOX_INSETS);
 
-        self.placeholder.rebuild_if_needed(ctx.text(), env);
+        self.placeholder.rebuild_if_needed(ctx.text_factory(), env);
         let min_size = bc.constrain((min_width, 0.0));
         let child_bc = BoxConstraints::new(min_size, bc.max());
 
@@ -621,6 +651,11 @@ impl<T: TextStorage + EditableText> Widget<T> for TextBox<T> {
         // Paint the border
         ctx.stroke(clip_rect, &border_color, border_width);
     }
+
+    #[cfg(feature = "debug_state")]
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState::with_value("TextBox", data.as_str())
+    }
 }
 
 impl<T: TextStorage + EditableText> Default for TextBox<T> {
@@ -628,3 +663,176 @@ impl<T: TextStorage + EditableText> Default for TextBox<T> {
         TextBox::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::widget::Controller;
+    use crate::{commands, MouseButton, MouseButtons, MouseEvent, Selector, WidgetExt};
+    use test_env_log::test;
+
+    const INSERT: Selector<&'static str> = Selector::new("druid-test.insert");
+    const SET: Selector<String> = Selector::new("druid-test.set");
+
+    /// Handles `INSERT` the way the text box handles a paste, and replaces
+    /// the data on `SET`.
+    struct Inserter;
+
+    impl Controller<String, TextBox<String>> for Inserter {
+        fn event(
+            &mut self,
+            child: &mut TextBox<String>,
+            ctx: &mut EventCtx,
+            event: &Event,
+            data: &mut String,
+            env: &Env,
+        ) {
+            if let Event::Command(cmd) = event {
+                if let Some(text) = cmd.get(INSERT) {
+                    let inval = child.text_mut().borrow_mut().insert_text(data, text);
+                    ctx.invalidate_text_input(inval);
+                } else if let Some(text) = cmd.get(SET) {
+                    *data = text.clone();
+                }
+            }
+            child.event(ctx, event, data, env)
+        }
+    }
+
+    fn focus(harness: &mut Harness<String>) {
+        let mouse = MouseEvent {
+            pos: Point::new(5., 5.),
+            window_pos: Point::new(5., 5.),
+            buttons: MouseButtons::new().with(MouseButton::Left),
+            mods: Default::default(),
+            count: 1,
+            focus: false,
+            button: MouseButton::Left,
+            wheel_delta: Vec2::ZERO,
+        };
+        harness.event(Event::MouseDown(mouse.clone()));
+        harness.event(Event::MouseUp(mouse));
+    }
+
+    /// Type `text` one character at a time, as the platform would.
+    fn type_text(harness: &mut Harness<String>, text: &str) {
+        for c in text.chars() {
+            harness.ime_edit(|handler| {
+                let selection = handler.selection();
+                handler.replace_range(selection.range(), &c.to_string());
+                handler.set_selection(Selection::caret(selection.min() + c.len_utf8()));
+            });
+        }
+    }
+
+    fn set_selection(harness: &mut Harness<String>, selection: Selection) {
+        harness.ime_edit(|handler| handler.set_selection(selection));
+    }
+
+    fn selection(harness: &mut Harness<String>) -> Selection {
+        let mut selection = None;
+        harness.ime_edit(|handler| selection = Some(handler.selection()));
+        selection.unwrap()
+    }
+
+    /// Fire all pending timers, which ends the current undo group.
+    fn pause(harness: &mut Harness<String>) {
+        let timers: Vec<_> = harness.window().timers.keys().copied().collect();
+        for token in timers {
+            harness.event(Event::Timer(token));
+        }
+    }
+
+    fn text_box() -> impl Widget<String> {
+        TextBox::new().controller(Inserter).fix_width(200.)
+    }
+
+    #[test]
+    fn ime_geometry_uses_fixed_width_glyphs() {
+        Harness::create_simple(String::new(), text_box(), |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            focus(harness);
+            type_text(harness, "abcdef");
+
+            // the mock engine lays text out with glyphs half as wide as the
+            // 15 point default font, and lines 1.25 times as high
+            harness.ime_edit(|handler| {
+                assert_eq!(handler.hit_test_point(Point::new(17., 5.)).idx, 2);
+                assert_eq!(handler.hit_test_point(Point::new(100., 5.)).idx, 6);
+                let origin = handler.bounding_box().unwrap().origin().to_vec2();
+                assert_eq!(
+                    handler.slice_bounding_box(1..3).map(|rect| rect - origin),
+                    Some(Rect::new(7.5, 0., 22.5, 18.75))
+                );
+            });
+        });
+    }
+
+    #[test]
+    fn undo_and_redo_typing() {
+        Harness::create_simple(String::new(), text_box(), |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            focus(harness);
+
+            type_text(harness, "abc");
+            pause(harness);
+            type_text(harness, "def");
+            assert_eq!(harness.data(), "abcdef");
+
+            harness.submit_command(commands::UNDO);
+            assert_eq!(harness.data(), "abc");
+            assert_eq!(selection(harness), Selection::caret(3));
+
+            harness.submit_command(commands::UNDO);
+            assert_eq!(harness.data(), "");
+            assert_eq!(selection(harness), Selection::caret(0));
+
+            harness.submit_command(commands::REDO);
+            assert_eq!(harness.data(), "abc");
+            assert_eq!(selection(harness), Selection::caret(3));
+
+            // nothing left to redo after typing
+            type_text(harness, "x");
+            harness.submit_command(commands::REDO);
+            assert_eq!(harness.data(), "abcx");
+        });
+    }
+
+    #[test]
+    fn undo_paste_over_selection() {
+        Harness::create_simple(String::new(), text_box(), |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            focus(harness);
+
+            type_text(harness, "hello world");
+            set_selection(harness, Selection::new(6, 11));
+            harness.submit_command(INSERT.with("there"));
+            assert_eq!(harness.data(), "hello there");
+            assert_eq!(selection(harness), Selection::caret(11));
+
+            harness.submit_command(commands::UNDO);
+            assert_eq!(harness.data(), "hello world");
+            assert_eq!(selection(harness), Selection::new(6, 11));
+        });
+    }
+
+    #[test]
+    fn external_change_clears_history() {
+        Harness::create_simple(String::new(), text_box(), |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            focus(harness);
+
+            type_text(harness, "abc");
+            harness.submit_command(SET.with("xyz".into()));
+            assert_eq!(harness.data(), "xyz");
+
+            harness.submit_command(commands::UNDO);
+            assert_eq!(harness.data(), "xyz");
+        });
+    }
+}
@@ -0,0 +1,356 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that rotates, scales or skews its child.
+
+use std::mem;
+
+use tracing::{instrument, trace};
+
+use crate::core::WidgetState;
+use crate::kurbo::{Affine, Insets, Point, Rect};
+use crate::widget::{prelude::*, WidgetWrapper};
+use crate::{Data, Region, WidgetPod};
+
+type TransformFn<T> = dyn Fn(&T, &Env) -> Affine;
+
+/// A widget that applies an affine transform to its child.
+///
+/// Unlike transforming the [`PaintCtx`] in a custom widget, this also maps
+/// pointer events into the child's coordinate space, so the child can be
+/// clicked and hovered where it is drawn.
+///
+/// The transform is applied to the child's layout rect, and the result is
+/// moved so that its bounding box starts at the origin; the `Transform`
+/// takes up the size of that bounding box. Rotating about the origin or
+/// about the child's center therefore has the same result.
+///
+/// The child's window origin, as used for IME and sub-windows, is not
+/// transformed.
+///
+/// # Examples
+///
+/// ```
+/// use druid::kurbo::Affine;
+/// use druid::widget::{Button, Label, Transform};
+///
+/// let tilted: Transform<(), _> =
+///     Transform::new(Affine::rotate(30f64.to_radians()), Button::new("tilted"));
+/// let zoomed = Transform::dynamic(|zoom: &f64, _| Affine::scale(*zoom), Label::new("zoom"));
+/// ```
+pub struct Transform<T, W> {
+    transform: Box<TransformFn<T>>,
+    child: WidgetPod<T, W>,
+    /// The transform from `transform`, when the child was last laid out.
+    current: Affine,
+    /// `current`, moved so that the child's bounding box starts at the origin.
+    to_parent: Affine,
+}
+
+impl<T, W: Widget<T>> Transform<T, W> {
+    /// Create a new `Transform` that applies `transform` to `child`.
+    pub fn new(transform: Affine, child: W) -> Self {
+        Transform::dynamic(move |_, _| transform, child)
+    }
+
+    /// Create a new `Transform` whose transform is computed from the data,
+    /// for instance from a zoom factor.
+    pub fn dynamic(transform: impl Fn(&T, &Env) -> Affine + 'static, child: W) -> Self {
+        Transform {
+            transform: Box::new(transform),
+            child: WidgetPod::new(child),
+            current: Affine::default(),
+            to_parent: Affine::default(),
+        }
+    }
+
+    /// Map a pointer event into the child's coordinate space.
+    ///
+    /// Returns `None` if the pointer is outside the child and the child
+    /// doesn't need to see it.
+    fn transform_event(&self, event: &Event) -> Option<Event> {
+        let force =
+            self.child.is_hot() || self.child.has_active() || self.child.has_pointer_capture();
+        let to_child = self.to_parent.inverse();
+        let bounds = self.child.layout_rect();
+        let mut event = event.clone();
+        let mouse = match &mut event {
+            Event::MouseDown(mouse)
+            | Event::MouseUp(mouse)
+            | Event::MouseMove(mouse)
+            | Event::Wheel(mouse) => mouse,
+            _ => return Some(event),
+        };
+        if !to_child.is_finite() {
+            return None;
+        }
+        mouse.pos = to_child * mouse.pos;
+        if force || bounds.contains(mouse.pos) {
+            Some(event)
+        } else {
+            None
+        }
+    }
+
+    /// Move the child's invalid rects, which `WidgetPod` merged into ours as
+    /// they are, into our coordinate space.
+    ///
+    /// `own` is the region we had invalidated before calling the child.
+    fn transform_invalid(&self, state: &mut WidgetState, own: Region) {
+        let child = mem::replace(&mut state.invalid, own);
+        for &rect in child.rects() {
+            state
+                .invalid
+                .add_rect(self.to_parent.transform_rect_bbox(rect));
+        }
+    }
+}
+
+impl<T, W> WidgetWrapper for Transform<T, W> {
+    widget_wrapper_pod_body!(W, child);
+}
+
+impl<T: Data, W: Widget<T>> Widget<T> for Transform<T, W> {
+    #[instrument(name = "Transform", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if let Some(child_event) = self.transform_event(event) {
+            let own = mem::replace(&mut ctx.widget_state.invalid, Region::EMPTY);
+            self.child.event(ctx, &child_event, data, env);
+            self.transform_invalid(ctx.widget_state, own);
+        }
+    }
+
+    #[instrument(name = "Transform", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        let own = mem::replace(&mut ctx.widget_state.invalid, Region::EMPTY);
+        self.child.lifecycle(ctx, event, data, env);
+        self.transform_invalid(ctx.widget_state, own);
+    }
+
+    #[instrument(name = "Transform", level = "trace", skip(self, ctx, _old, data, env))]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old: &T, data: &T, env: &Env) {
+        if (self.transform)(data, env) != self.current {
+            ctx.request_layout();
+        }
+        let own = mem::replace(&mut ctx.widget_state.invalid, Region::EMPTY);
+        self.child.update(ctx, data, env);
+        self.transform_invalid(ctx.widget_state, own);
+    }
+
+    #[instrument(name = "Transform", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("Transform");
+        let transform = (self.transform)(data, env);
+        // An axis-aligned scale can be undone on the constraints; anything
+        // else just gets our maximum.
+        let [a, b, c, d, _, _] = transform.as_coeffs();
+        let child_bc = if b == 0.0 && c == 0.0 && a != 0.0 && d != 0.0 {
+            let max = bc.max();
+            BoxConstraints::new(
+                Size::ZERO,
+                Size::new(max.width / a.abs(), max.height / d.abs()),
+            )
+        } else {
+            bc.loosen()
+        };
+
+        // The child is at our origin, but in its own coordinate space.
+        let mouse_pos = ctx.mouse_pos;
+        ctx.mouse_pos = mouse_pos.map(|pos| self.to_parent.inverse() * pos);
+        let child_size = self.child.layout(ctx, &child_bc, data, env);
+        self.child.set_origin(ctx, data, env, Point::ORIGIN);
+        ctx.mouse_pos = mouse_pos;
+
+        let bounds = transform.transform_rect_bbox(child_size.to_rect());
+        self.current = transform;
+        self.to_parent = Affine::translate(-bounds.origin().to_vec2()) * transform;
+        let size = bc.constrain(bounds.size());
+
+        // The child's invalid rects are clipped to our paint rect before we
+        // get to transform them, so it has to cover the child's own rect too.
+        let paint_rect = self
+            .to_parent
+            .transform_rect_bbox(self.child.paint_rect())
+            .union(self.child.paint_rect());
+        ctx.set_paint_insets(insets_to_cover(size, paint_rect));
+        trace!(
+            "Computed layout: size={}, transform={:?}",
+            size,
+            self.to_parent
+        );
+        size
+    }
+
+    #[instrument(name = "Transform", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let to_parent = self.to_parent;
+        let to_child = to_parent.inverse();
+        if !to_child.is_finite() {
+            return;
+        }
+        let mut visible = Region::EMPTY;
+        for &rect in ctx.region().rects() {
+            visible.add_rect(to_child.transform_rect_bbox(rect));
+        }
+        ctx.with_save(|ctx| {
+            ctx.transform(to_parent);
+            ctx.with_child_ctx(visible, |ctx| self.child.paint_raw(ctx, data, env));
+        });
+    }
+
+    #[cfg(feature = "debug_state")]
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState {
+            display_name: "Transform".to_string(),
+            children: vec![self.child.debug_state(data)],
+            ..Default::default()
+        }
+    }
+}
+
+/// The insets that grow a rect of `size` at the origin to cover `rect`.
+fn insets_to_cover(size: Size, rect: Rect) -> Insets {
+    Insets::new(
+        (-rect.x0).max(0.0),
+        (-rect.y0).max(0.0),
+        (rect.x1 - size.width).max(0.0),
+        (rect.y1 - size.height).max(0.0),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use test_env_log::test;
+
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::{widget_ids, ModularWidget};
+    use crate::tests::move_mouse;
+    use crate::{MouseButton, MouseButtons, MouseEvent, Vec2, WidgetExt};
+
+    const CHILD: Size = Size::new(100.0, 40.0);
+    const INVALIDATE: crate::Selector = crate::Selector::new("druid-test.invalidate");
+
+    fn click(pos: Point) -> Event {
+        Event::MouseDown(MouseEvent {
+            pos,
+            window_pos: pos,
+            buttons: MouseButtons::default().with(MouseButton::Left),
+            mods: Default::default(),
+            count: 1,
+            focus: false,
+            button: MouseButton::Left,
+            wheel_delta: Vec2::ZERO,
+        })
+    }
+
+    /// A fixed-size child that records where it was clicked.
+    fn child(clicks: Rc<RefCell<Vec<Point>>>) -> impl Widget<()> {
+        ModularWidget::new(clicks)
+            .event_fn(|clicks, ctx, event, _, _| match event {
+                Event::MouseDown(mouse) => clicks.borrow_mut().push(mouse.pos),
+                Event::Command(cmd) if cmd.is(INVALIDATE) => ctx.request_paint(),
+                _ => (),
+            })
+            .layout_fn(|_, _, _, _, _| CHILD)
+    }
+
+    /// Where a point in the child ends up, for a transform applied the way
+    /// `Transform` applies it.
+    fn to_parent(transform: Affine, point: Point) -> Point {
+        let bounds = transform.transform_rect_bbox(CHILD.to_rect());
+        Affine::translate(-bounds.origin().to_vec2()) * transform * point
+    }
+
+    fn assert_near(a: Point, b: Point) {
+        assert!((a - b).hypot() < 1e-6, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn clicks_on_a_rotated_child() {
+        let rotate = Affine::rotate(30f64.to_radians());
+        let clicks = Rc::new(RefCell::new(Vec::new()));
+        let [id] = widget_ids();
+        let widget = Transform::new(rotate, child(clicks.clone()))
+            .with_id(id)
+            .center();
+
+        Harness::create_simple((), widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            let offset = harness.get_state(id).layout_rect().origin().to_vec2();
+
+            let inside = Point::new(95.0, 35.0);
+            harness.event(click(to_parent(rotate, inside) + offset));
+            assert_eq!(clicks.borrow().len(), 1);
+            assert_near(clicks.borrow()[0], inside);
+
+            // inside the bounding box, but not inside the child
+            let outside = Point::new(-5.0, 20.0);
+            harness.event(click(to_parent(rotate, outside) + offset));
+            assert_eq!(clicks.borrow().len(), 1);
+        });
+    }
+
+    #[test]
+    fn clicks_on_a_scaled_child() {
+        let clicks = Rc::new(RefCell::new(Vec::new()));
+        let widget = Transform::new(Affine::scale(2.0), child(clicks.clone()));
+
+        Harness::create_simple((), widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.event(click(Point::new(150.0, 60.0)));
+            harness.event(click(Point::new(150.0, 90.0)));
+            assert_eq!(*clicks.borrow(), [Point::new(75.0, 30.0)]);
+        });
+    }
+
+    #[test]
+    fn hover_follows_the_transform() {
+        let [id] = widget_ids();
+        let widget = Transform::new(Affine::scale(2.0), child(Rc::default()).with_id(id));
+
+        Harness::create_simple((), widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.event(Event::MouseMove(move_mouse((150., 60.))));
+            assert!(harness.get_state(id).is_hot);
+            harness.event(Event::MouseMove(move_mouse((150., 90.))));
+            assert!(!harness.get_state(id).is_hot);
+        });
+    }
+
+    #[test]
+    fn invalidation_covers_the_rotated_bounds() {
+        let [id] = widget_ids();
+        let rotate = Affine::rotate(30f64.to_radians());
+        let widget = Transform::new(rotate, child(Rc::default()).with_id(id));
+
+        Harness::create_simple((), widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.paint();
+            harness.submit_command(INVALIDATE.to(id));
+            let bounds = rotate.transform_rect_bbox(CHILD.to_rect());
+            let rects = harness.window().invalid().rects().to_vec();
+            assert_eq!(rects.len(), 1);
+            assert_near(rects[0].origin(), Point::ORIGIN);
+            assert!((rects[0].size() - bounds.size()).to_vec2().hypot() < 1e-6);
+        });
+    }
+}
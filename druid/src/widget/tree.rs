@@ -0,0 +1,908 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A tree of expandable rows, with a keyboard and mouse driven selection.
+
+use std::collections::{BTreeSet, HashMap};
+
+use tracing::{instrument, trace};
+
+use crate::kurbo::BezPath;
+use crate::widget::prelude::*;
+use crate::widget::ListSelection;
+use crate::{theme, KbKey, KeyEvent, Point, Rect, Selector, WidgetPod};
+
+/// The path from the root to a node: the index of each child along the way.
+type NodePath = Vec<usize>;
+
+/// Builds the widget for a row, given its depth and whether it is expanded.
+type RowBuilder<T> = dyn Fn(usize, bool) -> Box<dyn Widget<T>>;
+
+/// Data that can be displayed by a [`Tree`].
+///
+/// A node is [`Data`] with an ordered list of children of the same type.
+/// Children are accessed by index, so that the tree only ever looks at
+/// the nodes it is showing.
+pub trait TreeNode: Data {
+    /// The number of children of this node.
+    fn children_count(&self) -> usize;
+
+    /// The child at `index`.
+    ///
+    /// `index` is always less than [`children_count`].
+    ///
+    /// [`children_count`]: TreeNode::children_count
+    fn get_child(&self, index: usize) -> &Self;
+
+    /// Call `cb` with a mutable reference to the child at `index`.
+    ///
+    /// As with [`ListIter::for_each_mut`], implementations that share their
+    /// children should only write back a child that has actually changed.
+    ///
+    /// [`ListIter::for_each_mut`]: crate::widget::ListIter::for_each_mut
+    fn for_child_mut(&mut self, index: usize, cb: impl FnOnce(&mut Self));
+
+    /// Whether this node is expanded.
+    ///
+    /// This is only used by trees that keep their expansion state in the
+    /// data; see [`Tree::expansion_in_data`].
+    fn is_expanded(&self) -> bool {
+        false
+    }
+
+    /// Expand or collapse this node.
+    ///
+    /// This is only used by trees that keep their expansion state in the
+    /// data; see [`Tree::expansion_in_data`].
+    fn set_expanded(&mut self, expanded: bool) {
+        let _ = expanded;
+    }
+}
+
+/// A visible row of a [`Tree`].
+struct Row<T> {
+    path: NodePath,
+    expanded: bool,
+    has_children: bool,
+    widget: WidgetPod<T, Box<dyn Widget<T>>>,
+}
+
+/// A widget that displays a hierarchy of [`TreeNode`]s as indented rows
+/// that can be expanded and collapsed.
+///
+/// The data for this widget is a tuple of the root node and a
+/// [`ListSelection`], as with [`SelectableList`]. The indices in the
+/// selection refer to the *visible* rows, from the root at index 0 down;
+/// expanding or collapsing a node shifts them as needed.
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use druid::widget::{Label, ListSelection, Tree, TreeNode};
+/// # use druid::{Data, Lens, Widget, WidgetExt};
+/// #[derive(Clone, Data)]
+/// struct Entry {
+///     name: String,
+///     children: Arc<Vec<Entry>>,
+/// }
+///
+/// impl TreeNode for Entry {
+///     fn children_count(&self) -> usize {
+///         self.children.len()
+///     }
+///
+///     fn get_child(&self, index: usize) -> &Entry {
+///         &self.children[index]
+///     }
+///
+///     fn for_child_mut(&mut self, index: usize, cb: impl FnOnce(&mut Entry)) {
+///         let mut child = self.children[index].clone();
+///         cb(&mut child);
+///         if !child.same(&self.children[index]) {
+///             Arc::make_mut(&mut self.children)[index] = child;
+///         }
+///     }
+/// }
+///
+/// #[derive(Clone, Data, Lens)]
+/// struct AppState {
+///     root: Entry,
+///     selection: ListSelection,
+/// }
+///
+/// fn file_browser() -> impl Widget<AppState> {
+///     Tree::new(|_depth, _expanded| Label::dynamic(|entry: &Entry, _| entry.name.clone()))
+///         .lens((AppState::root, AppState::selection))
+///         .scroll()
+/// }
+/// ```
+///
+/// Each row is built by the closure passed to [`Tree::new`], which receives
+/// the row's depth and whether it is expanded; the row is rebuilt when it is
+/// expanded or collapsed. Rows are only built for visible nodes, so the
+/// children of a collapsed node do not exist as widgets at all, and
+/// collapsing a node drops the widgets of its descendants.
+///
+/// By default, which nodes are expanded is remembered by the widget, keyed
+/// by each node's path from the root. Use [`expansion_in_data`] to keep it
+/// in the nodes themselves instead.
+///
+/// Clicking a row selects it, and clicking its chevron expands or collapses
+/// it. When the tree is focused, Up and Down move the selection across the
+/// visible rows, Right expands the selected node or moves to its first
+/// child, and Left collapses it or moves to its parent. Double clicking a
+/// row or pressing Enter sends an [`ACTIVATE`] notification.
+///
+/// [`SelectableList`]: crate::widget::SelectableList
+/// [`expansion_in_data`]: Tree::expansion_in_data
+/// [`ACTIVATE`]: Tree::ACTIVATE
+pub struct Tree<T> {
+    make_row: Box<RowBuilder<T>>,
+    expansion_in_data: bool,
+    /// The expanded nodes, when the expansion state is not in the data.
+    expanded: BTreeSet<NodePath>,
+    rows: Vec<Row<T>>,
+}
+
+impl Tree<()> {
+    /// A notification sent when the user activates a row, by double
+    /// clicking it or pressing Enter.
+    ///
+    /// The payload is the path of the activated node: the index of each
+    /// child on the way from the root, which has an empty path.
+    pub const ACTIVATE: Selector<Vec<usize>> = Selector::new("druid-builtin.tree-activate");
+}
+
+impl<T: TreeNode> Tree<T> {
+    /// Create a new tree. The closure is called with the depth of a row,
+    /// where the root has depth 0, and whether it is expanded, every time
+    /// a row needs to be constructed.
+    pub fn new<W: Widget<T> + 'static>(make_row: impl Fn(usize, bool) -> W + 'static) -> Self {
+        Tree {
+            make_row: Box::new(move |depth, expanded| Box::new(make_row(depth, expanded))),
+            expansion_in_data: false,
+            expanded: BTreeSet::new(),
+            rows: Vec::new(),
+        }
+    }
+
+    /// Builder-style method to set whether the expansion state is kept in
+    /// the data, through [`TreeNode::is_expanded`] and
+    /// [`TreeNode::set_expanded`].
+    ///
+    /// Keeping it in the data lets the application expand nodes itself and
+    /// persist the state; otherwise the widget remembers it, keyed by path,
+    /// which can point at the wrong node after nodes are inserted or removed.
+    pub fn expansion_in_data(mut self, in_data: bool) -> Self {
+        self.expansion_in_data = in_data;
+        self
+    }
+
+    fn is_expanded(&self, node: &T, path: &[usize]) -> bool {
+        if self.expansion_in_data {
+            node.is_expanded()
+        } else {
+            self.expanded.contains(path)
+        }
+    }
+
+    /// The path, expansion state and whether it has children, for every
+    /// visible node in order.
+    fn visible_rows(&self, root: &T) -> Vec<(NodePath, bool, bool)> {
+        fn walk<T: TreeNode>(
+            tree: &Tree<T>,
+            node: &T,
+            path: &mut NodePath,
+            out: &mut Vec<(NodePath, bool, bool)>,
+        ) {
+            let has_children = node.children_count() > 0;
+            let expanded = has_children && tree.is_expanded(node, path);
+            out.push((path.clone(), expanded, has_children));
+            if expanded {
+                for i in 0..node.children_count() {
+                    path.push(i);
+                    walk(tree, node.get_child(i), path, out);
+                    path.pop();
+                }
+            }
+        }
+
+        let mut out = Vec::new();
+        walk(self, root, &mut Vec::new(), &mut out);
+        out
+    }
+
+    /// Make the rows match the visible nodes, reusing the widgets of rows
+    /// that are still visible. Returns `true` if anything changed.
+    fn rebuild_rows(&mut self, root: &T) -> bool {
+        let wanted = self.visible_rows(root);
+        let unchanged = wanted.len() == self.rows.len()
+            && wanted
+                .iter()
+                .zip(&self.rows)
+                .all(|((path, expanded, has_children), row)| {
+                    *path == row.path
+                        && *expanded == row.expanded
+                        && *has_children == row.has_children
+                });
+        if unchanged {
+            return false;
+        }
+
+        let mut old: HashMap<NodePath, Row<T>> = self
+            .rows
+            .drain(..)
+            .map(|row| (row.path.clone(), row))
+            .collect();
+        let make_row = &self.make_row;
+        self.rows = wanted
+            .into_iter()
+            .map(|(path, expanded, has_children)| match old.remove(&path) {
+                Some(row) if row.expanded == expanded => Row {
+                    has_children,
+                    ..row
+                },
+                _ => Row {
+                    widget: WidgetPod::new(make_row(path.len(), expanded)),
+                    path,
+                    expanded,
+                    has_children,
+                },
+            })
+            .collect();
+        trace!("rebuilt tree with {} visible rows", self.rows.len());
+        true
+    }
+
+    /// Expand or collapse the row at `index`, keeping the selection on the
+    /// same rows. Returns `false` if the row has no children.
+    fn toggle(&mut self, root: &mut T, index: usize, selection: &mut ListSelection) -> bool {
+        let row = &self.rows[index];
+        if !row.has_children {
+            return false;
+        }
+        let expand = !row.expanded;
+        let path = row.path.clone();
+        if self.expansion_in_data {
+            with_node_mut(root, &path, &mut |node| node.set_expanded(expand));
+        } else if expand {
+            self.expanded.insert(path);
+        } else {
+            self.expanded.remove(&path);
+        }
+
+        // Only the rows below `index` come and go.
+        let old_len = self.rows.len();
+        self.rebuild_rows(root);
+        let new_len = self.rows.len();
+        if new_len > old_len {
+            for _ in old_len..new_len {
+                selection.insert_index(index + 1);
+            }
+        } else {
+            let hidden = old_len - new_len;
+            let cursor_hidden =
+                matches!(selection.cursor(), Some(c) if c > index && c <= index + hidden);
+            for _ in 0..hidden {
+                selection.remove_index(index + 1);
+            }
+            if cursor_hidden {
+                selection.select(index);
+            }
+        }
+        true
+    }
+
+    /// The index of the parent of the row at `index`, if it has one.
+    fn parent_row(&self, index: usize) -> Option<usize> {
+        let path = &self.rows[index].path;
+        let parent = path.get(..path.len().checked_sub(1)?)?;
+        self.rows[..index]
+            .iter()
+            .rposition(|row| row.path == parent)
+    }
+
+    /// The rect of the row at `index`, stretched to the full width.
+    fn row_rect(&self, index: usize, size: Size) -> Option<Rect> {
+        let rect = self.rows.get(index)?.widget.layout_rect();
+        Some(Rect::new(0.0, rect.y0, size.width.max(rect.x1), rect.y1))
+    }
+
+    /// The index of the row under `pos`, if any.
+    fn row_at(&self, pos: Point, size: Size) -> Option<usize> {
+        (0..self.rows.len())
+            .find(|&i| matches!(self.row_rect(i, size), Some(rect) if rect.contains(pos)))
+    }
+
+    /// The rect of the chevron column of the row at `index`.
+    ///
+    /// Each level is indented by [`theme::TREE_INDENT`], which is also the
+    /// width of the chevron column.
+    fn chevron_rect(&self, index: usize, size: Size, env: &Env) -> Option<Rect> {
+        let rect = self.row_rect(index, size)?;
+        let indent = env.get(theme::TREE_INDENT);
+        let x0 = self.rows[index].path.len() as f64 * indent;
+        Some(Rect::new(x0, rect.y0, x0 + indent, rect.y1))
+    }
+
+    fn activate(&self, ctx: &mut EventCtx, selection: &ListSelection) {
+        if let Some(row) = selection.cursor().and_then(|index| self.rows.get(index)) {
+            ctx.submit_notification(Tree::ACTIVATE.with(row.path.clone()));
+        }
+    }
+
+    fn handle_key(
+        &mut self,
+        ctx: &mut EventCtx,
+        key: &KeyEvent,
+        root: &mut T,
+        selection: &mut ListSelection,
+    ) -> bool {
+        if self.rows.is_empty() {
+            return false;
+        }
+        let last = self.rows.len() - 1;
+        let cursor = selection.cursor();
+        let target = match (&key.key, cursor) {
+            (KbKey::ArrowUp, _) => Some(cursor.map_or(last, |c| c.saturating_sub(1))),
+            (KbKey::ArrowDown, _) => Some(cursor.map_or(0, |c| (c + 1).min(last))),
+            (KbKey::Home, _) => Some(0),
+            (KbKey::End, _) => Some(last),
+            (KbKey::ArrowRight, Some(c)) if self.rows[c].expanded => Some(c + 1),
+            (KbKey::ArrowRight, Some(c)) => return self.toggle(root, c, selection),
+            (KbKey::ArrowLeft, Some(c)) if self.rows[c].expanded => {
+                return self.toggle(root, c, selection);
+            }
+            (KbKey::ArrowLeft, Some(c)) => self.parent_row(c),
+            (KbKey::Enter, _) => {
+                self.activate(ctx, selection);
+                return true;
+            }
+            _ => None,
+        };
+
+        match target {
+            Some(index) => {
+                selection.select(index);
+                if let Some(rect) = self.row_rect(index, ctx.size()) {
+                    ctx.scroll_area_to_view(rect);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn paint_selection(&self, ctx: &mut PaintCtx, selection: &ListSelection, env: &Env) {
+        let color = if ctx.has_focus() {
+            env.get(theme::SELECTED_TEXT_BACKGROUND_COLOR)
+        } else {
+            env.get(theme::SELECTED_TEXT_INACTIVE_BACKGROUND_COLOR)
+        };
+        for index in selection.selected() {
+            if let Some(rect) = self.row_rect(index, ctx.size()) {
+                ctx.fill(rect, &color);
+            }
+        }
+
+        if ctx.is_focused() {
+            if let Some(rect) = selection
+                .cursor()
+                .and_then(|index| self.row_rect(index, ctx.size()))
+            {
+                let rect = rect.inset(-0.5);
+                ctx.stroke(rect, &env.get(theme::PRIMARY_LIGHT), 1.0);
+            }
+        }
+    }
+
+    /// Paint a chevron pointing right for collapsed rows, and down for
+    /// expanded ones.
+    fn paint_chevrons(&self, ctx: &mut PaintCtx, env: &Env) {
+        let color = if ctx.is_disabled() {
+            env.get(theme::DISABLED_TEXT_COLOR)
+        } else {
+            env.get(theme::TEXT_COLOR)
+        };
+        let mut chevrons = BezPath::new();
+        for (index, row) in self.rows.iter().enumerate() {
+            if !row.has_children {
+                continue;
+            }
+            let center = match self.chevron_rect(index, ctx.size(), env) {
+                Some(rect) => rect.center(),
+                None => continue,
+            };
+            let (dx, dy) = (3.0, 4.0);
+            if row.expanded {
+                chevrons.move_to(center + (-dy, -dx / 2.0));
+                chevrons.line_to(center + (dy, -dx / 2.0));
+                chevrons.line_to(center + (0.0, dx));
+            } else {
+                chevrons.move_to(center + (-dx / 2.0, -dy));
+                chevrons.line_to(center + (dx, 0.0));
+                chevrons.line_to(center + (-dx / 2.0, dy));
+            }
+            chevrons.close_path();
+        }
+        ctx.fill(chevrons, &color);
+    }
+}
+
+impl<T: TreeNode> Widget<(T, ListSelection)> for Tree<T> {
+    #[instrument(name = "Tree", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(
+        &mut self,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut (T, ListSelection),
+        env: &Env,
+    ) {
+        let (root, selection) = data;
+        for Row { path, widget, .. } in &mut self.rows {
+            with_node_mut(root, path, &mut |node| widget.event(ctx, event, node, env));
+        }
+        // The tree may have shrunk since we last saw it.
+        selection.truncate(self.rows.len());
+        if ctx.is_handled() || ctx.is_disabled() {
+            return;
+        }
+
+        match event {
+            Event::MouseDown(mouse) if mouse.button.is_left() => {
+                if let Some(index) = self.row_at(mouse.pos, ctx.size()) {
+                    ctx.request_focus();
+                    let on_chevron = matches!(
+                        self.chevron_rect(index, ctx.size(), env),
+                        Some(rect) if rect.contains(mouse.pos)
+                    );
+                    if on_chevron && self.toggle(root, index, selection) {
+                        ctx.children_changed();
+                    } else if mouse.count == 2 {
+                        self.activate(ctx, selection);
+                    } else {
+                        selection.select(index);
+                    }
+                    ctx.request_paint();
+                    ctx.set_handled();
+                }
+            }
+            Event::KeyDown(key) if ctx.is_focused() => {
+                let rows = self.rows.len();
+                if self.handle_key(ctx, key, root, selection) {
+                    trace!("selection changed to {:?}", selection);
+                    if self.rows.len() != rows {
+                        ctx.children_changed();
+                    }
+                    ctx.request_paint();
+                    ctx.set_handled();
+                }
+            }
+            _ => (),
+        }
+    }
+
+    #[instrument(name = "Tree", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(
+        &mut self,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &(T, ListSelection),
+        env: &Env,
+    ) {
+        match event {
+            LifeCycle::WidgetAdded if self.rebuild_rows(&data.0) => ctx.children_changed(),
+            LifeCycle::BuildFocusChain => ctx.register_for_focus(),
+            LifeCycle::FocusChanged(_) => ctx.request_paint(),
+            _ => (),
+        }
+        for row in &mut self.rows {
+            row.widget
+                .lifecycle(ctx, event, node_at(&data.0, &row.path), env);
+        }
+    }
+
+    #[instrument(name = "Tree", level = "trace", skip(self, ctx, old_data, data, env))]
+    fn update(
+        &mut self,
+        ctx: &mut UpdateCtx,
+        old_data: &(T, ListSelection),
+        data: &(T, ListSelection),
+        env: &Env,
+    ) {
+        if !old_data.1.same(&data.1) {
+            ctx.request_paint();
+        }
+        if ctx.env_key_changed(&theme::TREE_INDENT) {
+            ctx.request_layout();
+        }
+        // Nodes may have been added, removed or expanded; rows for nodes that
+        // are gone are dropped first, and new rows get WidgetAdded before
+        // they see any data.
+        if self.rebuild_rows(&data.0) {
+            ctx.children_changed();
+        }
+        for row in &mut self.rows {
+            if row.widget.is_initialized() {
+                row.widget.update(ctx, node_at(&data.0, &row.path), env);
+            }
+        }
+    }
+
+    #[instrument(name = "Tree", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &(T, ListSelection),
+        env: &Env,
+    ) -> Size {
+        let indent = env.get(theme::TREE_INDENT);
+        let mut width: f64 = 0.0;
+        let mut y = 0.0;
+        let mut paint_rect = Rect::ZERO;
+        for row in &mut self.rows {
+            let node = node_at(&data.0, &row.path);
+            let x = (row.path.len() + 1) as f64 * indent;
+            let max_width = (bc.max().width - x).max(0.0);
+            let row_bc = BoxConstraints::new(Size::ZERO, Size::new(max_width, f64::INFINITY));
+            let row_size = row.widget.layout(ctx, &row_bc, node, env);
+            row.widget.set_origin(ctx, node, env, Point::new(x, y));
+            paint_rect = paint_rect.union(row.widget.paint_rect());
+            width = width.max(x + row_size.width);
+            y += row_size.height;
+        }
+
+        let my_size = bc.constrain(Size::new(width, y));
+        let insets = paint_rect - my_size.to_rect();
+        ctx.set_paint_insets(insets);
+        trace!("Computed layout: size={}, insets={:?}", my_size, insets);
+        my_size
+    }
+
+    #[instrument(name = "Tree", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &(T, ListSelection), env: &Env) {
+        self.paint_selection(ctx, &data.1, env);
+        self.paint_chevrons(ctx, env);
+        for row in &mut self.rows {
+            row.widget.paint(ctx, node_at(&data.0, &row.path), env);
+        }
+    }
+
+    #[cfg(feature = "debug_state")]
+    fn debug_state(&self, data: &(T, ListSelection)) -> DebugState {
+        let (root, selection) = data;
+        let children = self
+            .rows
+            .iter()
+            .enumerate()
+            .map(|(i, row)| {
+                let mut state = row.widget.debug_state(node_at(root, &row.path));
+                let other = &mut state.other_values;
+                other.insert("path".into(), format!("{:?}", row.path));
+                other.insert("expanded".into(), row.expanded.to_string());
+                other.insert("selected".into(), selection.is_selected(i).to_string());
+                state
+            })
+            .collect();
+        DebugState {
+            main_value: format!("{:?}", selection.selected().collect::<Vec<_>>()),
+            children,
+            ..DebugState::new("Tree")
+        }
+    }
+}
+
+/// The node at `path` below `root`.
+fn node_at<'a, T: TreeNode>(root: &'a T, path: &[usize]) -> &'a T {
+    path.iter().fold(root, |node, &i| node.get_child(i))
+}
+
+/// Call `f` with the node at `path` below `root`.
+fn with_node_mut<T: TreeNode>(root: &mut T, path: &[usize], f: &mut dyn FnMut(&mut T)) {
+    match path.split_first() {
+        Some((&i, rest)) => root.for_child_mut(i, |child| with_node_mut(child, rest, f)),
+        None => f(root),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::widget_ids;
+    use crate::widget::{Align, Controller, Label};
+    use crate::{Modifiers, MouseButton, MouseButtons, MouseEvent, UnitPoint, Vec2, WidgetExt};
+    use std::cell::{Cell, RefCell};
+    use std::rc::Rc;
+    use std::sync::Arc;
+    use test_env_log::test;
+
+    #[derive(Clone, Data)]
+    struct Node {
+        name: String,
+        expanded: bool,
+        children: Arc<Vec<Node>>,
+    }
+
+    type TreeData = (Node, ListSelection);
+
+    impl TreeNode for Node {
+        fn children_count(&self) -> usize {
+            self.children.len()
+        }
+
+        fn get_child(&self, index: usize) -> &Node {
+            &self.children[index]
+        }
+
+        fn for_child_mut(&mut self, index: usize, cb: impl FnOnce(&mut Node)) {
+            let mut child = self.children[index].clone();
+            cb(&mut child);
+            if !child.same(&self.children[index]) {
+                Arc::make_mut(&mut self.children)[index] = child;
+            }
+        }
+
+        fn is_expanded(&self) -> bool {
+            self.expanded
+        }
+
+        fn set_expanded(&mut self, expanded: bool) {
+            self.expanded = expanded;
+        }
+    }
+
+    fn node(name: &str, children: Vec<Node>) -> Node {
+        Node {
+            name: name.into(),
+            expanded: false,
+            children: Arc::new(children),
+        }
+    }
+
+    /// root
+    /// ├ a
+    /// │ ├ a1
+    /// │ └ a2
+    /// └ b
+    ///   └ b1
+    ///     └ b1x
+    fn fixture() -> Node {
+        node(
+            "root",
+            vec![
+                node("a", vec![node("a1", vec![]), node("a2", vec![])]),
+                node("b", vec![node("b1", vec![node("b1x", vec![])])]),
+            ],
+        )
+    }
+
+    /// Each row is 20px high, so row `i` is at y = 20 * i + 10.
+    fn tree(built: Rc<Cell<usize>>) -> Tree<Node> {
+        Tree::new(move |_, _| {
+            built.set(built.get() + 1);
+            Label::dynamic(|node: &Node, _| node.name.clone()).fix_height(20.)
+        })
+    }
+
+    fn click(pos: impl Into<Point>, count: u8) -> Event {
+        let pos = pos.into();
+        Event::MouseDown(MouseEvent {
+            pos,
+            window_pos: pos,
+            buttons: MouseButtons::default().with(MouseButton::Left),
+            mods: Modifiers::empty(),
+            count,
+            focus: false,
+            button: MouseButton::Left,
+            wheel_delta: Vec2::ZERO,
+        })
+    }
+
+    fn key(key: KbKey) -> Event {
+        Event::KeyDown(KeyEvent::for_test(Modifiers::empty(), key))
+    }
+
+    fn cursor(data: &TreeData) -> Option<usize> {
+        data.1.cursor()
+    }
+
+    const EXPAND_B: Selector = Selector::new("druid-test.expand-b");
+
+    /// Records activations, and expands b in the data on [`EXPAND_B`].
+    struct Observer(Rc<RefCell<Vec<Vec<usize>>>>);
+
+    impl<W: Widget<TreeData>> Controller<TreeData, W> for Observer {
+        fn event(
+            &mut self,
+            child: &mut W,
+            ctx: &mut EventCtx,
+            event: &Event,
+            data: &mut TreeData,
+            env: &Env,
+        ) {
+            match event {
+                Event::Command(cmd) if cmd.is(EXPAND_B) => {
+                    Arc::make_mut(&mut data.0.children)[1].expanded = true;
+                }
+                Event::Notification(note) if note.is(Tree::ACTIVATE) => {
+                    self.0
+                        .borrow_mut()
+                        .push(note.get_unchecked(Tree::ACTIVATE).clone());
+                    ctx.set_handled();
+                }
+                _ => child.event(ctx, event, data, env),
+            }
+        }
+    }
+
+    #[test]
+    fn collapsed_subtrees_are_not_built() {
+        let built = Rc::new(Cell::new(0));
+        let data = (fixture(), ListSelection::new());
+        Harness::create_simple(data, tree(built.clone()), |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            assert_eq!(built.get(), 1);
+            harness.event(click((50., 10.), 1));
+
+            // expanding the root rebuilds its row and builds its children,
+            // but not theirs
+            harness.event(key(KbKey::ArrowRight));
+            harness.just_layout();
+            assert_eq!(built.get(), 4);
+            assert_eq!(cursor(harness.data()), Some(0));
+
+            // collapsing drops the children; expanding builds them again
+            harness.event(key(KbKey::ArrowLeft));
+            harness.event(key(KbKey::ArrowRight));
+            harness.just_layout();
+            assert_eq!(built.get(), 8);
+        });
+    }
+
+    #[test]
+    fn indent_follows_the_theme() {
+        const INDENT: f64 = theme::metrics::DENSE.tree_indent;
+        let [tree_id] = widget_ids();
+        let mut root = fixture();
+        root.expanded = true;
+        let data = (root, ListSelection::new());
+        let widget = tree(Default::default())
+            .expansion_in_data(true)
+            .with_id(tree_id);
+        let widget = crate::widget::EnvScope::new(
+            |env, _| theme::metrics::DENSE.apply_to(env),
+            Align::new(UnitPoint::TOP_LEFT, widget),
+        );
+        Harness::create_simple(data, widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            // root, a, b
+            assert_eq!(harness.get_state(tree_id).layout_rect().height(), 60.);
+
+            // the chevron column of a starts one indent in, which is left of
+            // where it would be with the default metrics
+            harness.event(click((INDENT + 1.0, 30.), 1));
+            harness.just_layout();
+            assert!(harness.data().0.children[0].expanded);
+            assert_eq!(harness.get_state(tree_id).layout_rect().height(), 100.);
+        });
+    }
+
+    #[test]
+    fn expansion_round_trips_through_data() {
+        let [tree_id] = widget_ids();
+        let mut root = fixture();
+        root.expanded = true;
+        Arc::make_mut(&mut root.children)[0].expanded = true;
+        let data = (root, ListSelection::new());
+        let widget = tree(Default::default())
+            .expansion_in_data(true)
+            .with_id(tree_id);
+        let widget =
+            Align::new(UnitPoint::TOP_LEFT, widget).controller(Observer(Default::default()));
+        Harness::create_simple(data, widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            // root, a, a1, a2, b
+            assert_eq!(harness.get_state(tree_id).layout_rect().height(), 100.);
+
+            harness.event(click((50., 30.), 1));
+            assert_eq!(cursor(harness.data()), Some(1));
+            harness.event(key(KbKey::ArrowLeft));
+            harness.just_layout();
+            assert!(!harness.data().0.children[0].expanded);
+            assert_eq!(harness.get_state(tree_id).layout_rect().height(), 60.);
+
+            // expanding b in the data shows its child
+            harness.submit_command(EXPAND_B);
+            harness.just_layout();
+            assert_eq!(harness.get_state(tree_id).layout_rect().height(), 80.);
+
+            harness.event(key(KbKey::ArrowRight));
+            assert!(harness.data().0.children[0].expanded);
+            assert_eq!(cursor(harness.data()), Some(1));
+        });
+    }
+
+    #[test]
+    fn keyboard_navigation() {
+        let activations = Rc::new(RefCell::new(Vec::new()));
+        let data = (fixture(), ListSelection::new());
+        let widget = Align::new(UnitPoint::TOP_LEFT, tree(Default::default()))
+            .controller(Observer(activations.clone()));
+        Harness::create_simple(data, widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.event(click((30., 10.), 1));
+            assert_eq!(cursor(harness.data()), Some(0));
+
+            let mut press = |k: KbKey| {
+                harness.event(key(k));
+                harness.just_layout();
+                cursor(harness.data())
+            };
+            // expand root, then move into a
+            assert_eq!(press(KbKey::ArrowRight), Some(0));
+            assert_eq!(press(KbKey::ArrowRight), Some(1));
+            // a stays collapsed, so Down moves to b
+            assert_eq!(press(KbKey::ArrowDown), Some(2));
+            // expand b and b1, and walk down to b1x
+            assert_eq!(press(KbKey::ArrowRight), Some(2));
+            assert_eq!(press(KbKey::ArrowRight), Some(3));
+            assert_eq!(press(KbKey::ArrowRight), Some(3));
+            assert_eq!(press(KbKey::ArrowRight), Some(4));
+            assert_eq!(press(KbKey::ArrowDown), Some(4));
+            // back up to b1, collapse it, then up to b
+            assert_eq!(press(KbKey::ArrowLeft), Some(3));
+            assert_eq!(press(KbKey::ArrowLeft), Some(3));
+            assert_eq!(press(KbKey::ArrowLeft), Some(2));
+            // from a, up to the root and collapse it
+            assert_eq!(press(KbKey::ArrowUp), Some(1));
+            assert_eq!(press(KbKey::ArrowLeft), Some(0));
+            assert_eq!(press(KbKey::ArrowLeft), Some(0));
+            assert_eq!(press(KbKey::End), Some(0));
+
+            // re-expanding restores b and b1 as they were
+            assert_eq!(press(KbKey::ArrowRight), Some(0));
+            assert_eq!(press(KbKey::End), Some(3));
+            assert_eq!(press(KbKey::Enter), Some(3));
+            assert_eq!(*activations.borrow(), vec![vec![1, 0]]);
+        });
+    }
+
+    #[test]
+    fn clicking_a_chevron_toggles() {
+        let data = (fixture(), ListSelection::new());
+        Harness::create_simple(data, tree(Default::default()), |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.event(click((50., 10.), 1));
+            assert_eq!(harness.data().1.selected().collect::<Vec<_>>(), vec![0]);
+
+            harness.event(click((8., 10.), 1));
+            harness.just_layout();
+            // b's chevron is one level in
+            harness.event(click((24., 50.), 1));
+            harness.just_layout();
+            harness.event(click((50., 70.), 1));
+            assert_eq!(cursor(harness.data()), Some(3));
+
+            // collapsing the root moves the selection out of the hidden rows
+            harness.event(click((8., 10.), 1));
+            assert_eq!(cursor(harness.data()), Some(0));
+        });
+    }
+}
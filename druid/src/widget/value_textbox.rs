@@ -414,4 +414,13 @@ impl<T: Data + std::fmt::Debug> Widget<T> for ValueTextBox<T> {
     fn paint(&mut self, ctx: &mut PaintCtx, _data: &T, env: &Env) {
         self.inner.paint(ctx, &self.buffer, env);
     }
+
+    #[cfg(feature = "debug_state")]
+    fn debug_state(&self, data: &T) -> DebugState {
+        let mut state = DebugState::with_value("ValueTextBox", self.buffer.clone());
+        state
+            .other_values
+            .insert("value".into(), format!("{:?}", data));
+        state
+    }
 }
@@ -116,4 +116,13 @@ impl<T: Data, U: Data> Widget<T> for ViewSwitcher<T, U> {
             child.paint_raw(ctx, data, env);
         }
     }
+
+    #[cfg(feature = "debug_state")]
+    fn debug_state(&self, data: &T) -> DebugState {
+        let children = self
+            .active_child
+            .iter()
+            .map(|child| child.debug_state(data));
+        DebugState::with_children("ViewSwitcher", children.collect())
+    }
 }
@@ -16,6 +16,7 @@ use std::num::NonZeroU64;
 use std::ops::{Deref, DerefMut};
 
 use super::prelude::*;
+use super::Axis;
 
 /// A unique identifier for a single [`Widget`].
 ///
@@ -50,6 +51,19 @@ use super::prelude::*;
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
 pub struct WidgetId(NonZeroU64);
 
+/// Which intrinsic size [`Widget::compute_intrinsic_size`] is asked for.
+///
+/// [`Widget::compute_intrinsic_size`]: trait.Widget.html#method.compute_intrinsic_size
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Intrinsic {
+    /// The smallest size at which the widget can still show its content,
+    /// for example the width of the longest word of a label that wraps.
+    Min,
+    /// The size the widget would take if it had as much room as it wanted,
+    /// for example the width of a label's text on a single line.
+    Preferred,
+}
+
 /// The trait implemented by all widgets.
 ///
 /// All appearance and behavior for a widget is encapsulated in an
@@ -136,9 +150,16 @@ pub trait Widget<T> {
     /// with any keys that are used in your widget, to see if they have changed;
     /// you can then request layout or paint as needed.
     ///
+    /// A [`WidgetPod`] or [`LensWrap`] will skip this call entirely when
+    /// neither its data nor the [`Env`] has changed and no widget in the
+    /// subtree has called [`request_update`].
+    ///
     /// [`Data`]: trait.Data.html
     /// [`Env`]: struct.Env.html
     /// [`UpdateCtx`]: struct.UpdateCtx.html
+    /// [`WidgetPod`]: struct.WidgetPod.html
+    /// [`LensWrap`]: widget/struct.LensWrap.html
+    /// [`request_update`]: struct.EventCtx.html#method.request_update
     /// [`env_changed`]: struct.UpdateCtx.html#method.env_changed
     /// [`env_key_changed`]: struct.UpdateCtx.html#method.env_changed
     /// [`request_paint`]: struct.UpdateCtx.html#method.request_paint
@@ -169,6 +190,42 @@ pub trait Widget<T> {
     /// [`set_origin`]: struct.WidgetPod.html#method.set_origin
     fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size;
 
+    /// Compute the size this widget would like to have along `axis`.
+    ///
+    /// This lets containers ask a child how small it can get, or how much
+    /// space it can make use of, without having to probe it with different
+    /// constraints. Flex uses it to decide which children to shrink when
+    /// there isn't enough room for all of them.
+    ///
+    /// `bc` constrains the other axis; its values along `axis` should be
+    /// ignored. The result should not depend on the widget's current size.
+    ///
+    /// The default implementation lays the widget out with unbounded
+    /// constraints along `axis`, and returns its size along that axis for
+    /// both kinds of intrinsic size. This is always correct, but it means
+    /// that the widget will never be shrunk below its natural size; widgets
+    /// that can get smaller, like labels that wrap, should implement this.
+    ///
+    /// Containers should ask their children with
+    /// [`WidgetPod::compute_intrinsic_size`], which caches the results.
+    /// Because computing an intrinsic size may lay the child out, a
+    /// container has to lay the child out again afterwards.
+    ///
+    /// [`WidgetPod::compute_intrinsic_size`]: struct.WidgetPod.html#method.compute_intrinsic_size
+    fn compute_intrinsic_size(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        axis: Axis,
+        kind: Intrinsic,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        let _ = kind;
+        let bc = axis.constraints(bc, 0.0, f64::INFINITY);
+        axis.major(self.layout(ctx, &bc, data, env))
+    }
+
     /// Paint the widget appearance.
     ///
     /// The [`PaintCtx`] derefs to something that implements the [`RenderContext`]
@@ -184,6 +241,21 @@ pub trait Widget<T> {
     /// [`RenderContext`]: trait.RenderContext.html
     fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env);
 
+    /// Describe this widget and its children, for tests and tooling.
+    ///
+    /// The default implementation reports the widget's type name and nothing
+    /// else. Widgets with a meaningful value should report it, and container
+    /// widgets should report their children, usually by calling
+    /// [`WidgetPod::debug_state`] on each of them.
+    ///
+    /// [`WidgetPod::debug_state`]: struct.WidgetPod.html#method.debug_state
+    #[cfg(feature = "debug_state")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "debug_state")))]
+    fn debug_state(&self, data: &T) -> DebugState {
+        let _ = data;
+        DebugState::for_type(self.type_name())
+    }
+
     #[doc(hidden)]
     /// Get the identity of the widget; this is basically only implemented by
     /// `IdentityWrapper`. Widgets should not implement this on their own.
@@ -251,10 +323,28 @@ impl<T> Widget<T> for Box<dyn Widget<T>> {
         self.deref_mut().layout(ctx, bc, data, env)
     }
 
+    fn compute_intrinsic_size(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        axis: Axis,
+        kind: Intrinsic,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        self.deref_mut()
+            .compute_intrinsic_size(ctx, axis, kind, bc, data, env)
+    }
+
     fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
         self.deref_mut().paint(ctx, data, env);
     }
 
+    #[cfg(feature = "debug_state")]
+    fn debug_state(&self, data: &T) -> DebugState {
+        self.deref().debug_state(data)
+    }
+
     fn id(&self) -> Option<WidgetId> {
         self.deref().id()
     }
@@ -14,14 +14,17 @@
 
 //! Convenience methods for widgets.
 
+use std::time::Duration;
+
 use super::invalidation::DebugInvalidation;
 use super::{
-    Added, Align, BackgroundBrush, Click, Container, Controller, ControllerHost, EnvScope,
-    IdentityWrapper, LensWrap, Padding, Parse, SizedBox, WidgetId,
+    Added, Align, AnimateLayout, BackgroundBrush, Click, Container, Controller, ControllerHost,
+    Curve, EnvScope, IdentityWrapper, LensWrap, OnChange, Padding, Parse, SizedBox, WidgetId,
 };
 use crate::widget::{DisabledIf, Scroll};
 use crate::{
-    Color, Data, Env, EventCtx, Insets, KeyOrValue, Lens, LifeCycleCtx, UnitPoint, Widget,
+    Color, Data, Env, EventCtx, Insets, KeyOrValue, Lens, LifeCycleCtx, UnitPoint, UpdateCtx,
+    Widget,
 };
 
 /// A trait that provides extra methods for combining `Widget`s.
@@ -197,8 +200,40 @@ pub trait WidgetExt<T: Data>: Widget<T> + Sized + 'static {
         ControllerHost::new(self, Click::new(f))
     }
 
+    /// Provide a closure that will be called whenever the data of this widget changes.
+    ///
+    /// The closure is called from [`update`] with the old and the new data.
+    /// It can't change the data itself, but it can submit commands that do.
+    ///
+    /// [`update`]: Widget::update
+    fn on_change(
+        self,
+        f: impl Fn(&mut UpdateCtx, &T, &T, &Env) + 'static,
+    ) -> ControllerHost<Self, OnChange<T>> {
+        ControllerHost::new(self, OnChange::new(f))
+    }
+
+    /// Like [`on_change`], but only call the closure once the data has stopped
+    /// changing for `delay`.
+    ///
+    /// This is useful for expensive reactions such as saving to disk.
+    /// See [`OnChange::debounced`] for details.
+    ///
+    /// [`on_change`]: WidgetExt::on_change
+    fn on_change_debounced(
+        self,
+        delay: Duration,
+        f: impl Fn(&mut UpdateCtx, &T, &T, &Env) + 'static,
+    ) -> ControllerHost<Self, OnChange<T>> {
+        ControllerHost::new(self, OnChange::debounced(delay, f))
+    }
+
     /// Draw the [`layout`] `Rect`s of  this widget and its children.
     ///
+    /// With the `debug_state` feature, this also records the constraints each
+    /// widget received and the size it returned in the last layout pass, as
+    /// the `"constraints"` and `"size"` values of its `DebugState`.
+    ///
     /// [`layout`]: trait.Widget.html#tymethod.layout
     fn debug_paint_layout(self) -> EnvScope<T, Self> {
         EnvScope::new(|env, _| env.set(Env::DEBUG_PAINT, true), self)
@@ -221,6 +256,17 @@ pub trait WidgetExt<T: Data>: Widget<T> + Sized + 'static {
         DebugInvalidation::new(self)
     }
 
+    /// Record the events and lifecycle events reaching this widget in an
+    /// [`EventLog`], to be shown by an [`EventViewer`].
+    ///
+    /// [`EventLog`]: super::EventLog
+    /// [`EventViewer`]: super::EventViewer
+    #[cfg(feature = "diagnostics")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "diagnostics")))]
+    fn debug_event_log(self, log: super::EventLog) -> ControllerHost<Self, super::EventLogger> {
+        ControllerHost::new(self, super::EventLogger::new(log))
+    }
+
     /// Set the [`DEBUG_WIDGET`] env variable for this widget (and its descendants).
     ///
     /// This does nothing by default, but you can use this variable while
@@ -274,6 +320,14 @@ pub trait WidgetExt<T: Data>: Widget<T> + Sized + 'static {
         Scroll::new(self)
     }
 
+    /// Wrap this widget in an [`AnimateLayout`] widget, so that when its
+    /// layout rect changes, it moves to the new one over `duration`.
+    ///
+    /// [`AnimateLayout`]: crate::widget::AnimateLayout
+    fn animate_layout(self, duration: Duration, curve: Curve) -> AnimateLayout<T, Self> {
+        AnimateLayout::new(self, duration, curve)
+    }
+
     /// Wrap this widget in a [`DisabledIf`] widget.
     ///
     /// The provided closure will determine if the widget is disabled.
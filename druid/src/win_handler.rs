@@ -26,14 +26,15 @@ use crate::shell::{
     Scale, TextFieldToken, WinHandler, WindowHandle,
 };
 
-use crate::app_delegate::{AppDelegate, DelegateCtx};
+use crate::app_delegate::{AppDelegate, DelegateCtx, DelegateQueries, QueryHandler};
+use crate::asset_cache::AssetCache;
 use crate::core::CommandQueue;
 use crate::ext_event::{ExtEventHost, ExtEventSink};
 use crate::menu::{ContextMenu, MenuItemId, MenuManager};
 use crate::window::{ImeUpdateFn, Window};
 use crate::{
-    Command, Data, Env, Event, Handled, InternalEvent, KeyEvent, PlatformError, Selector, Target,
-    TimerToken, WidgetId, WindowDesc, WindowId,
+    Command, Data, Env, EnvUpdate, Event, Handled, InternalEvent, KeyEvent, PlatformError,
+    Selector, Target, TimerToken, WidgetId, WindowDesc, WindowId,
 };
 
 use crate::app::{PendingWindow, WindowConfig};
@@ -90,6 +91,7 @@ struct Inner<T> {
     command_queue: CommandQueue,
     file_dialogs: HashMap<FileDialogToken, DialogInfo>,
     ext_event_host: ExtEventHost,
+    asset_cache: AssetCache,
     windows: Windows<T>,
     /// the application-level menu, only set on macos and only if there
     /// are no open windows.
@@ -109,10 +111,17 @@ struct Windows<T> {
     windows: HashMap<WindowId, Window<T>>,
 }
 
-impl<T> Windows<T> {
-    fn connect(&mut self, id: WindowId, handle: WindowHandle, ext_handle: ExtEventSink) {
+impl<T: Data> Windows<T> {
+    fn connect(
+        &mut self,
+        id: WindowId,
+        handle: WindowHandle,
+        ext_handle: ExtEventSink,
+        asset_cache: AssetCache,
+    ) {
         if let Some(pending) = self.pending.remove(&id) {
-            let win = Window::new(id, handle, pending, ext_handle);
+            let mut win = Window::new(id, handle, pending, ext_handle);
+            win.asset_cache = asset_cache;
             assert!(self.windows.insert(id, win).is_none(), "duplicate window");
         } else {
             tracing::error!("no window for connecting handle {:?}", id);
@@ -127,6 +136,10 @@ impl<T> Windows<T> {
         self.windows.remove(&id)
     }
 
+    fn iter(&self) -> impl Iterator<Item = &'_ Window<T>> {
+        self.windows.values()
+    }
+
     fn iter_mut(&mut self) -> impl Iterator<Item = &'_ mut Window<T>> {
         self.windows.values_mut()
     }
@@ -157,6 +170,7 @@ impl<T> AppState<T> {
         env: Env,
         delegate: Option<Box<dyn AppDelegate<T>>>,
         ext_event_host: ExtEventHost,
+        asset_cache: AssetCache,
     ) -> Self {
         let inner = Rc::new(RefCell::new(Inner {
             app,
@@ -166,6 +180,7 @@ impl<T> AppState<T> {
             root_menu: None,
             menu_window: None,
             ext_event_host,
+            asset_cache,
             data,
             env,
             windows: Windows::default(),
@@ -226,6 +241,38 @@ impl<T: Data> Inner<T> {
             .map(|delegate| f(delegate, data, env, &mut ctx))
     }
 
+    /// Call `f` with the window `id` and everything it needs to handle an event,
+    /// including the delegate, for answering queries from widgets.
+    fn with_window<R, F>(&mut self, id: WindowId, f: F) -> Option<R>
+    where
+        F: FnOnce(
+            &mut Window<T>,
+            &mut CommandQueue,
+            &mut T,
+            &Env,
+            Option<&mut dyn QueryHandler>,
+        ) -> R,
+    {
+        let Inner {
+            ref mut delegate,
+            ref mut command_queue,
+            ref mut windows,
+            ref mut data,
+            ref ext_event_host,
+            ref env,
+            ..
+        } = self;
+        let mut queries = delegate.as_deref_mut().map(|delegate| DelegateQueries {
+            delegate,
+            ext_event_host,
+            env,
+        });
+        windows.get_mut(id).map(|win| {
+            let queries = queries.as_mut().map(|q| q as &mut dyn QueryHandler);
+            f(win, command_queue, data, env, queries)
+        })
+    }
+
     fn delegate_event(&mut self, id: WindowId, event: Event) -> Option<Event> {
         if self.delegate.is_some() {
             self.with_delegate(|del, data, env, ctx| del.event(ctx, id, event, data, env))
@@ -241,8 +288,12 @@ impl<T: Data> Inner<T> {
     }
 
     fn connect(&mut self, id: WindowId, handle: WindowHandle) {
-        self.windows
-            .connect(id, handle, self.ext_event_host.make_sink());
+        self.windows.connect(
+            id,
+            handle,
+            self.ext_event_host.make_sink(),
+            self.asset_cache.clone(),
+        );
 
         // If the external event host has no handle, it cannot wake us
         // when an event arrives.
@@ -328,10 +379,18 @@ impl<T: Data> Inner<T> {
         }
     }
 
-    fn prepare_paint(&mut self, window_id: WindowId) {
-        if let Some(win) = self.windows.get_mut(window_id) {
-            win.prepare_paint(&mut self.command_queue, &mut self.data, &self.env);
+    fn update_env(&mut self, update: &EnvUpdate) {
+        update.apply(&mut self.env);
+        // widgets aren't required to repaint when a color they use changes
+        for win in self.windows.iter_mut() {
+            win.handle.invalidate();
         }
+    }
+
+    fn prepare_paint(&mut self, window_id: WindowId) {
+        self.with_window(window_id, |win, queue, data, env, queries| {
+            win.prepare_paint(queue, data, env, queries)
+        });
         self.do_update();
     }
 
@@ -360,51 +419,48 @@ impl<T: Data> Inner<T> {
                     self.show_context_menu(id, &cmd);
                     return Handled::Yes;
                 }
-                if let Some(w) = self.windows.get_mut(id) {
-                    return if cmd.is(sys_cmd::CLOSE_WINDOW) {
+                let handled = self.with_window(id, |w, queue, data, env, mut queries| {
+                    if cmd.is(sys_cmd::CLOSE_WINDOW) {
                         let handled = w.event(
-                            &mut self.command_queue,
+                            queue,
                             Event::WindowCloseRequested,
-                            &mut self.data,
-                            &self.env,
+                            data,
+                            env,
+                            queries.as_mut().map(|q| &mut **q as &mut dyn QueryHandler),
                         );
                         if !handled.is_handled() {
-                            w.event(
-                                &mut self.command_queue,
-                                Event::WindowDisconnected,
-                                &mut self.data,
-                                &self.env,
-                            );
+                            w.event(queue, Event::WindowDisconnected, data, env, queries);
                         }
                         handled
                     } else {
-                        w.event(
-                            &mut self.command_queue,
-                            Event::Command(cmd),
-                            &mut self.data,
-                            &self.env,
-                        )
-                    };
+                        w.event(queue, Event::Command(cmd), data, env, queries)
+                    }
+                });
+                if let Some(handled) = handled {
+                    return handled;
                 }
             }
             // in this case we send it to every window that might contain
             // this widget, breaking if the event is handled.
             Target::Widget(id) => {
-                for w in self.windows.iter_mut().filter(|w| w.may_contain_widget(id)) {
+                let ids: Vec<_> = self
+                    .windows
+                    .iter()
+                    .filter(|w| w.may_contain_widget(id))
+                    .map(|w| w.id)
+                    .collect();
+                for window_id in ids {
                     let event = Event::Internal(InternalEvent::TargetedCommand(cmd.clone()));
-                    if w.event(&mut self.command_queue, event, &mut self.data, &self.env)
-                        .is_handled()
-                    {
+                    if self.window_event(window_id, event).is_handled() {
                         return Handled::Yes;
                     }
                 }
             }
             Target::Global => {
-                for w in self.windows.iter_mut() {
+                let ids: Vec<_> = self.windows.iter().map(|w| w.id).collect();
+                for window_id in ids {
                     let event = Event::Command(cmd.clone());
-                    if w.event(&mut self.command_queue, event, &mut self.data, &self.env)
-                        .is_handled()
-                    {
+                    if self.window_event(window_id, event).is_handled() {
                         return Handled::Yes;
                     }
                 }
@@ -430,11 +486,15 @@ impl<T: Data> Inner<T> {
             None => return Handled::Yes,
         };
 
-        if let Some(win) = self.windows.get_mut(source_id) {
-            win.event(&mut self.command_queue, event, &mut self.data, &self.env)
-        } else {
-            Handled::No
-        }
+        self.window_event(source_id, event)
+    }
+
+    /// Send an event to a window, if it exists.
+    fn window_event(&mut self, id: WindowId, event: Event) -> Handled {
+        self.with_window(id, |win, queue, data, env, queries| {
+            win.event(queue, event, data, env, queries)
+        })
+        .unwrap_or(Handled::No)
     }
 
     fn show_context_menu(&mut self, window_id: WindowId, cmd: &Command) {
@@ -665,6 +725,11 @@ impl<T: Data> AppState<T> {
                 }
             }
             _ if cmd.is(sys_cmd::CLOSE_ALL_WINDOWS) => self.request_close_all_windows(),
+            _ if cmd.is(sys_cmd::UPDATE_ENV) => self.update_env(cmd),
+            _ if cmd.is(sys_cmd::ACTIVATE_EXISTING) => {
+                self.inner.borrow_mut().dispatch_cmd(cmd);
+                self.show_main_window();
+            }
             T::Window(id) if cmd.is(sys_cmd::INVALIDATE_IME) => self.invalidate_ime(cmd, id),
             // these should come from a window
             // FIXME: we need to be able to open a file without a window handle
@@ -688,7 +753,12 @@ impl<T: Data> AppState<T> {
                 tracing::warn!("SHOW_OPEN_PANEL command must target a window.")
             }
             _ => {
-                self.inner.borrow_mut().dispatch_cmd(cmd);
+                let mut inner = self.inner.borrow_mut();
+                inner.dispatch_cmd(cmd.clone());
+                // a request that nobody answered gets a `RequestCanceled` response
+                if let Some(cancellation) = cmd.cancellation() {
+                    inner.command_queue.push_back(cancellation);
+                }
             }
         }
     }
@@ -804,12 +874,26 @@ impl<T: Data> AppState<T> {
         self.inner.borrow_mut().show_window(id);
     }
 
+    /// Bring the oldest open window to the front.
+    fn show_main_window(&mut self) {
+        let main = self.inner.borrow().windows.windows.keys().min().copied();
+        if let Some(id) = main {
+            self.show_window(id);
+        }
+    }
+
     fn configure_window(&mut self, cmd: Command, id: WindowId) {
         if let Some(config) = cmd.get(sys_cmd::CONFIGURE_WINDOW) {
             self.inner.borrow_mut().configure_window(config, id);
         }
     }
 
+    fn update_env(&mut self, cmd: Command) {
+        if let Some(update) = cmd.get(sys_cmd::UPDATE_ENV) {
+            self.inner.borrow_mut().update_env(update);
+        }
+    }
+
     fn do_paste(&mut self, window_id: WindowId) {
         let event = Event::Paste(self.inner.borrow().app.clipboard());
         self.inner.borrow_mut().do_window_event(window_id, event);
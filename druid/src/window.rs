@@ -16,7 +16,7 @@
 
 use std::collections::{HashMap, VecDeque};
 use std::mem;
-use tracing::{error, info, info_span};
+use tracing::{error, info, info_span, trace, warn};
 
 // Automatically defaults to std::time::Instant on non Wasm platforms
 use instant::Instant;
@@ -25,17 +25,25 @@ use crate::piet::{Color, Piet, RenderContext};
 use crate::shell::{text::InputHandler, Counter, Cursor, Region, TextFieldToken, WindowHandle};
 
 use crate::app::{PendingWindow, WindowSizePolicy};
+use crate::app_delegate::QueryHandler;
+use crate::asset_cache::AssetCache;
+use crate::command::sys as sys_cmd;
 use crate::contexts::ContextState;
-use crate::core::{CommandQueue, FocusChange, WidgetState};
+use crate::core::{CommandQueue, FocusChange, PointerCaptureChange, WidgetState};
+use crate::event::{PathCollector, StateCell};
 use crate::menu::{MenuItemId, MenuManager};
-use crate::text::TextFieldRegistration;
+use crate::overlay::OverlayHost;
+use crate::text::{SharedTextEngine, TextFieldRegistration};
+use crate::toast::ToastStack;
 use crate::util::ExtendDrain;
 use crate::widget::LabelText;
 use crate::win_handler::RUN_COMMANDS_TOKEN;
+#[cfg(feature = "debug_state")]
+use crate::DebugState;
 use crate::{
-    BoxConstraints, Data, Env, Event, EventCtx, ExtEventSink, Handled, InternalEvent,
-    InternalLifeCycle, LayoutCtx, LifeCycle, LifeCycleCtx, Menu, PaintCtx, Point, Size, TimerToken,
-    UpdateCtx, Widget, WidgetId, WidgetPod,
+    BoxConstraints, Command, Data, Env, Event, EventCtx, ExtEventSink, Handled, InternalEvent,
+    InternalLifeCycle, LayoutCtx, LifeCycle, LifeCycleCtx, Menu, PaintCtx, Point, Size, Target,
+    TimerToken, UpdateCtx, WidgetId, WidgetPod,
 };
 
 pub type ImeUpdateFn = dyn FnOnce(crate::shell::text::Event);
@@ -47,7 +55,7 @@ pub struct WindowId(u64);
 /// Per-window state not owned by user code.
 pub struct Window<T> {
     pub(crate) id: WindowId,
-    pub(crate) root: WidgetPod<T, Box<dyn Widget<T>>>,
+    pub(crate) root: WidgetPod<T, OverlayHost<T>>,
     pub(crate) title: LabelText<T>,
     size_policy: WindowSizePolicy,
     size: Size,
@@ -58,15 +66,23 @@ pub struct Window<T> {
     pub(crate) last_anim: Option<Instant>,
     pub(crate) last_mouse_pos: Option<Point>,
     pub(crate) focus: Option<WidgetId>,
+    pub(crate) pointer_capture: Option<WidgetId>,
     pub(crate) handle: WindowHandle,
     pub(crate) timers: HashMap<TimerToken, WidgetId>,
     pub(crate) transparent: bool,
     pub(crate) ime_handlers: Vec<(TextFieldToken, TextFieldRegistration)>,
     ext_handle: ExtEventSink,
+    /// The app's cache of decoded images, shared by all windows.
+    pub(crate) asset_cache: AssetCache,
+    pub(crate) text_engine: Option<SharedTextEngine>,
     pub(crate) ime_focus_change: Option<Option<TextFieldToken>>,
+    /// The ancestors of every widget in the tree, used to route commands.
+    ///
+    /// This is `None` when the tree has changed since it was last collected.
+    widget_paths: Option<HashMap<WidgetId, Vec<WidgetId>>>,
 }
 
-impl<T> Window<T> {
+impl<T: Data> Window<T> {
     pub(crate) fn new(
         id: WindowId,
         handle: WindowHandle,
@@ -75,7 +91,10 @@ impl<T> Window<T> {
     ) -> Window<T> {
         Window {
             id,
-            root: WidgetPod::new(pending.root),
+            root: WidgetPod::new(OverlayHost::new(
+                pending.root,
+                ToastStack::new(pending.toast_position, pending.max_visible_toasts),
+            )),
             size_policy: pending.size_policy,
             size: Size::ZERO,
             invalid: Region::EMPTY,
@@ -86,16 +105,28 @@ impl<T> Window<T> {
             last_anim: None,
             last_mouse_pos: None,
             focus: None,
+            pointer_capture: None,
             handle,
             timers: HashMap::new(),
             ext_handle,
+            asset_cache: AssetCache::default(),
+            text_engine: pending.text_engine,
             ime_handlers: Vec::new(),
             ime_focus_change: None,
+            widget_paths: None,
         }
     }
-}
 
-impl<T: Data> Window<T> {
+    /// Describe the widget tree of this window.
+    ///
+    /// See [`Widget::debug_state`] for more information.
+    ///
+    /// [`Widget::debug_state`]: crate::Widget::debug_state
+    #[cfg(feature = "debug_state")]
+    pub fn debug_state(&self, data: &T) -> DebugState {
+        self.root.widget().root_debug_state(data)
+    }
+
     /// `true` iff any child requested an animation frame since the last `AnimFrame` event.
     pub(crate) fn wants_animation_frame(&self) -> bool {
         self.root.state().request_anim
@@ -201,6 +232,14 @@ impl<T: Data> Window<T> {
 
         self.update_focus(widget_state, queue, data, env);
 
+        // Release the pointer capture if the capturing widget was removed.
+        if let Some(capture) = self.pointer_capture {
+            if widget_state.children_changed && !self.contains_widget(capture, queue, data, env) {
+                widget_state.request_pointer_capture = Some(PointerCaptureChange::Release(capture));
+            }
+        }
+        self.update_pointer_capture(widget_state, queue, data, env);
+
         // Add all the requested timers to the window's timers map.
         self.timers.extend_drain(&mut widget_state.timers);
 
@@ -233,6 +272,7 @@ impl<T: Data> Window<T> {
         event: Event,
         data: &mut T,
         env: &Env,
+        queries: Option<&mut dyn QueryHandler>,
     ) -> Handled {
         match &event {
             Event::WindowSize(size) => self.size = *size,
@@ -252,6 +292,35 @@ impl<T: Data> Window<T> {
                     return Handled::No;
                 }
             }
+            // checked here whatever their target, and then delivered to the
+            // widget they are about by its `WidgetPod`
+            Event::Command(cmd) | Event::Internal(InternalEvent::TargetedCommand(cmd))
+                if cmd.is(sys_cmd::REQUEST_FOCUS) || cmd.is(sys_cmd::RESIGN_FOCUS) =>
+            {
+                // a global command is offered to every window, and one for a
+                // widget to every window that may contain it
+                let ours = match cmd.target() {
+                    Target::Window(id) => id == self.id,
+                    Target::Widget(id) => self.has_widget(id, queue, data, env),
+                    _ => false,
+                };
+                if matches!(cmd.target(), Target::Widget(_)) && !ours {
+                    return Handled::No;
+                }
+                match self.route_focus_command(&cmd, ours, queue, data, env) {
+                    Some(cmd) => Event::Internal(InternalEvent::TargetedCommand(cmd)),
+                    None => return Handled::from(ours),
+                }
+            }
+            #[cfg(feature = "debug_state")]
+            Event::Command(cmd) | Event::Internal(InternalEvent::TargetedCommand(cmd))
+                if cmd.is(sys_cmd::QUERY_DEBUG_STATE)
+                    && cmd.target() == Target::Window(self.id) =>
+            {
+                let state = self.debug_state(data);
+                queue.push_back(sys_cmd::DEBUG_STATE.with(state).to(self.id));
+                return Handled::Yes;
+            }
             other => other,
         };
 
@@ -265,10 +334,40 @@ impl<T: Data> Window<T> {
             );
         }
 
+        let target = match &event {
+            Event::Internal(InternalEvent::TargetedCommand(cmd)) => match cmd.target() {
+                Target::Widget(id) => Some(id),
+                _ => None,
+            },
+            _ => None,
+        };
+        if let Some(id) = target {
+            if self.widget_paths.is_none() {
+                self.collect_widget_paths(queue, data, env);
+            }
+            if let Some(paths) = &self.widget_paths {
+                if !paths.contains_key(&id) {
+                    trace!("{:?} is not in this window", id);
+                    return Handled::No;
+                }
+            }
+        }
+
         let mut widget_state = WidgetState::new(self.root.id(), Some(self.size));
         let is_handled = {
-            let mut state =
-                ContextState::new::<T>(queue, &self.ext_handle, &self.handle, self.id, self.focus);
+            let mut state = ContextState::new::<T>(
+                queue,
+                &self.ext_handle,
+                &self.asset_cache,
+                &self.handle,
+                self.id,
+                self.focus,
+                self.pointer_capture,
+                self.text_engine.as_deref(),
+            );
+            state.query_handler = queries.map(|q| q as &mut dyn QueryHandler);
+            let paths = self.widget_paths.as_ref();
+            state.target_path = target.and_then(|id| paths?.get(&id)).map(Vec::as_slice);
             let mut notifications = VecDeque::new();
             let mut ctx = EventCtx {
                 state: &mut state,
@@ -330,9 +429,21 @@ impl<T: Data> Window<T> {
         env: &Env,
         process_commands: bool,
     ) {
+        if let LifeCycle::Internal(InternalLifeCycle::RouteWidgetAdded) = event {
+            self.widget_paths = None;
+        }
+
         let mut widget_state = WidgetState::new(self.root.id(), Some(self.size));
-        let mut state =
-            ContextState::new::<T>(queue, &self.ext_handle, &self.handle, self.id, self.focus);
+        let mut state = ContextState::new::<T>(
+            queue,
+            &self.ext_handle,
+            &self.asset_cache,
+            &self.handle,
+            self.id,
+            self.focus,
+            self.pointer_capture,
+            self.text_engine.as_deref(),
+        );
         let mut ctx = LifeCycleCtx {
             state: &mut state,
             widget_state: &mut widget_state,
@@ -351,8 +462,16 @@ impl<T: Data> Window<T> {
         self.update_title(data, env);
 
         let mut widget_state = WidgetState::new(self.root.id(), Some(self.size));
-        let mut state =
-            ContextState::new::<T>(queue, &self.ext_handle, &self.handle, self.id, self.focus);
+        let mut state = ContextState::new::<T>(
+            queue,
+            &self.ext_handle,
+            &self.asset_cache,
+            &self.handle,
+            self.id,
+            self.focus,
+            self.pointer_capture,
+            self.text_engine.as_deref(),
+        );
         let mut update_ctx = UpdateCtx {
             widget_state: &mut widget_state,
             state: &mut state,
@@ -395,7 +514,13 @@ impl<T: Data> Window<T> {
     }
 
     /// Get ready for painting, by doing layout and sending an `AnimFrame` event.
-    pub(crate) fn prepare_paint(&mut self, queue: &mut CommandQueue, data: &mut T, env: &Env) {
+    pub(crate) fn prepare_paint(
+        &mut self,
+        queue: &mut CommandQueue,
+        data: &mut T,
+        env: &Env,
+        queries: Option<&mut dyn QueryHandler>,
+    ) {
         let now = Instant::now();
         // TODO: this calculation uses wall-clock time of the paint call, which
         // potentially has jitter.
@@ -405,7 +530,7 @@ impl<T: Data> Window<T> {
         let elapsed_ns = last.map(|t| now.duration_since(t).as_nanos()).unwrap_or(0) as u64;
 
         if self.wants_animation_frame() {
-            self.event(queue, Event::AnimFrame(elapsed_ns), data, env);
+            self.event(queue, Event::AnimFrame(elapsed_ns), data, env, queries);
             self.last_anim = Some(now);
         }
     }
@@ -437,8 +562,16 @@ impl<T: Data> Window<T> {
 
     fn layout(&mut self, queue: &mut CommandQueue, data: &T, env: &Env) {
         let mut widget_state = WidgetState::new(self.root.id(), Some(self.size));
-        let mut state =
-            ContextState::new::<T>(queue, &self.ext_handle, &self.handle, self.id, self.focus);
+        let mut state = ContextState::new::<T>(
+            queue,
+            &self.ext_handle,
+            &self.asset_cache,
+            &self.handle,
+            self.id,
+            self.focus,
+            self.pointer_capture,
+            self.text_engine.as_deref(),
+        );
         let mut layout_ctx = LayoutCtx {
             state: &mut state,
             widget_state: &mut widget_state,
@@ -490,8 +623,16 @@ impl<T: Data> Window<T> {
         env: &Env,
     ) {
         let widget_state = WidgetState::new(self.root.id(), Some(self.size));
-        let mut state =
-            ContextState::new::<T>(queue, &self.ext_handle, &self.handle, self.id, self.focus);
+        let mut state = ContextState::new::<T>(
+            queue,
+            &self.ext_handle,
+            &self.asset_cache,
+            &self.handle,
+            self.id,
+            self.focus,
+            self.pointer_capture,
+            self.text_engine.as_deref(),
+        );
         let mut ctx = PaintCtx {
             render_ctx: piet,
             state: &mut state,
@@ -554,6 +695,56 @@ impl<T: Data> Window<T> {
             .unwrap()
     }
 
+    /// Turn a [`REQUEST_FOCUS`] or [`RESIGN_FOCUS`] command into one for the
+    /// widget that should take or give up focus, if it is in this window.
+    ///
+    /// [`REQUEST_FOCUS`]: sys_cmd::REQUEST_FOCUS
+    /// [`RESIGN_FOCUS`]: sys_cmd::RESIGN_FOCUS
+    ///
+    /// `ours` is `true` if the command was meant for this window, rather than
+    /// offered to every window; only then is a missing widget logged.
+    fn route_focus_command(
+        &mut self,
+        cmd: &Command,
+        ours: bool,
+        queue: &mut CommandQueue,
+        data: &T,
+        env: &Env,
+    ) -> Option<Command> {
+        if let Some(&id) = cmd.get(sys_cmd::REQUEST_FOCUS) {
+            if self.focus_chain().contains(&id) {
+                return Some(sys_cmd::TAKE_FOCUS.to(id));
+            }
+            if self.has_widget(id, queue, data, env) {
+                warn!(
+                    "can't focus {:?}; it is disabled, hidden, or not focusable",
+                    id
+                );
+            } else if ours {
+                warn!("can't focus {:?}; there is no such widget", id);
+            }
+            None
+        } else {
+            match (cmd.target(), self.focus) {
+                // aimed at a widget that doesn't have focus
+                (Target::Widget(target), Some(focus)) if target != focus => None,
+                (_, focus) => focus.map(|id| sys_cmd::GIVE_UP_FOCUS.to(id)),
+            }
+        }
+    }
+
+    /// Whether the widget with this id is in this window.
+    fn has_widget(&mut self, id: WidgetId, queue: &mut CommandQueue, data: &T, env: &Env) -> bool {
+        if self.widget_paths.is_none() {
+            self.collect_widget_paths(queue, data, env);
+        }
+        match &self.widget_paths {
+            Some(paths) => paths.contains_key(&id),
+            // the tree changed while collecting; assume it may be here
+            None => self.may_contain_widget(id),
+        }
+    }
+
     fn update_focus(
         &mut self,
         widget_state: &mut WidgetState,
@@ -598,6 +789,61 @@ impl<T: Data> Window<T> {
         }
     }
 
+    fn update_pointer_capture(
+        &mut self,
+        widget_state: &mut WidgetState,
+        queue: &mut CommandQueue,
+        data: &T,
+        env: &Env,
+    ) {
+        let old = self.pointer_capture;
+        let new = match widget_state.request_pointer_capture.take() {
+            Some(PointerCaptureChange::Capture(id)) => Some(id),
+            Some(PointerCaptureChange::Release(id)) if old == Some(id) => None,
+            _ => return,
+        };
+        if old != new {
+            self.pointer_capture = new;
+            let event =
+                LifeCycle::Internal(InternalLifeCycle::RoutePointerCaptureChanged { old, new });
+            self.lifecycle(queue, &event, data, env, false);
+        }
+    }
+
+    /// Returns `true` if the widget is currently in this window's tree.
+    ///
+    /// Unlike `may_contain_widget`, this has no false positives, but it has to
+    /// walk the tree.
+    fn contains_widget(
+        &mut self,
+        widget: WidgetId,
+        queue: &mut CommandQueue,
+        data: &T,
+        env: &Env,
+    ) -> bool {
+        if !self.may_contain_widget(widget) {
+            return false;
+        }
+        let state_cell = StateCell::default();
+        let event = LifeCycle::Internal(InternalLifeCycle::DebugRequestState {
+            widget,
+            state_cell: state_cell.clone(),
+        });
+        self.lifecycle(queue, &event, data, env, false);
+        state_cell.take().is_some()
+    }
+
+    /// Record the path to every widget in the tree.
+    fn collect_widget_paths(&mut self, queue: &mut CommandQueue, data: &T, env: &Env) {
+        let collector = PathCollector::default();
+        let event = LifeCycle::Internal(InternalLifeCycle::CollectWidgetPaths(collector.clone()));
+        self.lifecycle(queue, &event, data, env, false);
+        // The pass itself may have changed the tree.
+        if self.widget_paths.is_none() && !self.root.state().children_changed {
+            self.widget_paths = Some(collector.take());
+        }
+    }
+
     /// Create a function that can invalidate the provided widget's text state.
     ///
     /// This will be called from outside the main app state in order to avoid